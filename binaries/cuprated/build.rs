@@ -1,8 +1,13 @@
+use cuprate_fast_sync::FastSyncFile;
+use cuprate_helper::network::Network;
+
 fn main() {
-    generate_fast_sync_hashes();
+    generate_fast_sync_hashes("fast_sync_hashes", Network::Mainnet);
+    generate_fast_sync_hashes("fast_sync_hashes_stagenet", Network::Stagenet);
+    generate_fast_sync_hashes("fast_sync_hashes_testnet", Network::Testnet);
 }
 
-/// Generates `fast_sync_hashes.rs` from `fast_sync_hashes.json`.
+/// Generates `{name}.rs` from `{name}.json`.
 ///
 /// This creates a temporary build file with the
 /// `Debug` representation of the hashes, i.e.:
@@ -14,19 +19,20 @@ fn main() {
 /// ```rust
 /// let _: &[[u8; 32]] = &include!(...)
 /// ```
-fn generate_fast_sync_hashes() {
-    println!("cargo::rerun-if-changed=src/blockchain/fast_sync/fast_sync_hashes.json");
+///
+/// The JSON files are the [`FastSyncFile`]s written by `create-fs-file`,
+/// the build fails if one is not for `network` or its checksum is wrong.
+fn generate_fast_sync_hashes(name: &str, network: Network) {
+    let path = format!("src/blockchain/fast_sync/{name}.json");
+    println!("cargo::rerun-if-changed={path}");
 
-    let hashes = serde_json::from_str::<Vec<cuprate_hex::Hex<32>>>(include_str!(
-        "src/blockchain/fast_sync/fast_sync_hashes.json"
-    ))
-    .unwrap()
-    .into_iter()
-    .map(|h| h.0)
-    .collect::<Vec<[u8; 32]>>();
+    let hashes = serde_json::from_str::<FastSyncFile>(&std::fs::read_to_string(&path).unwrap())
+        .unwrap()
+        .into_hashes(network)
+        .unwrap_or_else(|e| panic!("Invalid fast-sync file {path}: {e}"));
 
     std::fs::write(
-        format!("{}/fast_sync_hashes.rs", std::env::var("OUT_DIR").unwrap()),
+        format!("{}/{name}.rs", std::env::var("OUT_DIR").unwrap()),
         format!("{hashes:?}"),
     )
     .unwrap();
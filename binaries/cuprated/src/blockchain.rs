@@ -19,15 +19,22 @@ use cuprate_types::{
 
 use crate::constants::PANIC_CRITICAL_SERVICE_ERROR;
 
+mod block_template;
+pub mod bootstrap;
 mod chain_service;
+pub mod check;
 mod fast_sync;
 pub mod interface;
 mod manager;
+mod mining;
+pub mod pruning;
 mod syncer;
 mod types;
 
+pub use block_template::{create_block_template, randomx_seed_hash};
 pub use fast_sync::set_fast_sync_hashes;
-pub use manager::init_blockchain_manager;
+pub use manager::{init_blockchain_manager, IncomingBlockOk};
+pub use mining::generate_blocks;
 pub use types::ConsensusBlockchainReadHandle;
 
 /// Checks if the genesis block is in the blockchain and adds it if not.
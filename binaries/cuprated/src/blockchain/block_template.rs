@@ -0,0 +1,477 @@
+//! Block templates.
+//!
+//! Builds the blocks given to miners (`get_block_template`) on top of the main chain.
+use std::cmp::{max, min, Ordering};
+
+use anyhow::anyhow;
+use curve25519_dalek::{EdwardsPoint, Scalar};
+use monero_address::MoneroAddress;
+use monero_serai::{
+    block::{Block, BlockHeader},
+    io::write_varint,
+    primitives::{keccak256, keccak256_to_scalar},
+    transaction::{Input, Output, Timelock, Transaction, TransactionPrefix},
+};
+use rand::RngCore;
+use tower::{Service, ServiceExt};
+
+use cuprate_blockchain::service::BlockchainReadHandle;
+use cuprate_consensus::BlockchainContextService;
+use cuprate_consensus_rules::{
+    blocks::{randomx_seed_height, RX_SEEDHASH_EPOCH_LAG},
+    miner_tx::calculate_block_reward,
+};
+use cuprate_helper::{
+    cast::{u64_to_usize, usize_to_u64},
+    time::current_unix_timestamp,
+};
+use cuprate_txpool::{
+    service::{
+        interface::{TxpoolReadRequest, TxpoolReadResponse},
+        TxpoolReadHandle,
+    },
+    TxEntry,
+};
+use cuprate_types::{
+    blockchain::{BlockchainReadRequest, BlockchainResponse},
+    BlockTemplate, Chain, HardFork,
+};
+
+/// The weight reserved for the miner transaction when selecting transactions.
+///
+/// `CRYPTONOTE_COINBASE_BLOB_RESERVED_SIZE` in `monerod`.
+const COINBASE_BLOB_RESERVED_SIZE: usize = 600;
+
+/// The amount of blocks the outputs of a miner transaction are locked for.
+const MINER_TX_LOCK_BLOCKS: usize = 60;
+
+/// The `tx_extra` tag of the transaction public key.
+const TX_EXTRA_TAG_PUBKEY: u8 = 0x01;
+
+/// The `tx_extra` tag of the extra nonce.
+const TX_EXTRA_NONCE: u8 = 0x02;
+
+/// The maximum amount of times the miner transaction is rebuilt to settle its weight.
+const MAX_MINER_TX_REBUILDS: usize = 8;
+
+/// Create a [`BlockTemplate`] on top of the main chain.
+///
+/// The block's miner transaction pays `address` and contains `extra_nonce`,
+/// [`BlockTemplate::reserved_offset`] is the offset of `extra_nonce` in the block blob,
+/// `0` if `extra_nonce` is empty.
+///
+/// # Errors
+/// This returns an error if a service fails or the miner transaction could not be built.
+pub async fn create_block_template(
+    blockchain_read_handle: &mut BlockchainReadHandle,
+    blockchain_context_service: &mut BlockchainContextService,
+    txpool_read_handle: &mut TxpoolReadHandle,
+    address: &MoneroAddress,
+    extra_nonce: &[u8],
+) -> Result<BlockTemplate, anyhow::Error> {
+    let context = blockchain_context_service.blockchain_context().clone();
+    let hf = context.current_hf;
+    let height = context.chain_height;
+
+    let TxpoolReadResponse::Backlog(backlog) = txpool_read_handle
+        .ready()
+        .await
+        .map_err(|e| anyhow!(e))?
+        .call(TxpoolReadRequest::Backlog)
+        .await
+        .map_err(|e| anyhow!(e))?
+    else {
+        unreachable!()
+    };
+
+    let selected = select_txs(
+        backlog,
+        context.median_weight_for_block_reward,
+        context.effective_median_weight,
+        context.already_generated_coins,
+        hf,
+    );
+
+    // Transactions could have left the pool since the backlog was taken.
+    let TxpoolReadResponse::TxsForBlock { txs, .. } = txpool_read_handle
+        .ready()
+        .await
+        .map_err(|e| anyhow!(e))?
+        .call(TxpoolReadRequest::TxsForBlock(
+            selected.iter().map(|tx| tx.id).collect(),
+        ))
+        .await
+        .map_err(|e| anyhow!(e))?
+    else {
+        unreachable!()
+    };
+
+    let transactions = selected
+        .iter()
+        .map(|tx| tx.id)
+        .filter(|tx_hash| txs.contains_key(tx_hash))
+        .collect::<Vec<_>>();
+    let txs_weight = txs.values().map(|tx| tx.tx_weight).sum::<usize>();
+    let fees = txs.values().map(|tx| tx.fee).sum::<u64>();
+
+    let (miner_transaction, expected_reward) = miner_transaction(
+        address,
+        extra_nonce,
+        height,
+        txs_weight,
+        fees,
+        context.median_weight_for_block_reward,
+        context.already_generated_coins,
+        hf,
+    )?;
+
+    let tx_pubkey = miner_tx_pubkey(&miner_transaction);
+
+    let block = Block {
+        header: BlockHeader {
+            hardfork_version: hf.as_u8(),
+            hardfork_signal: HardFork::LATEST.as_u8(),
+            timestamp: max(
+                current_unix_timestamp(),
+                context.median_block_timestamp.unwrap_or(0),
+            ),
+            previous: context.top_hash,
+            nonce: 0,
+        },
+        miner_transaction,
+        transactions,
+    };
+
+    let reserved_offset = if extra_nonce.is_empty() {
+        0
+    } else {
+        extra_nonce_offset(&block.serialize(), &tx_pubkey, extra_nonce.len())?
+    };
+
+    let (seed_height, seed_hash, next_seed_hash) = if hf >= HardFork::V12 {
+        let seed_height = randomx_seed_height(height);
+        let next_seed_height = randomx_seed_height(height + RX_SEEDHASH_EPOCH_LAG);
+
+        let seed_hash = block_hash(blockchain_read_handle, seed_height).await?;
+        let next_seed_hash = if next_seed_height == seed_height {
+            [0; 32]
+        } else {
+            block_hash(blockchain_read_handle, next_seed_height).await?
+        };
+
+        (seed_height, seed_hash, next_seed_hash)
+    } else {
+        (0, [0; 32], [0; 32])
+    };
+
+    Ok(BlockTemplate {
+        block,
+        reserved_offset,
+        difficulty: context.next_difficulty,
+        height: usize_to_u64(height),
+        expected_reward,
+        seed_height: usize_to_u64(seed_height),
+        seed_hash,
+        next_seed_hash,
+    })
+}
+
+/// Returns the RandomX seed hash of the block at `height`, the height of the next block.
+///
+/// This returns all `0`s before [`HardFork::V12`].
+///
+/// # Errors
+/// This returns an error if the blockchain service fails.
+pub async fn randomx_seed_hash(
+    blockchain_read_handle: &mut BlockchainReadHandle,
+    height: usize,
+    hf: HardFork,
+) -> Result<[u8; 32], anyhow::Error> {
+    if hf < HardFork::V12 {
+        return Ok([0; 32]);
+    }
+
+    block_hash(blockchain_read_handle, randomx_seed_height(height)).await
+}
+
+/// Returns the hash of the main chain block at `height`.
+async fn block_hash(
+    blockchain_read_handle: &mut BlockchainReadHandle,
+    height: usize,
+) -> Result<[u8; 32], anyhow::Error> {
+    let BlockchainResponse::BlockHash(hash) = blockchain_read_handle
+        .ready()
+        .await?
+        .call(BlockchainReadRequest::BlockHash(height, Chain::Main))
+        .await?
+    else {
+        unreachable!()
+    };
+
+    Ok(hash)
+}
+
+/// Select the transactions to put in a block from the pool's `backlog`.
+///
+/// Transactions are taken by highest fee-per-byte, a transaction that pushes the block
+/// over the median weight is only taken if its fee makes up for the reduced block reward.
+fn select_txs(
+    mut backlog: Vec<TxEntry>,
+    median_weight_for_block_reward: usize,
+    effective_median_weight: usize,
+    already_generated_coins: u64,
+    hf: HardFork,
+) -> Vec<TxEntry> {
+    let median_weight = min(median_weight_for_block_reward, effective_median_weight);
+    let max_total_weight = (2 * median_weight).saturating_sub(COINBASE_BLOB_RESERVED_SIZE);
+
+    backlog.sort_unstable_by(|a, b| cmp_fee_per_byte(b, a));
+
+    let mut total_weight = 0;
+    let mut fees = 0_u64;
+    let mut best_coinbase = calculate_block_reward(
+        COINBASE_BLOB_RESERVED_SIZE,
+        median_weight_for_block_reward,
+        already_generated_coins,
+        hf,
+    );
+
+    backlog
+        .into_iter()
+        .filter(|tx| {
+            let weight = u64_to_usize(tx.weight);
+
+            if total_weight + weight > max_total_weight {
+                return false;
+            }
+
+            let coinbase = calculate_block_reward(
+                total_weight + weight,
+                median_weight_for_block_reward,
+                already_generated_coins,
+                hf,
+            ) + fees
+                + tx.fee;
+
+            if coinbase < best_coinbase {
+                return false;
+            }
+
+            best_coinbase = coinbase;
+            total_weight += weight;
+            fees += tx.fee;
+            true
+        })
+        .collect()
+}
+
+/// Compares the fee-per-byte of 2 transactions.
+fn cmp_fee_per_byte(a: &TxEntry, b: &TxEntry) -> Ordering {
+    // a.fee / a.weight <=> b.fee / b.weight, without the division.
+    (u128::from(a.fee) * u128::from(b.weight)).cmp(&(u128::from(b.fee) * u128::from(a.weight)))
+}
+
+/// Build the miner transaction of a block paying `address`, returning it and the amount it pays.
+///
+/// The transaction is rebuilt until the reward matches the block weight, as the reward changes the
+/// weight of the miner transaction.
+#[expect(clippy::too_many_arguments)]
+fn miner_transaction(
+    address: &MoneroAddress,
+    extra_nonce: &[u8],
+    height: usize,
+    txs_weight: usize,
+    fees: u64,
+    median_weight_for_block_reward: usize,
+    already_generated_coins: u64,
+    hf: HardFork,
+) -> Result<(Transaction, u64), anyhow::Error> {
+    let mut tx_key_bytes = [0; 64];
+    rand::thread_rng().fill_bytes(&mut tx_key_bytes);
+    let tx_key = Scalar::from_bytes_mod_order_wide(&tx_key_bytes);
+
+    let mut miner_tx_weight = 0;
+
+    for _ in 0..MAX_MINER_TX_REBUILDS {
+        let reward = calculate_block_reward(
+            txs_weight + miner_tx_weight,
+            median_weight_for_block_reward,
+            already_generated_coins,
+            hf,
+        ) + fees;
+
+        let miner_tx = build_miner_transaction(address, &tx_key, extra_nonce, height, reward, hf);
+
+        let weight = miner_tx.weight();
+        if weight == miner_tx_weight {
+            return Ok((miner_tx, reward));
+        }
+
+        miner_tx_weight = weight;
+    }
+
+    Err(anyhow!("Failed to settle the miner transaction weight"))
+}
+
+/// Build a miner transaction paying `reward` to `address`.
+fn build_miner_transaction(
+    address: &MoneroAddress,
+    tx_key: &Scalar,
+    extra_nonce: &[u8],
+    height: usize,
+    reward: u64,
+    hf: HardFork,
+) -> Transaction {
+    // The one-time output key: `Hs(8rA || 0)G + B`.
+    let derivation = (tx_key * address.view())
+        .mul_by_cofactor()
+        .compress()
+        .to_bytes();
+    let output_index = 0_u8;
+
+    let shared_key = keccak256_to_scalar([derivation.as_slice(), &[output_index]].concat());
+    let key = (EdwardsPoint::mul_base(&shared_key) + address.spend()).compress();
+
+    let view_tag = (hf >= HardFork::V15)
+        .then(|| keccak256([b"view_tag".as_slice(), &derivation, &[output_index]].concat())[0]);
+
+    let mut extra = vec![TX_EXTRA_TAG_PUBKEY];
+    extra.extend_from_slice(&EdwardsPoint::mul_base(tx_key).compress().to_bytes());
+
+    if !extra_nonce.is_empty() {
+        extra.push(TX_EXTRA_NONCE);
+        write_varint(&extra_nonce.len(), &mut extra).unwrap();
+        extra.extend_from_slice(extra_nonce);
+    }
+
+    let prefix = TransactionPrefix {
+        additional_timelock: Timelock::Block(height + MINER_TX_LOCK_BLOCKS),
+        inputs: vec![Input::Gen(height)],
+        outputs: vec![Output {
+            amount: Some(reward),
+            key,
+            view_tag,
+        }],
+        extra,
+    };
+
+    if hf >= HardFork::V12 {
+        Transaction::V2 {
+            prefix,
+            proofs: None,
+        }
+    } else {
+        Transaction::V1 {
+            prefix,
+            signatures: vec![],
+        }
+    }
+}
+
+/// Returns the transaction public key in the `tx_extra` of a miner transaction built by [`build_miner_transaction`].
+fn miner_tx_pubkey(miner_tx: &Transaction) -> [u8; 32] {
+    miner_tx.prefix().extra[1..33].try_into().unwrap()
+}
+
+/// Returns the offset of the extra nonce in `block_blob`.
+///
+/// The extra nonce directly follows the transaction public key in the miner transaction's `tx_extra`.
+fn extra_nonce_offset(
+    block_blob: &[u8],
+    tx_pubkey: &[u8; 32],
+    extra_nonce_len: usize,
+) -> Result<u64, anyhow::Error> {
+    let pubkey_offset = block_blob
+        .windows(tx_pubkey.len())
+        .position(|window| window == tx_pubkey)
+        .ok_or_else(|| anyhow!("Failed to find the transaction public key in the block"))?;
+
+    let mut varint = Vec::new();
+    write_varint(&extra_nonce_len, &mut varint).unwrap();
+
+    Ok(usize_to_u64(
+        pubkey_offset + tx_pubkey.len() + 1 + varint.len(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use cuprate_consensus_rules::miner_tx::check_miner_tx;
+
+    use super::*;
+
+    /// The median weight used in the tests.
+    const MEDIAN: usize = 300_000;
+
+    fn tx(id: u8, weight: u64, fee: u64) -> TxEntry {
+        TxEntry {
+            id: [id; 32],
+            weight,
+            fee,
+            time_in_pool: Duration::ZERO,
+        }
+    }
+
+    /// Transactions are selected by fee-per-byte.
+    #[test]
+    fn select_txs_by_fee_per_byte() {
+        let backlog = vec![
+            tx(1, 1_000, 1_000),
+            tx(2, 1_000, 3_000),
+            tx(3, 2_000, 4_000),
+        ];
+
+        let selected = select_txs(backlog, MEDIAN, MEDIAN, 0, HardFork::V16);
+
+        let ids = selected.iter().map(|tx| tx.id[0]).collect::<Vec<_>>();
+        assert_eq!(ids, [2, 3, 1]);
+    }
+
+    /// Transactions over the median are skipped if they don't pay for the penalty.
+    #[test]
+    fn select_txs_penalty() {
+        let backlog = vec![tx(1, 290_000, 1_000_000), tx(2, 100_000, 10)];
+
+        let selected = select_txs(backlog, MEDIAN, MEDIAN, 0, HardFork::V16);
+
+        let ids = selected.iter().map(|tx| tx.id[0]).collect::<Vec<_>>();
+        assert_eq!(ids, [1]);
+    }
+
+    /// The built miner transaction passes consensus and contains the extra nonce at the returned offset.
+    #[test]
+    fn miner_transaction_valid() {
+        let address = MoneroAddress::from_str(
+            monero_address::Network::Mainnet,
+            "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A",
+        )
+        .unwrap();
+        let extra_nonce = [0; 8];
+
+        let (miner_tx, reward) =
+            miner_transaction(&address, &extra_nonce, 100, 0, 5, MEDIAN, 0, HardFork::V16).unwrap();
+
+        check_miner_tx(
+            &miner_tx,
+            5,
+            100,
+            miner_tx.weight(),
+            MEDIAN,
+            0,
+            HardFork::V16,
+        )
+        .unwrap();
+        assert_eq!(miner_tx.prefix().outputs[0].amount, Some(reward));
+
+        let blob = miner_tx.serialize();
+        let offset =
+            extra_nonce_offset(&blob, &miner_tx_pubkey(&miner_tx), extra_nonce.len()).unwrap();
+        assert_eq!(blob[u64_to_usize(offset) - 2], TX_EXTRA_NONCE);
+        assert_eq!(
+            &blob[u64_to_usize(offset)..u64_to_usize(offset) + extra_nonce.len()],
+            &extra_nonce
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! Blockchain bootstrap files.
+//!
+//! Exporting and importing the blockchain with `monerod`'s `blockchain.raw` format.
+mod export;
+mod file;
+mod import;
+
+pub use export::export_blockchain;
+pub use import::BlockchainImporter;
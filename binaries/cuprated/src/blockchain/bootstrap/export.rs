@@ -0,0 +1,103 @@
+//! Blockchain export.
+use std::{cmp::min, fs::File, io::BufWriter, path::Path};
+
+use anyhow::bail;
+use tower::{Service, ServiceExt};
+use tracing::info;
+
+use cuprate_blockchain::service::BlockchainReadHandle;
+use cuprate_types::{
+    blockchain::{BlockchainReadRequest, BlockchainResponse},
+    Chain,
+};
+
+use crate::{
+    blockchain::bootstrap::file::BootstrapWriter, constants::PANIC_CRITICAL_SERVICE_ERROR,
+};
+
+/// The amount of blocks to read from the database at once.
+const EXPORT_BATCH_LEN: usize = 100;
+
+/// Export the main chain to a bootstrap file at `path`.
+///
+/// All blocks below `stop_height` are exported, or the whole chain if [`None`].
+///
+/// # Errors
+///
+/// This returns an error if the file could not be written or if the blockchain is pruned.
+pub async fn export_blockchain(
+    path: &Path,
+    stop_height: Option<usize>,
+    mut blockchain_read_handle: BlockchainReadHandle,
+) -> Result<(), anyhow::Error> {
+    let BlockchainResponse::ChainHeight(chain_height, _) = blockchain_read_handle
+        .ready()
+        .await
+        .expect(PANIC_CRITICAL_SERVICE_ERROR)
+        .call(BlockchainReadRequest::ChainHeight)
+        .await?
+    else {
+        unreachable!();
+    };
+
+    let stop_height = stop_height.map_or(chain_height, |height| min(height, chain_height));
+
+    info!("Exporting {stop_height} blocks to {}", path.display());
+
+    let mut writer = BootstrapWriter::new(BufWriter::new(File::create(path)?))?;
+
+    for start_height in (0..stop_height).step_by(EXPORT_BATCH_LEN) {
+        let heights = start_height..min(start_height + EXPORT_BATCH_LEN, stop_height);
+
+        let BlockchainResponse::BlockCompleteEntriesByHeight(entries) = blockchain_read_handle
+            .ready()
+            .await
+            .expect(PANIC_CRITICAL_SERVICE_ERROR)
+            .call(BlockchainReadRequest::BlockCompleteEntriesByHeight(
+                heights.clone().collect(),
+            ))
+            .await?
+        else {
+            unreachable!();
+        };
+
+        let BlockchainResponse::BlockExtendedHeaderInRange(headers) = blockchain_read_handle
+            .ready()
+            .await
+            .expect(PANIC_CRITICAL_SERVICE_ERROR)
+            .call(BlockchainReadRequest::BlockExtendedHeaderInRange(
+                heights.clone(),
+                Chain::Main,
+            ))
+            .await?
+        else {
+            unreachable!();
+        };
+
+        for ((entry, header), height) in entries.into_iter().zip(headers).zip(heights) {
+            let Some(txs) = entry.txs.take_normal() else {
+                bail!("Block {height} is pruned, a pruned blockchain can not be exported");
+            };
+
+            writer.write_block(
+                &entry.block,
+                &txs,
+                header.block_weight,
+                header.cumulative_difficulty,
+                header
+                    .cumulative_generated_coins
+                    .expect("main chain headers have the generated coins"),
+            )?;
+        }
+
+        if (start_height + EXPORT_BATCH_LEN) % 10_000 == 0 {
+            info!(height = start_height + EXPORT_BATCH_LEN, "Exported blocks");
+        }
+    }
+
+    writer.finish()?;
+
+    info!("Exported {stop_height} blocks to {}", path.display());
+
+    Ok(())
+}
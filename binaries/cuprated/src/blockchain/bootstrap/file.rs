@@ -0,0 +1,338 @@
+//! `monerod`'s `blockchain.raw` bootstrap file format.
+//!
+//! The file starts with a magic number and a header padded to [`HEADER_SIZE`] bytes,
+//! this is followed by a chunk for each block, starting from genesis.
+//!
+//! A chunk is a little-endian `u32` length followed by a block package:
+//! - the block blob
+//! - the amount of transactions (varint) followed by the transaction blobs
+//! - the block weight (varint)
+//! - the cumulative difficulty, as the high then low 64 bits (varints)
+//! - the total generated coins (varint)
+use std::io::{self, Read, Write};
+
+use monero_serai::{
+    block::Block,
+    io::{read_varint, write_varint},
+    transaction::Transaction,
+};
+
+use cuprate_helper::cast::u32_to_usize;
+
+/// The magic number at the start of the file.
+const BLOCKCHAIN_RAW_MAGIC: u32 = 0x2872_1586;
+
+/// The size of the header after the magic number.
+const HEADER_SIZE: u32 = 1024;
+
+/// The header's major version we write, version 0 stored the cumulative difficulty as one varint.
+const MAJOR_VERSION: u8 = 1;
+
+/// The maximum size of a chunk we will read.
+const MAX_CHUNK_SIZE: u32 = 128 * 1024 * 1024;
+
+/// A block read from a bootstrap file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockPackage {
+    /// The block.
+    pub block: Block,
+    /// The block's transactions, in the same order as [`Block::transactions`].
+    pub txs: Vec<Transaction>,
+    /// The block's weight.
+    pub block_weight: usize,
+    /// The cumulative difficulty of the chain up to and including this block.
+    pub cumulative_difficulty: u128,
+    /// The total amount of generated coins up to and including this block.
+    pub generated_coins: u64,
+}
+
+/// Writes blocks to a bootstrap file.
+pub struct BootstrapWriter<W> {
+    writer: W,
+    /// A buffer for the current chunk.
+    chunk: Vec<u8>,
+}
+
+impl<W: Write> BootstrapWriter<W> {
+    /// Create a new [`BootstrapWriter`], this writes the file header.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&BLOCKCHAIN_RAW_MAGIC.to_le_bytes())?;
+
+        let mut header = Vec::with_capacity(u32_to_usize(HEADER_SIZE));
+
+        // The file info: the major & minor version and the header size.
+        let mut file_info = vec![MAJOR_VERSION, 0];
+        write_varint(&HEADER_SIZE, &mut file_info)?;
+
+        // The blocks info: the first & last block and the position of the last block.
+        // `monerod` always writes zeros here.
+        let blocks_info = [0_u8; 3];
+
+        for info in [file_info.as_slice(), &blocks_info] {
+            header.extend_from_slice(&u32::try_from(info.len()).unwrap().to_le_bytes());
+            header.extend_from_slice(info);
+        }
+
+        header.resize(u32_to_usize(HEADER_SIZE), 0);
+        writer.write_all(&header)?;
+
+        Ok(Self {
+            writer,
+            chunk: Vec::new(),
+        })
+    }
+
+    /// Write a block, `txs` must be the unpruned transaction blobs in the order of the block's transactions.
+    pub fn write_block(
+        &mut self,
+        block: &[u8],
+        txs: &[impl AsRef<[u8]>],
+        block_weight: usize,
+        cumulative_difficulty: u128,
+        generated_coins: u64,
+    ) -> io::Result<()> {
+        self.chunk.clear();
+
+        self.chunk.extend_from_slice(block);
+        write_varint(&txs.len(), &mut self.chunk)?;
+        for tx in txs {
+            self.chunk.extend_from_slice(tx.as_ref());
+        }
+
+        write_varint(&block_weight, &mut self.chunk)?;
+        let high = u64::try_from(cumulative_difficulty >> 64).unwrap();
+        let low = u64::try_from(cumulative_difficulty & u128::from(u64::MAX)).unwrap();
+        write_varint(&high, &mut self.chunk)?;
+        write_varint(&low, &mut self.chunk)?;
+        write_varint(&generated_coins, &mut self.chunk)?;
+
+        let len = u32::try_from(self.chunk.len())
+            .ok()
+            .filter(|len| *len <= MAX_CHUNK_SIZE)
+            .ok_or_else(|| invalid_data("block is too large"))?;
+
+        self.writer.write_all(&len.to_le_bytes())?;
+        self.writer.write_all(&self.chunk)
+    }
+
+    /// Flush the file and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads blocks from a bootstrap file.
+pub struct BootstrapReader<R> {
+    reader: R,
+    /// The header's major version.
+    major_version: u8,
+    /// A buffer for the current chunk.
+    chunk: Vec<u8>,
+}
+
+impl<R: Read> BootstrapReader<R> {
+    /// Create a new [`BootstrapReader`], this reads and checks the file header.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        if read_u32(&mut reader)? != BLOCKCHAIN_RAW_MAGIC {
+            return Err(invalid_data("not a blockchain.raw file"));
+        }
+
+        // The header is at least the file info's length, major & minor version and header size.
+        let mut header = [0; 8];
+        reader.read_exact(&mut header)?;
+
+        let major_version = header[4];
+        if major_version > MAJOR_VERSION {
+            return Err(invalid_data("unsupported blockchain.raw version"));
+        }
+
+        let header_size = read_varint::<_, u32>(&mut &header[6..])
+            .ok()
+            .filter(|size| (8..=MAX_CHUNK_SIZE).contains(size))
+            .ok_or_else(|| invalid_data("invalid blockchain.raw header"))?;
+
+        // Skip the rest of the header.
+        io::copy(
+            &mut (&mut reader).take(u64::from(header_size) - 8),
+            &mut io::sink(),
+        )?;
+
+        Ok(Self {
+            reader,
+            major_version,
+            chunk: Vec::new(),
+        })
+    }
+
+    /// Read the next chunk into the buffer, returns `false` at the end of the file.
+    fn next_chunk(&mut self) -> io::Result<bool> {
+        let mut len = [0; 4];
+        match self.reader.read_exact(&mut len) {
+            Ok(()) => (),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+            Err(e) => return Err(e),
+        }
+
+        let len = u32::from_le_bytes(len);
+        if len > MAX_CHUNK_SIZE {
+            return Err(invalid_data("block is too large"));
+        }
+
+        self.chunk.resize(u32_to_usize(len), 0);
+        self.reader.read_exact(&mut self.chunk)?;
+
+        Ok(true)
+    }
+
+    /// Skip the next block, returns `false` at the end of the file.
+    pub fn skip_block(&mut self) -> io::Result<bool> {
+        self.next_chunk()
+    }
+
+    /// Read the next block, returns [`None`] at the end of the file.
+    pub fn next_block(&mut self) -> io::Result<Option<BlockPackage>> {
+        if !self.next_chunk()? {
+            return Ok(None);
+        }
+
+        let r = &mut self.chunk.as_slice();
+
+        let block = Block::read(r)?;
+        let txs = (0..read_varint::<_, usize>(r)?)
+            .map(|_| Transaction::read(r))
+            .collect::<io::Result<_>>()?;
+        let block_weight = read_varint(r)?;
+
+        let cumulative_difficulty = if self.major_version == 0 {
+            u128::from(read_varint::<_, u64>(r)?)
+        } else {
+            let high = read_varint::<_, u64>(r)?;
+            let low = read_varint::<_, u64>(r)?;
+            (u128::from(high) << 64) | u128::from(low)
+        };
+
+        let generated_coins = read_varint(r)?;
+
+        if !r.is_empty() {
+            return Err(invalid_data("unexpected data after block"));
+        }
+
+        Ok(Some(BlockPackage {
+            block,
+            txs,
+            block_weight,
+            cumulative_difficulty,
+            generated_coins,
+        }))
+    }
+}
+
+/// Read a little-endian [`u32`].
+fn read_u32(r: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    r.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Returns an [`io::ErrorKind::InvalidData`] error.
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use cuprate_consensus::generate_genesis_block;
+    use cuprate_helper::network::Network;
+
+    use super::*;
+
+    /// Blocks written with [`BootstrapWriter`] are read back with [`BootstrapReader`].
+    #[test]
+    fn round_trip() {
+        let genesis = generate_genesis_block(Network::Mainnet);
+        // Any transaction will do, the reader does not check it is in the block.
+        let tx = generate_genesis_block(Network::Stagenet).miner_transaction;
+
+        let mut writer = BootstrapWriter::new(Vec::new()).unwrap();
+        writer
+            .write_block(&genesis.serialize(), &[] as &[&[u8]], 80, 1, 100)
+            .unwrap();
+        writer
+            .write_block(
+                &genesis.serialize(),
+                &[tx.serialize()],
+                1_000,
+                u128::MAX,
+                u64::MAX,
+            )
+            .unwrap();
+        let file = writer.finish().unwrap();
+
+        assert_eq!(file[..4], BLOCKCHAIN_RAW_MAGIC.to_le_bytes());
+        // The first chunk starts right after the header.
+        let first_chunk = 4 + u32_to_usize(HEADER_SIZE);
+        assert_eq!(
+            file[first_chunk..first_chunk + 4],
+            u32::try_from(genesis.serialize().len() + 5)
+                .unwrap()
+                .to_le_bytes()
+        );
+
+        let mut reader = BootstrapReader::new(file.as_slice()).unwrap();
+
+        assert_eq!(
+            reader.next_block().unwrap().unwrap(),
+            BlockPackage {
+                block: genesis.clone(),
+                txs: vec![],
+                block_weight: 80,
+                cumulative_difficulty: 1,
+                generated_coins: 100,
+            }
+        );
+        assert_eq!(
+            reader.next_block().unwrap().unwrap(),
+            BlockPackage {
+                block: genesis,
+                txs: vec![tx],
+                block_weight: 1_000,
+                cumulative_difficulty: u128::MAX,
+                generated_coins: u64::MAX,
+            }
+        );
+        assert!(reader.next_block().unwrap().is_none());
+    }
+
+    #[test]
+    fn skip_block() {
+        let genesis = generate_genesis_block(Network::Mainnet);
+
+        let mut writer = BootstrapWriter::new(Vec::new()).unwrap();
+        for generated_coins in 0..3 {
+            writer
+                .write_block(&genesis.serialize(), &[] as &[&[u8]], 0, 0, generated_coins)
+                .unwrap();
+        }
+        let file = writer.finish().unwrap();
+
+        let mut reader = BootstrapReader::new(file.as_slice()).unwrap();
+        assert!(reader.skip_block().unwrap());
+        assert!(reader.skip_block().unwrap());
+        assert_eq!(reader.next_block().unwrap().unwrap().generated_coins, 2);
+        assert!(!reader.skip_block().unwrap());
+    }
+
+    #[test]
+    fn invalid_file() {
+        assert!(BootstrapReader::new([0_u8; 1028].as_slice()).is_err());
+
+        // A truncated block.
+        let mut file = BootstrapWriter::new(Vec::new()).unwrap().finish().unwrap();
+        file.extend_from_slice(&100_u32.to_le_bytes());
+        file.extend_from_slice(&[0; 50]);
+
+        let mut reader = BootstrapReader::new(file.as_slice()).unwrap();
+        assert!(reader.next_block().is_err());
+    }
+}
@@ -0,0 +1,503 @@
+//! Blockchain import.
+use std::{fs::File, io::BufReader, path::Path};
+
+use anyhow::{ensure, Context};
+use monero_serai::{
+    block::Block,
+    transaction::{Input, Transaction},
+};
+use rayon::prelude::*;
+use tower::{BoxError, Service, ServiceExt};
+use tracing::info;
+
+use cuprate_blockchain::service::{BlockchainReadHandle, BlockchainWriteHandle};
+use cuprate_consensus::{
+    block::{batch_prepare_main_chain_blocks, verify_prepped_main_chain_block},
+    BlockChainContextRequest, BlockchainContextService,
+};
+use cuprate_consensus_context::NewBlockData;
+use cuprate_fast_sync::{
+    block_to_verified_block_information, fast_sync_stop_height, validate_block_hashes,
+    FAST_SYNC_BATCH_LEN,
+};
+use cuprate_txpool::service::{interface::TxpoolWriteRequest, TxpoolWriteHandle};
+use cuprate_types::{
+    blockchain::{BlockchainReadRequest, BlockchainResponse, BlockchainWriteRequest},
+    Chain, HardFork, VerifiedBlockInformation,
+};
+
+use crate::{
+    blockchain::{bootstrap::file::BootstrapReader, types::ConsensusBlockchainReadHandle},
+    constants::PANIC_CRITICAL_SERVICE_ERROR,
+};
+
+/// Imports blocks from a bootstrap file into the blockchain.
+pub struct BlockchainImporter {
+    /// The amount of blocks to verify at once, after the fast-sync hashes.
+    batch_size: usize,
+    blockchain_read_handle: BlockchainReadHandle,
+    blockchain_write_handle: BlockchainWriteHandle,
+    txpool_write_handle: TxpoolWriteHandle,
+    blockchain_context_service: BlockchainContextService,
+    /// Blocks under this height are checked against the fast-sync hashes instead of being fully verified.
+    fast_sync_stop_height: usize,
+    /// Checks sequential block hashes against the fast-sync hashes, see [`validate_block_hashes`].
+    validate_block_hashes: fn(usize, &[[u8; 32]]) -> bool,
+}
+
+impl BlockchainImporter {
+    /// Create a new [`BlockchainImporter`].
+    ///
+    /// # Panics
+    ///
+    /// This will panic if the fast-sync hashes have not been set.
+    pub fn new(
+        batch_size: usize,
+        blockchain_read_handle: BlockchainReadHandle,
+        blockchain_write_handle: BlockchainWriteHandle,
+        txpool_write_handle: TxpoolWriteHandle,
+        blockchain_context_service: BlockchainContextService,
+    ) -> Self {
+        Self {
+            batch_size,
+            blockchain_read_handle,
+            blockchain_write_handle,
+            txpool_write_handle,
+            blockchain_context_service,
+            fast_sync_stop_height: fast_sync_stop_height(),
+            validate_block_hashes,
+        }
+    }
+
+    /// Import the blocks in the bootstrap file at `path`.
+    ///
+    /// The file must start at genesis, blocks we already have are skipped so an interrupted
+    /// import can be resumed. Blocks under the fast-sync height are checked against the fast-sync
+    /// hashes, all other blocks are fully verified.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the file could not be read or contains an invalid block,
+    /// all blocks before the invalid one will have been imported.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if any internal service returns an unexpected error that we cannot
+    /// recover from.
+    pub async fn import(mut self, path: &Path) -> Result<(), anyhow::Error> {
+        let mut reader = BootstrapReader::new(BufReader::new(File::open(path)?))?;
+
+        let start_height = self.chain_height();
+        for _ in 0..start_height {
+            if !reader.skip_block()? {
+                info!("No blocks to import, the blockchain is already at height {start_height}");
+                return Ok(());
+            }
+        }
+
+        info!(
+            "Importing blocks from {}, starting at height {start_height}",
+            path.display()
+        );
+
+        loop {
+            let height = self.chain_height();
+
+            // Under the fast-sync height batches must end on a fast-sync hash.
+            let fast_sync = height < self.fast_sync_stop_height;
+            let batch_len = if fast_sync {
+                FAST_SYNC_BATCH_LEN - height % FAST_SYNC_BATCH_LEN
+            } else {
+                self.batch_size
+            };
+
+            let mut blocks = Vec::with_capacity(batch_len);
+            while blocks.len() < batch_len {
+                let Some(package) = reader.next_block()? else {
+                    break;
+                };
+
+                blocks.push((package.block, package.txs));
+            }
+
+            if blocks.is_empty() {
+                break;
+            }
+
+            ensure!(
+                blocks.par_iter().all(|(block, txs)| txs
+                    .iter()
+                    .map(Transaction::hash)
+                    .eq(block.transactions.iter().copied())),
+                "A block after height {height} does not have the correct transactions"
+            );
+
+            // If the file ends before the next fast-sync hash the blocks are fully verified instead.
+            if fast_sync && blocks.len() == batch_len {
+                self.import_fast_sync(height, blocks).await?;
+            } else {
+                self.import_verified(blocks).await?;
+            }
+
+            info!(height = self.chain_height(), "Imported blocks");
+        }
+
+        info!("Import finished at height {}", self.chain_height());
+
+        Ok(())
+    }
+
+    /// Returns the current chain height.
+    fn chain_height(&mut self) -> usize {
+        self.blockchain_context_service
+            .blockchain_context()
+            .chain_height
+    }
+
+    /// Imports blocks that end on a fast-sync hash, after checking them against the fast-sync hashes.
+    async fn import_fast_sync(
+        &mut self,
+        height: usize,
+        blocks: Vec<(Block, Vec<Transaction>)>,
+    ) -> Result<(), anyhow::Error> {
+        let batch_start = height - height % FAST_SYNC_BATCH_LEN;
+
+        let BlockchainResponse::BlockHashInRange(mut hashes) = self
+            .blockchain_read_handle
+            .ready()
+            .await
+            .expect(PANIC_CRITICAL_SERVICE_ERROR)
+            .call(BlockchainReadRequest::BlockHashInRange(
+                batch_start..height,
+                Chain::Main,
+            ))
+            .await
+            .expect(PANIC_CRITICAL_SERVICE_ERROR)
+        else {
+            unreachable!();
+        };
+
+        hashes.par_extend(blocks.par_iter().map(|(block, _)| block.hash()));
+
+        ensure!(
+            (self.validate_block_hashes)(batch_start, &hashes),
+            "The blocks after height {height} do not match the fast-sync hashes"
+        );
+
+        let mut verified_blocks = Vec::with_capacity(blocks.len());
+        for (block, txs) in blocks {
+            let block = block_to_verified_block_information(
+                block,
+                txs,
+                self.blockchain_context_service.blockchain_context(),
+            );
+            self.add_block_to_context_cache(&block).await;
+
+            verified_blocks.push(block);
+        }
+
+        let spent_key_images = spent_key_images(&verified_blocks);
+
+        self.blockchain_write_handle
+            .ready()
+            .await
+            .expect(PANIC_CRITICAL_SERVICE_ERROR)
+            .call(BlockchainWriteRequest::BatchWriteBlocks(verified_blocks))
+            .await
+            .expect(PANIC_CRITICAL_SERVICE_ERROR);
+
+        self.remove_spent_txpool_txs(spent_key_images).await;
+
+        Ok(())
+    }
+
+    /// Fully verifies and imports blocks.
+    async fn import_verified(
+        &mut self,
+        blocks: Vec<(Block, Vec<Transaction>)>,
+    ) -> Result<(), anyhow::Error> {
+        let read_handle =
+            ConsensusBlockchainReadHandle::new(self.blockchain_read_handle.clone(), BoxError::from);
+
+        let (prepped_blocks, mut output_cache) = batch_prepare_main_chain_blocks(
+            blocks,
+            &mut self.blockchain_context_service,
+            read_handle.clone(),
+        )
+        .await
+        .context("Invalid block")?;
+
+        for (block, txs) in prepped_blocks {
+            let height = self.chain_height();
+
+            let block = verify_prepped_main_chain_block(
+                block,
+                txs,
+                &mut self.blockchain_context_service,
+                read_handle.clone(),
+                Some(&mut output_cache),
+            )
+            .await
+            .with_context(|| format!("Invalid block at height {height}"))?;
+
+            self.add_block_to_context_cache(&block).await;
+
+            let spent_key_images = spent_key_images(std::slice::from_ref(&block));
+
+            self.blockchain_write_handle
+                .ready()
+                .await
+                .expect(PANIC_CRITICAL_SERVICE_ERROR)
+                .call(BlockchainWriteRequest::WriteBlock(block))
+                .await
+                .expect(PANIC_CRITICAL_SERVICE_ERROR);
+
+            self.remove_spent_txpool_txs(spent_key_images).await;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a [`VerifiedBlockInformation`] to the blockchain context cache.
+    async fn add_block_to_context_cache(&mut self, block: &VerifiedBlockInformation) {
+        self.blockchain_context_service
+            .ready()
+            .await
+            .expect(PANIC_CRITICAL_SERVICE_ERROR)
+            .call(BlockChainContextRequest::Update(NewBlockData {
+                block_hash: block.block_hash,
+                height: block.height,
+                timestamp: block.block.header.timestamp,
+                weight: block.weight,
+                long_term_weight: block.long_term_weight,
+                generated_coins: block.generated_coins,
+                vote: HardFork::from_vote(block.block.header.hardfork_signal),
+                cumulative_difficulty: block.cumulative_difficulty,
+            }))
+            .await
+            .expect(PANIC_CRITICAL_SERVICE_ERROR);
+    }
+
+    /// Removes txs spending these key images from the tx-pool.
+    async fn remove_spent_txpool_txs(&mut self, spent_key_images: Vec<[u8; 32]>) {
+        self.txpool_write_handle
+            .ready()
+            .await
+            .expect(PANIC_CRITICAL_SERVICE_ERROR)
+            .call(TxpoolWriteRequest::NewBlock { spent_key_images })
+            .await
+            .expect(PANIC_CRITICAL_SERVICE_ERROR);
+    }
+}
+
+/// Returns the key images spent in `blocks`.
+fn spent_key_images(blocks: &[VerifiedBlockInformation]) -> Vec<[u8; 32]> {
+    blocks
+        .iter()
+        .flat_map(|block| &block.txs)
+        .flat_map(|tx| &tx.tx.prefix().inputs)
+        .filter_map(|input| match input {
+            Input::ToKey { key_image, .. } => Some(key_image.0),
+            Input::Gen(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::OnceLock};
+
+    use tempfile::tempdir;
+
+    use cuprate_consensus_context::ContextConfig;
+    use cuprate_consensus_rules::{hard_forks::HFInfo, HFsInfo};
+    use cuprate_helper::network::Network;
+
+    use super::*;
+    use crate::blockchain::{
+        bootstrap::export_blockchain, check_add_genesis, manager::tests::generate_block,
+    };
+
+    /// An importer for a chain with a fixed difficulty of 1 and every hard-fork at genesis.
+    ///
+    /// Nothing is under the fast-sync height.
+    async fn mock_importer(data_dir: &Path) -> BlockchainImporter {
+        let blockchain_config = cuprate_blockchain::config::ConfigBuilder::new()
+            .data_directory(data_dir.to_path_buf())
+            .build();
+        let txpool_config = cuprate_txpool::config::ConfigBuilder::new()
+            .data_directory(data_dir.to_path_buf())
+            .build();
+
+        let (mut blockchain_read_handle, mut blockchain_write_handle, _) =
+            cuprate_blockchain::service::init(blockchain_config).unwrap();
+        let (_, txpool_write_handle, _) = cuprate_txpool::service::init(txpool_config).unwrap();
+
+        check_add_genesis(
+            &mut blockchain_read_handle,
+            &mut blockchain_write_handle,
+            Network::Mainnet,
+        )
+        .await;
+
+        let mut context_config = ContextConfig::main_net();
+        context_config.difficulty_cfg.fixed_difficulty = Some(1);
+        context_config.hard_fork_cfg.info = HFsInfo::new([HFInfo::new(0, 0); 16]);
+
+        let blockchain_context_service = cuprate_consensus_context::initialize_blockchain_context(
+            context_config,
+            ConsensusBlockchainReadHandle::new(blockchain_read_handle.clone(), BoxError::from),
+        )
+        .await
+        .unwrap();
+
+        BlockchainImporter {
+            batch_size: 4,
+            blockchain_read_handle,
+            blockchain_write_handle,
+            txpool_write_handle,
+            blockchain_context_service,
+            fast_sync_stop_height: 0,
+            validate_block_hashes: |_, _| false,
+        }
+    }
+
+    /// Adds `n` blocks to the importer's chain, without verifying them.
+    async fn add_blocks(importer: &mut BlockchainImporter, n: usize) {
+        for _ in 0..n {
+            let context = importer.blockchain_context_service.blockchain_context();
+            let block =
+                block_to_verified_block_information(generate_block(context), vec![], context);
+
+            importer.add_block_to_context_cache(&block).await;
+            importer
+                .blockchain_write_handle
+                .ready()
+                .await
+                .unwrap()
+                .call(BlockchainWriteRequest::WriteBlock(block))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn export_import_round_trip() {
+        let (source_dir, dest_dir) = (tempdir().unwrap(), tempdir().unwrap());
+
+        let mut source = mock_importer(source_dir.path()).await;
+        add_blocks(&mut source, 10).await;
+
+        let file = source_dir.path().join("blockchain.raw");
+        export_blockchain(&file, None, source.blockchain_read_handle.clone())
+            .await
+            .unwrap();
+
+        // The blocks are fully verified, in batches of 4.
+        let dest = mock_importer(dest_dir.path()).await;
+        let mut dest_context = dest.blockchain_context_service.clone();
+        let dest_read_handle = dest.blockchain_read_handle.clone();
+        dest.import(&file).await.unwrap();
+
+        assert_eq!(
+            dest_context.blockchain_context(),
+            source.blockchain_context_service.blockchain_context()
+        );
+
+        // Exporting the imported chain gives the same file.
+        let exported = dest_dir.path().join("blockchain.raw");
+        export_blockchain(&exported, None, dest_read_handle)
+            .await
+            .unwrap();
+        assert_eq!(fs::read(file).unwrap(), fs::read(exported).unwrap());
+    }
+
+    #[tokio::test]
+    async fn import_resumes_from_partial_chain() {
+        let (source_dir, dest_dir) = (tempdir().unwrap(), tempdir().unwrap());
+
+        let mut source = mock_importer(source_dir.path()).await;
+        add_blocks(&mut source, 10).await;
+
+        let file = source_dir.path().join("blockchain.raw");
+        export_blockchain(&file, None, source.blockchain_read_handle.clone())
+            .await
+            .unwrap();
+
+        // Give the destination the first 5 blocks after genesis, like an interrupted import.
+        let mut dest = mock_importer(dest_dir.path()).await;
+        let mut reader = BootstrapReader::new(BufReader::new(File::open(&file).unwrap())).unwrap();
+        assert!(reader.skip_block().unwrap());
+
+        let mut blocks = Vec::new();
+        for _ in 0..5 {
+            let package = reader.next_block().unwrap().unwrap();
+            blocks.push((package.block, package.txs));
+        }
+        dest.import_verified(blocks).await.unwrap();
+        assert_eq!(dest.chain_height(), 6);
+
+        let mut dest_context = dest.blockchain_context_service.clone();
+        dest.import(&file).await.unwrap();
+
+        assert_eq!(
+            dest_context.blockchain_context(),
+            source.blockchain_context_service.blockchain_context()
+        );
+    }
+
+    #[tokio::test]
+    async fn fast_sync_batch() {
+        /// The hashes of the first fast-sync batch of the source chain.
+        static BATCH_HASHES: OnceLock<Vec<[u8; 32]>> = OnceLock::new();
+
+        let (source_dir, dest_dir, bad_dest_dir) =
+            (tempdir().unwrap(), tempdir().unwrap(), tempdir().unwrap());
+
+        // One whole fast-sync batch, then 2 blocks that must be verified.
+        let mut source = mock_importer(source_dir.path()).await;
+        add_blocks(&mut source, FAST_SYNC_BATCH_LEN + 1).await;
+
+        let file = source_dir.path().join("blockchain.raw");
+        export_blockchain(&file, None, source.blockchain_read_handle.clone())
+            .await
+            .unwrap();
+
+        let BlockchainResponse::BlockHashInRange(hashes) = source
+            .blockchain_read_handle
+            .ready()
+            .await
+            .unwrap()
+            .call(BlockchainReadRequest::BlockHashInRange(
+                0..FAST_SYNC_BATCH_LEN,
+                Chain::Main,
+            ))
+            .await
+            .unwrap()
+        else {
+            unreachable!();
+        };
+        BATCH_HASHES.set(hashes).unwrap();
+
+        let mut dest = mock_importer(dest_dir.path()).await;
+        dest.fast_sync_stop_height = FAST_SYNC_BATCH_LEN;
+        dest.validate_block_hashes =
+            |start_height, hashes| start_height == 0 && hashes == BATCH_HASHES.get().unwrap();
+
+        let mut dest_context = dest.blockchain_context_service.clone();
+        dest.import(&file).await.unwrap();
+
+        assert_eq!(
+            dest_context.blockchain_context(),
+            source.blockchain_context_service.blockchain_context()
+        );
+
+        // Blocks that do not match the fast-sync hashes are not imported.
+        let mut bad_dest = mock_importer(bad_dest_dir.path()).await;
+        bad_dest.fast_sync_stop_height = FAST_SYNC_BATCH_LEN;
+
+        let mut bad_dest_context = bad_dest.blockchain_context_service.clone();
+        assert!(bad_dest.import(&file).await.is_err());
+        assert_eq!(bad_dest_context.blockchain_context().chain_height, 1);
+    }
+}
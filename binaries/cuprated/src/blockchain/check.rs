@@ -0,0 +1,103 @@
+//! Blockchain database check.
+//!
+//! Checking the blockchain database for inconsistencies and repairing it,
+//! without starting the node, see [`cuprate_blockchain::ops::check`].
+use anyhow::{bail, Context};
+use tracing::{error, info, warn};
+
+use cuprate_blockchain::{
+    ops::{alt_block::flush_alt_blocks, check},
+    tables::OpenTables,
+};
+use cuprate_database::{Env, EnvInner, TxRw};
+
+use crate::config::Config;
+
+/// Check the blockchain database for inconsistencies between its tables.
+///
+/// If `repair` is `true`, the blocks from the first inconsistent block to the top are
+/// popped and, if the alt-chain tables are inconsistent, all alt blocks are removed.
+///
+/// # Errors
+///
+/// This returns an error if the database could not be opened, read or repaired,
+/// or if it is still inconsistent afterwards.
+pub fn check_blockchain(config: &Config, repair: bool) -> Result<(), anyhow::Error> {
+    let env = cuprate_blockchain::open(config.blockchain_config())
+        .context("Failed to open the blockchain database")?;
+    let env_inner = env.env_inner();
+
+    info!("Checking the blockchain database, this may take a while.");
+    let report = {
+        let tx_ro = env_inner.tx_ro()?;
+        let tables = env_inner.open_tables(&tx_ro)?;
+        check::check(&tables)?
+    };
+
+    for problem in &report.problems {
+        error!("{problem}");
+    }
+
+    if report.is_consistent() {
+        info!(
+            "The blockchain database is consistent, chain height: {}",
+            report.chain_height
+        );
+        return Ok(());
+    }
+
+    let consistent_chain_height = report.consistent_chain_height();
+    let popped_blocks = report.chain_height - consistent_chain_height;
+
+    if !repair {
+        bail!(
+            "Found {} problems, `--repair` would pop {popped_blocks} blocks back to chain height {consistent_chain_height}",
+            report.problems.len()
+        );
+    }
+
+    info!("Popping {popped_blocks} blocks back to chain height {consistent_chain_height}.");
+
+    let mut tx_rw = env_inner.tx_rw()?;
+
+    {
+        let mut tables = env_inner.open_tables_mut(&tx_rw)?;
+        for (height, hash) in check::repair(&report, &mut tables)
+            .context("Failed to pop blocks, the database was not changed")?
+        {
+            info!("Popped block {height}: {}", hex::encode(hash));
+        }
+    }
+
+    if report.has_alt_chain_problems() {
+        info!("Removing all alt blocks.");
+        flush_alt_blocks(&env_inner, &mut tx_rw)?;
+    }
+
+    TxRw::commit(tx_rw)?;
+
+    info!("Checking the repaired blockchain database.");
+    let report = {
+        let tx_ro = env_inner.tx_ro()?;
+        let tables = env_inner.open_tables(&tx_ro)?;
+        check::check(&tables)?
+    };
+
+    for problem in &report.problems {
+        warn!("{problem}");
+    }
+
+    if !report.is_consistent() {
+        bail!(
+            "{} problems could not be repaired, the blockchain database must be deleted and re-synced",
+            report.problems.len()
+        );
+    }
+
+    info!(
+        "The blockchain database is repaired, chain height: {}",
+        report.chain_height
+    );
+
+    Ok(())
+}
@@ -1,19 +1,69 @@
-use std::slice;
+use std::{fs, path::Path};
 
+use anyhow::Context;
+
+use cuprate_fast_sync::FastSyncFile;
 use cuprate_helper::network::Network;
 
-/// The hashes of the compiled in fast sync file.
+/// The hashes of the compiled in mainnet fast sync file.
 ///
 /// See `build.rs` for how this file is generated.
 static FAST_SYNC_HASHES: &[[u8; 32]] = &include!(concat!(env!("OUT_DIR"), "/fast_sync_hashes.rs"));
 
+/// The hashes of the compiled in stagenet fast sync file.
+///
+/// This is empty until it is generated with `create-fs-file` from a synced stagenet node,
+/// so stagenet can only be fast synced with a `fast_sync_file`.
+static FAST_SYNC_HASHES_STAGENET: &[[u8; 32]] =
+    &include!(concat!(env!("OUT_DIR"), "/fast_sync_hashes_stagenet.rs"));
+
+/// The hashes of the compiled in testnet fast sync file.
+///
+/// This is empty until it is generated with `create-fs-file` from a synced testnet node,
+/// so testnet can only be fast synced with a `fast_sync_file`.
+static FAST_SYNC_HASHES_TESTNET: &[[u8; 32]] =
+    &include!(concat!(env!("OUT_DIR"), "/fast_sync_hashes_testnet.rs"));
+
 /// Set the fast-sync hashes according to the provided values.
-pub fn set_fast_sync_hashes(fast_sync: bool, network: Network) {
-    cuprate_fast_sync::set_fast_sync_hashes(if fast_sync && network == Network::Mainnet {
-        FAST_SYNC_HASHES
-    } else {
-        &[]
-    });
+///
+/// If `fast_sync_file` is not empty the hashes are loaded from that file,
+/// otherwise the compiled in hashes for `network` are used.
+///
+/// # Errors
+/// This returns an error if `fast_sync_file` could not be read or is invalid.
+pub fn set_fast_sync_hashes(
+    fast_sync: bool,
+    fast_sync_file: &Path,
+    network: Network,
+) -> Result<(), anyhow::Error> {
+    let hashes: &[[u8; 32]] = match network {
+        _ if !fast_sync => &[],
+        _ if !fast_sync_file.as_os_str().is_empty() => {
+            load_fast_sync_file(fast_sync_file, network)?
+        }
+        Network::Mainnet => FAST_SYNC_HASHES,
+        Network::Stagenet => FAST_SYNC_HASHES_STAGENET,
+        Network::Testnet => FAST_SYNC_HASHES_TESTNET,
+        Network::Regtest => &[],
+    };
+
+    cuprate_fast_sync::set_fast_sync_hashes(hashes);
+
+    Ok(())
+}
+
+/// Load and validate the fast-sync hashes in the file at `path`.
+fn load_fast_sync_file(
+    path: &Path,
+    network: Network,
+) -> Result<&'static [[u8; 32]], anyhow::Error> {
+    let file = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read fast-sync file: {}", path.display()))?;
+
+    let file: FastSyncFile = serde_json::from_str(&file)
+        .with_context(|| format!("Failed to parse fast-sync file: {}", path.display()))?;
+
+    Ok(file.into_hashes(network)?.leak())
 }
 
 #[cfg(test)]
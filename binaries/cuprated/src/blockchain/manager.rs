@@ -15,7 +15,7 @@ use cuprate_p2p::{
     block_downloader::{BlockBatch, BlockDownloaderConfig},
     BroadcastSvc, NetworkInterface,
 };
-use cuprate_p2p_core::ClearNet;
+use cuprate_p2p_core::NetworkZone;
 use cuprate_txpool::service::TxpoolWriteHandle;
 use cuprate_types::{
     blockchain::{BlockchainReadRequest, BlockchainResponse},
@@ -34,7 +34,7 @@ mod commands;
 mod handler;
 
 #[cfg(test)]
-mod tests;
+pub(super) mod tests;
 
 pub use commands::{BlockchainManagerCommand, IncomingBlockOk};
 
@@ -42,8 +42,10 @@ pub use commands::{BlockchainManagerCommand, IncomingBlockOk};
 ///
 /// This function sets up the [`BlockchainManager`] and the [`syncer`] so that the functions in [`interface`](super::interface)
 /// can be called.
-pub async fn init_blockchain_manager(
-    clearnet_interface: NetworkInterface<ClearNet>,
+///
+/// Blocks are synced and broadcast over the network zone `Z`.
+pub async fn init_blockchain_manager<Z: NetworkZone>(
+    network_interface: NetworkInterface<Z>,
     blockchain_write_handle: BlockchainWriteHandle,
     blockchain_read_handle: BlockchainReadHandle,
     txpool_write_handle: TxpoolWriteHandle,
@@ -60,7 +62,7 @@ pub async fn init_blockchain_manager(
     tokio::spawn(syncer::syncer(
         blockchain_context_service.clone(),
         ChainService(blockchain_read_handle.clone()),
-        clearnet_interface.clone(),
+        network_interface.clone(),
         batch_tx,
         Arc::clone(&stop_current_block_downloader),
         block_downloader_config,
@@ -75,7 +77,7 @@ pub async fn init_blockchain_manager(
         txpool_write_handle,
         blockchain_context_service,
         stop_current_block_downloader,
-        broadcast_svc: clearnet_interface.broadcast_svc(),
+        broadcast_svc: network_interface.broadcast_svc(),
     };
 
     tokio::spawn(manager.run(batch_rx, command_rx));
@@ -87,7 +89,7 @@ pub async fn init_blockchain_manager(
 /// go through this.
 ///
 /// Other parts of Cuprate can interface with this by using the functions in [`interface`](super::interface).
-pub struct BlockchainManager {
+pub struct BlockchainManager<Z: NetworkZone> {
     /// The [`BlockchainWriteHandle`], this is the _only_ part of Cuprate where a [`BlockchainWriteHandle`]
     /// is held.
     blockchain_write_handle: BlockchainWriteHandle,
@@ -102,10 +104,10 @@ pub struct BlockchainManager {
     /// attempt.
     stop_current_block_downloader: Arc<Notify>,
     /// The broadcast service, to broadcast new blocks.
-    broadcast_svc: BroadcastSvc<ClearNet>,
+    broadcast_svc: BroadcastSvc<Z>,
 }
 
-impl BlockchainManager {
+impl<Z: NetworkZone> BlockchainManager<Z> {
     /// The [`BlockchainManager`] task.
     pub async fn run(
         mut self,
@@ -24,6 +24,7 @@ use cuprate_consensus_context::{BlockchainContext, NewBlockData};
 use cuprate_fast_sync::{block_to_verified_block_information, fast_sync_stop_height};
 use cuprate_helper::cast::usize_to_u64;
 use cuprate_p2p::{block_downloader::BlockBatch, constants::LONG_BAN, BroadcastRequest};
+use cuprate_p2p_core::NetworkZone;
 use cuprate_txpool::service::interface::TxpoolWriteRequest;
 use cuprate_types::{
     blockchain::{BlockchainReadRequest, BlockchainResponse, BlockchainWriteRequest},
@@ -35,9 +36,10 @@ use crate::{
     blockchain::manager::commands::{BlockchainManagerCommand, IncomingBlockOk},
     constants::PANIC_CRITICAL_SERVICE_ERROR,
     signals::REORG_LOCK,
+    zmq,
 };
 
-impl super::BlockchainManager {
+impl<Z: NetworkZone> super::BlockchainManager<Z> {
     /// Handle an incoming command from another part of Cuprate.
     ///
     /// # Panics
@@ -608,6 +610,9 @@ impl super::BlockchainManager {
             })
             .collect::<Vec<[u8; 32]>>();
 
+        let zmq_block =
+            zmq::enabled().then(|| (verified_block.height, verified_block.block.clone()));
+
         self.add_valid_block_to_blockchain_cache(&verified_block)
             .await;
 
@@ -626,6 +631,10 @@ impl super::BlockchainManager {
             .call(TxpoolWriteRequest::NewBlock { spent_key_images })
             .await
             .expect(PANIC_CRITICAL_SERVICE_ERROR);
+
+        if let Some((height, block)) = zmq_block {
+            zmq::publish_chain_main(height, vec![block]);
+        }
     }
 
     /// Adds a [`VerifiedBlockInformation`] to the blockchain context cache.
@@ -668,6 +677,13 @@ impl super::BlockchainManager {
         &mut self,
         blocks: Vec<VerifiedBlockInformation>,
     ) {
+        let zmq_blocks = zmq::enabled().then(|| {
+            (
+                blocks[0].height,
+                blocks.iter().map(|block| block.block.clone()).collect(),
+            )
+        });
+
         self.blockchain_write_handle
             .ready()
             .await
@@ -675,6 +691,10 @@ impl super::BlockchainManager {
             .call(BlockchainWriteRequest::BatchWriteBlocks(blocks))
             .await
             .expect(PANIC_CRITICAL_SERVICE_ERROR);
+
+        if let Some((height, blocks)) = zmq_blocks {
+            zmq::publish_chain_main(height, blocks);
+        }
     }
 }
 
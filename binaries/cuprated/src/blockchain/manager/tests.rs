@@ -11,7 +11,7 @@ use cuprate_consensus_context::{BlockchainContext, ContextConfig};
 use cuprate_consensus_rules::{hard_forks::HFInfo, miner_tx::calculate_block_reward, HFsInfo};
 use cuprate_helper::network::Network;
 use cuprate_p2p::{block_downloader::BlockBatch, BroadcastSvc};
-use cuprate_p2p_core::handles::HandleBuilder;
+use cuprate_p2p_core::{handles::HandleBuilder, ClearNet};
 use cuprate_types::{CachedVerificationState, TransactionVerificationData, TxVersion};
 
 use crate::blockchain::{
@@ -19,7 +19,7 @@ use crate::blockchain::{
     ConsensusBlockchainReadHandle,
 };
 
-async fn mock_manager(data_dir: PathBuf) -> BlockchainManager {
+async fn mock_manager(data_dir: PathBuf) -> BlockchainManager<ClearNet> {
     let blockchain_config = cuprate_blockchain::config::ConfigBuilder::new()
         .data_directory(data_dir.clone())
         .build();
@@ -63,7 +63,9 @@ async fn mock_manager(data_dir: PathBuf) -> BlockchainManager {
     }
 }
 
-fn generate_block(context: &BlockchainContext) -> Block {
+/// Generates a valid empty block on top of `context`, for a chain with a fixed difficulty of 1
+/// and every hard-fork at genesis.
+pub(crate) fn generate_block(context: &BlockchainContext) -> Block {
     Block {
         header: BlockHeader {
             hardfork_version: 16,
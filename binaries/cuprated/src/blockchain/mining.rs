@@ -0,0 +1,148 @@
+//! Block mining.
+//!
+//! Mines blocks on the local CPU, this is only meant for regtest (`generateblocks`) where the
+//! difficulty is low enough for this to be practical.
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::anyhow;
+use monero_address::MoneroAddress;
+use monero_serai::block::Block;
+use tower::{Service, ServiceExt};
+
+use cuprate_blockchain::service::BlockchainReadHandle;
+use cuprate_consensus::BlockchainContextService;
+use cuprate_consensus_context::{
+    rx_vms::RandomXVm, BlockChainContextRequest, BlockChainContextResponse, RandomXConfig,
+};
+use cuprate_consensus_rules::blocks::{calculate_pow_hash, check_block_pow};
+use cuprate_helper::{asynch::rayon_spawn_async, cast::u64_to_usize};
+use cuprate_txpool::service::TxpoolReadHandle;
+use cuprate_types::{BlockTemplate, HardFork};
+
+use crate::blockchain::{create_block_template, interface::handle_incoming_block, IncomingBlockOk};
+
+/// Mine `amount_of_blocks` blocks on top of the main chain paying `address`.
+///
+/// The nonce search of every block starts at `starting_nonce`.
+///
+/// Returns the hashes of the mined blocks and the height of the last one.
+///
+/// # Errors
+/// This returns an error if a block template could not be created, no nonce
+/// satisfies the difficulty or a mined block was not added to the main chain.
+pub async fn generate_blocks(
+    blockchain_read_handle: &mut BlockchainReadHandle,
+    blockchain_context_service: &mut BlockchainContextService,
+    txpool_read_handle: &mut TxpoolReadHandle,
+    address: &MoneroAddress,
+    amount_of_blocks: u64,
+    starting_nonce: u32,
+) -> Result<(Vec<[u8; 32]>, u64), anyhow::Error> {
+    let mut blocks = Vec::new();
+    let mut height = 0;
+
+    for _ in 0..amount_of_blocks {
+        let template = create_block_template(
+            blockchain_read_handle,
+            blockchain_context_service,
+            txpool_read_handle,
+            address,
+            &[],
+        )
+        .await?;
+
+        let hf = HardFork::from_version(template.block.header.hardfork_version)?;
+        let randomx_vm = if hf >= HardFork::V12 {
+            Some(randomx_vm(blockchain_context_service, &template).await?)
+        } else {
+            None
+        };
+
+        height = template.height;
+
+        let block = rayon_spawn_async(move || {
+            find_nonce(template, hf, randomx_vm.as_deref(), starting_nonce)
+        })
+        .await?;
+        let block_hash = block.hash();
+
+        match handle_incoming_block(
+            block,
+            HashMap::new(),
+            blockchain_read_handle,
+            txpool_read_handle,
+        )
+        .await?
+        {
+            IncomingBlockOk::AddedToMainChain => blocks.push(block_hash),
+            IncomingBlockOk::NotReady => return Err(anyhow!("Core is busy")),
+            IncomingBlockOk::AddedToAltChain | IncomingBlockOk::AlreadyHave => {
+                return Err(anyhow!("Mined block was not added to the main chain"))
+            }
+        }
+    }
+
+    Ok((blocks, height))
+}
+
+/// Returns the RandomX VM needed to mine a block from `template`.
+///
+/// The VM is taken from the context service if it has it, otherwise a new one is created.
+async fn randomx_vm(
+    blockchain_context_service: &mut BlockchainContextService,
+    template: &BlockTemplate,
+) -> Result<Arc<RandomXVm>, anyhow::Error> {
+    let BlockChainContextResponse::RxVms(vms) = blockchain_context_service
+        .ready()
+        .await
+        .map_err(|e| anyhow!(e))?
+        .call(BlockChainContextRequest::CurrentRxVms)
+        .await
+        .map_err(|e| anyhow!(e))?
+    else {
+        unreachable!()
+    };
+
+    if let Some(vm) = vms.get(&u64_to_usize(template.seed_height)) {
+        return Ok(Arc::clone(vm));
+    }
+
+    // This VM is only used for this template, so don't spend time building a dataset.
+    let rx_vms_cfg = RandomXConfig {
+        fast_mode: false,
+        ..*blockchain_context_service.rx_vms_config()
+    };
+
+    let seed_hash = template.seed_hash;
+    Ok(Arc::new(
+        rayon_spawn_async(move || RandomXVm::new(&seed_hash, &rx_vms_cfg)).await?,
+    ))
+}
+
+/// Search for a nonce that makes the block from `template` satisfy its difficulty, starting at `starting_nonce`.
+fn find_nonce(
+    template: BlockTemplate,
+    hf: HardFork,
+    randomx_vm: Option<&RandomXVm>,
+    starting_nonce: u32,
+) -> Result<Block, anyhow::Error> {
+    let BlockTemplate {
+        mut block,
+        difficulty,
+        height,
+        ..
+    } = template;
+    let height = u64_to_usize(height);
+
+    for nonce in starting_nonce..=u32::MAX {
+        block.header.nonce = nonce;
+
+        let pow_hash = calculate_pow_hash(randomx_vm, &block.serialize_pow_hash(), height, &hf)?;
+
+        if check_block_pow(&pow_hash, difficulty).is_ok() {
+            return Ok(block);
+        }
+    }
+
+    Err(anyhow!("Failed to find a nonce for the block"))
+}
@@ -0,0 +1,73 @@
+//! Blockchain pruning.
+//!
+//! Keeps track of the [`PruningSeed`] of the blockchain so it can be advertised to peers.
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use rand::Rng;
+use tower::{Service, ServiceExt};
+use tracing::info;
+
+use cuprate_blockchain::service::BlockchainWriteHandle;
+use cuprate_pruning::{PruningSeed, CRYPTONOTE_PRUNING_LOG_STRIPES};
+use cuprate_types::blockchain::{BlockchainResponse, BlockchainWriteRequest};
+
+use crate::constants::PANIC_CRITICAL_SERVICE_ERROR;
+
+/// The compressed [`PruningSeed`] of the blockchain, `0` if the blockchain is not pruned.
+static PRUNING_SEED: AtomicU32 = AtomicU32::new(0);
+
+/// Returns the [`PruningSeed`] of the blockchain.
+pub fn pruning_seed() -> PruningSeed {
+    PruningSeed::decompress(PRUNING_SEED.load(Ordering::Relaxed)).unwrap()
+}
+
+/// Returns a [`PruningSeed`] with a random stripe.
+pub fn random_pruning_seed() -> PruningSeed {
+    let stripe = rand::thread_rng().gen_range(1..=(1 << CRYPTONOTE_PRUNING_LOG_STRIPES));
+
+    PruningSeed::new_pruned(stripe, CRYPTONOTE_PRUNING_LOG_STRIPES).unwrap()
+}
+
+/// Prune the blockchain with the given [`PruningSeed`], returning the seed the blockchain is pruned with.
+///
+/// If the blockchain is already pruned, the existing seed is kept.
+/// This will only return once all prunable blocks have been pruned.
+pub async fn prune(
+    blockchain_write_handle: &mut BlockchainWriteHandle,
+    pruning_seed: PruningSeed,
+) -> PruningSeed {
+    let BlockchainResponse::Prune(pruning_seed) = blockchain_write_handle
+        .ready()
+        .await
+        .expect(PANIC_CRITICAL_SERVICE_ERROR)
+        .call(BlockchainWriteRequest::Prune(pruning_seed))
+        .await
+        .expect(PANIC_CRITICAL_SERVICE_ERROR)
+    else {
+        unreachable!()
+    };
+
+    PRUNING_SEED.store(pruning_seed.compress(), Ordering::Relaxed);
+
+    pruning_seed
+}
+
+/// Initialize blockchain pruning.
+///
+/// This resumes pruning if the blockchain is pruned, or starts pruning it if `prune_blockchain` is `true`.
+pub async fn init_pruning(
+    blockchain_write_handle: &mut BlockchainWriteHandle,
+    prune_blockchain: bool,
+) {
+    let pruning_seed = if prune_blockchain {
+        random_pruning_seed()
+    } else {
+        PruningSeed::NotPruned
+    };
+
+    let pruning_seed = prune(blockchain_write_handle, pruning_seed).await;
+
+    if let Some(stripe) = pruning_seed.get_stripe() {
+        info!("Blockchain is pruned, stripe: {stripe}");
+    }
+}
@@ -15,7 +15,7 @@ use cuprate_p2p::{
     block_downloader::{BlockBatch, BlockDownloaderConfig, ChainSvcRequest, ChainSvcResponse},
     NetworkInterface, PeerSetRequest, PeerSetResponse,
 };
-use cuprate_p2p_core::{ClearNet, NetworkZone};
+use cuprate_p2p_core::NetworkZone;
 
 const CHECK_SYNC_FREQUENCY: Duration = Duration::from_secs(30);
 
@@ -28,28 +28,26 @@ pub enum SyncerError {
     ServiceError(#[from] tower::BoxError),
 }
 
-/// The syncer tasks that makes sure we are fully synchronised with our connected peers.
+/// The syncer tasks that makes sure we are fully synchronised with our connected peers in the zone `Z`.
 #[instrument(level = "debug", skip_all)]
 #[expect(clippy::significant_drop_tightening)]
-pub async fn syncer<CN>(
+pub async fn syncer<Z, CN>(
     mut context_svc: BlockchainContextService,
     our_chain: CN,
-    mut clearnet_interface: NetworkInterface<ClearNet>,
+    mut network_interface: NetworkInterface<Z>,
     incoming_block_batch_tx: mpsc::Sender<(BlockBatch, Arc<OwnedSemaphorePermit>)>,
     stop_current_block_downloader: Arc<Notify>,
     block_downloader_config: BlockDownloaderConfig,
 ) -> Result<(), SyncerError>
 where
-    CN: Service<
-            ChainSvcRequest<ClearNet>,
-            Response = ChainSvcResponse<ClearNet>,
-            Error = tower::BoxError,
-        > + Clone
+    Z: NetworkZone,
+    CN: Service<ChainSvcRequest<Z>, Response = ChainSvcResponse<Z>, Error = tower::BoxError>
+        + Clone
         + Send
         + 'static,
     CN::Future: Send + 'static,
 {
-    tracing::info!("Starting blockchain syncer");
+    tracing::info!("Starting blockchain syncer over {}", Z::NAME);
 
     let mut check_sync_interval = interval(CHECK_SYNC_FREQUENCY);
 
@@ -65,7 +63,7 @@ where
 
         let blockchain_context = context_svc.blockchain_context();
 
-        if !check_behind_peers(blockchain_context, &mut clearnet_interface).await? {
+        if !check_behind_peers(blockchain_context, &mut network_interface).await? {
             continue;
         }
 
@@ -73,7 +71,7 @@ where
             "We are behind peers claimed cumulative difficulty, starting block downloader"
         );
         let mut block_batch_stream =
-            clearnet_interface.block_downloader(our_chain.clone(), block_downloader_config);
+            network_interface.block_downloader(our_chain.clone(), block_downloader_config);
 
         loop {
             tokio::select! {
@@ -94,7 +92,7 @@ where
 
                         let blockchain_context = context_svc.blockchain_context();
 
-                        if !check_behind_peers(blockchain_context, &mut clearnet_interface).await? {
+                        if !check_behind_peers(blockchain_context, &mut network_interface).await? {
                             tracing::info!("Synchronised with the network.");
                         }
 
@@ -112,14 +110,14 @@ where
 }
 
 /// Returns `true` if we are behind the current connected network peers.
-async fn check_behind_peers(
+async fn check_behind_peers<Z: NetworkZone>(
     blockchain_context: &BlockchainContext,
-    mut clearnet_interface: &mut NetworkInterface<ClearNet>,
+    network_interface: &mut NetworkInterface<Z>,
 ) -> Result<bool, tower::BoxError> {
     let PeerSetResponse::MostPoWSeen {
         cumulative_difficulty,
         ..
-    } = clearnet_interface
+    } = network_interface
         .peer_set()
         .ready()
         .await?
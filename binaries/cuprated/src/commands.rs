@@ -16,6 +16,7 @@ use cuprate_helper::time::secs_to_hms;
 use crate::{
     constants::PANIC_CRITICAL_SERVICE_ERROR,
     logging::{self, CupratedTracingFilter},
+    p2p::ban_list::BanList,
     statics,
 };
 
@@ -50,6 +51,9 @@ pub enum Command {
 
     /// Print the height of first block not contained in the fast sync hashes.
     FastSyncStopHeight,
+
+    /// Reload the clear-net ban-list file.
+    ReloadBanList,
 }
 
 /// The log output target.
@@ -88,9 +92,12 @@ pub fn command_listener(incoming_commands: mpsc::Sender<Command>) -> ! {
 }
 
 /// The [`Command`] handler loop.
+///
+/// `ban_list` is [`None`] if clear-net or the ban-list is disabled.
 pub async fn io_loop(
     mut incoming_commands: mpsc::Receiver<Command>,
     mut context_service: BlockchainContextService,
+    mut ban_list: Option<BanList>,
 ) {
     loop {
         let Some(command) = incoming_commands.recv().await else {
@@ -131,6 +138,13 @@ pub async fn io_loop(
 
                 println!("{stop_height}");
             }
+            Command::ReloadBanList => match &mut ban_list {
+                Some(ban_list) => match ban_list.load().await {
+                    Ok(len) => println!("Loaded {len} entries from the ban-list."),
+                    Err(e) => println!("Failed to reload the ban-list: {e}"),
+                },
+                None => println!("No ban-list is configured."),
+            },
         }
     }
 }
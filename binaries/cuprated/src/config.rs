@@ -3,7 +3,7 @@ use std::{
     fmt,
     fs::{read_to_string, File},
     io,
-    path::Path,
+    path::{Path, PathBuf},
     str::FromStr,
     time::Duration,
 };
@@ -16,8 +16,9 @@ use cuprate_helper::{
     fs::{CUPRATE_CONFIG_DIR, DEFAULT_CONFIG_FILE_NAME},
     network::Network,
 };
-use cuprate_p2p::block_downloader::BlockDownloaderConfig;
-use cuprate_p2p_core::ClearNet;
+use cuprate_p2p::{block_downloader::BlockDownloaderConfig, config::TransportConfig};
+use cuprate_p2p_core::{ClearNet, I2p, IpSubnet, Tor};
+use cuprate_p2p_transport::{I2pTransport, TorTransport};
 
 use crate::{
     constants::{DEFAULT_CONFIG_STARTUP_DELAY, DEFAULT_CONFIG_WARNING},
@@ -27,22 +28,27 @@ use crate::{
 mod args;
 mod fs;
 mod p2p;
+mod randomx;
 mod rayon;
 mod rpc;
 mod storage;
 mod tokio;
 mod tracing_config;
+mod zmq;
 
 #[macro_use]
 mod macros;
 
+pub use args::Subcommand;
 use fs::FileSystemConfig;
 use p2p::P2PConfig;
+use randomx::RandomXConfig;
 use rayon::RayonConfig;
 pub use rpc::RpcConfig;
 use storage::StorageConfig;
 use tokio::TokioConfig;
 use tracing_config::TracingConfig;
+pub use zmq::ZmqConfig;
 
 /// Header to put at the start of the generated config file.
 const HEADER: &str = r"##     ____                      _
@@ -62,9 +68,9 @@ const HEADER: &str = r"##     ____                      _
 
 ";
 
-/// Reads the args & config file, returning a [`Config`].
-pub fn read_config_and_args() -> Config {
-    let args = args::Args::parse();
+/// Reads the args & config file, returning a [`Config`] and the [`Subcommand`] to run, if any.
+pub fn read_config_and_args() -> (Config, Option<Subcommand>) {
+    let mut args = args::Args::parse();
     args.do_quick_requests();
 
     let config: Config = if let Some(config_file) = &args.config_file {
@@ -98,7 +104,7 @@ pub fn read_config_and_args() -> Config {
             .unwrap_or_default()
     };
 
-    args.apply_args(config)
+    (args.apply_args(config), args.command.take())
 }
 
 config_struct! {
@@ -108,9 +114,19 @@ config_struct! {
     pub struct Config {
         /// The network cuprated should run on.
         ///
-        /// Valid values | "Mainnet", "Testnet", "Stagenet"
+        /// Valid values | "Mainnet", "Testnet", "Stagenet", "Regtest"
         pub network: Network,
 
+        /// The fixed difficulty of blocks on "Regtest".
+        ///
+        /// Every block will need this difficulty, 0 uses the normal
+        /// difficulty algorithm. This is ignored on other networks.
+        ///
+        /// Type         | Number
+        /// Valid values | >= 0
+        /// Examples     | 0, 1, 100
+        pub fixed_difficulty: u64,
+
         /// Enable/disable fast sync.
         ///
         /// Fast sync skips verification of old blocks by
@@ -118,10 +134,26 @@ config_struct! {
         /// disabling this will significantly increase sync time.
         /// New blocks are still fully validated.
         ///
+        /// There are no built-in hashes for stagenet and testnet yet,
+        /// use `fast_sync_file` to fast sync those networks.
+        ///
         /// Type         | boolean
         /// Valid values | true, false
         pub fast_sync: bool,
 
+        /// A fast sync hash file to use instead of the built-in hashes.
+        ///
+        /// This is a file written by `create-fs-file`, it must be for
+        /// the same network and its checksum must match. Blocks up to
+        /// the height in this file are not fully verified so only use
+        /// a file you trust. This is ignored if fast sync is disabled.
+        ///
+        /// An empty path uses the built-in hashes.
+        ///
+        /// Type     | Path
+        /// Examples | "", "/home/alice/fast_sync_hashes.json"
+        pub fast_sync_file: PathBuf,
+
         #[child = true]
         /// Configuration for cuprated's logging system, tracing.
         ///
@@ -140,6 +172,10 @@ config_struct! {
         /// Rayon is used for CPU intensive tasks.
         pub rayon: RayonConfig,
 
+        #[child = true]
+        /// Configuration for RandomX, the proof-of-work algorithm.
+        pub randomx: RandomXConfig,
+
         #[child = true]
         /// Configuration for cuprated's P2P system.
         pub p2p: P2PConfig,
@@ -148,6 +184,10 @@ config_struct! {
         /// Configuration for cuprated's RPC system.
         pub rpc: RpcConfig,
 
+        #[child = true]
+        /// Configuration for cuprated's ZMQ pub/sub system.
+        pub zmq: ZmqConfig,
+
         #[child = true]
         /// Configuration for persistent data storage.
         pub storage: StorageConfig,
@@ -162,12 +202,16 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             network: Default::default(),
+            fixed_difficulty: 0,
             fast_sync: true,
+            fast_sync_file: PathBuf::new(),
             tracing: Default::default(),
             tokio: Default::default(),
             rayon: Default::default(),
+            randomx: Default::default(),
             p2p: Default::default(),
             rpc: Default::default(),
+            zmq: Default::default(),
             storage: Default::default(),
             fs: Default::default(),
         }
@@ -209,7 +253,9 @@ impl Config {
     }
 
     /// The [`ClearNet`], [`cuprate_p2p::P2PConfig`].
-    pub fn clearnet_p2p_config(&self) -> cuprate_p2p::P2PConfig<ClearNet> {
+    ///
+    /// `ban_list` is the ban-list the address book starts with.
+    pub fn clearnet_p2p_config(&self, ban_list: Vec<IpSubnet>) -> cuprate_p2p::P2PConfig<ClearNet> {
         cuprate_p2p::P2PConfig {
             network: self.network,
             seeds: p2p::clear_net_seed_nodes(self.network),
@@ -219,11 +265,15 @@ impl Config {
             gray_peers_percent: self.p2p.clear_net.gray_peers_percent,
             p2p_port: self.p2p.clear_net.p2p_port,
             rpc_port: self.rpc.restricted.port_for_p2p(),
-            address_book_config: self.p2p.clear_net.address_book_config.address_book_config(
-                &self.fs.cache_directory,
-                self.network,
-                None,
-            ),
+            address_book_config: cuprate_address_book::AddressBookConfig {
+                ban_list,
+                ..self.p2p.clear_net.address_book_config.address_book_config(
+                    &self.fs.cache_directory,
+                    self.network,
+                    self.p2p.clear_net.allow_local_ips,
+                    None,
+                )
+            },
         }
     }
 
@@ -233,26 +283,75 @@ impl Config {
             network: self.network,
             seeds: p2p::i2p_seed_nodes(self.network),
             outbound_connections: self.p2p.i2p.outbound_connections,
-            extra_outbound_connections: 0, // I2P doesn't typically use extra connections
+            extra_outbound_connections: self.p2p.i2p.extra_outbound_connections,
             max_inbound_connections: self.p2p.i2p.max_inbound_connections,
-            gray_peers_percent: 0.7,
-            p2p_port: 0, // I2P doesn't use traditional ports
-            rpc_port: 0, // I2P doesn't expose RPC port
+            gray_peers_percent: self.p2p.i2p.gray_peers_percent,
+            // We don't advertise a port or RPC over I2P, peers can't ping us back.
+            p2p_port: 0,
+            rpc_port: 0,
             address_book_config: self.p2p.i2p.address_book_config.address_book_config(
                 &self.fs.cache_directory,
                 self.network,
-                Some("i2p"),
+                false,
+                None,
             ),
         }
     }
 
+    /// The [`I2p`], [`TransportConfig`].
+    pub fn i2p_transport_config(&self) -> TransportConfig<I2p, I2pTransport> {
+        self.p2p
+            .i2p
+            .transport_config(&self.fs.data_directory, self.network)
+    }
+
+    /// The [`Tor`], [`cuprate_p2p::P2PConfig`].
+    pub fn tor_p2p_config(&self) -> cuprate_p2p::P2PConfig<Tor> {
+        let our_own_address = (!self.p2p.tor.onion_address.is_empty()).then(|| {
+            self.p2p.tor.onion_address.parse().unwrap_or_else(|e| {
+                eprintln_red(&format!("Invalid Tor onion address: {e}"));
+                std::process::exit(1);
+            })
+        });
+
+        cuprate_p2p::P2PConfig {
+            network: self.network,
+            seeds: p2p::tor_net_seed_nodes(self.network),
+            outbound_connections: self.p2p.tor.outbound_connections,
+            extra_outbound_connections: self.p2p.tor.extra_outbound_connections,
+            max_inbound_connections: self.p2p.tor.max_inbound_connections,
+            gray_peers_percent: self.p2p.tor.gray_peers_percent,
+            // Our onion address carries the port, we don't advertise RPC over Tor.
+            p2p_port: 0,
+            rpc_port: 0,
+            address_book_config: self.p2p.tor.address_book_config.address_book_config(
+                &self.fs.cache_directory,
+                self.network,
+                false,
+                our_own_address,
+            ),
+        }
+    }
+
+    /// The [`Tor`], [`TransportConfig`].
+    pub fn tor_transport_config(&self) -> TransportConfig<Tor, TorTransport> {
+        (&self.p2p.tor).into()
+    }
+
     /// The [`ContextConfig`].
-    pub const fn context_config(&self) -> ContextConfig {
-        match self.network {
+    pub fn context_config(&self) -> ContextConfig {
+        let mut context_config = match self.network {
             Network::Mainnet => ContextConfig::main_net(),
             Network::Stagenet => ContextConfig::stage_net(),
             Network::Testnet => ContextConfig::test_net(),
-        }
+            Network::Regtest => ContextConfig::reg_test(
+                (self.fixed_difficulty != 0).then(|| u128::from(self.fixed_difficulty)),
+            ),
+        };
+
+        context_config.rx_vms_cfg = (&self.randomx).into();
+
+        context_config
     }
 
     /// The [`cuprate_blockchain`] config.
@@ -276,6 +375,7 @@ impl Config {
             .network(self.network)
             .data_directory(self.fs.data_directory.clone())
             .sync_mode(txpool.sync_mode)
+            .max_txpool_weight(txpool.max_txpool_byte_size)
             .build()
     }
 
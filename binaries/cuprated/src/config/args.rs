@@ -1,4 +1,4 @@
-use std::{io::Write, path::PathBuf, process::exit};
+use std::{io::Write, net::SocketAddr, path::PathBuf, process::exit};
 
 use clap::builder::TypedValueParser;
 use serde_json::Value;
@@ -15,11 +15,15 @@ pub struct Args {
     #[arg(
         long,
         default_value_t = Network::Mainnet,
-        value_parser = clap::builder::PossibleValuesParser::new(["mainnet", "testnet", "stagenet"])
+        value_parser = clap::builder::PossibleValuesParser::new(["mainnet", "testnet", "stagenet", "regtest"])
             .map(|s| s.parse::<Network>().unwrap()),
     )]
     pub network: Network,
 
+    /// The fixed difficulty of blocks on regtest, 0 uses the normal difficulty algorithm.
+    #[arg(long)]
+    pub fixed_difficulty: Option<u64>,
+
     /// Disable fast sync, all past blocks will undergo full verification when syncing.
     ///
     /// This significantly increases initial sync time. This provides no extra security, you just
@@ -27,6 +31,14 @@ pub struct Args {
     #[arg(long)]
     no_fast_sync: bool,
 
+    /// Prune the blockchain, this can not be undone.
+    #[arg(long)]
+    prune_blockchain: bool,
+
+    /// Enable the ZMQ pub server on this address, e.g. `tcp://127.0.0.1:18083`.
+    #[arg(long, value_parser = parse_zmq_address)]
+    pub zmq_pub: Option<SocketAddr>,
+
     /// The amount of outbound clear-net connections to maintain.
     #[arg(long)]
     pub outbound_connections: Option<usize>,
@@ -46,6 +58,50 @@ pub struct Args {
     /// Print misc version information in JSON.
     #[arg(short, long)]
     pub version: bool,
+
+    /// Run a command instead of starting the node.
+    #[command(subcommand)]
+    pub command: Option<Subcommand>,
+}
+
+/// A command to run instead of starting the node.
+#[derive(clap::Subcommand, Debug)]
+pub enum Subcommand {
+    /// Export the blockchain to a file in `monerod`'s `blockchain.raw` format.
+    Export {
+        /// The PATH of the file to write.
+        path: PathBuf,
+
+        /// Only export the blocks below this height.
+        #[arg(long)]
+        stop_height: Option<usize>,
+    },
+
+    /// Import blocks from a file in `monerod`'s `blockchain.raw` format.
+    ///
+    /// Blocks already in the blockchain are skipped, so an interrupted import can be resumed.
+    Import {
+        /// The PATH of the file to read.
+        path: PathBuf,
+
+        /// The amount of blocks to verify at once, after the fast-sync height.
+        #[arg(
+            long,
+            default_value_t = 100,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        )]
+        batch_size: usize,
+    },
+
+    /// Check the blockchain database for inconsistencies between its tables.
+    ///
+    /// This opens the blockchain database without starting the node.
+    Check {
+        /// Pop the blocks from the first inconsistent block to the top,
+        /// and remove all alt blocks if they are inconsistent.
+        #[arg(long)]
+        repair: bool,
+    },
 }
 
 impl Args {
@@ -72,6 +128,16 @@ impl Args {
     pub const fn apply_args(&self, mut config: Config) -> Config {
         config.network = self.network;
         config.fast_sync = config.fast_sync && !self.no_fast_sync;
+        config.storage.blockchain.prune_blockchain |= self.prune_blockchain;
+
+        if let Some(fixed_difficulty) = self.fixed_difficulty {
+            config.fixed_difficulty = fixed_difficulty;
+        }
+
+        if let Some(zmq_pub) = self.zmq_pub {
+            config.zmq.pub_enable = true;
+            config.zmq.pub_address = zmq_pub;
+        }
 
         if let Some(outbound_connections) = self.outbound_connections {
             config.p2p.clear_net.outbound_connections = outbound_connections;
@@ -80,3 +146,12 @@ impl Args {
         config
     }
 }
+
+/// Parse a ZMQ address, the `tcp://` prefix `monerod` uses is optional.
+fn parse_zmq_address(address: &str) -> Result<SocketAddr, String> {
+    address
+        .strip_prefix("tcp://")
+        .unwrap_or(address)
+        .parse()
+        .map_err(|e| format!("{e}"))
+}
@@ -1,23 +1,33 @@
 use std::{
     marker::PhantomData,
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
-    path::Path,
+    path::{Path, PathBuf},
     time::Duration,
 };
 
 use serde::{Deserialize, Serialize};
 
-use cuprate_helper::{fs::address_book_path, network::Network};
+use cuprate_helper::{
+    fs::{address_book_path, i2p_path},
+    network::Network,
+};
 use cuprate_p2p::config::TransportConfig;
 use cuprate_p2p_core::{
     transports::{Tcp, TcpServerConfig},
-    ClearNet, I2p, NetworkZone, Transport,
+    ClearNet, I2p, NetworkZone, Tor,
+};
+use cuprate_p2p_transport::{
+    i2p::DEFAULT_SAM_ADDRESS,
+    tor::{DEFAULT_INBOUND_ADDRESS, DEFAULT_SOCKS_PROXY},
+    I2pClientConfig, I2pServerConfig, I2pTransport, TorClientConfig, TorServerConfig, TorTransport,
 };
-use cuprate_wire::{network_address::GarlicAddr, OnionAddr};
-use cuprate_p2p_transport;
+use cuprate_wire::{GarlicAddr, OnionAddr};
 
 use super::macros::config_struct;
 
+/// The name of the file holding the private keys of our I2P destination.
+const I2P_KEYS_FILE: &str = "destination.keys";
+
 config_struct! {
     /// P2P config.
     #[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
@@ -31,6 +41,10 @@ config_struct! {
         /// The I2P P2P config.
         pub i2p: I2pConfig,
 
+        #[child = true]
+        /// The Tor P2P config.
+        pub tor: TorConfig,
+
         #[child = true]
         /// Block downloader config.
         ///
@@ -152,28 +166,26 @@ config_struct! {
         /// Examples     | 0.0, 0.5, 0.123, 0.999, 1.0
         pub gray_peers_percent: f64,
 
-        /// The port to use to accept incoming IPv4 P2P connections.
-        ///
-        /// Setting this to 0 will disable incoming P2P connections.
-        ///
-        /// Type         | Number
-        /// Valid values | 0..65534
-        /// Examples     | 18080, 9999, 5432
-        pub p2p_port: u16,
-
         #[child = true]
         /// The address book config.
         pub address_book_config: AddressBookConfig,
-
-        #[child = true]
-        /// The I2P config.
-        pub i2p: I2PConfig,
     }
 
     /// The config values for P2P clear-net.
     #[derive(Debug, Deserialize, Serialize, PartialEq)]
     #[serde(deny_unknown_fields, default)]
     pub struct ClearNetConfig {
+        /// Enable the clear-net network zone.
+        ///
+        /// Setting this to `false` requires I2P or Tor to be enabled,
+        /// blocks will then be synced and broadcast over Tor, or over
+        /// I2P if Tor is disabled, and cuprated will never use clear-net.
+        ///
+        /// Type         | boolean
+        /// Valid values | false, true
+        /// Examples     | true
+        pub enable: bool,
+
         /// The IPv4 address to bind and listen for connections on.
         ///
         /// Type     | IPv4 address
@@ -194,20 +206,152 @@ config_struct! {
         /// Type     | IPv6 address
         /// Examples | "::", "2001:0db8:85a3:0000:0000:8a2e:0370:7334"
         pub listen_on_v6: Ipv6Addr,
+
+        /// The port to use to accept incoming IPv4 P2P connections.
+        ///
+        /// Setting this to 0 will disable incoming P2P connections.
+        ///
+        /// Type         | Number
+        /// Valid values | 0..65534
+        /// Examples     | 18080, 9999, 5432
+        pub p2p_port: u16,
+
+        /// A file of IP addresses and subnets to ban.
+        ///
+        /// The file has one IP address or subnet (CIDR notation)
+        /// per line, empty lines and lines starting with `#` are
+        /// ignored. These bans never expire, the file is read on
+        /// start up and can be reloaded with `reload_ban_list`.
+        ///
+        /// An empty path disables the ban-list.
+        ///
+        /// Type     | Path
+        /// Examples | "", "/home/alice/ban_list.txt"
+        pub ban_list: PathBuf,
+
+        /// Allow local IP addresses in the peer lists.
+        ///
+        /// Loopback, private and link-local addresses received
+        /// from peers are dropped unless this is enabled, this
+        /// should only be enabled on private test networks.
+        ///
+        /// Type         | boolean
+        /// Valid values | false, true
+        /// Examples     | false
+        pub allow_local_ips: bool,
+    }
+
+    /// The config values for P2P over I2P.
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    #[serde(deny_unknown_fields, default)]
+    pub struct I2pConfig {
+        /// Enable the I2P network zone.
+        ///
+        /// This requires an I2P router (i2pd or Java I2P)
+        /// with its SAM bridge enabled.
+        ///
+        /// Type         | boolean
+        /// Valid values | false, true
+        /// Examples     | false
+        pub enable: bool,
+
+        /// The address of the I2P router's SAM bridge.
+        ///
+        /// Type     | Socket address
+        /// Examples | "127.0.0.1:7656", "192.168.1.50:7656"
+        pub sam_address: String,
+
+        /// Enable the I2P inbound server.
+        ///
+        /// cuprated's I2P destination keys are stored in the `i2p`
+        /// folder of the data directory and created on first use.
+        ///
+        /// Type         | boolean
+        /// Valid values | false, true
+        /// Examples     | false
+        pub enable_inbound: bool,
+
+        /// Broadcast transactions submitted through RPC only over I2P.
+        ///
+        /// These transactions are hidden from clear-net peers
+        /// until they are seen coming back from the network.
+        ///
+        /// Type         | boolean
+        /// Valid values | false, true
+        /// Examples     | false
+        pub route_local_txs: bool,
+    }
+
+    /// The config values for P2P over Tor.
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    #[serde(deny_unknown_fields, default)]
+    pub struct TorConfig {
+        /// Enable the Tor network zone.
+        ///
+        /// This requires a Tor daemon with its SOCKS5 proxy enabled.
+        ///
+        /// Type         | boolean
+        /// Valid values | false, true
+        /// Examples     | false
+        pub enable: bool,
+
+        /// The address of the Tor daemon's SOCKS5 proxy.
+        ///
+        /// Type     | Socket address
+        /// Examples | "127.0.0.1:9050", "192.168.1.50:9150"
+        pub socks_proxy: SocketAddr,
+
+        /// Enable the Tor inbound server.
+        ///
+        /// This requires an onion service forwarding
+        /// connections to `listen_on`, for example:
+        /// `HiddenServicePort 18083 127.0.0.1:18083`.
+        ///
+        /// Type         | boolean
+        /// Valid values | false, true
+        /// Examples     | false
+        pub enable_inbound: bool,
+
+        /// The local address the onion service forwards connections to.
+        ///
+        /// Type     | Socket address
+        /// Examples | "127.0.0.1:18083"
+        pub listen_on: SocketAddr,
+
+        /// Our onion service's address, advertised to Tor peers.
+        ///
+        /// Leaving this empty will not advertise an address.
+        ///
+        /// Type     | Onion address and port
+        /// Examples | "", "xyz...abc.onion:18083"
+        pub onion_address: String,
+
+        /// Broadcast transactions submitted through RPC only over Tor.
+        ///
+        /// These transactions are hidden from clear-net peers
+        /// until they are seen coming back from the network.
+        ///
+        /// Type         | boolean
+        /// Valid values | false, true
+        /// Examples     | false
+        pub route_local_txs: bool,
     }
 }
 
 impl Default for ClearNetConfig {
     fn default() -> Self {
         Self {
+            enable: true,
             listen_on: Ipv4Addr::UNSPECIFIED,
             enable_inbound_v6: false,
             listen_on_v6: Ipv6Addr::UNSPECIFIED,
+            p2p_port: 18080,
+            ban_list: PathBuf::new(),
+            allow_local_ips: false,
             outbound_connections: 32,
             extra_outbound_connections: 8,
             max_inbound_connections: 128,
             gray_peers_percent: 0.7,
-            p2p_port: 18080,
             address_book_config: AddressBookConfig::default(),
         }
     }
@@ -232,6 +376,74 @@ impl From<&ClearNetConfig> for TransportConfig<ClearNet, Tcp> {
     }
 }
 
+impl Default for I2pConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            sam_address: DEFAULT_SAM_ADDRESS.to_owned(),
+            enable_inbound: false,
+            route_local_txs: false,
+            outbound_connections: 12,
+            extra_outbound_connections: 4,
+            max_inbound_connections: 64,
+            gray_peers_percent: 0.7,
+            address_book_config: AddressBookConfig::default(),
+        }
+    }
+}
+
+impl I2pConfig {
+    /// Returns the [`TransportConfig`] for [`I2p`].
+    ///
+    /// The keys of our destination are stored in `data_dir`.
+    pub fn transport_config(
+        &self,
+        data_dir: &Path,
+        network: Network,
+    ) -> TransportConfig<I2p, I2pTransport> {
+        let server_config = self.enable_inbound.then(|| I2pServerConfig {
+            sam_address: self.sam_address.clone(),
+            keys_file: Some(i2p_path(data_dir, network).join(I2P_KEYS_FILE)),
+        });
+
+        TransportConfig {
+            client_config: I2pClientConfig::new(self.sam_address.clone()),
+            server_config,
+        }
+    }
+}
+
+impl Default for TorConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            socks_proxy: DEFAULT_SOCKS_PROXY,
+            enable_inbound: false,
+            listen_on: DEFAULT_INBOUND_ADDRESS,
+            onion_address: String::new(),
+            route_local_txs: false,
+            outbound_connections: 12,
+            extra_outbound_connections: 4,
+            max_inbound_connections: 64,
+            gray_peers_percent: 0.7,
+            address_book_config: AddressBookConfig::default(),
+        }
+    }
+}
+
+impl From<&TorConfig> for TransportConfig<Tor, TorTransport> {
+    fn from(value: &TorConfig) -> Self {
+        Self {
+            client_config: TorClientConfig {
+                socks_proxy: value.socks_proxy,
+            },
+            server_config: value.enable_inbound.then_some(TorServerConfig {
+                listen_on: value.listen_on,
+            }),
+        }
+    }
+}
+
 config_struct! {
     /// The addressbook config exposed to users.
     #[derive(Debug, Deserialize, Serialize, Eq, PartialEq)]
@@ -256,6 +468,20 @@ config_struct! {
         /// Examples     | 1000, 500, 241
         pub max_gray_list_length: usize,
 
+        /// The maximum number of anchor peers, and of inbound
+        /// peers, in a single subnet.
+        ///
+        /// Subnets are /16 for IPv4 and /32 for IPv6, outbound
+        /// connections are not made to peers in full subnets and
+        /// inbound connections from full subnets are rejected,
+        /// keeping the peers we connect to diverse. Local addresses
+        /// are not limited, 0 disables the limit.
+        ///
+        /// Type         | Number
+        /// Valid values | >= 0
+        /// Examples     | 2, 1, 4, 0
+        pub max_peers_per_subnet: usize,
+
         #[inline = true]
         /// The time period between address book saves.
         ///
@@ -270,6 +496,7 @@ impl Default for AddressBookConfig {
         Self {
             max_white_list_length: 1_000,
             max_gray_list_length: 5_000,
+            max_peers_per_subnet: 2,
             peer_save_period: Duration::from_secs(90),
         }
     }
@@ -277,10 +504,13 @@ impl Default for AddressBookConfig {
 
 impl AddressBookConfig {
     /// Returns the [`cuprate_address_book::AddressBookConfig`].
+    ///
+    /// `allow_local_ips` should only be `true` for [`ClearNet`].
     pub fn address_book_config<Z: NetworkZone>(
         &self,
         cache_dir: &Path,
         network: Network,
+        allow_local_ips: bool,
         our_own_address: Option<Z::Addr>,
     ) -> cuprate_address_book::AddressBookConfig<Z> {
         cuprate_address_book::AddressBookConfig {
@@ -288,6 +518,9 @@ impl AddressBookConfig {
             max_gray_list_length: self.max_gray_list_length,
             peer_store_directory: address_book_path(cache_dir, network),
             peer_save_period: self.peer_save_period,
+            max_peers_per_subnet: self.max_peers_per_subnet,
+            allow_local_ips,
+            ban_list: Vec::new(),
             our_own_address,
         }
     }
@@ -322,6 +555,7 @@ pub fn clear_net_seed_nodes(network: Network) -> Vec<SocketAddr> {
             "77.172.183.193:28080",
         ]
         .as_slice(),
+        Network::Regtest => [].as_slice(),
     };
 
     seeds
@@ -331,7 +565,7 @@ pub fn clear_net_seed_nodes(network: Network) -> Vec<SocketAddr> {
         .unwrap()
 }
 
-/// Seed nodes for `Tor`.
+/// Seed nodes for [`Tor`].
 pub fn tor_net_seed_nodes(network: Network) -> Vec<OnionAddr> {
     let seeds = match network {
         Network::Mainnet => [
@@ -343,7 +577,7 @@ pub fn tor_net_seed_nodes(network: Network) -> Vec<OnionAddr> {
             "aclc4e2jhhtr44guufbnwk5bzwhaecinax4yip4wr4tjn27sjsfg6zqd.onion:18083",
         ]
         .as_slice(),
-        Network::Stagenet | Network::Testnet => [].as_slice(),
+        Network::Stagenet | Network::Testnet | Network::Regtest => [].as_slice(),
     };
 
     seeds
@@ -353,121 +587,20 @@ pub fn tor_net_seed_nodes(network: Network) -> Vec<OnionAddr> {
         .unwrap()
 }
 
-/// Seed nodes for I2P network.
+/// Seed nodes for [`I2p`].
 pub fn i2p_seed_nodes(network: Network) -> Vec<GarlicAddr> {
-    // For now, return empty - I2P seed nodes would need to be collected
-    // from known I2P Monero nodes
     let seeds = match network {
         Network::Mainnet => [
-            // TODO: Add actual I2P Monero seed node destinations
-            // "base64destination.b32.i2p:18080",
+            "s3l6ke4ed3df466khuebb4poienoingwof7oxtbo6j4n56sghe3a.b32.i2p:18080",
+            "sel36x6fibfzujwvt4hf5gxolz6kd3jpvbjqg6o3ud2xtionyl2q.b32.i2p:18080",
         ]
         .as_slice(),
-        Network::Stagenet | Network::Testnet => [].as_slice(),
+        Network::Stagenet | Network::Testnet | Network::Regtest => [].as_slice(),
     };
 
     seeds
         .iter()
         .map(|s| s.parse())
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap_or_default()
-}
-
-/// The config values for P2P I2P network.
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
-#[serde(deny_unknown_fields, default)]
-pub struct I2PConfig {
-    /// Enable I2P network.
-    ///
-    /// Setting this to `false` will disable I2P P2P connections entirely.
-    ///
-    /// Type         | boolean
-    /// Valid values | false, true
-    /// Examples     | false, true
-    pub enable: bool,
-
-    /// I2P router address.
-    ///
-    /// The address of the I2P router (SAM bridge).
-    ///
-    /// Type     | String
-    /// Examples | "127.0.0.1:7656", "localhost:7656"
-    pub router_address: String,
-
-    /// Number of outbound I2P connections to maintain.
-    ///
-    /// Type         | Number
-    /// Valid values | >= 0
-    /// Examples     | 8, 16, 32
-    pub outbound_connections: usize,
-
-    /// Maximum number of inbound I2P connections.
-    ///
-    /// Type         | Number  
-    /// Valid values | >= 0
-    /// Examples     | 32, 64, 128
-    pub max_inbound_connections: usize,
-
-    /// Our I2P destination (base64).
-    ///
-    /// If not provided, a new destination will be generated.
-    ///
-    /// Type     | String (base64)
-    /// Examples | "base64-encoded-destination"
-    pub destination: Option<String>,
-
-    /// Private key for our I2P destination.
-    ///
-    /// If not provided with destination, a new key will be generated.
-    ///
-    /// Type     | String (base64)
-    /// Examples | "base64-encoded-private-key"
-    pub private_key: Option<String>,
-
-    #[child = true]
-    /// The address book config.
-    pub address_book_config: AddressBookConfig,
-}
-
-impl Default for I2PConfig {
-    fn default() -> Self {
-        Self {
-            enable: false, // Disabled by default
-            router_address: "127.0.0.1:7656".to_string(),
-            outbound_connections: 8,
-            max_inbound_connections: 32,
-            destination: None,
-            private_key: None,
-            address_book_config: AddressBookConfig::default(),
-        }
-    }
-}
-
-impl From<&I2pConfig> for TransportConfig<I2p, cuprate_p2p_transport::I2pTransport> {
-    fn from(config: &I2pConfig) -> Self {
-        use cuprate_p2p_transport::{I2pClientConfig, I2pServerConfig};
-        
-        let client_config = I2pClientConfig {
-            sam_address: config.router_address.clone(),
-            connect_timeout: Duration::from_secs(30),
-            session_timeout: Duration::from_secs(600),
-        };
-        
-        let server_config = if config.enable {
-            Some(I2pServerConfig {
-                sam_address: config.router_address.clone(),
-                session_name: "cuprate-server".to_string(),
-                destination: config.destination.clone(),
-                private_key: config.private_key.clone(),
-                signature_type: "EDDSA_SHA512_ED25519".to_string(),
-            })
-        } else {
-            None
-        };
-        
-        Self {
-            client_config,
-            server_config,
-        }
-    }
+        .collect::<Result<_, _>>()
+        .unwrap()
 }
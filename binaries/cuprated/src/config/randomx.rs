@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+use cuprate_consensus_context::rx_vms::RX_SEEDS_CACHED;
+
+use super::macros::config_struct;
+
+config_struct! {
+    /// The RandomX config.
+    #[derive(Debug, Deserialize, Serialize, Eq, PartialEq)]
+    #[serde(deny_unknown_fields, default)]
+    pub struct RandomXConfig {
+        /// Enable RandomX fast mode.
+        ///
+        /// Fast mode uses a 2 GiB dataset per cached seed to make
+        /// hashing much faster, the dataset is built on the rayon pool
+        /// when a seed is first used. Verification falls back to light
+        /// mode if the dataset can not be allocated.
+        ///
+        /// Type         | boolean
+        /// Valid values | true, false
+        pub fast_mode: bool,
+
+        /// Use large pages for RandomX allocations.
+        ///
+        /// Large pages must be set up in the OS, normal
+        /// pages are used if the allocation fails.
+        ///
+        /// Type         | boolean
+        /// Valid values | true, false
+        pub large_pages: bool,
+
+        /// Enable W^X for the RandomX JIT compiler.
+        ///
+        /// This makes hashing slower, it has no effect if
+        /// the JIT is not supported on this CPU.
+        ///
+        /// Type         | boolean
+        /// Valid values | true, false
+        pub secure_jit: bool,
+
+        /// The amount of RandomX seeds to keep VMs for.
+        ///
+        /// In fast mode each seed needs 2 GiB of memory.
+        ///
+        /// Type         | Number
+        /// Valid values | >= 2
+        /// Examples     | 2, 3, 5
+        pub seeds_cached: usize,
+    }
+}
+
+impl Default for RandomXConfig {
+    fn default() -> Self {
+        Self {
+            fast_mode: false,
+            large_pages: false,
+            secure_jit: false,
+            seeds_cached: RX_SEEDS_CACHED,
+        }
+    }
+}
+
+impl From<&RandomXConfig> for cuprate_consensus_context::RandomXConfig {
+    fn from(value: &RandomXConfig) -> Self {
+        Self {
+            fast_mode: value.fast_mode,
+            large_pages: value.large_pages,
+            secure_jit: value.secure_jit,
+            seeds_cached: value.seeds_cached,
+        }
+    }
+}
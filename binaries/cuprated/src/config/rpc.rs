@@ -49,7 +49,56 @@ config_struct! {
         /// Examples     | 0 (no limit), 5242880 (5MB), 10485760 (10MB)
         pub request_byte_limit: usize,
 
-        // TODO: <https://github.com/Cuprate/cuprate/issues/445>
+        #[comment_out = true]
+        /// Require HTTP digest authentication for the RPC server.
+        ///
+        /// This is in the same `username:password` format as
+        /// `monerod`'s `--rpc-login`. If there is no `:`,
+        /// the password is empty.
+        ///
+        /// Setting this to an empty string will disable authentication.
+        ///
+        /// Type     | String
+        /// Examples | "", "user:password"
+        pub login: String,
+
+        #[comment_out = true]
+        /// The maximum amount of requests a single
+        /// IP address can make per minute.
+        ///
+        /// Requests over this limit are rejected, each request
+        /// in a JSON-RPC batch counts as a request.
+        ///
+        /// Setting this to `0` will disable the limit.
+        ///
+        /// Type         | Number
+        /// Valid values | >= 0
+        /// Examples     | 0 (no limit), 60, 600
+        pub requests_per_ip_per_minute: u32,
+
+        #[comment_out = true]
+        /// The maximum amount of connections a single
+        /// IP address can have open at once.
+        ///
+        /// Setting this to `0` will disable the limit.
+        ///
+        /// Type         | Number
+        /// Valid values | >= 0
+        /// Examples     | 0 (no limit), 3, 25
+        pub connections_per_ip: u32,
+
+        #[comment_out = true]
+        /// The maximum amount of requests in a
+        /// JSON-RPC batch request to `/json_rpc`.
+        ///
+        /// Larger batches are rejected as a whole.
+        ///
+        /// Setting this to `0` will disable the limit.
+        ///
+        /// Type         | Number
+        /// Valid values | >= 0
+        /// Examples     | 0 (no limit), 100, 1000
+        pub max_batch_size: usize,
     }
 
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -91,6 +140,10 @@ impl Default for UnrestrictedRpcConfig {
             address: SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 18081)),
             enable: true,
             request_byte_limit: 0,
+            login: String::new(),
+            requests_per_ip_per_minute: 0,
+            connections_per_ip: 0,
+            max_batch_size: 0,
         }
     }
 }
@@ -104,6 +157,11 @@ impl Default for RestrictedRpcConfig {
             // 1 megabyte.
             // <https://github.com/monero-project/monero/blob/3b01c490953fe92f3c6628fa31d280a4f0490d28/src/cryptonote_config.h#L134>
             request_byte_limit: 1024 * 1024,
+            login: String::new(),
+            requests_per_ip_per_minute: 0,
+            // Same as `monerod`'s `--rpc-max-connections-per-public-ip`.
+            connections_per_ip: 3,
+            max_batch_size: 100,
         }
     }
 }
@@ -59,7 +59,22 @@ config_struct! {
     /// The blockchain config.
     #[derive(Default, Debug, Deserialize, Serialize, PartialEq, Eq)]
     #[serde(deny_unknown_fields, default)]
-    pub struct BlockchainConfig { }
+    pub struct BlockchainConfig {
+        #[comment_out = true]
+        /// Prune the blockchain.
+        ///
+        /// A pruned blockchain only stores 1/8th of the
+        /// prunable transaction data of old blocks, which
+        /// makes the database roughly 3x smaller.
+        ///
+        /// Once the blockchain is pruned it can not be
+        /// un-pruned, setting this back to `false` will
+        /// not restore the pruned data.
+        ///
+        /// Type         | boolean
+        /// Valid values | true, false
+        pub prune_blockchain: bool,
+    }
 
     /// The tx-pool config.
     #[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
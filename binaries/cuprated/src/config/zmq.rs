@@ -0,0 +1,37 @@
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+use serde::{Deserialize, Serialize};
+
+use super::macros::config_struct;
+
+config_struct! {
+    /// ZMQ config.
+    #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+    #[serde(deny_unknown_fields, default)]
+    pub struct ZmqConfig {
+        /// Toggle the ZMQ pub server.
+        ///
+        /// If `true` the `monerod` compatible `json-full-*` and
+        /// `json-minimal-*` topics will be published, these are
+        /// used by P2Pool and block explorers.
+        ///
+        /// Type     | boolean
+        /// Examples | true, false
+        pub pub_enable: bool,
+
+        /// The address and port the ZMQ pub server will listen on.
+        ///
+        /// Type     | IPv4/IPv6 address + port
+        /// Examples | "127.0.0.1:18083", "192.168.1.50:18083"
+        pub pub_address: SocketAddr,
+    }
+}
+
+impl Default for ZmqConfig {
+    fn default() -> Self {
+        Self {
+            pub_enable: false,
+            pub_address: SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 18083)),
+        }
+    }
+}
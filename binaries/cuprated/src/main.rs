@@ -21,7 +21,7 @@ use std::{mem, sync::Arc};
 use p2p::initialize_zones_p2p;
 use tokio::sync::mpsc;
 use tower::{Service, ServiceExt};
-use tracing::{error, info, level_filters::LevelFilter};
+use tracing::{error, info, level_filters::LevelFilter, warn};
 use tracing_subscriber::{layer::SubscriberExt, reload::Handle, util::SubscriberInitExt, Registry};
 
 use cuprate_consensus_context::{
@@ -29,12 +29,16 @@ use cuprate_consensus_context::{
 };
 use cuprate_database::{InitError, DATABASE_CORRUPT_MSG};
 use cuprate_helper::time::secs_to_hms;
+use cuprate_p2p::NetworkInterface;
 use cuprate_p2p_core::{transports::Tcp, ClearNet};
 use cuprate_types::blockchain::BlockchainWriteRequest;
 use txpool::IncomingTxHandler;
 
 use crate::{
-    config::Config, constants::PANIC_CRITICAL_SERVICE_ERROR, logging::CupratedTracingFilter,
+    config::{Config, Subcommand},
+    constants::PANIC_CRITICAL_SERVICE_ERROR,
+    logging::CupratedTracingFilter,
+    p2p::MainZone,
 };
 
 mod blockchain;
@@ -49,6 +53,7 @@ mod signals;
 mod statics;
 mod txpool;
 mod version;
+mod zmq;
 
 fn main() {
     // Initialize the killswitch.
@@ -57,9 +62,14 @@ fn main() {
     // Initialize global static `LazyLock` data.
     statics::init_lazylock_statics();
 
-    let config = config::read_config_and_args();
+    let (config, command) = config::read_config_and_args();
 
-    blockchain::set_fast_sync_hashes(config.fast_sync, config.network());
+    if let Err(e) =
+        blockchain::set_fast_sync_hashes(config.fast_sync, &config.fast_sync_file, config.network())
+    {
+        logging::eprintln_red(&format!("Failed to set fast-sync hashes: {e:#}"));
+        std::process::exit(1);
+    }
 
     // Initialize logging.
     logging::init_logging(&config);
@@ -67,6 +77,24 @@ fn main() {
     //Printing configuration
     info!("{config}");
 
+    // The compiled in stagenet and testnet fast-sync hashes are empty until they are generated.
+    if config.fast_sync && cuprate_fast_sync::fast_sync_stop_height() == 0 {
+        warn!(
+            "Fast-sync is enabled but there are no fast-sync hashes for {}, set `fast_sync_file` to fast-sync.",
+            config.network()
+        );
+    }
+
+    // Check the blockchain database without starting the node.
+    if let Some(Subcommand::Check { repair }) = &command {
+        if let Err(e) = blockchain::check::check_blockchain(&config, *repair) {
+            error!("{e:#}");
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
     // Initialize the thread-pools
 
     init_global_rayon_pool(&config);
@@ -112,15 +140,73 @@ fn main() {
         )
         .await;
 
+        // Resume or start pruning the blockchain.
+        blockchain::pruning::init_pruning(
+            &mut blockchain_write_handle,
+            config.storage.blockchain.prune_blockchain,
+        )
+        .await;
+
         // Start the context service and the block/tx verifier.
         let context_svc =
             blockchain::init_consensus(blockchain_read_handle.clone(), config.context_config())
                 .await
                 .unwrap();
 
+        // Run the command instead of starting the node.
+        if let Some(command) = command {
+            let res = match command {
+                Subcommand::Export { path, stop_height } => {
+                    blockchain::bootstrap::export_blockchain(
+                        &path,
+                        stop_height,
+                        blockchain_read_handle,
+                    )
+                    .await
+                }
+                Subcommand::Import { path, batch_size } => {
+                    blockchain::bootstrap::BlockchainImporter::new(
+                        batch_size,
+                        blockchain_read_handle,
+                        blockchain_write_handle,
+                        txpool_write_handle,
+                        context_svc,
+                    )
+                    .import(&path)
+                    .await
+                }
+                Subcommand::Check { .. } => unreachable!("checked before starting the services"),
+            };
+
+            if let Err(e) = res {
+                error!("{e:#}");
+                std::process::exit(1);
+            }
+
+            return;
+        }
+
+        // Read the clear-net ban-list, it is given to the address book before any peers can connect.
+        let clearnet_ban_list = if config.p2p.clear_net.enable {
+            match p2p::ban_list::read_ban_list(&config.p2p.clear_net.ban_list) {
+                Ok(Some(ban_list)) => {
+                    info!("Loaded {} entries from the ban-list.", ban_list.len());
+                    ban_list
+                }
+                Ok(None) => Vec::new(),
+                Err(e) => {
+                    error!("Failed to load the ban-list: {e}");
+                    std::process::exit(1);
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
         // Start p2p network zones
         let (network_interfaces, tx_handler_subscribers) = p2p::initialize_zones_p2p(
             &config,
+            clearnet_ban_list,
             context_svc.clone(),
             blockchain_write_handle.clone(),
             blockchain_read_handle.clone(),
@@ -129,14 +215,75 @@ fn main() {
         )
         .await;
 
-        // Create the incoming tx handler service.
-        let tx_handler = IncomingTxHandler::init(
-            network_interfaces.clearnet_network_interface.clone(),
-            txpool_write_handle.clone(),
-            txpool_read_handle.clone(),
-            context_svc.clone(),
+        let Some(main_zone) = network_interfaces.main_zone() else {
+            error!("No P2P network zone is running, enable clear-net, I2P or Tor.");
+            std::process::exit(1);
+        };
+
+        // The clear-net ban-list, for reloading it with a command.
+        let clearnet_address_book = network_interfaces
+            .clearnet_network_interface
+            .as_ref()
+            .map(NetworkInterface::address_book);
+        let ban_list = clearnet_address_book.clone().and_then(|address_book| {
+            p2p::ban_list::BanList::new(config.p2p.clear_net.ban_list.clone(), address_book)
+        });
+
+        // Start removing expired txs and keeping the tx-pool under its weight limit.
+        txpool::start_txpool_maintenance(txpool_write_handle.clone());
+
+        // Start the ZMQ pub server, before the blockchain manager so no new blocks are missed.
+        zmq::init_zmq_pub(
+            &config.zmq,
             blockchain_read_handle.clone(),
-        );
+            context_svc.clone(),
+            txpool_read_handle.clone(),
+        )
+        .await;
+
+        // Start the incoming tx handler and the blockchain manager on the main zone.
+        let anon_zones = network_interfaces.local_tx_anon_zones(&config);
+        let tx_handler = match main_zone {
+            MainZone::ClearNet(clear_net) => {
+                p2p::start_main_zone(
+                    clear_net,
+                    anon_zones,
+                    &config,
+                    &blockchain_write_handle,
+                    &blockchain_read_handle,
+                    &txpool_write_handle,
+                    &txpool_read_handle,
+                    &context_svc,
+                )
+                .await
+            }
+            MainZone::Tor(tor) => {
+                p2p::start_main_zone(
+                    tor,
+                    anon_zones,
+                    &config,
+                    &blockchain_write_handle,
+                    &blockchain_read_handle,
+                    &txpool_write_handle,
+                    &txpool_read_handle,
+                    &context_svc,
+                )
+                .await
+            }
+            MainZone::I2p(i2p) => {
+                p2p::start_main_zone(
+                    i2p,
+                    anon_zones,
+                    &config,
+                    &blockchain_write_handle,
+                    &blockchain_read_handle,
+                    &txpool_write_handle,
+                    &txpool_read_handle,
+                    &context_svc,
+                )
+                .await
+            }
+        };
 
         // Send tx handler sender to all network zones
         for zone in tx_handler_subscribers {
@@ -145,23 +292,17 @@ fn main() {
             }
         }
 
-        // Initialize the blockchain manager.
-        blockchain::init_blockchain_manager(
-            network_interfaces.clearnet_network_interface,
-            blockchain_write_handle,
-            blockchain_read_handle.clone(),
-            txpool_write_handle.clone(),
-            context_svc.clone(),
-            config.block_downloader_config(),
-        )
-        .await;
-
         // Initialize the RPC server(s).
+        let network = config.network();
         rpc::init_rpc_servers(
             config.rpc,
+            network,
             blockchain_read_handle,
+            blockchain_write_handle,
             context_svc.clone(),
             txpool_read_handle,
+            tx_handler,
+            clearnet_address_book,
         );
 
         // Start the command listener.
@@ -170,7 +311,7 @@ fn main() {
             std::thread::spawn(|| commands::command_listener(command_tx));
 
             // Wait on the io_loop, spawned on a separate task as this improves performance.
-            tokio::spawn(commands::io_loop(command_rx, context_svc))
+            tokio::spawn(commands::io_loop(command_rx, context_svc, ban_list))
                 .await
                 .unwrap();
         } else {
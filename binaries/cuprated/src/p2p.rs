@@ -6,13 +6,18 @@ use std::convert::From;
 
 use futures::{FutureExt, TryFutureExt};
 use tokio::sync::oneshot::{self, Sender};
-use tower::{Service, ServiceExt};
+use tower::{util::BoxCloneService, Service, ServiceExt};
 
 use cuprate_blockchain::service::{BlockchainReadHandle, BlockchainWriteHandle};
 use cuprate_consensus::BlockchainContextService;
 use cuprate_p2p::{config::TransportConfig, NetworkInterface, P2PConfig};
-use cuprate_p2p_core::{client::InternalPeerID, transports::Tcp, ClearNet, I2p, NetworkZone, Transport};
-use cuprate_p2p_transport::I2pTransport;
+use cuprate_p2p_core::{
+    client::InternalPeerID,
+    services::{AddressBookRequest, AddressBookResponse},
+    transports::Tcp,
+    ClearNet, I2p, IpSubnet, NetZoneAddress, NetworkZone, Tor, Transport,
+};
+use cuprate_p2p_transport::{I2pTransport, TorTransport};
 use cuprate_txpool::service::{TxpoolReadHandle, TxpoolWriteHandle};
 use cuprate_types::blockchain::BlockchainWriteRequest;
 
@@ -20,89 +25,229 @@ use crate::{
     blockchain,
     config::Config,
     constants::PANIC_CRITICAL_SERVICE_ERROR,
-    txpool::{self, IncomingTxHandler},
+    txpool::{self, AnonTxService, AnonZones, IncomingTxHandler},
 };
 
+pub mod ban_list;
 mod core_sync_service;
 mod network_address;
 pub mod request_handler;
 
 pub use network_address::CrossNetworkInternalPeerId;
 
+/// A handle to the address book of a network zone.
+pub type AddressBookHandle<Z> =
+    BoxCloneService<AddressBookRequest<Z>, AddressBookResponse<Z>, tower::BoxError>;
+
 /// This struct collect all supported and optional network zone interfaces.
 pub struct NetworkInterfaces {
-    /// Mandatory clearnet network interface
-    pub clearnet_network_interface: NetworkInterface<ClearNet>,
+    /// Optional clearnet network interface
+    pub clearnet_network_interface: Option<NetworkInterface<ClearNet>>,
     /// Optional I2P network interface
     pub i2p_network_interface: Option<NetworkInterface<I2p>>,
+    /// Optional Tor network interface
+    pub tor_network_interface: Option<NetworkInterface<Tor>>,
     // ...one can dream for more!
 }
 
+/// The network zone blocks are synced and broadcast over, and public txs are relayed over.
+pub enum MainZone {
+    /// The clear-net zone.
+    ClearNet(NetworkInterface<ClearNet>),
+    /// The Tor zone, for an anonymous-only node.
+    Tor(NetworkInterface<Tor>),
+    /// The I2P zone, for an anonymous-only node.
+    I2p(NetworkInterface<I2p>),
+}
+
 impl NetworkInterfaces {
     pub const fn new(
-        clearnet_network_interface: NetworkInterface<ClearNet>,
+        clearnet_network_interface: Option<NetworkInterface<ClearNet>>,
         i2p_network_interface: Option<NetworkInterface<I2p>>,
+        tor_network_interface: Option<NetworkInterface<Tor>>,
     ) -> Self {
         Self {
             clearnet_network_interface,
             i2p_network_interface,
+            tor_network_interface,
+        }
+    }
+
+    /// Returns the [`MainZone`].
+    ///
+    /// This is clear-net if it is running, otherwise Tor is preferred over I2P.
+    /// [`None`] is returned if no zone is running.
+    pub fn main_zone(&self) -> Option<MainZone> {
+        if let Some(clear_net) = &self.clearnet_network_interface {
+            return Some(MainZone::ClearNet(clear_net.clone()));
+        }
+
+        if let Some(tor) = &self.tor_network_interface {
+            return Some(MainZone::Tor(tor.clone()));
+        }
+
+        self.i2p_network_interface
+            .as_ref()
+            .map(|i2p| MainZone::I2p(i2p.clone()))
+    }
+
+    /// Returns the [`AnonZones`] local txs should be routed over, from the zones enabled in the config.
+    pub fn local_tx_anon_zones(&self, config: &Config) -> AnonZones {
+        let mut anon_zones = AnonZones::default();
+
+        if config.p2p.i2p.route_local_txs {
+            match &self.i2p_network_interface {
+                Some(i2p) => anon_zones.i2p = Some(AnonTxService::new(i2p.clone())),
+                None => tracing::warn!("I2P is not running, local txs can not be routed over it."),
+            }
         }
+
+        if config.p2p.tor.route_local_txs {
+            match &self.tor_network_interface {
+                Some(tor) => anon_zones.tor = Some(AnonTxService::new(tor.clone())),
+                None => tracing::warn!("Tor is not running, local txs can not be routed over it."),
+            }
+        }
+
+        if anon_zones.is_empty()
+            && self.clearnet_network_interface.is_some()
+            && (config.p2p.i2p.route_local_txs || config.p2p.tor.route_local_txs)
+        {
+            tracing::warn!(
+                "No anonymity zones available, local txs will be broadcast over clear-net."
+            );
+        }
+
+        anon_zones
     }
 }
 
+/// Starts the [`IncomingTxHandler`] and the blockchain manager, syncing blocks and relaying txs over the zone `Z`.
+#[expect(clippy::too_many_arguments)]
+pub async fn start_main_zone<Z: NetworkZone>(
+    network_interface: NetworkInterface<Z>,
+    anon_zones: AnonZones,
+    config: &Config,
+    blockchain_write_handle: &BlockchainWriteHandle,
+    blockchain_read_handle: &BlockchainReadHandle,
+    txpool_write_handle: &TxpoolWriteHandle,
+    txpool_read_handle: &TxpoolReadHandle,
+    context_svc: &BlockchainContextService,
+) -> IncomingTxHandler
+where
+    CrossNetworkInternalPeerId: From<InternalPeerID<Z::Addr>>,
+{
+    tracing::info!("Syncing blocks and relaying txs over {}.", Z::NAME);
+
+    let tx_handler = IncomingTxHandler::init(
+        network_interface.clone(),
+        anon_zones,
+        txpool_write_handle.clone(),
+        txpool_read_handle.clone(),
+        context_svc.clone(),
+        blockchain_read_handle.clone(),
+    );
+
+    blockchain::init_blockchain_manager(
+        network_interface,
+        blockchain_write_handle.clone(),
+        blockchain_read_handle.clone(),
+        txpool_write_handle.clone(),
+        context_svc.clone(),
+        config.block_downloader_config(),
+    )
+    .await;
+
+    tx_handler
+}
+
 /// Initialize all P2P network zones. Returning a [`NetworkInterfaces`] collection and
 /// a [`Vec<Sender<IncomingTxHandler>>`] for propagating the tx handler.
+///
+/// `clearnet_ban_list` is set in the clear-net address book before any connections are made.
 pub async fn initialize_zones_p2p(
     config: &Config,
+    clearnet_ban_list: Vec<IpSubnet>,
     context_svc: BlockchainContextService,
     mut blockchain_write_handle: BlockchainWriteHandle,
     mut blockchain_read_handle: BlockchainReadHandle,
     txpool_write_handle: TxpoolWriteHandle,
     txpool_read_handle: TxpoolReadHandle,
 ) -> (NetworkInterfaces, Vec<Sender<IncomingTxHandler>>) {
-    // Start TCP clearnet P2P.
-    let (clearnet, incoming_tx_handler_tx) = start_zone_p2p::<ClearNet, Tcp>(
-        blockchain_read_handle.clone(),
-        context_svc.clone(),
-        txpool_read_handle.clone(),
-        config.clearnet_p2p_config(),
-        (&config.p2p.clear_net).into(),
-    )
-    .await
-    .unwrap();
-
-    // Start I2P P2P if enabled
-    let mut tx_handler_subscribers = vec![incoming_tx_handler_tx];
-    let i2p_interface = if config.p2p.i2p.enable {
-        info!("Starting I2P P2P network zone");
-        
+    let mut tx_handler_subscribers = vec![];
+
+    // Start TCP clearnet P2P, if enabled.
+    let clearnet = if config.p2p.clear_net.enable {
+        let (clearnet, incoming_tx_handler_tx) = start_zone_p2p::<ClearNet, Tcp>(
+            blockchain_read_handle.clone(),
+            context_svc.clone(),
+            txpool_read_handle.clone(),
+            config.clearnet_p2p_config(clearnet_ban_list),
+            (&config.p2p.clear_net).into(),
+        )
+        .await
+        .unwrap();
+
+        tx_handler_subscribers.push(incoming_tx_handler_tx);
+        Some(clearnet)
+    } else {
+        tracing::info!("Clear-net is disabled, running as an anonymous-only node.");
+        None
+    };
+
+    // Start I2P P2P, if enabled.
+    let i2p = if config.p2p.i2p.enable {
+        tracing::info!("Starting I2P P2P network zone.");
+
         match start_zone_p2p::<I2p, I2pTransport>(
             blockchain_read_handle.clone(),
             context_svc.clone(),
             txpool_read_handle.clone(),
             config.i2p_p2p_config(),
-            (&config.p2p.i2p).into(),
+            config.i2p_transport_config(),
         )
         .await
         {
-            Ok((i2p, i2p_tx_handler)) => {
-                info!("Successfully started I2P network zone");
-                tx_handler_subscribers.push(i2p_tx_handler);
+            Ok((i2p, incoming_tx_handler_tx)) => {
+                tx_handler_subscribers.push(incoming_tx_handler_tx);
                 Some(i2p)
             }
             Err(e) => {
-                warn!("Failed to start I2P network zone: {}", e);
-                info!("Continuing without I2P support");
+                tracing::warn!("Failed to start I2P network zone, continuing without it: {e}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Start Tor P2P, if enabled.
+    let tor = if config.p2p.tor.enable {
+        tracing::info!("Starting Tor P2P network zone.");
+
+        match start_zone_p2p::<Tor, TorTransport>(
+            blockchain_read_handle.clone(),
+            context_svc.clone(),
+            txpool_read_handle.clone(),
+            config.tor_p2p_config(),
+            config.tor_transport_config(),
+        )
+        .await
+        {
+            Ok((tor, incoming_tx_handler_tx)) => {
+                tx_handler_subscribers.push(incoming_tx_handler_tx);
+                Some(tor)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to start Tor network zone, continuing without it: {e}");
                 None
             }
         }
     } else {
-        debug!("I2P network zone disabled in configuration");
         None
     };
 
-    // Create network interface collection
-    let network_interfaces = NetworkInterfaces::new(clearnet, i2p_interface);
+    let network_interfaces = NetworkInterfaces::new(clearnet, i2p, tor);
     (network_interfaces, tx_handler_subscribers)
 }
 
@@ -121,6 +266,8 @@ where
     N: NetworkZone,
     T: Transport<N>,
     N::Addr: borsh::BorshDeserialize + borsh::BorshSerialize,
+    <N::Addr as NetZoneAddress>::BanID: borsh::BorshDeserialize + borsh::BorshSerialize,
+    <N::Addr as NetZoneAddress>::BanSubnet: borsh::BorshDeserialize + borsh::BorshSerialize,
     CrossNetworkInternalPeerId: From<InternalPeerID<<N as NetworkZone>::Addr>>,
 {
     let (incoming_tx_handler_tx, incoming_tx_handler_rx) = oneshot::channel();
@@ -144,3 +291,157 @@ where
         incoming_tx_handler_tx,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use monero_address::MoneroAddress;
+
+    use cuprate_consensus_context::ContextConfig;
+    use cuprate_dandelion_tower::TxState;
+    use cuprate_helper::network::Network;
+    use cuprate_types::HardFork;
+
+    use super::*;
+    use crate::{
+        blockchain::{check_add_genesis, generate_blocks, ConsensusBlockchainReadHandle},
+        txpool::IncomingTxs,
+    };
+
+    #[test]
+    fn main_zone_priority() {
+        let clear_net = || Some(NetworkInterface::<ClearNet>::mock());
+        let i2p = || Some(NetworkInterface::<I2p>::mock());
+        let tor = || Some(NetworkInterface::<Tor>::mock());
+
+        assert!(matches!(
+            NetworkInterfaces::new(clear_net(), i2p(), tor()).main_zone(),
+            Some(MainZone::ClearNet(_))
+        ));
+
+        // Clear-net disabled.
+        assert!(matches!(
+            NetworkInterfaces::new(None, i2p(), tor()).main_zone(),
+            Some(MainZone::Tor(_))
+        ));
+        assert!(matches!(
+            NetworkInterfaces::new(None, None, tor()).main_zone(),
+            Some(MainZone::Tor(_))
+        ));
+        assert!(matches!(
+            NetworkInterfaces::new(None, i2p(), None).main_zone(),
+            Some(MainZone::I2p(_))
+        ));
+
+        assert!(NetworkInterfaces::new(None, None, None)
+            .main_zone()
+            .is_none());
+    }
+
+    #[test]
+    fn local_tx_anon_zones_without_clear_net() {
+        let mut config = Config::default();
+        config.p2p.tor.route_local_txs = true;
+        config.p2p.i2p.route_local_txs = true;
+
+        // I2P is not running, so only Tor is used.
+        let anon_zones = NetworkInterfaces::new(None, None, Some(NetworkInterface::mock()))
+            .local_tx_anon_zones(&config);
+
+        assert!(anon_zones.tor.is_some());
+        assert!(anon_zones.i2p.is_none());
+    }
+
+    /// Starts the main zone on Tor, with clear-net disabled, and checks blocks mined on a regtest chain
+    /// are added by the blockchain manager and the tx handler is running.
+    ///
+    /// The blockchain manager's command channel is a global, so this must be the only test that starts one.
+    #[tokio::test]
+    async fn start_main_zone_tor_only() {
+        const FIXED_DIFFICULTY: u128 = 10;
+
+        let data_dir = tempfile::tempdir().unwrap();
+
+        let blockchain_config = cuprate_blockchain::config::ConfigBuilder::new()
+            .data_directory(data_dir.path().to_path_buf())
+            .build();
+        let txpool_config = cuprate_txpool::config::ConfigBuilder::new()
+            .data_directory(data_dir.path().to_path_buf())
+            .build();
+
+        let (mut blockchain_read_handle, mut blockchain_write_handle, _) =
+            cuprate_blockchain::service::init(blockchain_config).unwrap();
+        let (mut txpool_read_handle, txpool_write_handle, _) =
+            cuprate_txpool::service::init(txpool_config).unwrap();
+
+        check_add_genesis(
+            &mut blockchain_read_handle,
+            &mut blockchain_write_handle,
+            Network::Regtest,
+        )
+        .await;
+
+        let mut context_svc = cuprate_consensus_context::initialize_blockchain_context(
+            ContextConfig::reg_test(Some(FIXED_DIFFICULTY)),
+            ConsensusBlockchainReadHandle::new(
+                blockchain_read_handle.clone(),
+                tower::BoxError::from,
+            ),
+        )
+        .await
+        .unwrap();
+
+        let Some(MainZone::Tor(tor)) =
+            NetworkInterfaces::new(None, None, Some(NetworkInterface::mock())).main_zone()
+        else {
+            panic!("Tor should be the main zone");
+        };
+
+        let tx_handler = start_main_zone(
+            tor,
+            AnonZones::default(),
+            &Config::default(),
+            &blockchain_write_handle,
+            &blockchain_read_handle,
+            &txpool_write_handle,
+            &txpool_read_handle,
+            &context_svc,
+        )
+        .await;
+
+        let address = MoneroAddress::from_str(
+            monero_address::Network::Mainnet,
+            "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A",
+        )
+        .unwrap();
+
+        let (hashes, height) = generate_blocks(
+            &mut blockchain_read_handle,
+            &mut context_svc,
+            &mut txpool_read_handle,
+            &address,
+            3,
+            0,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(height, 3);
+
+        let context = context_svc.blockchain_context();
+        assert_eq!(context.chain_height, 4);
+        assert_eq!(context.top_hash, hashes[2]);
+        assert_eq!(context.current_hf, HardFork::LATEST);
+        assert_eq!(context.next_difficulty, FIXED_DIFFICULTY);
+        assert_eq!(context.cumulative_difficulty, 1 + 3 * FIXED_DIFFICULTY);
+
+        tx_handler
+            .oneshot(IncomingTxs {
+                txs: vec![],
+                state: TxState::Local,
+                relay: true,
+            })
+            .await
+            .unwrap();
+    }
+}
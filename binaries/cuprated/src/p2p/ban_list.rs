@@ -0,0 +1,102 @@
+//! The clear-net ban-list.
+//!
+//! Like `monerod`'s `--ban-list`, this is a file of IP addresses and subnets which are banned for as
+//! long as they are in the file.
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use tower::{Service, ServiceExt};
+use tracing::warn;
+
+use cuprate_p2p_core::{services::AddressBookRequest, ClearNet, IpSubnet};
+
+use crate::p2p::AddressBookHandle;
+
+/// The ban-list file and the clear-net address book its bans are given to.
+#[derive(Clone)]
+pub struct BanList {
+    /// The path of the ban-list file.
+    path: PathBuf,
+    /// The clear-net address book.
+    address_book: AddressBookHandle<ClearNet>,
+}
+
+impl BanList {
+    /// Returns a new [`BanList`], or [`None`] if `path` is empty.
+    pub fn new(path: PathBuf, address_book: AddressBookHandle<ClearNet>) -> Option<Self> {
+        (!path.as_os_str().is_empty()).then_some(Self { path, address_book })
+    }
+
+    /// Read the ban-list file and replace the address book's ban-list with it.
+    ///
+    /// Returns the amount of banned subnets.
+    ///
+    /// # Errors
+    /// This returns an error if the file could not be read or the address book returned an error.
+    pub async fn load(&mut self) -> Result<usize, anyhow::Error> {
+        let ban_list = parse_ban_list(&fs::read_to_string(&self.path)?);
+        let len = ban_list.len();
+
+        self.address_book
+            .ready()
+            .await
+            .map_err(|e| anyhow!(e))?
+            .call(AddressBookRequest::SetBanList(ban_list))
+            .await
+            .map_err(|e| anyhow!(e))?;
+
+        Ok(len)
+    }
+}
+
+/// Read the ban-list file at `path`.
+///
+/// Returns [`None`] if `path` is empty, i.e. the ban-list is disabled.
+///
+/// # Errors
+/// This returns an error if the file could not be read.
+pub fn read_ban_list(path: &Path) -> Result<Option<Vec<IpSubnet>>, io::Error> {
+    if path.as_os_str().is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(parse_ban_list(&fs::read_to_string(path)?)))
+}
+
+/// Parse a ban-list, invalid entries are skipped.
+fn parse_ban_list(ban_list: &str) -> Vec<IpSubnet> {
+    ban_list
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            line.parse()
+                .inspect_err(|e| warn!("Skipping invalid ban-list entry \"{line}\": {e}"))
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let ban_list = parse_ban_list(
+            "# comment\n\n  1.2.3.4  \n10.0.0.0/8\n2001:db8::/32\nnot an ip\n1.2.3.4/40\n",
+        );
+
+        assert_eq!(
+            ban_list,
+            vec![
+                "1.2.3.4/32".parse().unwrap(),
+                "10.0.0.0/8".parse().unwrap(),
+                "2001:db8::/32".parse().unwrap(),
+            ]
+        );
+    }
+}
@@ -15,13 +15,13 @@ use tokio::sync::{broadcast, oneshot, watch};
 use tokio_stream::wrappers::WatchStream;
 use tower::{Service, ServiceExt};
 
-use cuprate_blockchain::service::BlockchainReadHandle;
+use cuprate_blockchain::{ops::tx::tx_pruned_prunable_blobs, service::BlockchainReadHandle};
 use cuprate_consensus::{
     transactions::new_tx_verification_data, BlockChainContextRequest, BlockChainContextResponse,
     BlockchainContextService,
 };
 use cuprate_dandelion_tower::TxState;
-use cuprate_fixed_bytes::ByteArrayVec;
+use cuprate_fixed_bytes::{ByteArray, ByteArrayVec};
 use cuprate_helper::cast::u64_to_usize;
 use cuprate_helper::{
     asynch::rayon_spawn_async,
@@ -38,7 +38,7 @@ use cuprate_p2p_core::{
 use cuprate_txpool::service::TxpoolReadHandle;
 use cuprate_types::{
     blockchain::{BlockchainReadRequest, BlockchainResponse},
-    BlockCompleteEntry, TransactionBlobs, TxsInBlock,
+    BlockCompleteEntry, PrunedTxBlobEntry, TransactionBlobs, TxsInBlock,
 };
 use cuprate_wire::protocol::{
     ChainRequest, ChainResponse, FluffyMissingTransactionsRequest, GetObjectsRequest,
@@ -177,12 +177,13 @@ async fn get_objects(
     }
 
     let block_hashes: Vec<[u8; 32]> = (&request.blocks).into();
+    let want_pruned_data = request.pruned;
     // deallocate the backing `Bytes`.
     drop(request);
 
     let BlockchainResponse::BlockCompleteEntries {
         blocks,
-        missing_hashes,
+        mut missing_hashes,
         blockchain_height,
     } = blockchain_read_handle
         .ready()
@@ -193,6 +194,25 @@ async fn get_objects(
         unreachable!();
     };
 
+    let (blocks, missing_hashes) = rayon_spawn_async(move || -> anyhow::Result<_> {
+        let mut entries = Vec::with_capacity(blocks.len());
+
+        for entry in blocks {
+            match (want_pruned_data, entry.pruned) {
+                (true, false) => entries.push(prune_block_complete_entry(entry)?),
+                // The block is outside our pruning stripe so we only have its pruned
+                // transactions, like `monerod` it is not sent so the peer gets it elsewhere.
+                (false, true) => {
+                    missing_hashes.push(Block::read(&mut entry.block.as_ref())?.hash());
+                }
+                _ => entries.push(entry),
+            }
+        }
+
+        Ok((entries, missing_hashes))
+    })
+    .await?;
+
     Ok(ProtocolResponse::GetObjects(GetObjectsResponse {
         blocks,
         missed_ids: ByteArrayVec::from(missing_hashes),
@@ -200,6 +220,39 @@ async fn get_objects(
     }))
 }
 
+/// Prunes the transactions of a [`BlockCompleteEntry`] that has all of its transaction data.
+///
+/// Peers can not calculate the weight of a block with pruned transactions, so it is added.
+fn prune_block_complete_entry(entry: BlockCompleteEntry) -> anyhow::Result<BlockCompleteEntry> {
+    let BlockCompleteEntry { block, txs, .. } = entry;
+
+    let mut block_weight = Block::read(&mut block.as_ref())?.miner_transaction.weight();
+
+    let txs = txs
+        .take_normal()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|tx_blob| {
+            let tx = Transaction::read(&mut tx_blob.as_ref())?;
+            block_weight += tx.weight();
+
+            let (pruned_blob, _, prunable_hash) = tx_pruned_prunable_blobs(&tx, &tx_blob);
+
+            Ok(PrunedTxBlobEntry {
+                blob: tx_blob.slice_ref(pruned_blob),
+                prunable_hash: ByteArray::from(prunable_hash),
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(BlockCompleteEntry {
+        pruned: true,
+        block,
+        block_weight: usize_to_u64(block_weight),
+        txs: TransactionBlobs::Pruned(txs),
+    })
+}
+
 /// [`ProtocolRequest::GetChain`]
 async fn get_chain(
     request: ChainRequest,
@@ -401,7 +454,11 @@ where
         .ready()
         .await
         .expect(PANIC_CRITICAL_SERVICE_ERROR)
-        .call(IncomingTxs { txs, state })
+        .call(IncomingTxs {
+            txs,
+            state,
+            relay: true,
+        })
         .await;
 
     match res {
@@ -409,3 +466,45 @@ where
         Err(e) => Err(e.into()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use cuprate_test_utils::data::BLOCK_V9_TX3;
+
+    use super::*;
+
+    /// [`prune_block_complete_entry`] prunes every transaction and adds the block weight.
+    #[test]
+    fn prune_block_complete_entry_txs() {
+        let block = &*BLOCK_V9_TX3;
+
+        let entry = BlockCompleteEntry {
+            pruned: false,
+            block: Bytes::from(block.block_blob.clone()),
+            block_weight: 0,
+            txs: TransactionBlobs::Normal(
+                block
+                    .txs
+                    .iter()
+                    .map(|tx| Bytes::from(tx.tx_blob.clone()))
+                    .collect(),
+            ),
+        };
+
+        let entry = prune_block_complete_entry(entry).unwrap();
+        assert!(entry.pruned);
+        assert_eq!(entry.block_weight, usize_to_u64(block.weight));
+
+        let txs = entry.txs.take_pruned().unwrap();
+        assert_eq!(txs.len(), block.txs.len());
+
+        for (entry, tx) in txs.iter().zip(&block.txs) {
+            let (pruned_blob, _, prunable_hash) = tx_pruned_prunable_blobs(&tx.tx, &tx.tx_blob);
+
+            assert_eq!(entry.blob, pruned_blob);
+            assert_eq!(*entry.prunable_hash, prunable_hash);
+        }
+    }
+}
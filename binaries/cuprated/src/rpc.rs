@@ -7,6 +7,8 @@ mod handlers;
 mod rpc_handler;
 mod server;
 mod service;
+#[cfg(test)]
+mod tests;
 
 pub use rpc_handler::CupratedRpcHandler;
 pub use server::init_rpc_servers;
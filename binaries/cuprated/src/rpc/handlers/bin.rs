@@ -4,13 +4,17 @@
 //! Some handlers have `todo!()`s for other Cuprate internals that must be completed, see:
 //! <https://github.com/Cuprate/cuprate/pull/355>
 
-use std::num::NonZero;
+use std::{cmp::min, iter, mem, num::NonZero};
 
 use anyhow::{anyhow, Error};
 use bytes::Bytes;
+use monero_serai::{block::Block, transaction::Transaction};
 
-use cuprate_constants::rpc::{RESTRICTED_BLOCK_COUNT, RESTRICTED_TRANSACTIONS_COUNT};
-use cuprate_fixed_bytes::ByteArrayVec;
+use cuprate_blockchain::ops::tx::tx_pruned_prunable_blobs;
+use cuprate_constants::rpc::{
+    GET_BLOCKS_BIN_MAX_BLOCK_COUNT, RESTRICTED_BLOCK_COUNT, RESTRICTED_TRANSACTIONS_COUNT,
+};
+use cuprate_fixed_bytes::{ByteArray, ByteArrayVec};
 use cuprate_helper::cast::{u64_to_usize, usize_to_u64};
 use cuprate_rpc_interface::RpcHandler;
 use cuprate_rpc_types::{
@@ -25,8 +29,8 @@ use cuprate_rpc_types::{
     misc::RequestedInfo,
 };
 use cuprate_types::{
-    rpc::{PoolInfo, PoolInfoExtent},
-    BlockCompleteEntry,
+    rpc::{BlockOutputIndices, PoolInfo, TxOutputIndices},
+    BlockCompleteEntry, PrunedTxBlobEntry, TransactionBlobs,
 };
 
 use crate::rpc::{
@@ -44,13 +48,17 @@ pub async fn map_request(
     use BinResponse as Resp;
 
     Ok(match request {
-        Req::GetBlocks(r) => Resp::GetBlocks(not_available()?),
-        Req::GetBlocksByHeight(r) => Resp::GetBlocksByHeight(not_available()?),
-        Req::GetHashes(r) => Resp::GetHashes(not_available()?),
-        Req::GetOutputIndexes(r) => Resp::GetOutputIndexes(not_available()?),
-        Req::GetOuts(r) => Resp::GetOuts(not_available()?),
-        Req::GetTransactionPoolHashes(r) => Resp::GetTransactionPoolHashes(not_available()?),
-        Req::GetOutputDistribution(r) => Resp::GetOutputDistribution(not_available()?),
+        Req::GetBlocks(r) => Resp::GetBlocks(get_blocks(state, r).await?),
+        Req::GetBlocksByHeight(r) => Resp::GetBlocksByHeight(get_blocks_by_height(state, r).await?),
+        Req::GetHashes(r) => Resp::GetHashes(get_hashes(state, r).await?),
+        Req::GetOutputIndexes(r) => Resp::GetOutputIndexes(get_output_indexes(state, r).await?),
+        Req::GetOuts(r) => Resp::GetOuts(get_outs(state, r).await?),
+        Req::GetTransactionPoolHashes(r) => {
+            Resp::GetTransactionPoolHashes(get_transaction_pool_hashes(state, r).await?)
+        }
+        Req::GetOutputDistribution(r) => {
+            Resp::GetOutputDistribution(get_output_distribution(state, r).await?)
+        }
     })
 }
 
@@ -75,7 +83,7 @@ async fn get_blocks(
     let block_hashes: Vec<[u8; 32]> = (&block_ids).into();
     drop(block_ids);
 
-    let Some(requested_info) = RequestedInfo::from_u8(request.requested_info) else {
+    let Some(requested_info) = RequestedInfo::from_u8(requested_info) else {
         return Err(anyhow!("Wrong requested info"));
     };
 
@@ -85,8 +93,6 @@ async fn get_blocks(
         RequestedInfo::PoolOnly => (false, true),
     };
 
-    let pool_info_extent = PoolInfoExtent::None;
-
     let pool_info = if get_pool {
         let is_restricted = state.is_restricted();
         let include_sensitive_txs = !is_restricted;
@@ -97,13 +103,27 @@ async fn get_blocks(
             usize::MAX
         };
 
-        txpool::pool_info(
+        let mut pool_info = txpool::pool_info(
             &mut state.txpool_read,
             include_sensitive_txs,
             max_tx_count,
-            NonZero::new(u64_to_usize(request.pool_info_since)),
+            NonZero::new(u64_to_usize(pool_info_since)),
         )
-        .await?
+        .await?;
+
+        if prune {
+            let added_pool_txs = match &mut pool_info {
+                PoolInfo::None => None,
+                PoolInfo::Incremental(info) => Some(&mut info.added_pool_txs),
+                PoolInfo::Full(info) => Some(&mut info.added_pool_txs),
+            };
+
+            for tx in added_pool_txs.into_iter().flatten() {
+                tx.tx_blob = prune_tx_blob(&tx.tx_blob)?;
+            }
+        }
+
+        pool_info
     } else {
         PoolInfo::None
     };
@@ -122,39 +142,151 @@ async fn get_blocks(
         return Ok(resp);
     }
 
-    if let Some(block_id) = block_hashes.first() {
-        let (height, hash) = helper::top_height(&mut state).await?;
+    let (top_height, top_hash) = helper::top_height(&mut state).await?;
+    let chain_height = top_height + 1;
 
-        if hash == *block_id {
-            return Ok(GetBlocksResponse {
-                current_height: height + 1,
-                ..resp
-            });
-        }
+    if block_hashes.first() == Some(&top_hash) {
+        return Ok(GetBlocksResponse {
+            current_height: chain_height,
+            ..resp
+        });
     }
 
-    let (block_hashes, start_height, _) =
-        blockchain::next_chain_entry(&mut state.blockchain_read, block_hashes, start_height)
-            .await?;
+    // Like `monerod`'s `find_blockchain_supplement`, start at `start_height` if
+    // it is set, otherwise at the split point of `block_ids`.
+    let start_height = if start_height > 0 {
+        if start_height >= chain_height {
+            return Err(anyhow!("Start height is past the top of the chain"));
+        }
 
-    if start_height.is_none() {
-        return Err(anyhow!("Block IDs were not sorted properly"));
-    }
+        start_height
+    } else {
+        let (_, split_height, _) =
+            blockchain::next_chain_entry(&mut state.blockchain_read, block_hashes, 1).await?;
+
+        let Some(split_height) = split_height else {
+            return Err(anyhow!("Block IDs were not sorted properly"));
+        };
 
-    let (blocks, missing_hashes, height) =
-        blockchain::block_complete_entries(&mut state.blockchain_read, block_hashes).await?;
+        usize_to_u64(split_height)
+    };
 
-    if !missing_hashes.is_empty() {
-        return Err(anyhow!("Missing blocks"));
+    let heights =
+        (start_height..min(start_height + GET_BLOCKS_BIN_MAX_BLOCK_COUNT, chain_height)).collect();
+
+    let mut blocks =
+        blockchain::block_complete_entries_by_height(&mut state.blockchain_read, heights).await?;
+
+    let blocks_tx_hashes = blocks
+        .iter()
+        .map(|block_entry| {
+            let block = Block::read(&mut block_entry.block.as_ref())?;
+            Ok((block.miner_transaction.hash(), block.transactions))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let tx_hashes = blocks_tx_hashes
+        .iter()
+        .flat_map(|(miner_tx_hash, tx_hashes)| {
+            (!no_miner_tx)
+                .then_some(*miner_tx_hash)
+                .into_iter()
+                .chain(tx_hashes.iter().copied())
+        })
+        .collect();
+
+    let txs_output_indexes =
+        blockchain::txs_output_indexes(&mut state.blockchain_read, tx_hashes).await?;
+
+    let output_indices = block_output_indices(&blocks_tx_hashes, no_miner_tx, txs_output_indexes);
+
+    for block_entry in &mut blocks {
+        if prune {
+            let txs = match mem::take(&mut block_entry.txs) {
+                TransactionBlobs::Normal(txs) => txs,
+                TransactionBlobs::Pruned(txs) => {
+                    // `monerod` does not send the prunable hashes here.
+                    block_entry.txs = TransactionBlobs::Pruned(
+                        txs.into_iter()
+                            .map(|tx| PrunedTxBlobEntry {
+                                prunable_hash: ByteArray::from([0; 32]),
+                                ..tx
+                            })
+                            .collect(),
+                    );
+                    continue;
+                }
+                TransactionBlobs::None => vec![],
+            };
+
+            // `monerod` does not send the prunable hashes here.
+            let txs = txs
+                .iter()
+                .map(|tx_blob| {
+                    Ok(PrunedTxBlobEntry {
+                        blob: prune_tx_blob(tx_blob)?.into(),
+                        prunable_hash: ByteArray::from([0; 32]),
+                    })
+                })
+                .collect::<Result<_, Error>>()?;
+
+            block_entry.txs = TransactionBlobs::Pruned(txs);
+            block_entry.pruned = true;
+        } else if block_entry.pruned {
+            return Err(anyhow!(
+                "Failed to get full transactions, the blockchain is pruned"
+            ));
+        }
     }
 
     Ok(GetBlocksResponse {
         blocks,
-        current_height: usize_to_u64(height),
+        start_height,
+        current_height: chain_height,
+        output_indices,
         ..resp
     })
 }
 
+/// Groups the output indexes of the txs in each block into [`BlockOutputIndices`].
+///
+/// `blocks_tx_hashes` is the miner tx hash and tx hashes of each block, `txs_output_indexes`
+/// the output indexes of all these txs in order, without the miner txs if `no_miner_tx`.
+///
+/// There is always an entry for the miner transaction, which is empty if not requested.
+fn block_output_indices(
+    blocks_tx_hashes: &[([u8; 32], Vec<[u8; 32]>)],
+    no_miner_tx: bool,
+    txs_output_indexes: Vec<Vec<u64>>,
+) -> Vec<BlockOutputIndices> {
+    let mut txs_output_indexes = txs_output_indexes.into_iter();
+
+    blocks_tx_hashes
+        .iter()
+        .map(|(_, tx_hashes)| {
+            let miner_tx_indexes = if no_miner_tx {
+                vec![]
+            } else {
+                txs_output_indexes.next().unwrap()
+            };
+
+            let indices = iter::once(miner_tx_indexes)
+                .chain(txs_output_indexes.by_ref().take(tx_hashes.len()))
+                .map(|indices| TxOutputIndices { indices })
+                .collect();
+
+            BlockOutputIndices { indices }
+        })
+        .collect()
+}
+
+/// Returns the pruned blob of a transaction blob.
+fn prune_tx_blob(tx_blob: &[u8]) -> Result<Vec<u8>, Error> {
+    let tx = Transaction::read(&mut &*tx_blob)?;
+    let (pruned_blob, _, _) = tx_pruned_prunable_blobs(&tx, tx_blob);
+    Ok(pruned_blob.to_vec())
+}
+
 /// <https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454/src/rpc/core_rpc_server.cpp#L817-L857>
 async fn get_blocks_by_height(
     mut state: CupratedRpcHandler,
@@ -215,7 +347,10 @@ async fn get_output_indexes(
 ) -> Result<GetOutputIndexesResponse, Error> {
     Ok(GetOutputIndexesResponse {
         base: helper::access_response_base(false),
-        o_indexes: blockchain::tx_output_indexes(&mut state.blockchain_read, request.txid).await?,
+        o_indexes: blockchain::txs_output_indexes(&mut state.blockchain_read, vec![request.txid])
+            .await?
+            .pop()
+            .unwrap(),
     })
 }
 
@@ -240,10 +375,128 @@ async fn get_transaction_pool_hashes(
     })
 }
 
-/// <https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454/src/rpc/core_rpc_server.cpp#L3352-L3398>
+/// <https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454/src/rpc/core_rpc_server.cpp#L3400-L3416>
 async fn get_output_distribution(
     state: CupratedRpcHandler,
     request: GetOutputDistributionRequest,
 ) -> Result<GetOutputDistributionResponse, Error> {
+    if !request.binary {
+        return Err(anyhow!("Binary only call"));
+    }
+
     shared::get_output_distribution(state, request).await
 }
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use cuprate_epee_encoding::{from_bytes, to_bytes};
+    use cuprate_test_utils::rpc::data::bin;
+
+    use super::*;
+    use crate::rpc::tests::mock_rpc_handler;
+
+    fn indices(indices: &[&[u64]]) -> BlockOutputIndices {
+        BlockOutputIndices {
+            indices: indices
+                .iter()
+                .map(|indices| TxOutputIndices {
+                    indices: indices.to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    /// The output indexes from the batched read are split back into their blocks and txs.
+    #[test]
+    fn block_output_indices_grouping() {
+        let blocks_tx_hashes = [
+            ([0; 32], vec![[1; 32], [2; 32]]),
+            ([3; 32], vec![]),
+            ([4; 32], vec![[5; 32]]),
+        ];
+
+        assert_eq!(
+            block_output_indices(
+                &blocks_tx_hashes,
+                false,
+                vec![vec![0], vec![1, 2], vec![3], vec![4], vec![5], vec![6, 7]],
+            ),
+            vec![
+                indices(&[&[0], &[1, 2], &[3]]),
+                indices(&[&[4]]),
+                indices(&[&[5], &[6, 7]]),
+            ]
+        );
+
+        // Without miner txs there is still an empty entry for them.
+        assert_eq!(
+            block_output_indices(
+                &blocks_tx_hashes,
+                true,
+                vec![vec![1, 2], vec![3], vec![6, 7]],
+            ),
+            vec![
+                indices(&[&[], &[1, 2], &[3]]),
+                indices(&[&[]]),
+                indices(&[&[], &[6, 7]]),
+            ]
+        );
+    }
+
+    /// Replays [`bin::GET_BLOCKS_REQUEST`], the genesis block is the only block returned.
+    #[tokio::test]
+    async fn get_blocks_replay() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let state = mock_rpc_handler(data_dir.path()).await;
+
+        let request = from_bytes(&mut &*bin::GET_BLOCKS_REQUEST).unwrap();
+        let mut response = get_blocks(state, request).await.unwrap();
+
+        // The daemon time will always differ.
+        let expected: GetBlocksResponse = from_bytes(&mut &*bin::GET_BLOCKS_RESPONSE).unwrap();
+        response.daemon_time = expected.daemon_time;
+
+        assert_eq!(&*to_bytes(response).unwrap(), bin::GET_BLOCKS_RESPONSE);
+    }
+
+    /// Replays [`bin::GET_O_INDEXES_REQUEST`].
+    #[tokio::test]
+    async fn get_output_indexes_replay() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let state = mock_rpc_handler(data_dir.path()).await;
+
+        let request = from_bytes(&mut &*bin::GET_O_INDEXES_REQUEST).unwrap();
+        let response = get_output_indexes(state, request).await.unwrap();
+
+        assert_eq!(&*to_bytes(response).unwrap(), bin::GET_O_INDEXES_RESPONSE);
+    }
+
+    /// Replays [`bin::GET_OUTS_REQUEST`].
+    #[tokio::test]
+    async fn get_outs_replay() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let state = mock_rpc_handler(data_dir.path()).await;
+
+        let request = from_bytes(&mut &*bin::GET_OUTS_REQUEST).unwrap();
+        let response = get_outs(state, request).await.unwrap();
+
+        assert_eq!(&*to_bytes(response).unwrap(), bin::GET_OUTS_RESPONSE);
+    }
+
+    /// Replays [`bin::GET_OUTPUT_DISTRIBUTION_REQUEST`].
+    #[tokio::test]
+    async fn get_output_distribution_replay() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let state = mock_rpc_handler(data_dir.path()).await;
+
+        let request = from_bytes(&mut &*bin::GET_OUTPUT_DISTRIBUTION_REQUEST).unwrap();
+        let response = get_output_distribution(state, request).await.unwrap();
+
+        assert_eq!(
+            &*to_bytes(response).unwrap(),
+            bin::GET_OUTPUT_DISTRIBUTION_RESPONSE
+        );
+    }
+}
@@ -6,11 +6,14 @@
 //! These build on-top of [`crate::rpc::service`] functions.
 
 use anyhow::{anyhow, Error};
+use monero_address::{AddressType, MoneroAddress};
 
 use cuprate_helper::{
     cast::{u64_to_usize, usize_to_u64},
     map::split_u128_into_low_high_bits,
+    network::Network,
 };
+use cuprate_p2p_core::ClearNet;
 use cuprate_rpc_types::{
     base::{AccessResponseBase, ResponseBase},
     misc::BlockHeader,
@@ -18,9 +21,12 @@ use cuprate_rpc_types::{
 use cuprate_types::{Chain, HardFork};
 use monero_serai::transaction::Timelock;
 
-use crate::rpc::{
-    service::{blockchain, blockchain_context},
-    CupratedRpcHandler,
+use crate::{
+    p2p::AddressBookHandle,
+    rpc::{
+        service::{blockchain, blockchain_context},
+        CupratedRpcHandler,
+    },
 };
 
 /// Map some data into a [`BlockHeader`].
@@ -162,6 +168,38 @@ pub(super) fn hex_to_hash(hex: String) -> Result<[u8; 32], Error> {
     Ok(hash)
 }
 
+/// Parse the address a miner wants to be paid to on `network`.
+///
+/// Only standard (legacy) addresses are allowed, regtest uses main-net addresses.
+pub(super) fn miner_address(network: Network, address: &str) -> Result<MoneroAddress, Error> {
+    let network = match network {
+        Network::Mainnet | Network::Regtest => monero_address::Network::Mainnet,
+        Network::Stagenet => monero_address::Network::Stagenet,
+        Network::Testnet => monero_address::Network::Testnet,
+    };
+
+    let address = MoneroAddress::from_str(network, address)?;
+
+    if *address.kind() != AddressType::Legacy {
+        return Err(anyhow!("Incorrect address type"));
+    }
+
+    Ok(address)
+}
+
+/// Returns a handle to the clear-net address book.
+///
+/// # Errors
+/// This returns an error if clear-net is disabled.
+pub(super) fn clearnet_address_book(
+    state: &CupratedRpcHandler,
+) -> Result<AddressBookHandle<ClearNet>, Error> {
+    state
+        .clearnet_address_book
+        .clone()
+        .ok_or_else(|| anyhow!("Clear-net is disabled"))
+}
+
 /// [`cuprate_types::blockchain::BlockchainResponse::ChainHeight`] minus 1.
 pub(super) async fn top_height(state: &mut CupratedRpcHandler) -> Result<(u64, [u8; 32]), Error> {
     let (chain_height, hash) = blockchain::chain_height(&mut state.blockchain_read).await?;
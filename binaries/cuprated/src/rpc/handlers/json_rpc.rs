@@ -5,6 +5,7 @@
 //! <https://github.com/Cuprate/cuprate/pull/355>
 
 use std::{
+    collections::HashMap,
     net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
     num::NonZero,
     time::{Duration, Instant},
@@ -21,10 +22,15 @@ use cuprate_constants::{
 use cuprate_helper::{
     cast::{u32_to_usize, u64_to_usize, usize_to_u64},
     fmt::HexPrefix,
-    map::split_u128_into_low_high_bits,
+    map::{split_u128_into_low_high_bits, u32_from_ipv4},
 };
 use cuprate_hex::{Hex, HexVec};
-use cuprate_p2p_core::{client::handshaker::builder::DummyAddressBook, ClearNet, Network};
+use cuprate_p2p_core::{
+    client::handshaker::builder::DummyAddressBook,
+    types::{BanTarget, SetBan},
+    ClearNet, IpSubnet, Network,
+};
+use cuprate_pruning::PruningSeed;
 use cuprate_rpc_interface::RpcHandler;
 use cuprate_rpc_types::{
     base::{AccessResponseBase, ResponseBase},
@@ -58,6 +64,7 @@ use cuprate_types::{
 };
 
 use crate::{
+    blockchain::{interface::handle_incoming_block, pruning, IncomingBlockOk},
     constants::VERSION_BUILD,
     rpc::{
         constants::{FIELD_NOT_SUPPORTED, UNSUPPORTED_RPC_CALL},
@@ -77,11 +84,11 @@ pub async fn map_request(
     use JsonRpcResponse as Resp;
 
     Ok(match request {
-        Req::GetBlockTemplate(r) => Resp::GetBlockTemplate(not_available()?),
+        Req::GetBlockTemplate(r) => Resp::GetBlockTemplate(get_block_template(state, r).await?),
         Req::GetBlockCount(r) => Resp::GetBlockCount(get_block_count(state, r).await?),
         Req::OnGetBlockHash(r) => Resp::OnGetBlockHash(on_get_block_hash(state, r).await?),
-        Req::SubmitBlock(r) => Resp::SubmitBlock(not_available()?),
-        Req::GenerateBlocks(r) => Resp::GenerateBlocks(not_available()?),
+        Req::SubmitBlock(r) => Resp::SubmitBlock(submit_block(state, r).await?),
+        Req::GenerateBlocks(r) => Resp::GenerateBlocks(generate_blocks(state, r).await?),
         Req::GetLastBlockHeader(r) => {
             Resp::GetLastBlockHeader(get_last_block_header(state, r).await?)
         }
@@ -98,9 +105,9 @@ pub async fn map_request(
         Req::GetConnections(r) => Resp::GetConnections(not_available()?),
         Req::GetInfo(r) => Resp::GetInfo(not_available()?),
         Req::HardForkInfo(r) => Resp::HardForkInfo(not_available()?),
-        Req::SetBans(r) => Resp::SetBans(not_available()?),
-        Req::GetBans(r) => Resp::GetBans(not_available()?),
-        Req::Banned(r) => Resp::Banned(not_available()?),
+        Req::SetBans(r) => Resp::SetBans(set_bans(state, r).await?),
+        Req::GetBans(r) => Resp::GetBans(get_bans(state, r).await?),
+        Req::Banned(r) => Resp::Banned(banned(state, r).await?),
         Req::FlushTransactionPool(r) => Resp::FlushTransactionPool(not_available()?),
         Req::GetOutputHistogram(r) => Resp::GetOutputHistogram(not_available()?),
         Req::GetCoinbaseTxSum(r) => Resp::GetCoinbaseTxSum(not_available()?),
@@ -110,8 +117,8 @@ pub async fn map_request(
         Req::RelayTx(r) => Resp::RelayTx(not_available()?),
         Req::SyncInfo(r) => Resp::SyncInfo(not_available()?),
         Req::GetTransactionPoolBacklog(r) => Resp::GetTransactionPoolBacklog(not_available()?),
-        Req::GetMinerData(r) => Resp::GetMinerData(not_available()?),
-        Req::PruneBlockchain(r) => Resp::PruneBlockchain(not_available()?),
+        Req::GetMinerData(r) => Resp::GetMinerData(get_miner_data(state, r).await?),
+        Req::PruneBlockchain(r) => Resp::PruneBlockchain(prune_blockchain(state, r).await?),
         Req::CalcPow(r) => Resp::CalcPow(not_available()?),
         Req::AddAuxPow(r) => Resp::AddAuxPow(not_available()?),
 
@@ -139,20 +146,21 @@ async fn get_block_template(
         return Err(anyhow!("Too big extra_nonce size"));
     }
 
-    // TODO: This should be `cuprated`'s active network.
-    let network = match Network::Mainnet {
-        Network::Mainnet => monero_address::Network::Mainnet,
-        Network::Stagenet => monero_address::Network::Stagenet,
-        Network::Testnet => monero_address::Network::Testnet,
-    };
+    let address = helper::miner_address(state.network, &request.wallet_address)?;
 
-    let address = monero_address::MoneroAddress::from_str(network, &request.wallet_address)?;
+    let prev_block = request.prev_block.try_into().unwrap_or([0; 32]);
 
-    if *address.kind() != monero_address::AddressType::Legacy {
-        return Err(anyhow!("Incorrect address type"));
+    // Templates are only built on top of the main chain.
+    if prev_block != [0; 32] && prev_block != state.blockchain_context.blockchain_context().top_hash
+    {
+        return Err(anyhow!("Invalid prev_block, not the top of the main chain"));
     }
 
-    let prev_block = request.prev_block.try_into().unwrap_or([0; 32]);
+    let extra_nonce = if request.reserve_size == 0 {
+        request.extra_nonce.0
+    } else {
+        vec![0; u64_to_usize(request.reserve_size)]
+    };
 
     let BlockTemplate {
         block,
@@ -163,14 +171,22 @@ async fn get_block_template(
         seed_height,
         seed_hash,
         next_seed_hash,
-    } = *blockchain_manager::create_block_template(
-        todo!(),
-        prev_block,
-        request.wallet_address,
-        request.extra_nonce.0,
+    } = crate::blockchain::create_block_template(
+        &mut state.blockchain_read,
+        &mut state.blockchain_context,
+        &mut state.txpool_read,
+        &address,
+        &extra_nonce,
     )
     .await?;
 
+    // `monerod` only reports the offset if space was reserved.
+    let reserved_offset = if request.reserve_size == 0 {
+        0
+    } else {
+        reserved_offset
+    };
+
     let blockhashing_blob = HexVec(block.serialize_pow_hash());
     let blocktemplate_blob = HexVec(block.serialize());
     let (difficulty, difficulty_top64) = split_u128_into_low_high_bits(difficulty);
@@ -233,8 +249,22 @@ async fn submit_block(
     let block = Block::read(&mut blob.as_slice())?;
     let block_id = Hex(block.hash());
 
-    // Attempt to relay the block.
-    blockchain_manager::relay_block(todo!(), Box::new(block)).await?;
+    // Add the block, this will relay it if it is added to the main chain.
+    match handle_incoming_block(
+        block,
+        HashMap::new(),
+        &mut state.blockchain_read,
+        &mut state.txpool_read,
+    )
+    .await
+    {
+        Ok(IncomingBlockOk::AddedToMainChain) => (),
+        // Only blocks that extend the main chain are accepted.
+        Ok(IncomingBlockOk::AddedToAltChain) => return Err(anyhow!("Block not accepted")),
+        Ok(IncomingBlockOk::AlreadyHave) => return Err(anyhow!("Block already exists")),
+        Ok(IncomingBlockOk::NotReady) => return Err(anyhow!("Core is busy")),
+        Err(e) => return Err(anyhow!("Block not accepted: {e}")),
+    }
 
     Ok(SubmitBlockResponse {
         base: helper::response_base(false),
@@ -244,28 +274,30 @@ async fn submit_block(
 
 /// <https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454/src/rpc/core_rpc_server.cpp#L2268-L2340>
 async fn generate_blocks(
-    state: CupratedRpcHandler,
+    mut state: CupratedRpcHandler,
     request: GenerateBlocksRequest,
 ) -> Result<GenerateBlocksResponse, Error> {
-    if todo!("active cuprated chain") != todo!("regtest chain") {
+    if state.network != Network::Regtest {
         return Err(anyhow!("Regtest required when generating blocks"));
     }
 
-    // FIXME:
-    // is this field only used as a local variable in the handler in `monerod`?
-    // It may not be needed in the request type.
-    let prev_block = if request.prev_block.is_empty() {
-        None
-    } else {
-        Some(request.prev_block.try_into()?)
-    };
+    // Blocks can only be generated on top of the main chain.
+    if !request.prev_block.is_empty() {
+        let prev_block: [u8; 32] = request.prev_block.try_into()?;
+        if prev_block != state.blockchain_context.blockchain_context().top_hash {
+            return Err(anyhow!("Invalid prev_block, not the top of the main chain"));
+        }
+    }
+
+    let address = helper::miner_address(state.network, &request.wallet_address)?;
 
-    let (blocks, height) = blockchain_manager::generate_blocks(
-        todo!(),
+    let (blocks, height) = crate::blockchain::generate_blocks(
+        &mut state.blockchain_read,
+        &mut state.blockchain_context,
+        &mut state.txpool_read,
+        &address,
         request.amount_of_blocks,
-        prev_block,
         request.starting_nonce,
-        request.wallet_address,
     )
     .await?;
 
@@ -496,16 +528,20 @@ async fn get_info(
         address_book::connection_count::<ClearNet>(&mut DummyAddressBook).await?
     };
 
-    // TODO: This should be `cuprated`'s active network.
-    let network = Network::Mainnet;
+    let network = state.network;
 
     let (mainnet, testnet, stagenet) = match network {
         Network::Mainnet => (true, false, false),
         Network::Testnet => (false, true, false),
         Network::Stagenet => (false, false, true),
+        Network::Regtest => (false, false, false),
     };
 
-    let nettype = network.to_string();
+    // `monerod` calls regtest "fakechain".
+    let nettype = match network {
+        Network::Regtest => "fakechain".to_string(),
+        network => network.to_string(),
+    };
     // TODO: access to CLI/config's `--offline`
     let offline = false;
 
@@ -616,22 +652,15 @@ async fn set_bans(
     state: CupratedRpcHandler,
     request: SetBansRequest,
 ) -> Result<SetBansResponse, Error> {
+    let mut address_book = helper::clearnet_address_book(&state)?;
+
     for peer in request.bans {
         // TODO: support non-clearnet addresses.
+        let host = peer.host.clone();
+        let set_ban =
+            SetBan::try_from(peer).map_err(|e| anyhow!("Unsupported host/subnet: {host} ({e})"))?;
 
-        // <https://architecture.cuprate.org/oddities/le-ipv4.html>
-        let ip = Ipv4Addr::from(peer.ip.to_le_bytes());
-        let address = SocketAddr::V4(SocketAddrV4::new(ip, 0));
-
-        let ban = if peer.ban {
-            Some(Duration::from_secs(peer.seconds.into()))
-        } else {
-            None
-        };
-
-        let set_ban = cuprate_p2p_core::types::SetBan { address, ban };
-
-        address_book::set_ban::<ClearNet>(&mut DummyAddressBook, set_ban).await?;
+        address_book::set_ban::<ClearNet>(&mut address_book, set_ban).await?;
     }
 
     Ok(SetBansResponse {
@@ -645,10 +674,10 @@ async fn get_bans(state: CupratedRpcHandler, _: GetBansRequest) -> Result<GetBan
 
     // TODO: support non-clearnet addresses.
 
-    let bans = address_book::get_bans::<ClearNet>(&mut DummyAddressBook)
+    let bans = address_book::get_bans::<ClearNet>(&mut helper::clearnet_address_book(&state)?)
         .await?
         .into_iter()
-        .filter_map(|ban| {
+        .map(|ban| {
             let seconds = if let Some(instant) = ban.unban_instant {
                 instant
                     .checked_duration_since(now)
@@ -660,17 +689,15 @@ async fn get_bans(state: CupratedRpcHandler, _: GetBansRequest) -> Result<GetBan
                 0
             };
 
+            // Like `monerod`, `ip` is only set for IPv4 hosts.
             // <https://architecture.cuprate.org/oddities/le-ipv4.html>
-            let ip = match ban.address.ip() {
-                IpAddr::V4(v4) => u32::from_le_bytes(v4.octets()),
-                IpAddr::V6(v6) => return None,
+            let (host, ip) = match ban.target {
+                BanTarget::Host(IpAddr::V4(v4)) => (v4.to_string(), u32_from_ipv4(v4)),
+                BanTarget::Host(ip) => (ip.to_string(), 0),
+                BanTarget::Subnet(subnet) => (subnet.to_string(), 0),
             };
 
-            Some(GetBan {
-                host: ban.address.to_string(),
-                ip,
-                seconds,
-            })
+            GetBan { host, ip, seconds }
         })
         .collect();
 
@@ -681,21 +708,42 @@ async fn get_bans(state: CupratedRpcHandler, _: GetBansRequest) -> Result<GetBan
 }
 
 /// <https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454/src/rpc/core_rpc_server.cpp#L2803-L2830>
+///
+/// `address` can be an IP address, socket address or a subnet,
+/// a subnet is only reported as banned if that exact subnet is banned.
 async fn banned(
     state: CupratedRpcHandler,
     request: BannedRequest,
 ) -> Result<BannedResponse, Error> {
-    let peer = match request.address.parse::<SocketAddr>() {
-        Ok(p) => p,
-        Err(e) => {
-            return Err(anyhow!(
-                "Failed to parse address: {} ({e})",
-                request.address
-            ))
-        }
-    };
+    let mut address_book = helper::clearnet_address_book(&state)?;
+    let parse_error =
+        |e: &dyn std::fmt::Display| anyhow!("Failed to parse address: {} ({e})", request.address);
+
+    let ban = if request.address.contains('/') {
+        let subnet = request
+            .address
+            .parse::<IpSubnet>()
+            .map_err(|e| parse_error(&e))?;
 
-    let ban = address_book::get_ban::<ClearNet>(&mut DummyAddressBook, peer).await?;
+        address_book::get_bans::<ClearNet>(&mut address_book)
+            .await?
+            .into_iter()
+            .find(|ban| matches!(ban.target, BanTarget::Subnet(s) if s == subnet))
+            .and_then(|ban| ban.unban_instant)
+    } else {
+        let peer = match request.address.parse::<SocketAddr>() {
+            Ok(p) => p,
+            Err(_) => SocketAddr::new(
+                request
+                    .address
+                    .parse::<IpAddr>()
+                    .map_err(|e| parse_error(&e))?,
+                0,
+            ),
+        };
+
+        address_book::get_ban::<ClearNet>(&mut address_book, peer).await?
+    };
 
     let (banned, seconds) = if let Some(instant) = ban {
         let seconds = instant
@@ -949,8 +997,12 @@ async fn get_miner_data(
     let major_version = c.current_hf.as_u8();
     let height = usize_to_u64(c.chain_height);
     let prev_id = Hex(c.top_hash);
-    // TODO: RX seed hash <https://github.com/Cuprate/cuprate/pull/355#discussion_r1911814320>.
-    let seed_hash = Hex([0; 32]);
+    let seed_hash = Hex(crate::blockchain::randomx_seed_hash(
+        &mut state.blockchain_read,
+        c.chain_height,
+        c.current_hf,
+    )
+    .await?);
     let difficulty = c.next_difficulty.hex_prefix();
     // TODO: <https://github.com/Cuprate/cuprate/pull/355#discussion_r1911821515>
     let median_weight = usize_to_u64(c.effective_median_weight);
@@ -983,8 +1035,14 @@ async fn prune_blockchain(
     mut state: CupratedRpcHandler,
     request: PruneBlockchainRequest,
 ) -> Result<PruneBlockchainResponse, Error> {
-    let pruned = blockchain_manager::pruned(todo!()).await?;
-    let pruning_seed = blockchain_manager::prune(todo!()).await?.compress();
+    let pruning_seed = if request.check {
+        pruning::pruning_seed()
+    } else {
+        pruning::prune(&mut state.blockchain_write, pruning::random_pruning_seed()).await
+    };
+
+    let pruned = pruning_seed != PruningSeed::NotPruned;
+    let pruning_seed = pruning_seed.compress();
 
     Ok(PruneBlockchainResponse {
         base: helper::response_base(false),
@@ -1262,3 +1320,110 @@ async fn flush_cache(
 ) -> Result<FlushCacheResponse, Error> {
     unreachable!()
 }
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use cuprate_consensus::generate_genesis_block;
+    use cuprate_test_utils::data::BLOCK_V1_TX2;
+
+    use super::*;
+    use crate::rpc::tests::mock_rpc_handler;
+
+    const WALLET_ADDRESS: &str = "44GBHzv6ZyQdJkjqZje6KLZ3xSyN1hBSFAnLP6EAqJtCRVzMzZmeXTC2AHKDS9aEDTRKmo6a6o9r9j86pYfhCWDkKjbtcns";
+
+    fn genesis_hash() -> [u8; 32] {
+        generate_genesis_block(Network::Mainnet).hash()
+    }
+
+    /// Blocks that are already in the chain or not on top of a known block are not accepted.
+    #[tokio::test]
+    async fn submit_block_not_accepted() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let state = mock_rpc_handler(data_dir.path()).await;
+
+        let submit = |block_blob: Vec<u8>| {
+            submit_block(
+                state.clone(),
+                SubmitBlockRequest {
+                    block_blob: [HexVec(block_blob)],
+                },
+            )
+        };
+
+        let err = submit(generate_genesis_block(Network::Mainnet).serialize())
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Block already exists");
+
+        // The parent of this block is not in the chain.
+        let err = submit(BLOCK_V1_TX2.block_blob.clone()).await.unwrap_err();
+        assert!(err.to_string().starts_with("Block not accepted"));
+
+        assert!(submit(vec![1, 2, 3]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_miner_data_genesis() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let state = mock_rpc_handler(data_dir.path()).await;
+
+        let response = get_miner_data(state, GetMinerDataRequest {}).await.unwrap();
+
+        assert_eq!(response.major_version, 1);
+        assert_eq!(response.height, 1);
+        assert_eq!(response.prev_id, Hex(genesis_hash()));
+        // RandomX is not used before v12.
+        assert_eq!(response.seed_hash, Hex([0; 32]));
+        // The genesis block reward.
+        assert_eq!(response.already_generated_coins, 17_592_186_044_415);
+        assert!(response.tx_backlog.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_block_template_genesis() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let state = mock_rpc_handler(data_dir.path()).await;
+
+        let response = get_block_template(
+            state.clone(),
+            GetBlockTemplateRequest {
+                extra_nonce: HexVec::default(),
+                prev_block: HexVec::default(),
+                reserve_size: 8,
+                wallet_address: WALLET_ADDRESS.into(),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.height, 1);
+        assert_eq!(response.prev_hash, Hex(genesis_hash()));
+
+        let block = Block::read(&mut response.blocktemplate_blob.as_slice()).unwrap();
+        assert_eq!(block.header.previous, genesis_hash());
+        assert_eq!(response.blockhashing_blob.0, block.serialize_pow_hash());
+
+        // The reserved space is zeroed.
+        let reserved_offset = u64_to_usize(response.reserved_offset);
+        assert_ne!(reserved_offset, 0);
+        assert_eq!(
+            response.blocktemplate_blob.0[reserved_offset..reserved_offset + 8],
+            [0; 8]
+        );
+
+        // Templates can not be built on top of other blocks.
+        assert!(get_block_template(
+            state,
+            GetBlockTemplateRequest {
+                extra_nonce: HexVec::default(),
+                prev_block: HexVec(vec![1; 32]),
+                reserve_size: 0,
+                wallet_address: WALLET_ADDRESS.into(),
+            },
+        )
+        .await
+        .is_err());
+    }
+}
@@ -67,9 +67,13 @@ pub async fn map_request(
     Ok(match request {
         Req::GetHeight(r) => Resp::GetHeight(get_height(state, r).await?),
         Req::GetTransactions(r) => Resp::GetTransactions(not_available()?),
-        Req::GetAltBlocksHashes(r) => Resp::GetAltBlocksHashes(not_available()?),
+        Req::GetAltBlocksHashes(r) => {
+            Resp::GetAltBlocksHashes(get_alt_blocks_hashes(state, r).await?)
+        }
         Req::IsKeyImageSpent(r) => Resp::IsKeyImageSpent(not_available()?),
-        Req::SendRawTransaction(r) => Resp::SendRawTransaction(not_available()?),
+        Req::SendRawTransaction(r) => {
+            Resp::SendRawTransaction(send_raw_transaction(state, r).await?)
+        }
         Req::SaveBc(r) => Resp::SaveBc(not_available()?),
         Req::GetPeerList(r) => Resp::GetPeerList(not_available()?),
         Req::SetLogLevel(r) => Resp::SetLogLevel(not_available()?),
@@ -82,9 +86,11 @@ pub async fn map_request(
         Req::OutPeers(r) => Resp::OutPeers(not_available()?),
         Req::InPeers(r) => Resp::InPeers(not_available()?),
         Req::GetNetStats(r) => Resp::GetNetStats(not_available()?),
-        Req::GetOuts(r) => Resp::GetOuts(not_available()?),
+        Req::GetOuts(r) => Resp::GetOuts(get_outs(state, r).await?),
         Req::PopBlocks(r) => Resp::PopBlocks(not_available()?),
-        Req::GetTransactionPoolHashes(r) => Resp::GetTransactionPoolHashes(not_available()?),
+        Req::GetTransactionPoolHashes(r) => {
+            Resp::GetTransactionPoolHashes(get_transaction_pool_hashes(state, r).await?)
+        }
         Req::GetPublicNodes(r) => Resp::GetPublicNodes(not_available()?),
 
         // Unsupported requests.
@@ -167,9 +173,14 @@ async fn get_transactions(
             txs_as_hex.push(as_hex.clone());
 
             let as_json = if request.decode_as_json {
-                let tx = Transaction::read(&mut as_hex.as_slice())?;
-                let json_type = cuprate_types::json::tx::Transaction::from(tx);
-                let json = serde_json::to_string(&json_type).unwrap();
+                // Pruned transactions have no full blob to decode.
+                let json = if as_hex.is_empty() {
+                    String::new()
+                } else {
+                    let tx = Transaction::read(&mut as_hex.as_slice())?;
+                    let json_type = cuprate_types::json::tx::Transaction::from(tx);
+                    serde_json::to_string(&json_type).unwrap()
+                };
                 txs_as_json.push(json.clone());
                 json
             } else {
@@ -442,9 +453,8 @@ async fn send_raw_transaction(
         }
     }
 
-    // TODO: handle to txpool service.
     let tx_relay_checks =
-        txpool::check_maybe_relay_local(todo!(), tx, !request.do_not_relay).await?;
+        txpool::check_maybe_relay_local(&mut state.tx_handler, tx, !request.do_not_relay).await?;
 
     if tx_relay_checks.is_empty() {
         return Ok(resp);
@@ -481,6 +491,9 @@ async fn send_raw_transaction(
         }
     }
 
+    resp.base.response_base.status = Status::Failed;
+    resp.reason = reasons;
+
     Ok(resp)
 }
 
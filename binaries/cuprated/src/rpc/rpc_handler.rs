@@ -4,11 +4,12 @@ use std::task::{Context, Poll};
 
 use anyhow::Error;
 use futures::future::BoxFuture;
-use monero_serai::block::Block;
 use tower::Service;
 
 use cuprate_blockchain::service::{BlockchainReadHandle, BlockchainWriteHandle};
 use cuprate_consensus::BlockchainContextService;
+use cuprate_helper::network::Network;
+use cuprate_p2p_core::ClearNet;
 use cuprate_pruning::PruningSeed;
 use cuprate_rpc_interface::RpcHandler;
 use cuprate_rpc_types::{
@@ -17,9 +18,8 @@ use cuprate_rpc_types::{
     other::{OtherRequest, OtherResponse},
 };
 use cuprate_txpool::service::TxpoolReadHandle;
-use cuprate_types::BlockTemplate;
 
-use crate::rpc::handlers;
+use crate::{p2p::AddressBookHandle, rpc::handlers, txpool::IncomingTxHandler};
 
 /// TODO: use real type when public.
 #[derive(Clone)]
@@ -35,12 +35,6 @@ pub enum BlockchainManagerRequest {
     /// Is the blockchain pruned?
     Pruned,
 
-    /// Relay a block to the network.
-    RelayBlock(
-        /// This is [`Box`]ed due to `clippy::large_enum_variant`.
-        Box<Block>,
-    ),
-
     /// Sync/flush the blockchain database to disk.
     Sync,
 
@@ -89,13 +83,6 @@ pub enum BlockchainManagerRequest {
     /// Get the next [`PruningSeed`] needed for a pruned sync.
     NextNeededPruningSeed,
 
-    /// Create a block template.
-    CreateBlockTemplate {
-        prev_block: [u8; 32],
-        account_public_address: String,
-        extra_nonce: Vec<u8>,
-    },
-
     /// Safely shutdown `cuprated`.
     Stop,
 }
@@ -107,7 +94,6 @@ pub enum BlockchainManagerResponse {
     ///
     /// Response to:
     /// - [`BlockchainManagerRequest::Prune`]
-    /// - [`BlockchainManagerRequest::RelayBlock`]
     /// - [`BlockchainManagerRequest::Sync`]
     Ok,
 
@@ -142,9 +128,6 @@ pub enum BlockchainManagerResponse {
 
     /// Response to [`BlockchainManagerRequest::NextNeededPruningSeed`].
     NextNeededPruningSeed(PruningSeed),
-
-    /// Response to [`BlockchainManagerRequest::CreateBlockTemplate`].
-    CreateBlockTemplate(Box<BlockTemplate>),
 }
 
 /// TODO: use real type when public.
@@ -161,30 +144,55 @@ pub struct CupratedRpcHandler {
     /// This is not `pub` on purpose, as it should not be mutated after [`Self::new`].
     restricted: bool,
 
+    /// The [maximum JSON-RPC batch size](RpcHandler::max_batch_size).
+    max_batch_size: usize,
+
+    /// The network `cuprated` is running on.
+    pub network: Network,
+
     /// Read handle to the blockchain database.
     pub blockchain_read: BlockchainReadHandle,
 
+    /// Write handle to the blockchain database.
+    pub blockchain_write: BlockchainWriteHandle,
+
     /// Handle to the blockchain context service.
     pub blockchain_context: BlockchainContextService,
 
     /// Read handle to the transaction pool database.
     pub txpool_read: TxpoolReadHandle,
-    // TODO: handle to txpool service.
+
+    /// Handle to the incoming transaction handler, used for local transactions.
+    pub tx_handler: IncomingTxHandler,
+
+    /// Handle to the clear-net address book, [`None`] if clear-net is disabled.
+    pub clearnet_address_book: Option<AddressBookHandle<ClearNet>>,
 }
 
 impl CupratedRpcHandler {
     /// Create a new [`Self`].
+    #[expect(clippy::too_many_arguments)]
     pub const fn new(
         restricted: bool,
+        max_batch_size: usize,
+        network: Network,
         blockchain_read: BlockchainReadHandle,
+        blockchain_write: BlockchainWriteHandle,
         blockchain_context: BlockchainContextService,
         txpool_read: TxpoolReadHandle,
+        tx_handler: IncomingTxHandler,
+        clearnet_address_book: Option<AddressBookHandle<ClearNet>>,
     ) -> Self {
         Self {
             restricted,
+            max_batch_size,
+            network,
             blockchain_read,
+            blockchain_write,
             blockchain_context,
             txpool_read,
+            tx_handler,
+            clearnet_address_book,
         }
     }
 }
@@ -193,6 +201,10 @@ impl RpcHandler for CupratedRpcHandler {
     fn is_restricted(&self) -> bool {
         self.restricted
     }
+
+    fn max_batch_size(&self) -> usize {
+        self.max_batch_size
+    }
 }
 
 impl Service<JsonRpcRequest> for CupratedRpcHandler {
@@ -11,14 +11,20 @@ use tower::limit::rate::RateLimitLayer;
 use tower_http::limit::RequestBodyLimitLayer;
 use tracing::{info, warn};
 
-use cuprate_blockchain::service::BlockchainReadHandle;
+use cuprate_blockchain::service::{BlockchainReadHandle, BlockchainWriteHandle};
 use cuprate_consensus::BlockchainContextService;
-use cuprate_rpc_interface::{RouterBuilder, RpcHandler};
+use cuprate_helper::network::Network;
+use cuprate_p2p_core::ClearNet;
+use cuprate_rpc_interface::{
+    DigestAuthLayer, DigestCredentials, IpLimitMakeService, IpLimits, RouterBuilder, RpcHandler,
+};
 use cuprate_txpool::service::TxpoolReadHandle;
 
 use crate::{
     config::RpcConfig,
+    p2p::AddressBookHandle,
     rpc::{rpc_handler::BlockchainManagerHandle, CupratedRpcHandler},
+    txpool::IncomingTxHandler,
 };
 
 /// Initialize the RPC server(s).
@@ -28,18 +34,29 @@ use crate::{
 /// - the server(s) could not be started
 /// - unrestricted RPC is started on non-local
 ///   address without override option
+#[expect(clippy::too_many_arguments)]
 pub fn init_rpc_servers(
     config: RpcConfig,
+    network: Network,
     blockchain_read: BlockchainReadHandle,
+    blockchain_write: BlockchainWriteHandle,
     blockchain_context: BlockchainContextService,
     txpool_read: TxpoolReadHandle,
+    tx_handler: IncomingTxHandler,
+    clearnet_address_book: Option<AddressBookHandle<ClearNet>>,
 ) {
-    for ((enable, addr, request_byte_limit), restricted) in [
+    for ((enable, addr, request_byte_limit, login, ip_limits, max_batch_size), restricted) in [
         (
             (
                 config.unrestricted.enable,
                 config.unrestricted.address,
                 config.unrestricted.request_byte_limit,
+                config.unrestricted.login,
+                IpLimits {
+                    requests_per_minute: config.unrestricted.requests_per_ip_per_minute,
+                    max_connections: config.unrestricted.connections_per_ip,
+                },
+                config.unrestricted.max_batch_size,
             ),
             false,
         ),
@@ -48,6 +65,12 @@ pub fn init_rpc_servers(
                 config.restricted.enable,
                 config.restricted.address,
                 config.restricted.request_byte_limit,
+                config.restricted.login,
+                IpLimits {
+                    requests_per_minute: config.restricted.requests_per_ip_per_minute,
+                    max_connections: config.restricted.connections_per_ip,
+                },
+                config.restricted.max_batch_size,
             ),
             true,
         ),
@@ -73,15 +96,27 @@ pub fn init_rpc_servers(
 
         let rpc_handler = CupratedRpcHandler::new(
             restricted,
+            max_batch_size,
+            network,
             blockchain_read.clone(),
+            blockchain_write.clone(),
             blockchain_context.clone(),
             txpool_read.clone(),
+            tx_handler.clone(),
+            clearnet_address_book.clone(),
         );
 
         tokio::task::spawn(async move {
-            run_rpc_server(rpc_handler, restricted, addr, request_byte_limit)
-                .await
-                .unwrap();
+            run_rpc_server(
+                rpc_handler,
+                restricted,
+                addr,
+                request_byte_limit,
+                login,
+                ip_limits,
+            )
+            .await
+            .unwrap();
         });
     }
 }
@@ -94,6 +129,8 @@ async fn run_rpc_server(
     restricted: bool,
     address: SocketAddr,
     request_byte_limit: usize,
+    login: String,
+    ip_limits: IpLimits,
 ) -> Result<(), Error> {
     info!(
         restricted,
@@ -101,30 +138,35 @@ async fn run_rpc_server(
         "Starting RPC server"
     );
 
-    // TODO:
-    // - add functions that are `all()` but for restricted RPC
-    // - enable aliases automatically `other_get_height` + `other_getheight`?
-    let router = RouterBuilder::new()
-        .json_rpc()
-        .other_get_height()
-        .fallback()
-        .build()
-        .with_state(rpc_handler);
+    // Restricted servers only enable the routes allowed on restricted RPC,
+    // unimplemented handlers return an error.
+    let router = if restricted {
+        RouterBuilder::new().all_restricted()
+    } else {
+        RouterBuilder::new().all()
+    }
+    .build()
+    .with_state(rpc_handler);
 
-    // Add restrictive layers if restricted RPC.
-    //
-    // TODO: <https://github.com/Cuprate/cuprate/issues/445>
+    // Add restrictive layers.
     let router = if request_byte_limit != 0 {
         router.layer(RequestBodyLimitLayer::new(request_byte_limit))
     } else {
         router
     };
 
+    // Authentication is checked before anything else.
+    let router = if login.is_empty() {
+        router
+    } else {
+        router.layer(DigestAuthLayer::new(DigestCredentials::from_login(&login)))
+    };
+
     // Start the server.
     //
     // TODO: impl custom server code, don't use axum.
     let listener = TcpListener::bind(address).await?;
-    axum::serve(listener, router).await?;
+    axum::serve(listener, IpLimitMakeService::new(router, ip_limits)).await?;
 
     Ok(())
 }
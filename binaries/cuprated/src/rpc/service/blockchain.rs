@@ -539,15 +539,15 @@ pub async fn block_complete_entries_by_height(
     Ok(blocks)
 }
 
-/// [`BlockchainReadRequest::TxOutputIndexes`].
-pub async fn tx_output_indexes(
+/// [`BlockchainReadRequest::TxsOutputIndexes`].
+pub async fn txs_output_indexes(
     blockchain_read: &mut BlockchainReadHandle,
-    tx_hash: [u8; 32],
-) -> Result<Vec<u64>, Error> {
-    let BlockchainResponse::TxOutputIndexes(o_indexes) = blockchain_read
+    tx_hashes: Vec<[u8; 32]>,
+) -> Result<Vec<Vec<u64>>, Error> {
+    let BlockchainResponse::TxsOutputIndexes(o_indexes) = blockchain_read
         .ready()
         .await?
-        .call(BlockchainReadRequest::TxOutputIndexes { tx_hash })
+        .call(BlockchainReadRequest::TxsOutputIndexes { tx_hashes })
         .await?
     else {
         unreachable!();
@@ -1,14 +1,12 @@
 //! Functions to send [`BlockchainManagerRequest`]s.
 
 use anyhow::Error;
-use monero_serai::block::Block;
 use tower::{Service, ServiceExt};
 
 use cuprate_helper::cast::{u64_to_usize, usize_to_u64};
 use cuprate_p2p_core::{types::ConnectionId, NetworkZone};
 use cuprate_pruning::PruningSeed;
 use cuprate_rpc_types::misc::Span;
-use cuprate_types::BlockTemplate;
 
 use crate::rpc::rpc_handler::{
     BlockchainManagerHandle, BlockchainManagerRequest, BlockchainManagerResponse,
@@ -61,23 +59,6 @@ pub async fn pruned(blockchain_manager: &mut BlockchainManagerHandle) -> Result<
     Ok(pruned)
 }
 
-/// [`BlockchainManagerRequest::RelayBlock`]
-pub async fn relay_block(
-    blockchain_manager: &mut BlockchainManagerHandle,
-    block: Box<Block>,
-) -> Result<(), Error> {
-    let BlockchainManagerResponse::Ok = blockchain_manager
-        .ready()
-        .await?
-        .call(BlockchainManagerRequest::RelayBlock(block))
-        .await?
-    else {
-        unreachable!();
-    };
-
-    Ok(())
-}
-
 /// [`BlockchainManagerRequest::Syncing`]
 pub async fn syncing(blockchain_manager: &mut BlockchainManagerHandle) -> Result<bool, Error> {
     let BlockchainManagerResponse::Syncing(syncing) = blockchain_manager
@@ -210,29 +191,6 @@ pub async fn next_needed_pruning_seed(
     Ok(seed)
 }
 
-/// [`BlockchainManagerRequest::CreateBlockTemplate`]
-pub async fn create_block_template(
-    blockchain_manager: &mut BlockchainManagerHandle,
-    prev_block: [u8; 32],
-    account_public_address: String,
-    extra_nonce: Vec<u8>,
-) -> Result<Box<BlockTemplate>, Error> {
-    let BlockchainManagerResponse::CreateBlockTemplate(block_template) = blockchain_manager
-        .ready()
-        .await?
-        .call(BlockchainManagerRequest::CreateBlockTemplate {
-            prev_block,
-            account_public_address,
-            extra_nonce,
-        })
-        .await?
-    else {
-        unreachable!();
-    };
-
-    Ok(block_template)
-}
-
 /// [`BlockchainManagerRequest::Sync`]
 pub async fn sync(blockchain_manager: &mut BlockchainManagerHandle) -> Result<(), Error> {
     let BlockchainManagerResponse::Ok = blockchain_manager
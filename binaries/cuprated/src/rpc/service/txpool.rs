@@ -6,6 +6,9 @@ use anyhow::{anyhow, Error};
 use monero_serai::transaction::Transaction;
 use tower::{Service, ServiceExt};
 
+use cuprate_consensus::ExtendedConsensusError;
+use cuprate_consensus_rules::{transactions::TransactionError, ConsensusError};
+use cuprate_dandelion_tower::TxState;
 use cuprate_helper::cast::usize_to_u64;
 use cuprate_rpc_types::misc::{SpentKeyImageInfo, TxInfo};
 use cuprate_txpool::{
@@ -20,6 +23,8 @@ use cuprate_types::{
     TxInPool, TxRelayChecks,
 };
 
+use crate::txpool::{IncomingTxError, IncomingTxHandler, IncomingTxs, RelayRuleError};
+
 // FIXME: use `anyhow::Error` over `tower::BoxError` in txpool.
 
 /// [`TxpoolReadRequest::Backlog`]
@@ -234,11 +239,56 @@ pub async fn relay(txpool_manager: &mut Infallible, tx_hashes: Vec<[u8; 32]>) ->
     Ok(())
 }
 
-/// TODO: impl txpool manager.
+/// Adds a local tx to the pool and, if `relay` is `true`, relays it, returning the checks the tx failed.
+///
+/// A tx that is not relayed stays hidden in the stem pool, like monerod's `do_not_relay`.
+///
+/// An empty [`TxRelayChecks`] means the tx was accepted.
 pub async fn check_maybe_relay_local(
-    txpool_manager: &mut Infallible,
+    tx_handler: &mut IncomingTxHandler,
     tx: Transaction,
     relay: bool,
 ) -> Result<TxRelayChecks, Error> {
-    Ok(todo!())
+    let res = tx_handler
+        .ready()
+        .await
+        .map_err(|e| anyhow!(e))?
+        .call(IncomingTxs {
+            txs: vec![tx.serialize().into()],
+            state: TxState::Local,
+            relay,
+        })
+        .await;
+
+    let err = match res {
+        Ok(()) => return Ok(TxRelayChecks::empty()),
+        Err(e) => e,
+    };
+
+    Ok(match err {
+        IncomingTxError::DoubleSpend => TxRelayChecks::DOUBLE_SPEND,
+        IncomingTxError::RelayRule(e) => match e {
+            RelayRuleError::NonZeroTimelock => TxRelayChecks::NONZERO_UNLOCK_TIME,
+            RelayRuleError::ExtraFieldTooLarge => TxRelayChecks::TX_EXTRA_TOO_BIG,
+            RelayRuleError::FeeBelowMinimum => TxRelayChecks::FEE_TOO_LOW,
+        },
+        IncomingTxError::Consensus(ExtendedConsensusError::ConErr(
+            ConsensusError::Transaction(e),
+        )) => match e {
+            TransactionError::KeyImageSpent => TxRelayChecks::DOUBLE_SPEND,
+            TransactionError::TooBig => TxRelayChecks::TOO_BIG,
+            TransactionError::InputDoesNotHaveExpectedNumbDecoys => TxRelayChecks::LOW_MIXIN,
+            TransactionError::InvalidNumberOfOutputs => TxRelayChecks::TOO_FEW_OUTPUTS,
+            TransactionError::OutputsOverflow | TransactionError::OutputsTooHigh => {
+                TxRelayChecks::OVERSPEND
+            }
+            TransactionError::OutputNotValidPoint
+            | TransactionError::OutputTypeInvalid
+            | TransactionError::ZeroOutputForV1
+            | TransactionError::NonZeroOutputForV2
+            | TransactionError::AmountNotDecomposed => TxRelayChecks::INVALID_OUTPUT,
+            _ => TxRelayChecks::INVALID_INPUT,
+        },
+        e => return Err(anyhow!(e)),
+    })
 }
@@ -0,0 +1,67 @@
+//! Test helpers for the RPC handlers.
+
+use std::path::Path;
+
+use tower::BoxError;
+
+use cuprate_consensus_context::ContextConfig;
+use cuprate_helper::network::Network;
+use cuprate_p2p::NetworkInterface;
+use cuprate_p2p_core::ClearNet;
+
+use crate::{
+    blockchain::{check_add_genesis, ConsensusBlockchainReadHandle},
+    rpc::CupratedRpcHandler,
+    txpool::{AnonZones, IncomingTxHandler},
+};
+
+/// Returns an unrestricted [`CupratedRpcHandler`] for a mainnet node whose chain only has
+/// the genesis block, with its databases in `data_dir`.
+pub(super) async fn mock_rpc_handler(data_dir: &Path) -> CupratedRpcHandler {
+    let blockchain_config = cuprate_blockchain::config::ConfigBuilder::new()
+        .data_directory(data_dir.to_path_buf())
+        .build();
+    let txpool_config = cuprate_txpool::config::ConfigBuilder::new()
+        .data_directory(data_dir.to_path_buf())
+        .build();
+
+    let (mut blockchain_read_handle, mut blockchain_write_handle, _) =
+        cuprate_blockchain::service::init(blockchain_config).unwrap();
+    let (txpool_read_handle, txpool_write_handle, _) =
+        cuprate_txpool::service::init(txpool_config).unwrap();
+
+    check_add_genesis(
+        &mut blockchain_read_handle,
+        &mut blockchain_write_handle,
+        Network::Mainnet,
+    )
+    .await;
+
+    let blockchain_context = cuprate_consensus_context::initialize_blockchain_context(
+        ContextConfig::main_net(),
+        ConsensusBlockchainReadHandle::new(blockchain_read_handle.clone(), BoxError::from),
+    )
+    .await
+    .unwrap();
+
+    let tx_handler = IncomingTxHandler::init(
+        NetworkInterface::<ClearNet>::mock(),
+        AnonZones::default(),
+        txpool_write_handle,
+        txpool_read_handle.clone(),
+        blockchain_context.clone(),
+        blockchain_read_handle.clone(),
+    );
+
+    CupratedRpcHandler::new(
+        false,
+        100,
+        Network::Mainnet,
+        blockchain_read_handle,
+        blockchain_write_handle,
+        blockchain_context,
+        txpool_read_handle,
+        tx_handler,
+        None,
+    )
+}
@@ -8,7 +8,11 @@ use cuprate_txpool::service::{TxpoolReadHandle, TxpoolWriteHandle};
 
 mod dandelion;
 mod incoming_tx;
+mod maintenance;
 mod relay_rules;
 mod txs_being_handled;
 
+pub use dandelion::{AnonTxService, AnonZones};
 pub use incoming_tx::{IncomingTxError, IncomingTxHandler, IncomingTxs};
+pub use maintenance::start_txpool_maintenance;
+pub use relay_rules::RelayRuleError;
@@ -1,10 +1,18 @@
-use std::time::Duration;
+use std::{
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, FutureExt, TryFutureExt};
+use rand::{thread_rng, Rng};
+use tower::Service;
 
 use cuprate_dandelion_tower::{
-    pool::DandelionPoolService, DandelionConfig, DandelionRouter, Graph,
+    pool::DandelionPoolService, DandelionConfig, DandelionRouteReq, DandelionRouter,
+    DandelionRouterError, Graph, State, TxState,
 };
 use cuprate_p2p::NetworkInterface;
-use cuprate_p2p_core::ClearNet;
+use cuprate_p2p_core::{client::InternalPeerID, I2p, NetworkZone, Tor};
 use cuprate_txpool::service::{TxpoolReadHandle, TxpoolWriteHandle};
 
 use crate::{
@@ -12,10 +20,15 @@ use crate::{
     txpool::incoming_tx::{DandelionTx, TxId},
 };
 
+mod anon_net_service;
 mod diffuse_service;
 mod stem_service;
+#[cfg(test)]
+mod tests;
 mod tx_store;
 
+pub use anon_net_service::{AnonTxService, AnonymousTxs};
+
 /// The configuration used for [`cuprate_dandelion_tower`].
 ///
 /// TODO: should we expose this to users of cuprated? probably not.
@@ -26,21 +39,174 @@ const DANDELION_CONFIG: DandelionConfig = DandelionConfig {
     graph: Graph::FourRegular,
 };
 
-/// A [`DandelionRouter`] with all generic types defined.
-type ConcreteDandelionRouter = DandelionRouter<
-    stem_service::OutboundPeerStream,
-    diffuse_service::DiffuseService,
+/// A [`DandelionRouter`] for the network zone `Z`, with all other generic types defined.
+type ConcreteDandelionRouter<Z> = DandelionRouter<
+    stem_service::OutboundPeerStream<Z>,
+    diffuse_service::DiffuseService<Z>,
     CrossNetworkInternalPeerId,
-    stem_service::StemPeerService<ClearNet>,
+    stem_service::StemPeerService<Z>,
     DandelionTx,
 >;
 
+/// The anonymity network zones local txs are routed over.
+#[derive(Clone, Default)]
+pub struct AnonZones {
+    /// The I2P zone.
+    pub i2p: Option<AnonTxService<I2p>>,
+    /// The Tor zone.
+    pub tor: Option<AnonTxService<Tor>>,
+}
+
+impl AnonZones {
+    /// Returns `true` if no anonymity zone is used for local txs.
+    pub const fn is_empty(&self) -> bool {
+        self.i2p.is_none() && self.tor.is_none()
+    }
+}
+
+impl Service<DandelionRouteReq<DandelionTx, CrossNetworkInternalPeerId>> for AnonZones {
+    type Response = State;
+    type Error = DandelionRouterError;
+    type Future = BoxFuture<'static, Result<State, DandelionRouterError>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if let Some(i2p) = &mut self.i2p {
+            ready!(i2p.poll_ready(cx))?;
+        }
+
+        if let Some(tor) = &mut self.tor {
+            ready!(tor.poll_ready(cx))?;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    /// Routes a tx over one of the anonymity zones, chosen at random if there are multiple.
+    ///
+    /// # Panics
+    /// This will panic if [`Self::is_empty`].
+    fn call(
+        &mut self,
+        req: DandelionRouteReq<DandelionTx, CrossNetworkInternalPeerId>,
+    ) -> Self::Future {
+        match (&mut self.i2p, &mut self.tor) {
+            (Some(i2p), Some(tor)) => {
+                if thread_rng().gen_bool(0.5) {
+                    i2p.call(req)
+                } else {
+                    tor.call(req)
+                }
+            }
+            (Some(i2p), None) => i2p.call(req),
+            (None, Some(tor)) => tor.call(req),
+            (None, None) => panic!("No anonymity zones to send local tx over"),
+        }
+    }
+}
+
+/// The dandelion router given to the pool manager.
+///
+/// This routes txs with the main zone's router `R`, except local txs when anonymity zones `A` are set,
+/// which are only ever sent over an anonymity zone until they are seen coming back from the network.
+///
+/// If the embargo timer of such a tx fires first, it is fluffed over an anonymity zone and then routed
+/// like any other tx.
+pub struct MainDandelionRouter<R, A> {
+    /// The main zone's router.
+    main_router: R,
+    /// The anonymity zones to send local txs over, [`None`] if local txs use the main zone.
+    anon_zones: Option<A>,
+    /// The local txs that must not be sent over the main zone.
+    anonymous_txs: AnonymousTxs,
+}
+
+impl<R, A> MainDandelionRouter<R, A> {
+    /// Create a new [`MainDandelionRouter`].
+    pub const fn new(main_router: R, anon_zones: Option<A>, anonymous_txs: AnonymousTxs) -> Self {
+        Self {
+            main_router,
+            anon_zones,
+            anonymous_txs,
+        }
+    }
+}
+
+impl<R, A> Service<DandelionRouteReq<DandelionTx, CrossNetworkInternalPeerId>>
+    for MainDandelionRouter<R, A>
+where
+    R: Service<
+        DandelionRouteReq<DandelionTx, CrossNetworkInternalPeerId>,
+        Response = State,
+        Error = DandelionRouterError,
+    >,
+    R::Future: Send + 'static,
+    A: Service<
+        DandelionRouteReq<DandelionTx, CrossNetworkInternalPeerId>,
+        Response = State,
+        Error = DandelionRouterError,
+    >,
+    A::Future: Send + 'static,
+{
+    type Response = State;
+    type Error = DandelionRouterError;
+    type Future = BoxFuture<'static, Result<State, DandelionRouterError>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Which service a request goes to depends on the tx, so both must be ready.
+        ready!(self.main_router.poll_ready(cx))?;
+
+        if let Some(anon_zones) = &mut self.anon_zones {
+            ready!(anon_zones.poll_ready(cx))?;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(
+        &mut self,
+        req: DandelionRouteReq<DandelionTx, CrossNetworkInternalPeerId>,
+    ) -> Self::Future {
+        let Some(anon_zones) = &mut self.anon_zones else {
+            return self.main_router.call(req).boxed();
+        };
+
+        let tx_hash = req.tx.tx_hash;
+
+        match req.state {
+            TxState::Local => {
+                self.anonymous_txs.insert(tx_hash);
+                anon_zones.call(req).boxed()
+            }
+            _ if !self.anonymous_txs.contains(&tx_hash) => self.main_router.call(req).boxed(),
+            // The tx was not seen back from the network in time, once it is fluffed over the anonymity
+            // zone there is nothing left to hide, so let the pool manager promote it.
+            TxState::Fluff => {
+                let anonymous_txs = self.anonymous_txs.clone();
+
+                anon_zones
+                    .call(req)
+                    .map_ok(move |state| {
+                        anonymous_txs.remove(&tx_hash);
+                        state
+                    })
+                    .boxed()
+            }
+            TxState::Stem { .. } => anon_zones.call(req).boxed(),
+        }
+    }
+}
+
 /// Starts the dandelion pool manager task and returns a handle to send txs to broadcast.
-pub fn start_dandelion_pool_manager(
-    router: ConcreteDandelionRouter,
+pub fn start_dandelion_pool_manager<Z: NetworkZone>(
+    router: MainDandelionRouter<ConcreteDandelionRouter<Z>, AnonZones>,
     txpool_read_handle: TxpoolReadHandle,
     txpool_write_handle: TxpoolWriteHandle,
-) -> DandelionPoolService<DandelionTx, TxId, CrossNetworkInternalPeerId> {
+) -> DandelionPoolService<DandelionTx, TxId, CrossNetworkInternalPeerId>
+where
+    CrossNetworkInternalPeerId: From<InternalPeerID<Z::Addr>>,
+{
+    let anonymous_txs = router.anonymous_txs.clone();
+
     cuprate_dandelion_tower::pool::start_dandelion_pool_manager(
         // TODO: make this constant configurable?
         32,
@@ -48,18 +214,27 @@ pub fn start_dandelion_pool_manager(
         tx_store::TxStoreService {
             txpool_read_handle,
             txpool_write_handle,
+            anonymous_txs,
         },
         DANDELION_CONFIG,
     )
 }
 
-/// Creates a [`DandelionRouter`] from a [`NetworkInterface`].
-pub fn dandelion_router(clear_net: NetworkInterface<ClearNet>) -> ConcreteDandelionRouter {
-    DandelionRouter::new(
-        diffuse_service::DiffuseService {
-            clear_net_broadcast_service: clear_net.broadcast_svc(),
-        },
-        stem_service::OutboundPeerStream::new(clear_net),
-        DANDELION_CONFIG,
+/// Creates a [`MainDandelionRouter`] from the main zone's [`NetworkInterface`] and the [`AnonZones`] to send local txs over.
+pub fn dandelion_router<Z: NetworkZone>(
+    network_interface: NetworkInterface<Z>,
+    anon_zones: AnonZones,
+    anonymous_txs: AnonymousTxs,
+) -> MainDandelionRouter<ConcreteDandelionRouter<Z>, AnonZones> {
+    MainDandelionRouter::new(
+        DandelionRouter::new(
+            diffuse_service::DiffuseService {
+                broadcast_service: network_interface.broadcast_svc(),
+            },
+            stem_service::OutboundPeerStream::new(network_interface),
+            DANDELION_CONFIG,
+        ),
+        (!anon_zones.is_empty()).then_some(anon_zones),
+        anonymous_txs,
     )
 }
@@ -0,0 +1,156 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use dashmap::DashSet;
+use futures::{future::BoxFuture, FutureExt, TryFutureExt};
+use tower::{Service, ServiceExt};
+
+use cuprate_dandelion_tower::{
+    traits::{DiffuseRequest, StemRequest},
+    DandelionRouteReq, DandelionRouterError, State, TxState,
+};
+use cuprate_p2p::{NetworkInterface, PeerSetRequest, PeerSetResponse};
+use cuprate_p2p_core::NetworkZone;
+
+use crate::{
+    p2p::CrossNetworkInternalPeerId,
+    txpool::dandelion::{
+        diffuse_service::DiffuseService, stem_service::StemPeerService, DandelionTx, TxId,
+    },
+};
+
+/// The amount of time to wait before looking for a peer again, when an anonymity zone has no peers.
+const NO_PEERS_RETRY_DELAY: Duration = Duration::from_secs(15);
+
+/// The set of local txs which must only be sent over anonymity zones.
+///
+/// A tx is removed once it is seen coming back from the network, after which it is routed like any other.
+#[derive(Clone)]
+pub struct AnonymousTxs(Arc<DashSet<TxId>>);
+
+impl AnonymousTxs {
+    /// Create a new, empty [`AnonymousTxs`].
+    pub fn new() -> Self {
+        Self(Arc::new(DashSet::new()))
+    }
+
+    /// Add a tx to the set.
+    pub fn insert(&self, tx_hash: TxId) {
+        self.0.insert(tx_hash);
+    }
+
+    /// Returns `true` if the tx must only be sent over anonymity zones.
+    pub fn contains(&self, tx_hash: &TxId) -> bool {
+        self.0.contains(tx_hash)
+    }
+
+    /// Remove a tx from the set.
+    pub fn remove(&self, tx_hash: &TxId) {
+        self.0.remove(tx_hash);
+    }
+}
+
+/// The service used to route local txs over an anonymity network zone.
+///
+/// Stem requests are sent to a single outbound peer of the zone, fluff requests are broadcast to the zone.
+#[derive(Clone)]
+pub struct AnonTxService<Z: NetworkZone> {
+    network_interface: NetworkInterface<Z>,
+    diffuse_service: DiffuseService<Z>,
+}
+
+impl<Z: NetworkZone> AnonTxService<Z> {
+    /// Create a new [`AnonTxService`] from the zone's [`NetworkInterface`].
+    pub fn new(network_interface: NetworkInterface<Z>) -> Self {
+        Self {
+            diffuse_service: DiffuseService {
+                broadcast_service: network_interface.broadcast_svc(),
+            },
+            network_interface,
+        }
+    }
+
+    /// Stems a tx to a random outbound peer of the zone.
+    ///
+    /// If the zone currently has no peers this will wait until it does, the tx is never given to
+    /// another zone.
+    fn stem_tx(
+        &mut self,
+        tx: DandelionTx,
+    ) -> BoxFuture<'static, Result<State, DandelionRouterError>> {
+        let mut peer_set = self.network_interface.peer_set().clone();
+
+        async move {
+            loop {
+                let PeerSetResponse::StemPeer(peer) = peer_set
+                    .ready()
+                    .await
+                    .map_err(DandelionRouterError::OutboundPeerStreamError)?
+                    .call(PeerSetRequest::StemPeer)
+                    .await
+                    .map_err(DandelionRouterError::OutboundPeerStreamError)?
+                else {
+                    unreachable!()
+                };
+
+                let Some(peer) = peer else {
+                    tracing::debug!(
+                        "No {} peers to send local tx to, waiting for a connection.",
+                        Z::NAME
+                    );
+                    tokio::time::sleep(NO_PEERS_RETRY_DELAY).await;
+                    continue;
+                };
+
+                tracing::debug!("Sending local tx over {}.", Z::NAME);
+
+                StemPeerService(peer)
+                    .ready()
+                    .await
+                    .map_err(DandelionRouterError::PeerError)?
+                    .call(StemRequest(tx))
+                    .await
+                    .map_err(DandelionRouterError::PeerError)?;
+
+                return Ok(State::Stem);
+            }
+        }
+        .boxed()
+    }
+}
+
+impl<Z: NetworkZone> Service<DandelionRouteReq<DandelionTx, CrossNetworkInternalPeerId>>
+    for AnonTxService<Z>
+{
+    type Response = State;
+    type Error = DandelionRouterError;
+    type Future = BoxFuture<'static, Result<State, DandelionRouterError>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The peer set is cloned into the stem future, which waits for it to be ready.
+        self.diffuse_service
+            .poll_ready(cx)
+            .map_err(DandelionRouterError::BroadcastError)
+    }
+
+    fn call(
+        &mut self,
+        req: DandelionRouteReq<DandelionTx, CrossNetworkInternalPeerId>,
+    ) -> Self::Future {
+        match req.state {
+            TxState::Fluff => {
+                tracing::debug!("Fluffing local tx over {}.", Z::NAME);
+
+                self.diffuse_service
+                    .call(DiffuseRequest(req.tx))
+                    .map_ok(|()| State::Fluff)
+                    .map_err(DandelionRouterError::BroadcastError)
+                    .boxed()
+            }
+            TxState::Stem { .. } | TxState::Local => self.stem_tx(req.tx),
+        }
+    }
+}
@@ -8,32 +8,31 @@ use tower::Service;
 
 use cuprate_dandelion_tower::traits::DiffuseRequest;
 use cuprate_p2p::{BroadcastRequest, BroadcastSvc};
-use cuprate_p2p_core::ClearNet;
+use cuprate_p2p_core::NetworkZone;
 
 use crate::txpool::dandelion::DandelionTx;
 
 /// The dandelion diffusion service.
-pub struct DiffuseService {
-    pub clear_net_broadcast_service: BroadcastSvc<ClearNet>,
+#[derive(Clone)]
+pub struct DiffuseService<Z: NetworkZone> {
+    pub broadcast_service: BroadcastSvc<Z>,
 }
 
-impl Service<DiffuseRequest<DandelionTx>> for DiffuseService {
+impl<Z: NetworkZone> Service<DiffuseRequest<DandelionTx>> for DiffuseService<Z> {
     type Response = ();
     type Error = tower::BoxError;
     type Future = Ready<Result<Self::Response, Self::Error>>;
 
     fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.clear_net_broadcast_service
-            .poll_ready(cx)
-            .map_err(Into::into)
+        self.broadcast_service.poll_ready(cx).map_err(Into::into)
     }
 
     fn call(&mut self, req: DiffuseRequest<DandelionTx>) -> Self::Future {
         // TODO: the dandelion crate should pass along where we got the tx from.
         let Ok(()) = self
-            .clear_net_broadcast_service
+            .broadcast_service
             .call(BroadcastRequest::Transaction {
-                tx_bytes: req.0 .0,
+                tx_bytes: req.0.tx_blob,
                 direction: None,
                 received_from: None,
             })
@@ -0,0 +1,227 @@
+use std::{future::ready, time::Duration};
+
+use bytes::Bytes;
+use tokio::sync::mpsc::{self, error::TryRecvError, UnboundedReceiver};
+use tower::{service_fn, Service, ServiceExt};
+
+use cuprate_dandelion_tower::{
+    pool::{start_dandelion_pool_manager, IncomingTxBuilder},
+    traits::{TxStoreRequest, TxStoreResponse},
+    DandelionConfig, DandelionRouteReq, DandelionRouterError, Graph, State, TxState,
+};
+use cuprate_p2p_core::client::InternalPeerID;
+
+use crate::{
+    p2p::CrossNetworkInternalPeerId,
+    txpool::dandelion::{AnonZones, AnonymousTxs, DandelionTx, MainDandelionRouter, TxId},
+};
+
+type RouteReq = DandelionRouteReq<DandelionTx, CrossNetworkInternalPeerId>;
+
+/// A router that records the state of every request it gets and fluffs txs if asked to.
+fn mock_router() -> (
+    impl Service<RouteReq, Response = State, Error = DandelionRouterError, Future: Send + 'static>
+        + Send
+        + 'static,
+    UnboundedReceiver<(TxId, TxState<CrossNetworkInternalPeerId>)>,
+) {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let router = service_fn(move |req: RouteReq| {
+        let state = match req.state {
+            TxState::Fluff => State::Fluff,
+            TxState::Stem { .. } | TxState::Local => State::Stem,
+        };
+
+        tx.send((req.tx.tx_hash, req.state)).unwrap();
+        ready(Ok(state))
+    });
+
+    (router, rx)
+}
+
+fn dandelion_tx(tx_hash: TxId) -> DandelionTx {
+    DandelionTx {
+        tx_blob: Bytes::from_static(&[1, 2, 3]),
+        tx_hash,
+    }
+}
+
+/// Waits for the next message, giving plenty of time for an embargo timer to fire.
+async fn recv<T>(rx: &mut UnboundedReceiver<T>) -> T {
+    tokio::time::timeout(Duration::from_secs(60), rx.recv())
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+const CLEAR_NET_PEER: CrossNetworkInternalPeerId =
+    CrossNetworkInternalPeerId::ClearNet(InternalPeerID::Unknown(1));
+
+#[tokio::test]
+async fn local_tx_never_routed_to_main_zone() {
+    let (main_router, mut main_rx) = mock_router();
+    let (anon_zones, mut anon_rx) = mock_router();
+    let anonymous_txs = AnonymousTxs::new();
+
+    let mut router = MainDandelionRouter::new(main_router, Some(anon_zones), anonymous_txs.clone());
+
+    let local_tx = [1; 32];
+
+    for state in [
+        TxState::Local,
+        TxState::Stem {
+            from: CrossNetworkInternalPeerId::Tor(InternalPeerID::Unknown(2)),
+        },
+        TxState::Stem {
+            from: CLEAR_NET_PEER,
+        },
+    ] {
+        let res = router
+            .ready()
+            .await
+            .unwrap()
+            .call(DandelionRouteReq {
+                tx: dandelion_tx(local_tx),
+                state: state.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(res, State::Stem);
+        assert_eq!(anon_rx.try_recv().unwrap(), (local_tx, state));
+        assert!(anonymous_txs.contains(&local_tx));
+    }
+
+    // Other txs use the main zone.
+    let other_tx = [2; 32];
+    router
+        .ready()
+        .await
+        .unwrap()
+        .call(DandelionRouteReq {
+            tx: dandelion_tx(other_tx),
+            state: TxState::Stem {
+                from: CLEAR_NET_PEER,
+            },
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(anon_rx.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(main_rx.try_recv().unwrap().0, other_tx);
+
+    // Once the local tx is seen back from the network it is routed like any other.
+    anonymous_txs.remove(&local_tx);
+    router
+        .ready()
+        .await
+        .unwrap()
+        .call(DandelionRouteReq {
+            tx: dandelion_tx(local_tx),
+            state: TxState::Fluff,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(anon_rx.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(main_rx.try_recv().unwrap(), (local_tx, TxState::Fluff));
+}
+
+#[tokio::test]
+async fn no_anon_zones_uses_main_zone() {
+    let (main_router, mut main_rx) = mock_router();
+    let anonymous_txs = AnonymousTxs::new();
+
+    let mut router =
+        MainDandelionRouter::<_, AnonZones>::new(main_router, None, anonymous_txs.clone());
+
+    router
+        .ready()
+        .await
+        .unwrap()
+        .call(DandelionRouteReq {
+            tx: dandelion_tx([1; 32]),
+            state: TxState::Local,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(main_rx.try_recv().unwrap(), ([1; 32], TxState::Local));
+    assert!(!anonymous_txs.contains(&[1; 32]));
+}
+
+/// When the embargo timer of a local tx fires before it is seen back from the network it must be fluffed
+/// over the anonymity zone, and only then promoted.
+#[tokio::test]
+async fn embargo_fluffs_over_anon_zone() {
+    let (main_router, mut main_rx) = mock_router();
+    let (anon_zones, mut anon_rx) = mock_router();
+    let anonymous_txs = AnonymousTxs::new();
+
+    let router = MainDandelionRouter::new(main_router, Some(anon_zones), anonymous_txs.clone());
+
+    let local_tx = [1; 32];
+
+    // Records each promotion, with if the tx was still hidden at the time.
+    let (promote_tx, mut promote_rx) = mpsc::unbounded_channel();
+    let tx_store = service_fn({
+        let anonymous_txs = anonymous_txs.clone();
+        move |req: TxStoreRequest<TxId>| {
+            let res = match req {
+                TxStoreRequest::Get(tx_id) => {
+                    TxStoreResponse::Transaction(Some((dandelion_tx(tx_id), State::Stem)))
+                }
+                TxStoreRequest::Promote(tx_id) => {
+                    promote_tx
+                        .send((tx_id, anonymous_txs.contains(&tx_id)))
+                        .unwrap();
+                    TxStoreResponse::Ok
+                }
+            };
+
+            ready(Ok::<_, tower::BoxError>(res))
+        }
+    });
+
+    let mut pool = start_dandelion_pool_manager(
+        8,
+        router,
+        tx_store,
+        DandelionConfig {
+            time_between_hop: Duration::from_millis(1),
+            epoch_duration: Duration::from_secs(60),
+            fluff_probability: 0.12,
+            graph: Graph::FourRegular,
+        },
+    );
+
+    pool.ready()
+        .await
+        .unwrap()
+        .call(
+            IncomingTxBuilder::new(dandelion_tx(local_tx), local_tx)
+                .with_routing_state(TxState::Local)
+                .with_state_in_db(None)
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(recv(&mut anon_rx).await, (local_tx, TxState::Local));
+    assert_eq!(recv(&mut anon_rx).await, (local_tx, TxState::Fluff));
+
+    // The tx is only promoted once it has been fluffed over the anonymity zone.
+    loop {
+        let (tx_id, hidden) = recv(&mut promote_rx).await;
+        assert_eq!(tx_id, local_tx);
+
+        if !hidden {
+            break;
+        }
+    }
+
+    assert!(!anonymous_txs.contains(&local_tx));
+    assert_eq!(main_rx.try_recv(), Err(TryRecvError::Empty));
+}
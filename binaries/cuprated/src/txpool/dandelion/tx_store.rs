@@ -1,4 +1,7 @@
-use std::task::{Context, Poll};
+use std::{
+    future::ready,
+    task::{Context, Poll},
+};
 
 use bytes::Bytes;
 use futures::{future::BoxFuture, FutureExt};
@@ -14,14 +17,21 @@ use cuprate_txpool::service::{
     TxpoolReadHandle, TxpoolWriteHandle,
 };
 
-use super::{DandelionTx, TxId};
+use super::{AnonymousTxs, DandelionTx, TxId};
+use crate::zmq;
 
 /// The dandelion tx-store service.
 ///
 /// This is just mapping the interface [`cuprate_dandelion_tower`] wants to what [`cuprate_txpool`] provides.
+///
+/// Txs in [`AnonymousTxs`] are never promoted, they stay hidden in the stem pool until they are seen
+/// coming back from the network.
+///
+/// Promoted txs are published to the ZMQ `txpool_add` topics, as they were not when added to the stem pool.
 pub struct TxStoreService {
     pub txpool_read_handle: TxpoolReadHandle,
     pub txpool_write_handle: TxpoolWriteHandle,
+    pub anonymous_txs: AnonymousTxs,
 }
 
 impl Service<TxStoreRequest<TxId>> for TxStoreService {
@@ -35,40 +45,72 @@ impl Service<TxStoreRequest<TxId>> for TxStoreService {
 
     fn call(&mut self, req: TxStoreRequest<TxId>) -> Self::Future {
         match req {
-            TxStoreRequest::Get(tx_id) => self
-                .txpool_read_handle
-                .clone()
-                .oneshot(TxpoolReadRequest::TxBlob(tx_id))
-                .map(|res| match res {
-                    Ok(TxpoolReadResponse::TxBlob {
-                        tx_blob,
-                        state_stem,
-                    }) => {
-                        let state = if state_stem {
-                            State::Stem
-                        } else {
-                            State::Fluff
-                        };
+            TxStoreRequest::Get(tx_id) => {
+                let anonymous_txs = self.anonymous_txs.clone();
+
+                self.txpool_read_handle
+                    .clone()
+                    .oneshot(TxpoolReadRequest::TxBlob(tx_id))
+                    .map(move |res| match res {
+                        Ok(TxpoolReadResponse::TxBlob {
+                            tx_blob,
+                            state_stem,
+                        }) => {
+                            let state = if state_stem {
+                                State::Stem
+                            } else {
+                                State::Fluff
+                            };
 
-                        Ok(TxStoreResponse::Transaction(Some((
-                            DandelionTx(Bytes::from(tx_blob)),
-                            state,
-                        ))))
+                            Ok(TxStoreResponse::Transaction(Some((
+                                DandelionTx {
+                                    tx_blob: Bytes::from(tx_blob),
+                                    tx_hash: tx_id,
+                                },
+                                state,
+                            ))))
+                        }
+                        Err(RuntimeError::KeyNotFound) => {
+                            // The tx left the pool, there is nothing left to hide.
+                            anonymous_txs.remove(&tx_id);
+                            Ok(TxStoreResponse::Transaction(None))
+                        }
+                        Err(e) => Err(e.into()),
+                        Ok(_) => unreachable!(),
+                    })
+                    .boxed()
+            }
+            TxStoreRequest::Promote(tx_id) if self.anonymous_txs.contains(&tx_id) => {
+                ready(Ok(TxStoreResponse::Ok)).boxed()
+            }
+            TxStoreRequest::Promote(tx_id) => {
+                let promote = self
+                    .txpool_write_handle
+                    .clone()
+                    .oneshot(TxpoolWriteRequest::Promote(tx_id));
+                let txpool_read_handle = self.txpool_read_handle.clone();
+
+                async move {
+                    match promote.await {
+                        Ok(_) => (),
+                        Err(RuntimeError::KeyNotFound) => return Ok(TxStoreResponse::Ok),
+                        Err(e) => return Err(e.into()),
+                    }
+
+                    // Only stem txs are promoted, so the tx has just become public.
+                    if zmq::enabled() {
+                        if let Ok(TxpoolReadResponse::TxVerificationData(tx)) = txpool_read_handle
+                            .oneshot(TxpoolReadRequest::TxVerificationData(tx_id))
+                            .await
+                        {
+                            zmq::publish_txpool_add(tx);
+                        }
                     }
-                    Err(RuntimeError::KeyNotFound) => Ok(TxStoreResponse::Transaction(None)),
-                    Err(e) => Err(e.into()),
-                    Ok(_) => unreachable!(),
-                })
-                .boxed(),
-            TxStoreRequest::Promote(tx_id) => self
-                .txpool_write_handle
-                .clone()
-                .oneshot(TxpoolWriteRequest::Promote(tx_id))
-                .map(|res| match res {
-                    Ok(_) | Err(RuntimeError::KeyNotFound) => Ok(TxStoreResponse::Ok),
-                    Err(e) => Err(e.into()),
-                })
-                .boxed(),
+
+                    Ok(TxStoreResponse::Ok)
+                }
+                .boxed()
+            }
         }
     }
 }
@@ -21,7 +21,7 @@ use cuprate_dandelion_tower::{
 };
 use cuprate_helper::asynch::rayon_spawn_async;
 use cuprate_p2p::NetworkInterface;
-use cuprate_p2p_core::ClearNet;
+use cuprate_p2p_core::{client::InternalPeerID, NetworkZone};
 use cuprate_txpool::{
     service::{
         interface::{
@@ -39,10 +39,11 @@ use crate::{
     p2p::CrossNetworkInternalPeerId,
     signals::REORG_LOCK,
     txpool::{
-        dandelion,
-        relay_rules::check_tx_relay_rules,
+        dandelion::{self, AnonZones, AnonymousTxs},
+        relay_rules::{check_tx_relay_rules, RelayRuleError},
         txs_being_handled::{TxsBeingHandled, TxsBeingHandledLocally},
     },
+    zmq,
 };
 
 /// An error that can happen handling an incoming tx.
@@ -54,6 +55,10 @@ pub enum IncomingTxError {
     Consensus(ExtendedConsensusError),
     #[error("Duplicate tx in message")]
     DuplicateTransaction,
+    #[error("Local tx failed relay rules: {0}")]
+    RelayRule(RelayRuleError),
+    #[error("Local tx double spends a tx in the pool")]
+    DoubleSpend,
 }
 
 /// Incoming transactions.
@@ -62,11 +67,21 @@ pub struct IncomingTxs {
     pub txs: Vec<Bytes>,
     /// The routing state of the transactions.
     pub state: TxState<CrossNetworkInternalPeerId>,
+    /// If `false` the txs are only added to the pool, they are not given to the dandelion router and stay
+    /// hidden in the stem pool.
+    ///
+    /// This should only be `false` for local txs.
+    pub relay: bool,
 }
 
 ///  The transaction type used for dandelion++.
 #[derive(Clone)]
-pub struct DandelionTx(pub Bytes);
+pub struct DandelionTx {
+    /// The raw bytes of the transaction.
+    pub tx_blob: Bytes,
+    /// The transaction's hash.
+    pub tx_hash: TxId,
+}
 
 /// A transaction ID/hash.
 pub(super) type TxId = [u8; 32];
@@ -83,6 +98,8 @@ pub struct IncomingTxHandler {
     /// The dandelion txpool manager.
     pub(super) dandelion_pool_manager:
         DandelionPoolService<DandelionTx, TxId, CrossNetworkInternalPeerId>,
+    /// The local txs only being sent over anonymity zones.
+    pub(super) anonymous_txs: AnonymousTxs,
     /// The txpool write handle.
     pub(super) txpool_write_handle: TxpoolWriteHandle,
     /// The txpool read handle.
@@ -93,15 +110,24 @@ pub struct IncomingTxHandler {
 
 impl IncomingTxHandler {
     /// Initialize the [`IncomingTxHandler`].
+    ///
+    /// Txs are relayed over the network zone `Z`, if `anon_zones` is not empty local txs are only broadcast
+    /// over those zones.
     #[expect(clippy::significant_drop_tightening)]
-    pub fn init(
-        clear_net: NetworkInterface<ClearNet>,
+    pub fn init<Z: NetworkZone>(
+        network_interface: NetworkInterface<Z>,
+        anon_zones: AnonZones,
         txpool_write_handle: TxpoolWriteHandle,
         txpool_read_handle: TxpoolReadHandle,
         blockchain_context_cache: BlockchainContextService,
         blockchain_read_handle: BlockchainReadHandle,
-    ) -> Self {
-        let dandelion_router = dandelion::dandelion_router(clear_net);
+    ) -> Self
+    where
+        CrossNetworkInternalPeerId: From<InternalPeerID<Z::Addr>>,
+    {
+        let anonymous_txs = AnonymousTxs::new();
+        let dandelion_router =
+            dandelion::dandelion_router(network_interface, anon_zones, anonymous_txs.clone());
 
         let dandelion_pool_manager = dandelion::start_dandelion_pool_manager(
             dandelion_router,
@@ -113,6 +139,7 @@ impl IncomingTxHandler {
             txs_being_handled: TxsBeingHandled::new(),
             blockchain_context_cache,
             dandelion_pool_manager,
+            anonymous_txs,
             txpool_write_handle,
             txpool_read_handle,
             blockchain_read_handle: ConsensusBlockchainReadHandle::new(
@@ -141,6 +168,7 @@ impl Service<IncomingTxs> for IncomingTxHandler {
             self.txpool_write_handle.clone(),
             self.txpool_read_handle.clone(),
             self.dandelion_pool_manager.clone(),
+            self.anonymous_txs.clone(),
         )
         .boxed()
     }
@@ -148,13 +176,14 @@ impl Service<IncomingTxs> for IncomingTxHandler {
 
 /// Handles the incoming txs.
 async fn handle_incoming_txs(
-    IncomingTxs { txs, state }: IncomingTxs,
+    IncomingTxs { txs, state, relay }: IncomingTxs,
     txs_being_handled: TxsBeingHandled,
     mut blockchain_context_cache: BlockchainContextService,
     blockchain_read_handle: ConsensusBlockchainReadHandle,
     mut txpool_write_handle: TxpoolWriteHandle,
     mut txpool_read_handle: TxpoolReadHandle,
     mut dandelion_pool_manager: DandelionPoolService<DandelionTx, TxId, CrossNetworkInternalPeerId>,
+    anonymous_txs: AnonymousTxs,
 ) -> Result<(), IncomingTxError> {
     let _reorg_guard = REORG_LOCK.read().await;
 
@@ -183,22 +212,39 @@ async fn handle_incoming_txs(
         // TODO: this could be a DoS, if someone spams us with txs that violate these rules?
         // Maybe we should remember these invalid txs for some time to prevent them getting repeatedly sent.
         if let Err(e) = check_tx_relay_rules(&tx, context) {
+            if matches!(state, TxState::Local) {
+                return Err(IncomingTxError::RelayRule(e));
+            }
+
             tracing::debug!(err = %e, tx = hex::encode(tx.tx_hash), "Tx failed relay check, skipping.");
 
             continue;
         }
 
-        handle_valid_tx(
+        let added = handle_valid_tx(
             tx,
             state.clone(),
+            relay,
             &mut txpool_write_handle,
             &mut dandelion_pool_manager,
         )
         .await;
+
+        if !added && matches!(state, TxState::Local) {
+            return Err(IncomingTxError::DoubleSpend);
+        }
     }
 
     // Re-relay any txs we got in the block that were already in our stem pool.
     for stem_tx in stem_pool_txs {
+        if reached_public_network(&state) {
+            anonymous_txs.remove(&stem_tx);
+        }
+
+        if !relay {
+            continue;
+        }
+
         rerelay_stem_tx(
             &stem_tx,
             state.clone(),
@@ -299,18 +345,29 @@ async fn prepare_incoming_txs(
 /// Handle a verified tx.
 ///
 /// This will add the tx to the txpool and route it to the network.
+///
+/// Returns `false` if the tx was not added as it double spends a tx in the pool.
 async fn handle_valid_tx(
     tx: TransactionVerificationData,
     state: TxState<CrossNetworkInternalPeerId>,
+    relay: bool,
     txpool_write_handle: &mut TxpoolWriteHandle,
     dandelion_pool_manager: &mut DandelionPoolService<
         DandelionTx,
         TxId,
         CrossNetworkInternalPeerId,
     >,
-) {
-    let incoming_tx =
-        IncomingTxBuilder::new(DandelionTx(Bytes::copy_from_slice(&tx.tx_blob)), tx.tx_hash);
+) -> bool {
+    let tx_hash = tx.tx_hash;
+    // Stem txs are not public yet, so they are not published.
+    let zmq_tx = (zmq::enabled() && !state.is_stem_stage()).then(|| tx.clone());
+    let incoming_tx = IncomingTxBuilder::new(
+        DandelionTx {
+            tx_blob: Bytes::copy_from_slice(&tx.tx_blob),
+            tx_hash,
+        },
+        tx_hash,
+    );
 
     let TxpoolWriteResponse::AddTransaction(double_spend) = txpool_write_handle
         .ready()
@@ -328,7 +385,16 @@ async fn handle_valid_tx(
 
     // TODO: track double spends to quickly ignore them from their blob hash.
     if let Some(tx_hash) = double_spend {
-        return;
+        return false;
+    }
+
+    if let Some(tx) = zmq_tx {
+        zmq::publish_txpool_add(tx);
+    }
+
+    if !relay {
+        tracing::debug!(tx = hex::encode(tx_hash), "Not relaying tx.");
+        return true;
     }
 
     // TODO: There is a race condition possible if a tx and block come in at the same time: <https://github.com/Cuprate/cuprate/issues/314>.
@@ -346,6 +412,22 @@ async fn handle_valid_tx(
         .call(incoming_tx)
         .await
         .expect(PANIC_CRITICAL_SERVICE_ERROR);
+
+    true
+}
+
+/// Returns `true` if a tx received in this state has made it past the anonymity zones it was sent over.
+///
+/// A stem tx from a Tor or I2P peer could still be on its way through the anonymity zone, so only a fluffed
+/// tx or one from a clear-net peer allows a local tx to be routed over the main zone.
+const fn reached_public_network(state: &TxState<CrossNetworkInternalPeerId>) -> bool {
+    matches!(
+        state,
+        TxState::Fluff
+            | TxState::Stem {
+                from: CrossNetworkInternalPeerId::ClearNet(_)
+            }
+    )
 }
 
 /// Re-relay a tx that was already in our stem pool.
@@ -370,8 +452,13 @@ async fn rerelay_stem_tx(
         return;
     };
 
-    let incoming_tx =
-        IncomingTxBuilder::new(DandelionTx(Bytes::copy_from_slice(&tx_blob)), *tx_hash);
+    let incoming_tx = IncomingTxBuilder::new(
+        DandelionTx {
+            tx_blob: Bytes::copy_from_slice(&tx_blob),
+            tx_hash: *tx_hash,
+        },
+        *tx_hash,
+    );
 
     let incoming_tx = incoming_tx
         .with_routing_state(state)
@@ -387,3 +474,26 @@ async fn rerelay_stem_tx(
         .await
         .expect(PANIC_CRITICAL_SERVICE_ERROR);
 }
+
+#[cfg(test)]
+mod tests {
+    use cuprate_p2p_core::client::InternalPeerID;
+
+    use super::*;
+
+    #[test]
+    fn anonymous_tx_released_on_public_network() {
+        assert!(reached_public_network(&TxState::Fluff));
+        assert!(reached_public_network(&TxState::Stem {
+            from: CrossNetworkInternalPeerId::ClearNet(InternalPeerID::Unknown(1)),
+        }));
+
+        assert!(!reached_public_network(&TxState::Local));
+        assert!(!reached_public_network(&TxState::Stem {
+            from: CrossNetworkInternalPeerId::Tor(InternalPeerID::Unknown(1)),
+        }));
+        assert!(!reached_public_network(&TxState::Stem {
+            from: CrossNetworkInternalPeerId::I2p(InternalPeerID::Unknown(1)),
+        }));
+    }
+}
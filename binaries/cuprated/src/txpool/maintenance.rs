@@ -0,0 +1,130 @@
+//! Tx-pool maintenance.
+//!
+//! Periodically removes expired transactions and keeps the pool under its weight limit.
+use std::time::Duration;
+
+use tower::{Service, ServiceExt};
+use tracing::{debug, instrument};
+
+use cuprate_txpool::service::{
+    interface::{TxpoolWriteRequest, TxpoolWriteResponse},
+    TxpoolWriteHandle,
+};
+
+use crate::constants::PANIC_CRITICAL_SERVICE_ERROR;
+
+/// The interval between maintenance runs.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Start the tx-pool maintenance task.
+pub fn start_txpool_maintenance(txpool_write_handle: TxpoolWriteHandle) {
+    tokio::spawn(txpool_maintenance(txpool_write_handle));
+}
+
+/// The tx-pool maintenance task, see the [module docs](self).
+async fn txpool_maintenance(mut txpool_write_handle: TxpoolWriteHandle) {
+    let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        for request in [
+            TxpoolWriteRequest::RemoveExpired,
+            TxpoolWriteRequest::TrimPool,
+        ] {
+            remove_transactions(&mut txpool_write_handle, request).await;
+        }
+    }
+}
+
+/// Send a request that removes transactions to the tx-pool.
+#[instrument(level = "debug", skip_all, name = "txpool_maintenance")]
+async fn remove_transactions(
+    txpool_write_handle: &mut TxpoolWriteHandle,
+    request: TxpoolWriteRequest,
+) {
+    let TxpoolWriteResponse::RemovedTransactions {
+        fluff_tx_hashes,
+        stem_tx_hashes,
+    } = txpool_write_handle
+        .ready()
+        .await
+        .expect(PANIC_CRITICAL_SERVICE_ERROR)
+        .call(request)
+        .await
+        .expect(PANIC_CRITICAL_SERVICE_ERROR)
+    else {
+        unreachable!()
+    };
+
+    // Stem txs are not logged by hash, they should not be exposed.
+    for tx_hash in &fluff_tx_hashes {
+        debug!(tx = hex::encode(tx_hash), "Removed tx from the pool.");
+    }
+
+    if !stem_tx_hashes.is_empty() {
+        debug!("Removed {} stem txs from the pool.", stem_tx_hashes.len());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cuprate_test_utils::data::{TX_V1_SIG0, TX_V1_SIG2};
+    use cuprate_txpool::service::interface::{TxpoolReadRequest, TxpoolReadResponse};
+    use cuprate_types::TransactionVerificationData;
+
+    use super::*;
+
+    /// The first maintenance run trims the pool to its weight limit, keeping stem txs.
+    #[tokio::test]
+    async fn maintenance_trims_pool() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let config = cuprate_txpool::config::ConfigBuilder::new()
+            .data_directory(tempdir.path().into())
+            .max_txpool_weight(TX_V1_SIG0.tx_weight)
+            .build();
+        let (txpool_read_handle, mut txpool_write_handle, _) =
+            cuprate_txpool::service::init(config).unwrap();
+
+        for (tx, state_stem) in [(&*TX_V1_SIG0, true), (&*TX_V1_SIG2, false)] {
+            txpool_write_handle
+                .ready()
+                .await
+                .unwrap()
+                .call(TxpoolWriteRequest::AddTransaction {
+                    tx: Box::new(TransactionVerificationData::try_from(tx.clone()).unwrap()),
+                    state_stem,
+                })
+                .await
+                .unwrap();
+        }
+
+        start_txpool_maintenance(txpool_write_handle);
+
+        let in_pool = |tx_hash| {
+            let mut txpool_read_handle = txpool_read_handle.clone();
+            async move {
+                matches!(
+                    txpool_read_handle
+                        .ready()
+                        .await
+                        .unwrap()
+                        .call(TxpoolReadRequest::TxBlob(tx_hash))
+                        .await,
+                    Ok(TxpoolReadResponse::TxBlob { .. })
+                )
+            }
+        };
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while in_pool(TX_V1_SIG2.tx_hash).await {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        assert!(in_pool(TX_V1_SIG0.tx_hash).await);
+    }
+}
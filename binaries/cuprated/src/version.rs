@@ -31,6 +31,8 @@ pub struct CupratedVersionInfo {
     hardfork: u8,
     /// The blockchain database version specific to `cuprated`.
     blockchain_db_version: u64,
+    /// The tx-pool database version specific to `cuprated`.
+    txpool_db_version: u64,
     /// `cuprated`'s semantic version.
     semantic_version: &'static str,
     /// Build type, either `debug` or `release`.
@@ -52,6 +54,7 @@ impl CupratedVersionInfo {
             rpc_minor_version: CORE_RPC_VERSION_MINOR,
             rpc_version: CORE_RPC_VERSION,
             blockchain_db_version: cuprate_blockchain::DATABASE_VERSION,
+            txpool_db_version: cuprate_txpool::DATABASE_VERSION,
             hardfork: HardFork::LATEST.as_u8(),
             semantic_version: VERSION,
             build: BUILD,
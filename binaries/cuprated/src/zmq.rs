@@ -0,0 +1,398 @@
+//! ZMQ pub server.
+//!
+//! Publishes the `monerod` compatible ZMQ JSON topics, which are used by P2Pool and block explorers:
+//!
+//! - `json-full-chain_main` & `json-minimal-chain_main`, when a block is added to the main chain.
+//! - `json-full-miner_data`, after each new main chain block.
+//! - `json-full-txpool_add` & `json-minimal-txpool_add`, when a public (fluff) transaction enters the pool.
+//!
+//! Each message is a single frame of the form `<topic>:<json>`.
+use std::sync::OnceLock;
+
+use monero_serai::block::Block;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tower::{Service, ServiceExt};
+use tracing::{info, warn};
+use zeromq::{PubSocket, Socket, SocketSend, ZmqMessage};
+
+use cuprate_blockchain::service::BlockchainReadHandle;
+use cuprate_consensus::BlockchainContextService;
+use cuprate_helper::{cast::usize_to_u64, map::split_u128_into_low_high_bits};
+use cuprate_hex::Hex;
+use cuprate_txpool::service::{
+    interface::{TxpoolReadRequest, TxpoolReadResponse},
+    TxpoolReadHandle,
+};
+use cuprate_types::TransactionVerificationData;
+use cuprate_zmq_types::json_message_types::{MinerData, TxBacklog};
+
+use crate::config::ZmqConfig;
+
+mod messages;
+
+/// The `json-full-chain_main` topic.
+const CHAIN_MAIN_FULL: &str = "json-full-chain_main";
+/// The `json-minimal-chain_main` topic.
+const CHAIN_MAIN_MIN: &str = "json-minimal-chain_main";
+/// The `json-full-miner_data` topic.
+const MINER_DATA_FULL: &str = "json-full-miner_data";
+/// The `json-full-txpool_add` topic.
+const TXPOOL_ADD_FULL: &str = "json-full-txpool_add";
+/// The `json-minimal-txpool_add` topic.
+const TXPOOL_ADD_MIN: &str = "json-minimal-txpool_add";
+
+/// The amount of events that can be waiting to be published before new events are dropped.
+const ZMQ_EVENT_CHANNEL_SIZE: usize = 256;
+
+/// The channel to the ZMQ pub task, only set if the ZMQ pub server is enabled.
+static ZMQ_EVENT_TX: OnceLock<mpsc::Sender<ZmqEvent>> = OnceLock::new();
+
+/// An event to publish.
+enum ZmqEvent {
+    /// Sequential blocks were added to the main chain, starting at `height`.
+    ChainMain { height: usize, blocks: Vec<Block> },
+    /// A transaction was added to the pool.
+    TxPoolAdd(Box<TransactionVerificationData>),
+}
+
+/// Returns `true` if the ZMQ pub server is running.
+///
+/// Callers can use this to skip preparing events that would not be published.
+pub fn enabled() -> bool {
+    ZMQ_EVENT_TX.get().is_some()
+}
+
+/// Publish sequential blocks that were added to the main chain, starting at `height`.
+///
+/// Like `monerod`, a batch of blocks is published as one message.
+///
+/// This is a no-op if the ZMQ pub server is not running or `blocks` is empty.
+pub fn publish_chain_main(height: usize, blocks: Vec<Block>) {
+    if blocks.is_empty() {
+        return;
+    }
+
+    send_event(ZmqEvent::ChainMain { height, blocks });
+}
+
+/// Publish a public transaction that was added to the pool.
+///
+/// This is a no-op if the ZMQ pub server is not running.
+pub fn publish_txpool_add(tx: TransactionVerificationData) {
+    send_event(ZmqEvent::TxPoolAdd(Box::new(tx)));
+}
+
+/// Send an event to the ZMQ pub task, events are dropped if the task is falling behind.
+fn send_event(event: ZmqEvent) {
+    let Some(event_tx) = ZMQ_EVENT_TX.get() else {
+        return;
+    };
+
+    if event_tx.try_send(event).is_err() {
+        warn!("ZMQ pub server is falling behind, dropping message.");
+    }
+}
+
+/// Start the ZMQ pub server, if it is enabled in the config.
+///
+/// # Panics
+/// This function panics if the pub socket could not be bound or if it is called more than once.
+pub async fn init_zmq_pub(
+    config: &ZmqConfig,
+    blockchain_read_handle: BlockchainReadHandle,
+    blockchain_context_service: BlockchainContextService,
+    txpool_read_handle: TxpoolReadHandle,
+) {
+    if !config.pub_enable {
+        return;
+    }
+
+    let mut socket = PubSocket::new();
+    let endpoint = socket
+        .bind(&format!("tcp://{}", config.pub_address))
+        .await
+        .unwrap_or_else(|e| panic!("Failed to bind the ZMQ pub socket: {e}"));
+
+    info!("ZMQ pub server listening on {endpoint}");
+
+    let (event_tx, event_rx) = mpsc::channel(ZMQ_EVENT_CHANNEL_SIZE);
+    assert!(
+        ZMQ_EVENT_TX.set(event_tx).is_ok(),
+        "ZMQ pub server initialized twice"
+    );
+
+    tokio::spawn(zmq_pub(
+        socket,
+        event_rx,
+        blockchain_read_handle,
+        blockchain_context_service,
+        txpool_read_handle,
+    ));
+}
+
+/// The ZMQ pub task, publishes events from `event_rx`.
+async fn zmq_pub(
+    mut socket: PubSocket,
+    mut event_rx: mpsc::Receiver<ZmqEvent>,
+    mut blockchain_read_handle: BlockchainReadHandle,
+    mut blockchain_context_service: BlockchainContextService,
+    mut txpool_read_handle: TxpoolReadHandle,
+) {
+    while let Some(event) = event_rx.recv().await {
+        match event {
+            ZmqEvent::ChainMain { height, blocks } => {
+                publish(
+                    &mut socket,
+                    CHAIN_MAIN_FULL,
+                    &blocks.iter().map(messages::chain_main).collect::<Vec<_>>(),
+                )
+                .await;
+                publish(
+                    &mut socket,
+                    CHAIN_MAIN_MIN,
+                    &messages::chain_main_min(height, &blocks),
+                )
+                .await;
+
+                match miner_data(
+                    &mut blockchain_read_handle,
+                    &mut blockchain_context_service,
+                    &mut txpool_read_handle,
+                )
+                .await
+                {
+                    Ok(miner_data) => publish(&mut socket, MINER_DATA_FULL, &miner_data).await,
+                    Err(e) => warn!("Failed to get ZMQ miner data: {e}"),
+                }
+            }
+            ZmqEvent::TxPoolAdd(tx) => {
+                if let Some(tx_pool_add) = messages::tx_pool_add(&tx.tx) {
+                    publish(&mut socket, TXPOOL_ADD_FULL, &[tx_pool_add]).await;
+                }
+
+                publish(
+                    &mut socket,
+                    TXPOOL_ADD_MIN,
+                    &[messages::tx_pool_add_min(&tx)],
+                )
+                .await;
+            }
+        }
+    }
+}
+
+/// Returns the `json-full-miner_data` message for the current top of the main chain.
+async fn miner_data(
+    blockchain_read_handle: &mut BlockchainReadHandle,
+    blockchain_context_service: &mut BlockchainContextService,
+    txpool_read_handle: &mut TxpoolReadHandle,
+) -> Result<MinerData, anyhow::Error> {
+    let c = blockchain_context_service.blockchain_context();
+
+    let seed_hash =
+        crate::blockchain::randomx_seed_hash(blockchain_read_handle, c.chain_height, c.current_hf)
+            .await?;
+
+    let TxpoolReadResponse::Backlog(backlog) = txpool_read_handle
+        .ready()
+        .await?
+        .call(TxpoolReadRequest::Backlog)
+        .await?
+    else {
+        unreachable!()
+    };
+
+    let tx_backlog = backlog
+        .into_iter()
+        .map(|entry| TxBacklog {
+            id: Hex(entry.id),
+            weight: entry.weight,
+            fee: entry.fee,
+        })
+        .collect();
+
+    Ok(MinerData {
+        major_version: c.current_hf.as_u8(),
+        height: usize_to_u64(c.chain_height),
+        prev_id: Hex(c.top_hash),
+        seed_hash: Hex(seed_hash),
+        difficulty: split_u128_into_low_high_bits(c.next_difficulty).0,
+        median_weight: usize_to_u64(c.effective_median_weight),
+        already_generated_coins: c.already_generated_coins,
+        tx_backlog,
+    })
+}
+
+/// Publish `message` as JSON on `topic`.
+async fn publish<T: Serialize + ?Sized>(socket: &mut PubSocket, topic: &str, message: &T) {
+    let json = serde_json::to_string(message).expect("ZMQ messages are always serializable");
+
+    if let Err(e) = socket
+        .send(ZmqMessage::from(format!("{topic}:{json}")))
+        .await
+    {
+        warn!("Failed to publish ZMQ message on {topic}: {e}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, net::TcpListener, slice, time::Duration};
+
+    use monero_serai::transaction::Transaction;
+    use tower::BoxError;
+    use zeromq::{SocketRecv, SubSocket};
+
+    use cuprate_consensus::{transactions::new_tx_verification_data, ContextConfig};
+    use cuprate_consensus_rules::genesis::generate_genesis_block;
+    use cuprate_helper::network::Network;
+    use cuprate_test_utils::rpc::data::other::GET_TRANSACTION_POOL_RESPONSE;
+
+    use super::*;
+    use crate::blockchain::{check_add_genesis, ConsensusBlockchainReadHandle};
+
+    /// Tests a subscriber receives `<topic>:<json>` frames.
+    #[tokio::test]
+    async fn chain_main_min_frame() {
+        let genesis = generate_genesis_block(Network::Mainnet);
+
+        let mut pub_socket = PubSocket::new();
+        let endpoint = pub_socket.bind("tcp://127.0.0.1:0").await.unwrap();
+
+        let mut sub_socket = SubSocket::new();
+        sub_socket.connect(&endpoint.to_string()).await.unwrap();
+        sub_socket.subscribe(CHAIN_MAIN_MIN).await.unwrap();
+
+        // Messages published before the subscription reaches the publisher are dropped,
+        // so keep publishing until one arrives.
+        let message = loop {
+            publish(
+                &mut pub_socket,
+                CHAIN_MAIN_MIN,
+                &messages::chain_main_min(0, slice::from_ref(&genesis)),
+            )
+            .await;
+
+            if let Ok(message) =
+                tokio::time::timeout(Duration::from_millis(100), sub_socket.recv()).await
+            {
+                break message.unwrap();
+            }
+        };
+
+        let frame = String::from_utf8(message.get(0).unwrap().to_vec()).unwrap();
+        let (topic, json) = frame.split_once(':').unwrap();
+        assert_eq!(topic, CHAIN_MAIN_MIN);
+
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(json["first_height"], 0);
+        assert_eq!(json["first_prev_id"], hex::encode([0; 32]));
+        assert_eq!(json["ids"][0], hex::encode(genesis.hash()));
+    }
+
+    /// Tests every topic is published by the ZMQ pub server, for a mainnet node with only the genesis block.
+    #[tokio::test]
+    async fn zmq_pub_all_topics() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let blockchain_config = cuprate_blockchain::config::ConfigBuilder::new()
+            .data_directory(data_dir.path().to_path_buf())
+            .build();
+        let txpool_config = cuprate_txpool::config::ConfigBuilder::new()
+            .data_directory(data_dir.path().to_path_buf())
+            .build();
+
+        let (mut blockchain_read_handle, mut blockchain_write_handle, _) =
+            cuprate_blockchain::service::init(blockchain_config).unwrap();
+        let (txpool_read_handle, _, _) = cuprate_txpool::service::init(txpool_config).unwrap();
+
+        check_add_genesis(
+            &mut blockchain_read_handle,
+            &mut blockchain_write_handle,
+            Network::Mainnet,
+        )
+        .await;
+
+        let blockchain_context = cuprate_consensus_context::initialize_blockchain_context(
+            ContextConfig::main_net(),
+            ConsensusBlockchainReadHandle::new(blockchain_read_handle.clone(), BoxError::from),
+        )
+        .await
+        .unwrap();
+
+        // Find a free port for the pub server.
+        let pub_address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        init_zmq_pub(
+            &ZmqConfig {
+                pub_enable: true,
+                pub_address,
+            },
+            blockchain_read_handle,
+            blockchain_context,
+            txpool_read_handle,
+        )
+        .await;
+
+        let genesis = generate_genesis_block(Network::Mainnet);
+
+        // A `ClsagBulletproofPlus` tx, other txs are not published on `json-full-txpool_add`.
+        let pool: serde_json::Value = serde_json::from_str(GET_TRANSACTION_POOL_RESPONSE).unwrap();
+        let tx_blob = hex::decode(pool["transactions"][0]["tx_blob"].as_str().unwrap()).unwrap();
+        let tx =
+            new_tx_verification_data(Transaction::read(&mut tx_blob.as_slice()).unwrap()).unwrap();
+
+        let mut sub_socket = SubSocket::new();
+        sub_socket
+            .connect(&format!("tcp://{pub_address}"))
+            .await
+            .unwrap();
+        sub_socket.subscribe("json-").await.unwrap();
+
+        let mut messages = HashMap::new();
+
+        tokio::time::timeout(Duration::from_secs(30), async {
+            // Messages published before the subscription reaches the publisher are dropped,
+            // so keep publishing until every topic arrives.
+            while messages.len() < 5 {
+                publish_chain_main(0, vec![genesis.clone()]);
+                publish_txpool_add(tx.clone());
+
+                while let Ok(message) =
+                    tokio::time::timeout(Duration::from_millis(100), sub_socket.recv()).await
+                {
+                    let frame =
+                        String::from_utf8(message.unwrap().get(0).unwrap().to_vec()).unwrap();
+                    let (topic, json) = frame.split_once(':').unwrap();
+                    let json: serde_json::Value = serde_json::from_str(json).unwrap();
+
+                    // Other tests can publish blocks and txs once the server is running.
+                    let ours = match topic {
+                        CHAIN_MAIN_FULL => json[0]["prev_id"] == hex::encode([0; 32]),
+                        CHAIN_MAIN_MIN => json["first_height"] == 0,
+                        TXPOOL_ADD_MIN => json[0]["id"] == hex::encode(tx.tx_hash),
+                        _ => true,
+                    };
+
+                    if ours {
+                        messages.insert(topic.to_string(), json);
+                    }
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        let genesis_hash = hex::encode(genesis.hash());
+
+        assert_eq!(messages[CHAIN_MAIN_FULL][0]["major_version"], 1);
+        assert_eq!(messages[CHAIN_MAIN_MIN]["ids"][0], genesis_hash);
+        assert_eq!(messages[MINER_DATA_FULL]["height"], 1);
+        assert_eq!(messages[MINER_DATA_FULL]["prev_id"], genesis_hash);
+        assert_eq!(messages[TXPOOL_ADD_FULL][0]["version"], 2);
+        assert_eq!(messages[TXPOOL_ADD_MIN][0]["blob_size"], tx.tx_blob.len());
+    }
+}
@@ -0,0 +1,257 @@
+//! Conversions to the ZMQ JSON message types.
+use curve25519_dalek::Scalar;
+use monero_serai::{
+    block::Block,
+    io::{read_bytes, read_varint},
+    ringct::{bulletproofs::Bulletproof, EncryptedAmount, RctPrunable, RctType},
+    transaction::{self, Input, Timelock, Transaction},
+};
+
+use cuprate_helper::cast::usize_to_u64;
+use cuprate_hex::Hex;
+use cuprate_types::TransactionVerificationData;
+use cuprate_zmq_types::json_message_types::{
+    BulletproofPlus, ChainMain, ChainMainMin, Clsag, Encrypted, MinerTx, Output, PoolInput,
+    PoolRingCt, Prunable, ToKey, ToTaggedKey, TxPoolAdd, TxPoolAddMin,
+};
+
+/// Create the `json-full-chain_main` message for a block.
+pub(super) fn chain_main(block: &Block) -> ChainMain {
+    let miner_tx = &block.miner_transaction;
+    let prefix = miner_tx.prefix();
+
+    let height = match prefix.inputs.first() {
+        Some(Input::Gen(height)) => usize_to_u64(*height),
+        _ => unreachable!("blocks in the main chain have a valid miner tx"),
+    };
+
+    ChainMain {
+        major_version: block.header.hardfork_version,
+        minor_version: block.header.hardfork_signal,
+        timestamp: block.header.timestamp,
+        prev_id: Hex(block.header.previous),
+        nonce: block.header.nonce,
+        miner_tx: MinerTx::new(
+            miner_tx.version(),
+            unlock_time(&prefix.additional_timelock),
+            height,
+            prefix.outputs.iter().map(output).collect(),
+            prefix.extra.clone(),
+        ),
+        tx_hashes: block.transactions.iter().copied().map(Hex).collect(),
+    }
+}
+
+/// Create the `json-minimal-chain_main` message for sequential blocks starting at `height`.
+///
+/// # Panics
+/// This panics if `blocks` is empty.
+pub(super) fn chain_main_min(height: usize, blocks: &[Block]) -> ChainMainMin {
+    ChainMainMin {
+        first_height: usize_to_u64(height),
+        first_prev_id: Hex(blocks[0].header.previous),
+        ids: blocks.iter().map(|block| Hex(block.hash())).collect(),
+    }
+}
+
+/// Create the `json-minimal-txpool_add` message for a transaction.
+pub(super) fn tx_pool_add_min(tx: &TransactionVerificationData) -> TxPoolAddMin {
+    TxPoolAddMin {
+        id: Hex(tx.tx_hash),
+        blob_size: usize_to_u64(tx.tx_blob.len()),
+        weight: usize_to_u64(tx.tx_weight),
+        fee: tx.fee,
+    }
+}
+
+/// Create the `json-full-txpool_add` message for a transaction.
+///
+/// Like `monerod`, this only supports the current transaction type ([`RctType::ClsagBulletproofPlus`]),
+/// [`None`] is returned for any other transaction.
+pub(super) fn tx_pool_add(tx: &Transaction) -> Option<TxPoolAdd> {
+    let Transaction::V2 {
+        prefix,
+        proofs: Some(proofs),
+    } = tx
+    else {
+        return None;
+    };
+
+    if proofs.rct_type() != RctType::ClsagBulletproofPlus {
+        return None;
+    }
+
+    let RctPrunable::Clsag {
+        bulletproof,
+        clsags,
+        pseudo_outs,
+    } = &proofs.prunable
+    else {
+        return None;
+    };
+
+    let inputs = prefix
+        .inputs
+        .iter()
+        .map(|input| match input {
+            Input::ToKey {
+                key_offsets,
+                key_image,
+                ..
+            } => Some(PoolInput {
+                to_key: ToKey::new(key_offsets.clone(), Hex(key_image.0)),
+            }),
+            Input::Gen(_) => None,
+        })
+        .collect::<Option<_>>()?;
+
+    let encrypted = proofs
+        .base
+        .encrypted_amounts
+        .iter()
+        .map(|amount| match amount {
+            EncryptedAmount::Compact { amount } => {
+                let mut padded = [0; 32];
+                padded[..8].copy_from_slice(amount);
+                Some(Encrypted::new(Hex(padded)))
+            }
+            EncryptedAmount::Original { .. } => None,
+        })
+        .collect::<Option<_>>()?;
+
+    let bulletproof_plus = bulletproof_plus(bulletproof, &proofs.base.commitments)?;
+
+    let clsags = clsags.iter().map(clsag).collect::<Option<Vec<_>>>()?;
+
+    let ringct = PoolRingCt {
+        r#type: 6,
+        encrypted,
+        commitments: proofs.base.commitments.iter().map(|c| Hex(c.0)).collect(),
+        fee: proofs.base.fee,
+        prunable: Prunable::new(
+            bulletproof_plus,
+            clsags,
+            pseudo_outs.iter().map(|p| Hex(p.0)).collect(),
+        ),
+    };
+
+    Some(TxPoolAdd::new(
+        2,
+        unlock_time(&prefix.additional_timelock),
+        inputs,
+        prefix.outputs.iter().map(output).collect(),
+        prefix.extra.clone(),
+        ringct,
+    ))
+}
+
+/// Convert a transaction output, outputs without a view tag have a view tag of `0`.
+fn output(output: &transaction::Output) -> Output {
+    Output {
+        amount: output.amount.unwrap_or(0),
+        to_tagged_key: ToTaggedKey {
+            key: Hex(output.key.0),
+            view_tag: Hex([output.view_tag.unwrap_or(0)]),
+        },
+    }
+}
+
+/// Returns the `unlock_time` field of a transaction.
+const fn unlock_time(timelock: &Timelock) -> u64 {
+    match timelock {
+        Timelock::None => 0,
+        Timelock::Block(height) => usize_to_u64(*height),
+        Timelock::Time(time) => *time,
+    }
+}
+
+/// Convert a Bulletproof+ into its JSON form.
+///
+/// `monero-serai` does not expose the proof's fields, so they are read back from its serialization.
+/// `V` is not serialized, it is the output `commitments` multiplied by `1/8`.
+fn bulletproof_plus(
+    bulletproof: &Bulletproof,
+    commitments: &[curve25519_dalek::edwards::CompressedEdwardsY],
+) -> Option<BulletproofPlus> {
+    if !matches!(bulletproof, Bulletproof::Plus(_)) {
+        return None;
+    }
+
+    let mut bytes = Vec::new();
+    bulletproof.write(&mut bytes).ok()?;
+    let mut reader = bytes.as_slice();
+
+    let mut point = || read_bytes::<_, 32>(&mut reader).ok().map(Hex);
+    let (a, a1, b, r1, s1, d1) = (point()?, point()?, point()?, point()?, point()?, point()?);
+
+    let mut points = || -> Option<Vec<Hex<32>>> {
+        let len = read_varint::<_, usize>(&mut reader).ok()?;
+        (0..len)
+            .map(|_| read_bytes::<_, 32>(&mut reader).ok().map(Hex))
+            .collect()
+    };
+    let (l, r) = (points()?, points()?);
+
+    let inv_eight = Scalar::from(8_u8).invert();
+    let v = commitments
+        .iter()
+        .map(|c| Some(Hex((c.decompress()? * inv_eight).compress().0)))
+        .collect::<Option<_>>()?;
+
+    Some(BulletproofPlus {
+        V: v,
+        A: a,
+        A1: a1,
+        B: b,
+        r1,
+        s1,
+        d1,
+        L: l,
+        R: r,
+    })
+}
+
+/// Convert a CLSAG into its JSON form.
+///
+/// Like [`bulletproof_plus`] the fields are read back from the signature's serialization:
+/// `s` (one scalar per ring member), `c1` and `D`.
+fn clsag(clsag: &monero_serai::ringct::clsag::Clsag) -> Option<Clsag> {
+    let mut bytes = Vec::new();
+    clsag.write(&mut bytes).ok()?;
+
+    let mut chunks = bytes
+        .chunks_exact(32)
+        .map(|chunk| Hex(chunk.try_into().unwrap()));
+
+    let d = chunks.next_back()?;
+    let c1 = chunks.next_back()?;
+
+    Some(Clsag {
+        s: chunks.collect(),
+        c1,
+        D: d,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Tests the `chain_main` messages match the genesis block.
+    #[test]
+    fn genesis_chain_main() {
+        let genesis = cuprate_consensus_rules::genesis::generate_genesis_block(
+            cuprate_helper::network::Network::Mainnet,
+        );
+
+        let chain_main = chain_main(&genesis);
+        assert_eq!(chain_main.major_version, 1);
+        assert_eq!(chain_main.prev_id, Hex([0; 32]));
+        assert_eq!(chain_main.nonce, 10000);
+        assert!(chain_main.tx_hashes.is_empty());
+
+        let chain_main_min = chain_main_min(0, &[genesis.clone()]);
+        assert_eq!(chain_main_min.first_height, 0);
+        assert_eq!(chain_main_min.ids, vec![Hex(genesis.hash())]);
+    }
+}
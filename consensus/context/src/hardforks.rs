@@ -50,6 +50,14 @@ impl HardForkConfig {
             window: DEFAULT_WINDOW_SIZE,
         }
     }
+
+    /// Config for reg-test.
+    pub const fn reg_test() -> Self {
+        Self {
+            info: HFsInfo::reg_test(),
+            window: DEFAULT_WINDOW_SIZE,
+        }
+    }
 }
 
 /// A struct that keeps track of the current hard-fork and current votes.
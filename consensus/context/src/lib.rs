@@ -44,6 +44,8 @@ use difficulty::DifficultyCache;
 use rx_vms::RandomXVm;
 use weight::BlockWeightsCache;
 
+pub use rx_vms::RandomXConfig;
+
 pub use alt_chains::{sealed::AltChainRequestToken, AltChainContextCache};
 pub use difficulty::DifficultyCacheConfig;
 pub use hardforks::HardForkConfig;
@@ -59,6 +61,8 @@ pub struct ContextConfig {
     pub difficulty_cfg: DifficultyCacheConfig,
     /// Block weight config.
     pub weights_config: BlockWeightsCacheConfig,
+    /// RandomX VM config.
+    pub rx_vms_cfg: RandomXConfig,
 }
 
 impl ContextConfig {
@@ -68,6 +72,7 @@ impl ContextConfig {
             hard_fork_cfg: HardForkConfig::main_net(),
             difficulty_cfg: DifficultyCacheConfig::main_net(),
             weights_config: BlockWeightsCacheConfig::main_net(),
+            rx_vms_cfg: RandomXConfig::light(),
         }
     }
 
@@ -78,6 +83,7 @@ impl ContextConfig {
             // These 2 have the same config as main-net.
             difficulty_cfg: DifficultyCacheConfig::main_net(),
             weights_config: BlockWeightsCacheConfig::main_net(),
+            rx_vms_cfg: RandomXConfig::light(),
         }
     }
 
@@ -88,6 +94,22 @@ impl ContextConfig {
             // These 2 have the same config as main-net.
             difficulty_cfg: DifficultyCacheConfig::main_net(),
             weights_config: BlockWeightsCacheConfig::main_net(),
+            rx_vms_cfg: RandomXConfig::light(),
+        }
+    }
+
+    /// Get the config for reg-test.
+    ///
+    /// If `fixed_difficulty` is [`Some`] every block will have this difficulty.
+    pub const fn reg_test(fixed_difficulty: Option<u128>) -> Self {
+        let mut difficulty_cfg = DifficultyCacheConfig::main_net();
+        difficulty_cfg.fixed_difficulty = fixed_difficulty;
+
+        Self {
+            hard_fork_cfg: HardForkConfig::reg_test(),
+            difficulty_cfg,
+            weights_config: BlockWeightsCacheConfig::main_net(),
+            rx_vms_cfg: RandomXConfig::light(),
         }
     }
 }
@@ -103,6 +125,8 @@ where
     D: Database + Clone + Send + Sync + 'static,
     D::Future: Send + 'static,
 {
+    let rx_vms_cfg = cfg.rx_vms_cfg;
+
     let (context_task, context_cache) = task::ContextTask::init_context(cfg, database).await?;
 
     // TODO: make buffer size configurable.
@@ -112,6 +136,7 @@ where
 
     Ok(BlockchainContextService {
         cached_context: Cache::new(context_cache),
+        rx_vms_cfg,
 
         channel: PollSender::new(tx),
     })
@@ -367,6 +392,8 @@ pub enum BlockChainContextResponse {
 #[derive(Clone)]
 pub struct BlockchainContextService {
     cached_context: Cache<Arc<arc_swap::ArcSwap<BlockchainContext>>, Arc<BlockchainContext>>,
+    /// The RandomX VM config, for VMs created outside the service.
+    rx_vms_cfg: RandomXConfig,
 
     channel: PollSender<task::ContextTaskRequest>,
 }
@@ -376,6 +403,11 @@ impl BlockchainContextService {
     pub fn blockchain_context(&mut self) -> &BlockchainContext {
         self.cached_context.load()
     }
+
+    /// Get the [`RandomXConfig`] the service was started with.
+    pub const fn rx_vms_config(&self) -> &RandomXConfig {
+        &self.rx_vms_cfg
+    }
 }
 
 impl Service<BlockChainContextRequest> for BlockchainContextService {
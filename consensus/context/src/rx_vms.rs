@@ -5,6 +5,7 @@
 //!
 use std::{
     collections::{HashMap, VecDeque},
+    fmt::Display,
     sync::Arc,
 };
 
@@ -28,32 +29,129 @@ use cuprate_types::{
 
 use crate::{ContextCacheError, Database};
 
-/// The amount of randomX VMs to keep in the cache.
+mod fast;
+
+use fast::FastVm;
+
+/// The default, and minimum, amount of randomX VMs to keep in the cache.
 pub const RX_SEEDS_CACHED: usize = 2;
 
+/// The RandomX VM config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomXConfig {
+    /// Use the full dataset (fast mode) instead of only the cache (light mode).
+    ///
+    /// Fast mode calculates hashes several times faster but each cached seed
+    /// needs a ~2 GiB dataset, compared to a ~256 MiB cache in light mode.
+    pub fast_mode: bool,
+    /// Allocate the cache and dataset with large pages.
+    pub large_pages: bool,
+    /// Use the secure JIT, which never has memory that is both writable and executable.
+    pub secure_jit: bool,
+    /// The amount of randomX seeds to keep VMs for, this can not be under [`RX_SEEDS_CACHED`].
+    pub seeds_cached: usize,
+}
+
+impl RandomXConfig {
+    /// Light mode, with the recommended flags.
+    pub const fn light() -> Self {
+        Self {
+            fast_mode: false,
+            large_pages: false,
+            secure_jit: false,
+            seeds_cached: RX_SEEDS_CACHED,
+        }
+    }
+
+    /// Returns the amount of seeds to cache VMs for.
+    fn seeds_to_cache(&self) -> usize {
+        self.seeds_cached.max(RX_SEEDS_CACHED)
+    }
+
+    /// Returns the flags for the cache, fast mode also needs [`RandomXFlag::FLAG_FULL_MEM`].
+    fn flags(&self) -> RandomXFlag {
+        let mut flags = RandomXFlag::get_recommended_flags();
+
+        if self.large_pages {
+            flags |= RandomXFlag::FLAG_LARGE_PAGES;
+        }
+
+        if self.secure_jit && flags.contains(RandomXFlag::FLAG_JIT) {
+            flags |= RandomXFlag::FLAG_SECURE;
+        }
+
+        flags
+    }
+}
+
+impl Default for RandomXConfig {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
 /// A multithreaded randomX VM.
 #[derive(Debug)]
 pub struct RandomXVm {
-    /// These RandomX VMs all share the same cache.
-    vms: ThreadLocal<VmInner>,
-    /// The RandomX cache.
-    cache: RandomXCache,
-    /// The flags used to start the RandomX VMs.
-    flags: RandomXFlag,
+    mode: VmMode,
+}
+
+/// The mode a [`RandomXVm`] runs in.
+#[derive(Debug)]
+enum VmMode {
+    /// Light mode, only using the cache.
+    Light {
+        /// These RandomX VMs all share the same cache.
+        vms: ThreadLocal<VmInner>,
+        /// The RandomX cache.
+        cache: RandomXCache,
+        /// The flags used to start the RandomX VMs.
+        flags: RandomXFlag,
+    },
+    /// Fast mode, using the full dataset.
+    Fast(FastVm),
 }
 
 impl RandomXVm {
     /// Create a new multithreaded randomX VM with the provided seed.
-    pub fn new(seed: &[u8; 32]) -> Result<Self, RandomXError> {
-        // TODO: allow passing in flags.
-        let flags = RandomXFlag::get_recommended_flags();
+    ///
+    /// If large pages or the dataset can not be allocated this falls back to
+    /// normal pages and light mode.
+    pub fn new(seed: &[u8; 32], config: &RandomXConfig) -> Result<Self, RandomXError> {
+        Self::new_with_fast_vm(seed, config, FastVm::new)
+    }
 
-        let cache = RandomXCache::new(flags, seed.as_slice())?;
+    /// [`RandomXVm::new`], creating the fast mode VM with `fast_vm`.
+    fn new_with_fast_vm(
+        seed: &[u8; 32],
+        config: &RandomXConfig,
+        fast_vm: impl FnOnce(&[u8; 32], RandomXFlag) -> Result<FastVm, RandomXError>,
+    ) -> Result<Self, RandomXError> {
+        let flags = config.flags();
+
+        if config.fast_mode {
+            match fast_vm(seed, flags) {
+                Ok(vm) => {
+                    return Ok(Self {
+                        mode: VmMode::Fast(vm),
+                    })
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to allocate the RandomX dataset, falling back to light mode: {e}"
+                ),
+            }
+        }
+
+        let (cache, flags) = alloc_with_fallback("cache", flags, |flags| {
+            RandomXCache::new(flags, seed.as_slice())
+        })?;
 
         Ok(Self {
-            vms: ThreadLocal::new(),
-            cache,
-            flags,
+            mode: VmMode::Light {
+                vms: ThreadLocal::new(),
+                cache,
+                flags,
+            },
         })
     }
 }
@@ -62,10 +160,32 @@ impl RandomX for RandomXVm {
     type Error = RandomXError;
 
     fn calculate_hash(&self, buf: &[u8]) -> Result<[u8; 32], Self::Error> {
-        self.vms
-            .get_or_try(|| VmInner::new(self.flags, Some(self.cache.clone()), None))?
-            .calculate_hash(buf)
-            .map(|out| out.try_into().unwrap())
+        match &self.mode {
+            VmMode::Light { vms, cache, flags } => vms
+                .get_or_try(|| VmInner::new(*flags, Some(cache.clone()), None))?
+                .calculate_hash(buf)
+                .map(|out| out.try_into().unwrap()),
+            VmMode::Fast(vm) => vm.calculate_hash(buf),
+        }
+    }
+}
+
+/// Calls `alloc` with `flags`, retrying without [`RandomXFlag::FLAG_LARGE_PAGES`] if that fails.
+///
+/// Returns the allocation and the flags it was made with.
+fn alloc_with_fallback<T, E: Display>(
+    what: &str,
+    mut flags: RandomXFlag,
+    mut alloc: impl FnMut(RandomXFlag) -> Result<T, E>,
+) -> Result<(T, RandomXFlag), E> {
+    match alloc(flags) {
+        Ok(t) => Ok((t, flags)),
+        Err(e) if flags.contains(RandomXFlag::FLAG_LARGE_PAGES) => {
+            tracing::warn!("Failed to allocate the RandomX {what} with large pages: {e}");
+            flags.remove(RandomXFlag::FLAG_LARGE_PAGES);
+            alloc(flags).map(|t| (t, flags))
+        }
+        Err(e) => Err(e),
     }
 }
 
@@ -73,7 +193,9 @@ impl RandomX for RandomXVm {
 /// couple more around this VM.
 #[derive(Clone, Debug)]
 pub struct RandomXVmCache {
-    /// The top [`RX_SEEDS_CACHED`] RX seeds.  
+    /// The config for new VMs.
+    pub config: RandomXConfig,
+    /// The top [`RandomXConfig::seeds_cached`] RX seeds.
     pub seeds: VecDeque<(usize, [u8; 32])>,
     /// The VMs for `seeds` (if after hf 12, otherwise this will be empty).
     pub vms: HashMap<usize, Arc<RandomXVm>>,
@@ -87,12 +209,14 @@ impl RandomXVmCache {
     pub async fn init_from_chain_height<D: Database + Clone>(
         chain_height: usize,
         hf: &HardFork,
+        config: RandomXConfig,
         database: D,
     ) -> Result<Self, ContextCacheError> {
-        let seed_heights = get_last_rx_seed_heights(chain_height - 1, RX_SEEDS_CACHED);
+        let seeds_cached = config.seeds_to_cache();
+        let seed_heights = get_last_rx_seed_heights(chain_height - 1, seeds_cached);
         let seed_hashes = get_block_hashes(seed_heights.clone(), database).await?;
 
-        tracing::debug!("last {RX_SEEDS_CACHED} randomX seed heights: {seed_heights:?}",);
+        tracing::debug!("last {seeds_cached} randomX seed heights: {seed_heights:?}",);
 
         let seeds: VecDeque<(usize, [u8; 32])> =
             seed_heights.into_iter().zip(seed_hashes).collect();
@@ -106,7 +230,10 @@ impl RandomXVmCache {
                     .map(|(height, seed)| {
                         (
                             *height,
-                            Arc::new(RandomXVm::new(seed).expect("Failed to create RandomX VM!")),
+                            Arc::new(
+                                RandomXVm::new(seed, &config)
+                                    .expect("Failed to create RandomX VM!"),
+                            ),
                         )
                     })
                     .collect()
@@ -118,6 +245,7 @@ impl RandomXVmCache {
         };
 
         Ok(Self {
+            config,
             seeds,
             vms,
             cached_vm: None,
@@ -156,7 +284,14 @@ impl RandomXVmCache {
             }
         }
 
-        let alt_vm = rayon_spawn_async(move || Arc::new(RandomXVm::new(&seed_hash).unwrap())).await;
+        // Alt-chain VMs are rarely used, so are always in light mode.
+        let config = RandomXConfig {
+            fast_mode: false,
+            ..self.config
+        };
+
+        let alt_vm =
+            rayon_spawn_async(move || Arc::new(RandomXVm::new(&seed_hash, &config).unwrap())).await;
 
         Ok(alt_vm)
     }
@@ -184,8 +319,11 @@ impl RandomXVmCache {
                         }
                     }
 
-                    rayon_spawn_async(move || Arc::new(RandomXVm::new(&next_seed_hash).unwrap()))
-                        .await
+                    let config = self.config;
+                    rayon_spawn_async(move || {
+                        Arc::new(RandomXVm::new(&next_seed_hash, &config).unwrap())
+                    })
+                    .await
                 };
 
                 self.vms.insert(seed_height, new_vm);
@@ -196,11 +334,13 @@ impl RandomXVmCache {
                 tracing::debug!("RandomX has activated, initialising VMs");
 
                 let seeds_clone = self.seeds.clone();
+                let config = self.config;
                 self.vms = rayon_spawn_async(move || {
                     seeds_clone
                         .par_iter()
                         .map(|(height, seed)| {
-                            let vm = RandomXVm::new(seed).expect("Failed to create RandomX VM!");
+                            let vm = RandomXVm::new(seed, &config)
+                                .expect("Failed to create RandomX VM!");
                             let vm = Arc::new(vm);
                             (*height, vm)
                         })
@@ -228,7 +368,7 @@ impl RandomXVmCache {
 
             self.seeds.push_front((height, *hash));
 
-            if self.seeds.len() > RX_SEEDS_CACHED {
+            if self.seeds.len() > self.config.seeds_to_cache() {
                 self.seeds.pop_back();
                 // HACK: This is really inefficient but the amount of VMs cached is not a lot.
                 self.vms.retain(|height, _| {
@@ -291,3 +431,100 @@ async fn get_block_hashes<D: Database + Clone>(
     }
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_flags() {
+        let light = RandomXConfig::light();
+        let flags = light.flags();
+        assert_eq!(flags, RandomXFlag::get_recommended_flags());
+        assert!(!flags.contains(RandomXFlag::FLAG_FULL_MEM));
+
+        let large_pages = RandomXConfig {
+            large_pages: true,
+            ..light
+        };
+        assert_eq!(large_pages.flags(), flags | RandomXFlag::FLAG_LARGE_PAGES);
+
+        // The fast mode flag is only added to the dataset VMs.
+        let fast = RandomXConfig {
+            fast_mode: true,
+            ..light
+        };
+        assert_eq!(fast.flags(), flags);
+
+        let secure = RandomXConfig {
+            secure_jit: true,
+            ..light
+        };
+        assert_eq!(
+            secure.flags().contains(RandomXFlag::FLAG_SECURE),
+            flags.contains(RandomXFlag::FLAG_JIT)
+        );
+    }
+
+    #[test]
+    fn alloc_falls_back_to_normal_pages() {
+        let mut tried = Vec::new();
+        let (_, flags) = alloc_with_fallback(
+            "test",
+            RandomXFlag::FLAG_JIT | RandomXFlag::FLAG_LARGE_PAGES,
+            |flags| {
+                tried.push(flags);
+                if flags.contains(RandomXFlag::FLAG_LARGE_PAGES) {
+                    Err("no large pages")
+                } else {
+                    Ok(())
+                }
+            },
+        )
+        .unwrap();
+
+        assert_eq!(flags, RandomXFlag::FLAG_JIT);
+        assert_eq!(
+            tried,
+            [
+                RandomXFlag::FLAG_JIT | RandomXFlag::FLAG_LARGE_PAGES,
+                RandomXFlag::FLAG_JIT
+            ]
+        );
+
+        // Without large pages there is nothing to fall back to.
+        let mut calls = 0;
+        alloc_with_fallback("test", RandomXFlag::FLAG_JIT, |_| {
+            calls += 1;
+            Err::<(), _>("out of memory")
+        })
+        .unwrap_err();
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn dataset_failure_falls_back_to_light_mode() {
+        let config = RandomXConfig {
+            fast_mode: true,
+            ..RandomXConfig::light()
+        };
+
+        let vm = RandomXVm::new_with_fast_vm(&[1; 32], &config, |_, _| {
+            Err(RandomXError::CreationError("no memory".to_string()))
+        })
+        .unwrap();
+
+        assert!(matches!(vm.mode, VmMode::Light { .. }));
+        vm.calculate_hash(b"cuprate").unwrap();
+    }
+
+    #[test]
+    fn light_mode_never_allocates_dataset() {
+        let vm = RandomXVm::new_with_fast_vm(&[1; 32], &RandomXConfig::light(), |_, _| {
+            panic!("light mode allocated a dataset")
+        })
+        .unwrap();
+
+        assert!(matches!(vm.mode, VmMode::Light { .. }));
+    }
+}
@@ -0,0 +1,218 @@
+//! RandomX fast mode.
+//!
+//! `randomx-rs` initialises the ~2 GiB dataset with one `randomx_init_dataset` call on a single thread,
+//! which takes minutes, so fast mode uses RandomX directly to split the dataset items across the rayon
+//! thread-pool, like monerod does with its RandomX init threads.
+use std::{
+    ffi::c_void,
+    fmt::{Debug, Formatter},
+    os::raw::c_ulong,
+    ptr::{self, NonNull},
+};
+
+use randomx_rs::{RandomXError, RandomXFlag};
+use rayon::prelude::*;
+use thread_local::ThreadLocal;
+
+use super::alloc_with_fallback;
+
+/// An opaque `randomx_cache`.
+#[repr(C)]
+struct RawCache {
+    _private: [u8; 0],
+}
+
+/// An opaque `randomx_dataset`.
+#[repr(C)]
+struct RawDataset {
+    _private: [u8; 0],
+}
+
+/// An opaque `randomx_vm`.
+#[repr(C)]
+struct RawVm {
+    _private: [u8; 0],
+}
+
+// `randomx-rs` links RandomX but does not expose its bindings, see `randomx.h` for the docs.
+extern "C" {
+    fn randomx_alloc_cache(flags: u32) -> *mut RawCache;
+    fn randomx_init_cache(cache: *mut RawCache, key: *const c_void, key_size: usize);
+    fn randomx_release_cache(cache: *mut RawCache);
+    fn randomx_alloc_dataset(flags: u32) -> *mut RawDataset;
+    fn randomx_dataset_item_count() -> c_ulong;
+    fn randomx_init_dataset(
+        dataset: *mut RawDataset,
+        cache: *mut RawCache,
+        start_item: c_ulong,
+        item_count: c_ulong,
+    );
+    fn randomx_release_dataset(dataset: *mut RawDataset);
+    fn randomx_create_vm(flags: u32, cache: *mut RawCache, dataset: *mut RawDataset) -> *mut RawVm;
+    fn randomx_destroy_vm(vm: *mut RawVm);
+    fn randomx_calculate_hash(
+        vm: *mut RawVm,
+        input: *const c_void,
+        input_size: usize,
+        output: *mut c_void,
+    );
+}
+
+/// An initialised RandomX cache, only needed to initialise the dataset.
+struct Cache(NonNull<RawCache>);
+
+// SAFETY: the cache is only read after it is initialised, which `randomx_init_dataset` can do from many threads.
+unsafe impl Sync for Cache {}
+
+impl Cache {
+    fn new(flags: RandomXFlag, seed: &[u8; 32]) -> Result<Self, RandomXError> {
+        // SAFETY: `randomx_alloc_cache` returns null on failure, which is checked.
+        let cache =
+            NonNull::new(unsafe { randomx_alloc_cache(flags.bits()) }).ok_or_else(|| {
+                RandomXError::CreationError("Could not allocate the RandomX cache".to_string())
+            })?;
+
+        // SAFETY: `cache` was just allocated and `seed` is valid for `seed.len()` bytes.
+        unsafe { randomx_init_cache(cache.as_ptr(), seed.as_ptr().cast(), seed.len()) };
+
+        Ok(Self(cache))
+    }
+}
+
+impl Drop for Cache {
+    fn drop(&mut self) {
+        // SAFETY: the cache is owned by `self` and no VM was created with it.
+        unsafe { randomx_release_cache(self.0.as_ptr()) };
+    }
+}
+
+/// A RandomX dataset.
+struct Dataset(NonNull<RawDataset>);
+
+// SAFETY: the dataset is only written to in `Dataset::init`, on disjoint items, after that it is only read.
+unsafe impl Send for Dataset {}
+// SAFETY: see above.
+unsafe impl Sync for Dataset {}
+
+impl Dataset {
+    fn alloc(flags: RandomXFlag) -> Result<Self, RandomXError> {
+        // SAFETY: `randomx_alloc_dataset` returns null on failure, which is checked.
+        NonNull::new(unsafe { randomx_alloc_dataset(flags.bits()) })
+            .map(Self)
+            .ok_or_else(|| {
+                RandomXError::CreationError("Could not allocate the RandomX dataset".to_string())
+            })
+    }
+
+    /// Initialise every item of the dataset from `cache`, split into one chunk per rayon thread.
+    fn init(&self, cache: &Cache) {
+        // SAFETY: `randomx_dataset_item_count` has no preconditions.
+        let item_count = unsafe { randomx_dataset_item_count() };
+        let chunks = c_ulong::try_from(rayon::current_num_threads()).unwrap();
+        let chunk_len = item_count.div_ceil(chunks);
+
+        (0..chunks).into_par_iter().for_each(|i| {
+            let start = i * chunk_len;
+            if start >= item_count {
+                return;
+            }
+            let count = chunk_len.min(item_count - start);
+
+            // SAFETY: `start..start + count` is in the dataset and no other chunk overlaps it, RandomX allows
+            // disjoint items to be initialised concurrently. `cache` is initialised and outlives this call.
+            unsafe { randomx_init_dataset(self.0.as_ptr(), cache.0.as_ptr(), start, count) };
+        });
+    }
+}
+
+impl Drop for Dataset {
+    fn drop(&mut self) {
+        // SAFETY: the dataset is owned by `self` and the VMs using it are dropped first, see [`FastVm`].
+        unsafe { randomx_release_dataset(self.0.as_ptr()) };
+    }
+}
+
+/// A RandomX VM using a [`Dataset`].
+struct Vm(NonNull<RawVm>);
+
+// SAFETY: a VM can be moved between threads, it is only ever used by one at a time.
+unsafe impl Send for Vm {}
+
+impl Vm {
+    fn new(flags: RandomXFlag, dataset: &Dataset) -> Result<Self, RandomXError> {
+        // SAFETY: fast mode VMs do not need a cache, `dataset` is initialised and outlives the VM, and
+        // `randomx_create_vm` returns null on failure, which is checked.
+        NonNull::new(unsafe {
+            randomx_create_vm(flags.bits(), ptr::null_mut(), dataset.0.as_ptr())
+        })
+        .map(Self)
+        .ok_or_else(|| RandomXError::CreationError("Could not create the RandomX VM".to_string()))
+    }
+}
+
+impl Drop for Vm {
+    fn drop(&mut self) {
+        // SAFETY: the VM is owned by `self`.
+        unsafe { randomx_destroy_vm(self.0.as_ptr()) };
+    }
+}
+
+/// A multithreaded RandomX VM in fast mode.
+pub(super) struct FastVm {
+    /// These RandomX VMs all share the same dataset.
+    ///
+    /// This must stay above `dataset` so the VMs are dropped before it.
+    vms: ThreadLocal<Vm>,
+    /// The RandomX dataset.
+    dataset: Dataset,
+    /// The flags used to start the RandomX VMs.
+    flags: RandomXFlag,
+}
+
+impl FastVm {
+    /// Allocate and initialise the dataset for `seed`, initialising it in parallel on the rayon thread-pool.
+    ///
+    /// `flags` are the cache flags, [`RandomXFlag::FLAG_FULL_MEM`] is added for the VMs.
+    pub(super) fn new(seed: &[u8; 32], flags: RandomXFlag) -> Result<Self, RandomXError> {
+        let (cache, flags) = alloc_with_fallback("cache", flags, |flags| Cache::new(flags, seed))?;
+        let (dataset, flags) = alloc_with_fallback("dataset", flags, Dataset::alloc)?;
+
+        dataset.init(&cache);
+
+        Ok(Self {
+            vms: ThreadLocal::new(),
+            dataset,
+            flags: flags | RandomXFlag::FLAG_FULL_MEM,
+        })
+    }
+
+    pub(super) fn calculate_hash(&self, buf: &[u8]) -> Result<[u8; 32], RandomXError> {
+        let vm = self.vms.get_or_try(|| {
+            alloc_with_fallback("VM", self.flags, |flags| Vm::new(flags, &self.dataset))
+                .map(|(vm, _)| vm)
+        })?;
+
+        let mut out = [0; 32];
+
+        // SAFETY: `vm` is only used by this thread, `buf` is valid for `buf.len()` bytes and `out` is
+        // `RANDOMX_HASH_SIZE` bytes.
+        unsafe {
+            randomx_calculate_hash(
+                vm.0.as_ptr(),
+                buf.as_ptr().cast(),
+                buf.len(),
+                out.as_mut_ptr().cast(),
+            );
+        };
+
+        Ok(out)
+    }
+}
+
+impl Debug for FastVm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FastVm")
+            .field("flags", &self.flags)
+            .finish_non_exhaustive()
+    }
+}
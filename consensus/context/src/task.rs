@@ -74,6 +74,7 @@ impl<D: Database + Clone + Send + 'static> ContextTask<D> {
             difficulty_cfg,
             weights_config,
             hard_fork_cfg,
+            rx_vms_cfg,
         } = cfg;
 
         tracing::debug!("Initialising blockchain context");
@@ -119,7 +120,13 @@ impl<D: Database + Clone + Send + 'static> ContextTask<D> {
 
         let db = database.clone();
         let rx_seed_handle = tokio::spawn(async move {
-            rx_vms::RandomXVmCache::init_from_chain_height(chain_height, &current_hf, db).await
+            rx_vms::RandomXVmCache::init_from_chain_height(
+                chain_height,
+                &current_hf,
+                rx_vms_cfg,
+                db,
+            )
+            .await
         });
 
         let difficulty_cache = difficulty_cache_handle.await.unwrap()?;
@@ -3,7 +3,7 @@
     reason = "binary shares same Cargo.toml as library"
 )]
 
-use std::fs::write;
+use std::{fs::write, path::PathBuf};
 
 use clap::Parser;
 use tower::{Service, ServiceExt};
@@ -11,13 +11,13 @@ use tower::{Service, ServiceExt};
 use cuprate_blockchain::{
     config::ConfigBuilder, cuprate_database::DbResult, service::BlockchainReadHandle,
 };
-use cuprate_hex::Hex;
+use cuprate_helper::network::Network;
 use cuprate_types::{
     blockchain::{BlockchainReadRequest, BlockchainResponse},
     Chain,
 };
 
-use cuprate_fast_sync::FAST_SYNC_BATCH_LEN;
+use cuprate_fast_sync::{FastSyncFile, FAST_SYNC_BATCH_LEN};
 
 async fn read_batch(
     handle: &mut BlockchainReadHandle,
@@ -42,6 +42,16 @@ async fn read_batch(
 struct Args {
     #[arg(long)]
     height: usize,
+    /// The network of the database, one of mainnet, testnet or stagenet.
+    #[arg(long, default_value_t = Network::Mainnet, value_parser = parse_network)]
+    network: Network,
+    /// The file to write the fast-sync hashes to.
+    #[arg(long, default_value = "fast_sync_hashes.json")]
+    output: PathBuf,
+}
+
+fn parse_network(s: &str) -> Result<Network, String> {
+    s.parse().map_err(|_| format!("unknown network: {s}"))
 }
 
 #[tokio::main]
@@ -49,7 +59,7 @@ async fn main() {
     let args = Args::parse();
     let height_target = args.height;
 
-    let config = ConfigBuilder::new().build();
+    let config = ConfigBuilder::new().network(args.network).build();
 
     let (mut read_handle, _, _) = cuprate_blockchain::service::init(config).unwrap();
 
@@ -60,7 +70,7 @@ async fn main() {
     while (height + FAST_SYNC_BATCH_LEN) < height_target {
         if let Ok(block_ids) = read_batch(&mut read_handle, height).await {
             let hash = hash_of_hashes(block_ids.as_slice());
-            hashes_of_hashes.push(Hex(hash));
+            hashes_of_hashes.push(hash);
         } else {
             println!("Failed to read next batch from database");
             break;
@@ -72,11 +82,9 @@ async fn main() {
 
     drop(read_handle);
 
-    write(
-        "fast_sync_hashes.json",
-        serde_json::to_string_pretty(&hashes_of_hashes).unwrap(),
-    )
-    .unwrap();
+    let file = FastSyncFile::new(args.network, hashes_of_hashes);
+
+    write(&args.output, serde_json::to_string_pretty(&file).unwrap()).unwrap();
 
     println!("Generated hashes up to block height {height}");
 }
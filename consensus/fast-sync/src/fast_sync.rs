@@ -173,6 +173,38 @@ pub async fn validate_entries<N: NetworkZone>(
     Ok((entries, unknown))
 }
 
+/// Validates that sequential block hashes are in the fast-sync hashes.
+///
+/// `start_height` should be the height of the first hash, it must be the start of a fast-sync
+/// batch and `hashes` must make up whole batches, otherwise this returns `false`.
+///
+/// # Panics
+///
+/// This will panic if [`set_fast_sync_hashes`] has not been called.
+pub fn validate_block_hashes(start_height: usize, hashes: &[[u8; 32]]) -> bool {
+    if start_height % FAST_SYNC_BATCH_LEN != 0
+        || hashes.len() % FAST_SYNC_BATCH_LEN != 0
+        || start_height + hashes.len() > fast_sync_stop_height()
+    {
+        return false;
+    }
+
+    let fast_sync_hashes =
+        &FAST_SYNC_HASHES.get().unwrap()[get_hash_index_for_height(start_height)..];
+
+    hashes
+        .chunks(FAST_SYNC_BATCH_LEN)
+        .zip(fast_sync_hashes)
+        .all(|(batch, fast_sync_hash)| {
+            let mut hasher = Hasher::default();
+            for hash in batch {
+                hasher.update(hash);
+            }
+
+            hasher.finalize() == *fast_sync_hash
+        })
+}
+
 /// Get the index of the hash that contains this block in the fast sync hashes.
 const fn get_hash_index_for_height(height: usize) -> usize {
     height / FAST_SYNC_BATCH_LEN
@@ -261,7 +293,8 @@ mod tests {
     use cuprate_p2p_core::{client::InternalPeerID, handles::HandleBuilder, ClearNet};
 
     use crate::{
-        fast_sync_stop_height, set_fast_sync_hashes, validate_entries, FAST_SYNC_BATCH_LEN,
+        fast_sync_stop_height, set_fast_sync_hashes, validate_block_hashes, validate_entries,
+        FAST_SYNC_BATCH_LEN,
     };
 
     static HASHES: LazyLock<&[[u8; 32]]> = LazyLock::new(|| {
@@ -293,6 +326,25 @@ mod tests {
         hashes
     });
 
+    #[test]
+    fn block_hashes() {
+        let start = FAST_SYNC_BATCH_LEN * 10;
+        let hashes = &HASHES[start..(start + FAST_SYNC_BATCH_LEN * 2)];
+
+        assert!(validate_block_hashes(start, hashes));
+
+        // Not aligned to a batch.
+        assert!(!validate_block_hashes(start + 1, &hashes[1..]));
+        assert!(!validate_block_hashes(start, &hashes[1..]));
+
+        // Past the fast-sync hashes.
+        assert!(!validate_block_hashes(fast_sync_stop_height(), hashes));
+
+        let mut hashes = hashes.to_vec();
+        hashes[FAST_SYNC_BATCH_LEN + 1] = [0; 32];
+        assert!(!validate_block_hashes(start, &hashes));
+    }
+
     proptest! {
         #[test]
         fn valid_entry(len in 0_usize..1_500_000) {
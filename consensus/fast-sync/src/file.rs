@@ -0,0 +1,120 @@
+//! The fast-sync hash file.
+//!
+//! This is the file written by `create-fs-file`, which can be loaded at runtime
+//! instead of using the hashes compiled into `cuprated`.
+
+use serde::{Deserialize, Serialize};
+
+use cuprate_helper::network::Network;
+use cuprate_hex::Hex;
+
+use crate::FAST_SYNC_BATCH_LEN;
+
+/// An error validating a [`FastSyncFile`].
+#[derive(Debug, thiserror::Error)]
+pub enum FastSyncFileError {
+    /// The file is for another network.
+    #[error("The fast-sync file is for {found}, expected {expected}")]
+    Network { expected: Network, found: Network },
+    /// The file's batch length is not [`FAST_SYNC_BATCH_LEN`].
+    #[error("The fast-sync file has a batch length of {0}, expected {FAST_SYNC_BATCH_LEN}")]
+    BatchLen(usize),
+    /// The checksum does not match the hashes.
+    #[error("The fast-sync file's checksum does not match its hashes")]
+    Checksum,
+}
+
+/// A fast-sync hash file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FastSyncFile {
+    /// The network the hashes are for.
+    pub network: Network,
+    /// The amount of block hashes hashed into each fast-sync hash.
+    pub batch_len: usize,
+    /// The fast-sync hashes, the hash of each batch of block hashes from genesis.
+    pub hashes: Vec<Hex<32>>,
+    /// The BLAKE3 hash of all `hashes`.
+    pub checksum: Hex<32>,
+}
+
+impl FastSyncFile {
+    /// Create a new [`FastSyncFile`] with the current [`FAST_SYNC_BATCH_LEN`].
+    pub fn new(network: Network, hashes: Vec<[u8; 32]>) -> Self {
+        Self {
+            network,
+            batch_len: FAST_SYNC_BATCH_LEN,
+            checksum: Hex(checksum(&hashes)),
+            hashes: hashes.into_iter().map(Hex).collect(),
+        }
+    }
+
+    /// Validate this file is for `network` and return the hashes.
+    ///
+    /// # Errors
+    /// This returns an error if the network, batch length or checksum is wrong.
+    pub fn into_hashes(self, network: Network) -> Result<Vec<[u8; 32]>, FastSyncFileError> {
+        if self.network != network {
+            return Err(FastSyncFileError::Network {
+                expected: network,
+                found: self.network,
+            });
+        }
+
+        if self.batch_len != FAST_SYNC_BATCH_LEN {
+            return Err(FastSyncFileError::BatchLen(self.batch_len));
+        }
+
+        let hashes = self.hashes.into_iter().map(|h| h.0).collect::<Vec<_>>();
+
+        if checksum(&hashes) != self.checksum.0 {
+            return Err(FastSyncFileError::Checksum);
+        }
+
+        Ok(hashes)
+    }
+}
+
+/// Returns the checksum of fast-sync `hashes`.
+fn checksum(hashes: &[[u8; 32]]) -> [u8; 32] {
+    blake3::hash(hashes.concat().as_slice()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_round_trip() {
+        let hashes = vec![[1; 32], [2; 32], [3; 32]];
+        let file = FastSyncFile::new(Network::Stagenet, hashes.clone());
+
+        let json = serde_json::to_string(&file).unwrap();
+        let file: FastSyncFile = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(file.into_hashes(Network::Stagenet).unwrap(), hashes);
+    }
+
+    #[test]
+    fn invalid_file() {
+        let file = FastSyncFile::new(Network::Testnet, vec![[1; 32], [2; 32]]);
+
+        assert!(matches!(
+            file.clone().into_hashes(Network::Mainnet),
+            Err(FastSyncFileError::Network { .. })
+        ));
+
+        let mut wrong_batch_len = file.clone();
+        wrong_batch_len.batch_len = 1024;
+        assert!(matches!(
+            wrong_batch_len.into_hashes(Network::Testnet),
+            Err(FastSyncFileError::BatchLen(1024))
+        ));
+
+        let mut wrong_hashes = file;
+        wrong_hashes.hashes.pop();
+        assert!(matches!(
+            wrong_hashes.into_hashes(Network::Testnet),
+            Err(FastSyncFileError::Checksum)
+        ));
+    }
+}
@@ -10,7 +10,8 @@ use cuprate_helper::network::Network;
 
 const fn genesis_nonce(network: Network) -> u32 {
     match network {
-        Network::Mainnet => 10000,
+        // Regtest uses the main-net genesis block, like `monerod`'s fakechain.
+        Network::Mainnet | Network::Regtest => 10000,
         Network::Testnet => 10001,
         Network::Stagenet => 10002,
     }
@@ -18,7 +19,7 @@ const fn genesis_nonce(network: Network) -> u32 {
 
 fn genesis_miner_tx(network: Network) -> Transaction {
     Transaction::read(&mut hex::decode(match network {
-        Network::Mainnet | Network::Testnet  => "013c01ff0001ffffffffffff03029b2e4c0281c0b02e7c53291a94d1d0cbff8883f8024f5142ee494ffbbd08807121017767aafcde9be00dcfd098715ebcf7f410daebc582fda69d24a28e9d0bc890d1",
+        Network::Mainnet | Network::Testnet | Network::Regtest => "013c01ff0001ffffffffffff03029b2e4c0281c0b02e7c53291a94d1d0cbff8883f8024f5142ee494ffbbd08807121017767aafcde9be00dcfd098715ebcf7f410daebc582fda69d24a28e9d0bc890d1",
         Network::Stagenet => "013c01ff0001ffffffffffff0302df5d56da0c7d643ddd1ce61901c7bdc5fb1738bfe39fbe69c28a3a7032729c0f2101168d0c4ca86fb55a4cf6a36d31431be1c53a3bd7411bb24e8832410289fa6f3b"
     }).unwrap().as_slice()).unwrap()
 }
@@ -130,6 +130,17 @@ impl HFsInfo {
             HFInfo::new(1151720, 0),
         ])
     }
+
+    /// Returns the reg-test hard-fork information.
+    ///
+    /// The genesis block is [`HardFork::V1`], every other hard-fork activates at height 1 so the chain
+    /// is on the latest hard-fork straight away, like `monerod`'s `--regtest`.
+    pub const fn reg_test() -> Self {
+        let mut hfs = [HFInfo::new(1, 0); NUMB_OF_HARD_FORKS];
+        hfs[0] = HFInfo::new(0, 0);
+
+        Self(hfs)
+    }
 }
 
 /// A struct holding the current voting state of the blockchain.
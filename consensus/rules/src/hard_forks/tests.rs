@@ -2,7 +2,7 @@ use std::convert::TryInto;
 
 use proptest::{arbitrary::any, prop_assert_eq, prop_compose, proptest};
 
-use crate::hard_forks::{HFVotes, HardFork, NUMB_OF_HARD_FORKS};
+use crate::hard_forks::{HFVotes, HFsInfo, HardFork, NUMB_OF_HARD_FORKS};
 
 const TEST_WINDOW_SIZE: usize = 25;
 
@@ -30,6 +30,21 @@ fn hard_forks_defined() {
     }
 }
 
+#[test]
+fn reg_test_latest_hard_fork() {
+    let votes = HFVotes::new(TEST_WINDOW_SIZE);
+    let hfs_info = HFsInfo::reg_test();
+
+    assert_eq!(
+        votes.current_fork(&HardFork::V1, 0, TEST_WINDOW_SIZE, &hfs_info),
+        HardFork::V1
+    );
+    assert_eq!(
+        votes.current_fork(&HardFork::V1, 1, TEST_WINDOW_SIZE, &hfs_info),
+        HardFork::LATEST
+    );
+}
+
 prop_compose! {
     /// Generates an arbitrary full [`HFVotes`].
     fn arb_full_hf_votes()
@@ -16,11 +16,11 @@ use cuprate_helper::asynch::rayon_spawn_async;
 use cuprate_types::{output_cache::OutputCache, TransactionVerificationData};
 
 use crate::{
+    __private::Database,
     batch_verifier::MultiThreadedBatchVerifier,
     block::{free::order_transactions, PreparedBlock, PreparedBlockExPow},
     transactions::{check_kis_unique, contextual_data::get_output_cache, start_tx_verification},
     BlockChainContextRequest, BlockChainContextResponse, ExtendedConsensusError,
-    __private::Database,
 };
 
 /// Cached state created when batch preparing a group of blocks.
@@ -152,8 +152,12 @@ pub async fn batch_prepare_main_chain_blocks<D: Database>(
     if let Some((new_vm_height, new_vm_seed)) = new_rx_vm {
         tracing::debug!("New randomX seed in batch, initialising VM");
 
+        let rx_vms_cfg = *context_svc.rx_vms_config();
         let new_vm = rayon_spawn_async(move || {
-            Arc::new(RandomXVm::new(&new_vm_seed).expect("RandomX VM gave an error on set up!"))
+            Arc::new(
+                RandomXVm::new(&new_vm_seed, &rx_vms_cfg)
+                    .expect("RandomX VM gave an error on set up!"),
+            )
         })
         .await;
 
@@ -4,6 +4,7 @@ use tower::ServiceExt;
 
 use cuprate_consensus_context::{
     initialize_blockchain_context, BlockChainContextRequest, ContextConfig, NewBlockData,
+    RandomXConfig,
 };
 
 use crate::{tests::mock_db::*, HardFork};
@@ -22,6 +23,7 @@ const TEST_CONTEXT_CONFIG: ContextConfig = ContextConfig {
     hard_fork_cfg: TEST_HARD_FORK_CONFIG,
     difficulty_cfg: TEST_DIFFICULTY_CONFIG,
     weights_config: TEST_WEIGHT_CONFIG,
+    rx_vms_cfg: RandomXConfig::light(),
 };
 
 #[tokio::test]
@@ -3,7 +3,7 @@ use std::collections::VecDeque;
 use proptest::prelude::*;
 use tokio::runtime::Builder;
 
-use cuprate_consensus_context::rx_vms::{get_last_rx_seed_heights, RandomXVmCache};
+use cuprate_consensus_context::rx_vms::{get_last_rx_seed_heights, RandomXConfig, RandomXVmCache};
 use cuprate_consensus_rules::{
     blocks::{is_randomx_seed_height, randomx_seed_height},
     HardFork,
@@ -41,9 +41,10 @@ fn rx_heights_consistent() {
 async fn rx_vm_created_on_hf_12() {
     let db = DummyDatabaseBuilder::default().finish(Some(10));
 
-    let mut cache = RandomXVmCache::init_from_chain_height(10, &HardFork::V11, db)
-        .await
-        .unwrap();
+    let mut cache =
+        RandomXVmCache::init_from_chain_height(10, &HardFork::V11, RandomXConfig::light(), db)
+            .await
+            .unwrap();
 
     assert!(cache.vms.is_empty());
     cache.new_block(11, &[30; 32]);
@@ -66,7 +67,7 @@ proptest! {
         let rt = Builder::new_multi_thread().enable_all().build().unwrap();
 
         rt.block_on(async move {
-            let cache = RandomXVmCache::init_from_chain_height(10, &hf, db).await.unwrap();
+            let cache = RandomXVmCache::init_from_chain_height(10, &hf, RandomXConfig::light(), db).await.unwrap();
             assert!(cache.seeds.len() == cache.vms.len() || hf < HardFork::V12);
         });
     }
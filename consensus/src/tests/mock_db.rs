@@ -67,6 +67,7 @@ impl From<DummyBlockExtendedHeader> for ExtendedBlockHeader {
             cumulative_difficulty: value.cumulative_difficulty.unwrap_or_default(),
             block_weight: value.block_weight.unwrap_or_default(),
             long_term_weight: value.long_term_weight.unwrap_or_default(),
+            cumulative_generated_coins: None,
         }
     }
 }
@@ -29,6 +29,7 @@
 
 //---------------------------------------------------------------------------------------------------- Use
 use std::{
+    io,
     path::{Path, PathBuf},
     sync::LazyLock,
 };
@@ -230,6 +231,91 @@ pub fn address_book_path(cache_dir: &Path, network: Network) -> PathBuf {
     path_with_network(cache_dir, network).join("addressbook")
 }
 
+/// Cuprate's I2P directory.
+///
+/// This is the PATH used for any Cuprate I2P files, e.g. our destination's keys.
+///
+/// ```rust
+/// use cuprate_helper::{network::Network, fs::{CUPRATE_DATA_DIR, i2p_path}};
+///
+/// assert_eq!(i2p_path(&**CUPRATE_DATA_DIR, Network::Mainnet).as_path(), CUPRATE_DATA_DIR.join("i2p"));
+/// assert_eq!(i2p_path(&**CUPRATE_DATA_DIR, Network::Stagenet).as_path(), CUPRATE_DATA_DIR.join(Network::Stagenet.to_string()).join("i2p"));
+/// assert_eq!(i2p_path(&**CUPRATE_DATA_DIR, Network::Testnet).as_path(), CUPRATE_DATA_DIR.join(Network::Testnet.to_string()).join("i2p"));
+/// ```
+pub fn i2p_path(data_dir: &Path, network: Network) -> PathBuf {
+    path_with_network(data_dir, network).join("i2p")
+}
+
+//---------------------------------------------------------------------------------------------------- Disk space
+/// Returns the amount of bytes available to us on the disk that `path` is located on.
+///
+/// ```rust
+/// use cuprate_helper::fs::free_space;
+///
+/// assert!(free_space(&std::env::temp_dir()).unwrap() > 0);
+/// assert!(free_space("/this/path/does/not/exist".as_ref()).is_err());
+/// ```
+///
+/// # Errors
+/// This will error if `path` does not exist or if the OS fails to report the disk space.
+pub fn free_space(path: &Path) -> io::Result<u64> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::ffi::OsStrExt;
+
+        use target_os_lib as windows;
+        use windows::{core::PCWSTR, Win32::Storage::FileSystem::GetDiskFreeSpaceExW};
+
+        let path = path
+            .as_os_str()
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect::<Vec<u16>>();
+        let mut free_bytes = 0_u64;
+
+        // SAFETY: calling C.
+        // `path` is a null-terminated wide string that outlives the call.
+        unsafe {
+            GetDiskFreeSpaceExW(
+                PCWSTR(path.as_ptr()),
+                Some(std::ptr::from_mut(&mut free_bytes)),
+                None,
+                None,
+            )?;
+        }
+
+        Ok(free_bytes)
+    }
+
+    #[cfg(target_family = "unix")]
+    {
+        use std::{ffi::CString, mem::MaybeUninit, os::unix::ffi::OsStrExt};
+
+        use target_os_lib as libc;
+
+        /// `statvfs`'s field types differ between platforms, they are all unsigned integers <= 64 bits.
+        fn widen<T: Into<u64>>(n: T) -> u64 {
+            n.into()
+        }
+
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+
+        // SAFETY: calling C.
+        // `path` is a null-terminated string that outlives the call and
+        // `stat` is only read after `statvfs` reports it was initialized.
+        let stat = unsafe {
+            if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            stat.assume_init()
+        };
+
+        Ok(widen(stat.f_bavail).saturating_mul(widen(stat.f_frsize)))
+    }
+}
+
 //---------------------------------------------------------------------------------------------------- Tests
 #[cfg(test)]
 mod test {
@@ -1,4 +1,4 @@
-//! This module contains an enum representing every Monero network: mainnet, testnet, stagenet and functionality
+//! This module contains an enum representing every Monero network: mainnet, testnet, stagenet, regtest and functionality
 //! related to that.
 //!
 //! This feels out of place for the helper crate but this is needed through out Cuprate and felt too small to split
@@ -21,6 +21,9 @@ const TESTNET_NETWORK_ID: [u8; 16] = [
 const STAGENET_NETWORK_ID: [u8; 16] = [
     0x12, 0x30, 0xF1, 0x71, 0x61, 0x04, 0x41, 0x61, 0x17, 0x31, 0x00, 0x82, 0x16, 0xA1, 0xA1, 0x12,
 ];
+const REGTEST_NETWORK_ID: [u8; 16] = [
+    0x12, 0x30, 0xF1, 0x71, 0x61, 0x04, 0x41, 0x61, 0x17, 0x31, 0x00, 0x82, 0x16, 0xA1, 0xA1, 0x13,
+];
 
 /// An enum representing every Monero network.
 #[derive(Debug, Clone, Copy, Default, Ord, PartialOrd, Eq, PartialEq)]
@@ -33,6 +36,10 @@ pub enum Network {
     Testnet,
     /// Stagenet
     Stagenet,
+    /// Regtest
+    ///
+    /// A private local network, `monerod`'s `--regtest`.
+    Regtest,
 }
 
 impl Network {
@@ -42,6 +49,7 @@ impl Network {
             Self::Mainnet => MAINNET_NETWORK_ID,
             Self::Testnet => TESTNET_NETWORK_ID,
             Self::Stagenet => STAGENET_NETWORK_ID,
+            Self::Regtest => REGTEST_NETWORK_ID,
         }
     }
 }
@@ -57,6 +65,7 @@ impl FromStr for Network {
             "mainnet" | "Mainnet" => Ok(Self::Mainnet),
             "testnet" | "Testnet" => Ok(Self::Testnet),
             "stagenet" | "Stagenet" => Ok(Self::Stagenet),
+            "regtest" | "Regtest" => Ok(Self::Regtest),
             _ => Err(ParseNetworkError),
         }
     }
@@ -67,6 +76,7 @@ impl Display for Network {
             Self::Mainnet => "mainnet",
             Self::Testnet => "testnet",
             Self::Stagenet => "stagenet",
+            Self::Regtest => "regtest",
         })
     }
 }
@@ -64,7 +64,7 @@ use hex as _;
 
 extern crate alloc;
 
-use alloc::string::ToString;
+use alloc::{string::ToString, vec::Vec};
 use core::str::from_utf8 as str_from_utf8;
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
@@ -174,6 +174,37 @@ pub fn write_field<T: EpeeValue, B: BufMut>(val: T, field_name: &str, w: &mut B)
     Ok(())
 }
 
+/// Write an epee object, the number of fields followed by the fields.
+///
+/// `monerod` keeps the fields of an object in a `std::map`, so they are always written
+/// sorted by name. The fields written by [`EpeeObject::write_fields`] are re-ordered here
+/// so our output is byte-for-byte the same.
+fn write_object<T: EpeeObject, B: BufMut>(val: T, w: &mut B) -> Result<()> {
+    let mut buf = BytesMut::new();
+    val.write_fields(&mut buf)?;
+    let mut buf = buf.freeze();
+
+    let mut fields = Vec::new();
+
+    while buf.has_remaining() {
+        let field = buf.clone();
+
+        let name = read_field_name_bytes(&mut buf)?;
+        skip_epee_value(&mut buf, &mut 0)?;
+
+        fields.push((name, field.slice(..field.len() - buf.len())));
+    }
+
+    fields.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+    write_varint(fields.len(), w)?;
+    for (_, field) in fields {
+        checked_write(w, BufMut::put_slice, &field, field.len())?;
+    }
+
+    Ok(())
+}
+
 fn read_object<T: EpeeObject, B: Buf>(r: &mut B, skipped_objects: &mut u8) -> Result<T> {
     let mut object_builder = T::Builder::default();
 
@@ -12,7 +12,7 @@ use cuprate_hex::{Hex, HexVec};
 use crate::{
     io::{checked_read_primitive, checked_write_primitive},
     max_upfront_capacity,
-    varint::{read_varint, write_varint},
+    varint::read_varint,
     write_bytes, write_iterator, EpeeObject, Error, InnerMarker, Marker, Result,
     MAX_STRING_LEN_POSSIBLE,
 };
@@ -54,8 +54,7 @@ impl<T: EpeeObject> EpeeValue for T {
     }
 
     fn write<B: BufMut>(self, w: &mut B) -> Result<()> {
-        write_varint(self.number_of_fields(), w)?;
-        self.write_fields(w)
+        crate::write_object(self, w)
     }
 }
 
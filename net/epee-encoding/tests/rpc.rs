@@ -66,9 +66,11 @@ epee_object!(
 fn rpc_get_outs_response() {
     let bytes = hex::decode("011101010101020101140763726564697473050000000000000000046f7574738c04140668656967687405a100000000000000036b65790a802d392d0be38eb4699c17767e62a063b8d2f989ec15c80e5d2665ab06f8397439046d61736b0a805e8b863c5b267deda13f4bc5d5ec8e59043028380f2431bc8691c15c83e1fea404747869640a80c0646e065a33b849f0d9563673ca48eb0c603fe721dd982720dba463172c246f08756e6c6f636b65640b00067374617475730a084f4b08746f705f686173680a0009756e747275737465640b00").unwrap();
     let val: GetOutsResponse = from_bytes(&mut bytes.as_slice()).unwrap();
-    let mut bytes = to_bytes(val.clone()).unwrap();
+    let mut encoded = to_bytes(val.clone()).unwrap();
 
-    assert_eq!(val, from_bytes(&mut bytes).unwrap());
+    // `monerod` writes the fields sorted by name, so should we.
+    assert_eq!(&*encoded, bytes.as_slice());
+    assert_eq!(val, from_bytes(&mut encoded).unwrap());
 }
 
 #[test]
@@ -79,7 +81,9 @@ fn get_out_indexes_response() {
         104, 10, 0, 9, 117, 110, 116, 114, 117, 115, 116, 101, 100, 11, 0,
     ];
     let val: GetOIndexesResponse = from_bytes(&mut bytes.as_slice()).unwrap();
-    let mut bytes = to_bytes(val.clone()).unwrap();
+    let mut encoded = to_bytes(val.clone()).unwrap();
 
-    assert_eq!(val, from_bytes(&mut bytes).unwrap());
+    // `monerod` writes the fields sorted by name, so should we.
+    assert_eq!(&*encoded, bytes.as_slice());
+    assert_eq!(val, from_bytes(&mut encoded).unwrap());
 }
@@ -15,7 +15,7 @@
 
 //! This module defines the addresses that will get passed around the
 //! Monero network. Core Monero has 4 main addresses: IPv4, IPv6, Tor,
-//! I2p. Currently this module only has IPv(4/6).
+//! I2p.
 //!
 use std::{hash::Hash, net, net::SocketAddr};
 
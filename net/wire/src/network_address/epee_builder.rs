@@ -16,7 +16,7 @@ use cuprate_types::AddressType;
 
 use crate::NetworkAddress;
 
-use super::OnionAddr;
+use super::{GarlicAddr, OnionAddr};
 
 //---------------------------------------------------------------------------------------------------- Network address construction
 
@@ -121,6 +121,16 @@ impl From<NetworkAddress> for TaggedNetworkAddress {
                     port: Some(onion_addr.port()),
                 }),
             },
+            NetworkAddress::I2p(garlic_addr) => Self {
+                ty: Some(AddressType::I2p),
+                addr: Some(AllFieldsNetworkAddress {
+                    m_ip: None,
+                    m_port: None,
+                    addr: None,
+                    host: Some(garlic_addr.addr_string()),
+                    port: Some(garlic_addr.port()),
+                }),
+            },
         }
     }
 }
@@ -169,8 +179,10 @@ impl AllFieldsNetworkAddress {
             AddressType::Tor => {
                 NetworkAddress::from(OnionAddr::new(self.host?.as_str(), self.port?).ok()?)
             }
-            // Invalid
-            _ => return None,
+            AddressType::I2p => {
+                NetworkAddress::from(GarlicAddr::new(self.host?.as_str(), self.port?).ok()?)
+            }
+            AddressType::Invalid => return None,
         })
     }
 }
@@ -1,116 +1,490 @@
 //! Garlic address
 //!
-//! This module define I2P Destination addresses
+//! This module define I2P garlic addresses and destinations
 //!
 
-use std::{
-    fmt::{Display, Formatter},
-    str::FromStr,
+use std::{fmt::Display, str::FromStr};
+
+use base64::{
+    alphabet::Alphabet,
+    engine::{general_purpose::PAD, GeneralPurpose},
+    Engine,
 };
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// The base32 alphabet used by `.b32.i2p` addresses.
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
 
-use cuprate_epee_encoding::{error::*, read_epee_value, write_field, EpeeObject, EpeeObjectBuilder};
+/// I2P's base64 alphabet, which replaces `+/` with `-~`.
+const I2P_BASE64_ALPHABET: Alphabet =
+    match Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-~") {
+        Ok(alphabet) => alphabet,
+        Err(_) => panic!("I2P base64 alphabet is invalid"),
+    };
 
-/// An I2P garlic address.
+/// I2P's base64 encoding, used for destinations.
+const I2P_BASE64: GeneralPurpose = GeneralPurpose::new(&I2P_BASE64_ALPHABET, PAD);
+
+/// The minimum length of a binary destination.
 ///
-/// This represents an I2P destination address used for anonymous networking.
-/// I2P destinations are represented as Base64-encoded strings that contain
-/// the public key and certificate information.
+/// A destination is a 256 byte public key, a 128 byte signing key and a certificate,
+/// which is at least a 1 byte type and a 2 byte length.
+const MIN_DESTINATION_LEN: usize = 387;
+
+/// A `Copy`able I2P garlic address.
+///
+/// This is the `.b32.i2p` address of a destination, the SHA-256 hash of the full [`I2pDestination`].
+/// This is the form monerod uses to exchange I2P peers.
 #[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 pub struct GarlicAddr {
-    /// The I2P destination encoded as Base64, truncated to 32 bytes for storage efficiency
-    /// The full destination would be much larger (~387+ bytes), but we store a hash/truncated version
-    destination: [u8; 32],
-    /// Virtual port of the service
+    /// The SHA-256 hash of the peer's destination.
+    /// <https://geti2p.net/spec/b32encrypted>
+    hash: [u8; 32],
+    /// Virtual port of the peer
     pub port: u16,
 }
 
+/// Error enum at parsing garlic addresses
+#[derive(Debug, Error)]
+pub enum GarlicAddrParsingError {
+    #[error("Address is either too long or short, length: {0}")]
+    InvalidLength(usize),
+    #[error("This is not a .b32.i2p address, Tld: {0}")]
+    InvalidTld(String),
+    #[error("Domain contains non base32 characters")]
+    NonBase32Char,
+    #[error("Destination contains non I2P base64 characters")]
+    NonBase64Char,
+    #[error("Destination is malformed, length: {0}")]
+    InvalidDestination(usize),
+    #[error("Invalid port specified")]
+    InvalidPort,
+}
+
 impl GarlicAddr {
-    /// Creates a new `GarlicAddr` from a destination hash and port.
-    pub const fn new(destination: [u8; 32], port: u16) -> Self {
-        Self { destination, port }
+    /// Attempt to create a [`GarlicAddr`] from a complete .b32.i2p address string and a port.
+    ///
+    /// Return a [`GarlicAddrParsingError`] if the supplied `addr` is invalid.
+    pub fn new(addr: &str, port: u16) -> Result<Self, GarlicAddrParsingError> {
+        Self::check_addr(addr).map(|hash| Self { hash, port })
+    }
+
+    /// Create a [`GarlicAddr`] from the SHA-256 hash of a destination and a port.
+    pub const fn from_hash(hash: [u8; 32], port: u16) -> Self {
+        Self { hash, port }
+    }
+
+    /// Establish if the .b32.i2p address is valid.
+    ///
+    /// Return the 32 byte hash of the destination if valid, `GarlicAddrParsingError` otherwise.
+    pub fn check_addr(addr: &str) -> Result<[u8; 32], GarlicAddrParsingError> {
+        // 52 base32 characters followed by ".b32.i2p"
+        if addr.len() != 60 {
+            return Err(GarlicAddrParsingError::InvalidLength(addr.len()));
+        }
+
+        let Some((domain, tld)) = addr.split_at_checked(52) else {
+            return Err(GarlicAddrParsingError::NonBase32Char);
+        };
+
+        if tld != ".b32.i2p" {
+            return Err(GarlicAddrParsingError::InvalidTld(String::from(tld)));
+        }
+
+        decode_base32(domain.as_bytes()).ok_or(GarlicAddrParsingError::NonBase32Char)
     }
 
-    /// Returns the destination hash.
-    pub const fn destination(&self) -> [u8; 32] {
-        self.destination
+    /// Generate a garlic address string.
+    ///
+    /// Returns a `String` containing the base32 hash and ".b32.i2p" TLD only, in form of `s3l6ke...sghe3a.b32.i2p`.
+    pub fn addr_string(&self) -> String {
+        let mut domain = encode_base32(&self.hash);
+        domain.push_str(".b32.i2p");
+        domain
     }
 
-    /// Returns the port.
+    #[inline]
     pub const fn port(&self) -> u16 {
         self.port
     }
+
+    #[inline]
+    pub const fn hash(&self) -> [u8; 32] {
+        self.hash
+    }
 }
 
+/// Display for [`GarlicAddr`]. It prints the garlic address and port, in the form of `<hash>.b32.i2p:<port>`
 impl Display for GarlicAddr {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        // Display as a truncated Base64-like representation with port
-        write!(
-            f,
-            "{}...{}:{}",
-            base64::encode(&self.destination[..8]),
-            base64::encode(&self.destination[24..]),
-            self.port
-        )
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&encode_base32(&self.hash))?;
+        f.write_str(".b32.i2p:")?;
+        self.port.fmt(f)
     }
 }
 
+/// [`GarlicAddr`] parses a garlic address or a full base64 destination, **and a port**.
+///
+/// Only the hash of a full destination is kept, use [`FullGarlicAddr`] to keep the destination.
 impl FromStr for GarlicAddr {
-    type Err = std::io::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Parse format: "base64destination:port" or "truncated_display_format"
-        let parts: Vec<&str> = s.rsplitn(2, ':').collect();
-        if parts.len() != 2 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Invalid garlic address format",
-            ));
+    type Err = GarlicAddrParsingError;
+
+    fn from_str(addr: &str) -> Result<Self, Self::Err> {
+        addr.parse::<FullGarlicAddr>()
+            .map(|full_addr| full_addr.addr)
+    }
+}
+
+/// A [`GarlicAddr`] and, if it is known, the full [`I2pDestination`] it is the hash of.
+///
+/// [`GarlicAddr`] is `Copy` so only holds the hash of the destination, this also keeps the
+/// destination when an address is parsed from one.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FullGarlicAddr {
+    /// The garlic address.
+    pub addr: GarlicAddr,
+    /// The full destination, [`None`] if only the `.b32.i2p` address is known.
+    pub destination: Option<I2pDestination>,
+}
+
+impl From<GarlicAddr> for FullGarlicAddr {
+    fn from(addr: GarlicAddr) -> Self {
+        Self {
+            addr,
+            destination: None,
+        }
+    }
+}
+
+/// Display for [`FullGarlicAddr`]. It prints the destination in I2P's base64 if it is known, otherwise the
+/// garlic address, and the port.
+impl Display for FullGarlicAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.destination {
+            Some(destination) => write!(f, "{destination}:{}", self.addr.port),
+            None => self.addr.fmt(f),
+        }
+    }
+}
+
+/// [`FullGarlicAddr`] parses a garlic address or a full base64 destination, **and a port**.
+impl FromStr for FullGarlicAddr {
+    type Err = GarlicAddrParsingError;
+
+    fn from_str(addr: &str) -> Result<Self, Self::Err> {
+        let (addr, port) = addr
+            .rsplit_once(':')
+            .ok_or(GarlicAddrParsingError::InvalidPort)?;
+
+        // Port
+        let port: u16 = port
+            .parse()
+            .map_err(|_| GarlicAddrParsingError::InvalidPort)?;
+
+        // Address, a base64 destination never contains a '.'
+        if addr.contains('.') {
+            GarlicAddr::new(addr, port).map(Self::from)
+        } else {
+            let destination = addr.parse::<I2pDestination>()?;
+
+            Ok(Self {
+                addr: destination.garlic_addr(port),
+                destination: Some(destination),
+            })
+        }
+    }
+}
+
+/// A full I2P destination.
+///
+/// This is needed to open a stream to a peer, but is ~400 bytes long so peers are
+/// exchanged and stored as the hash of their destination, a [`GarlicAddr`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct I2pDestination(Vec<u8>);
+
+impl I2pDestination {
+    /// Attempt to create an [`I2pDestination`] from its binary form.
+    ///
+    /// Return a [`GarlicAddrParsingError`] if the certificate length does not match the length of `bytes`.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, GarlicAddrParsingError> {
+        let Some(&[_cert_type, len_hi, len_lo]) =
+            bytes.get(MIN_DESTINATION_LEN - 3..MIN_DESTINATION_LEN)
+        else {
+            return Err(GarlicAddrParsingError::InvalidDestination(bytes.len()));
+        };
+
+        if bytes.len() != MIN_DESTINATION_LEN + usize::from(u16::from_be_bytes([len_hi, len_lo])) {
+            return Err(GarlicAddrParsingError::InvalidDestination(bytes.len()));
+        }
+
+        Ok(Self(bytes))
+    }
+
+    /// Returns the binary form of this destination.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the [`GarlicAddr`] of this destination, with the given port.
+    pub fn garlic_addr(&self, port: u16) -> GarlicAddr {
+        GarlicAddr {
+            hash: Sha256::digest(&self.0).into(),
+            port,
         }
+    }
+}
+
+/// Display for [`I2pDestination`]. It prints the destination in I2P's base64.
+impl Display for I2pDestination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&I2P_BASE64.encode(&self.0))
+    }
+}
+
+/// [`I2pDestination`] parses a destination in I2P's base64.
+impl FromStr for I2pDestination {
+    type Err = GarlicAddrParsingError;
 
-        let port = parts[0].parse::<u16>().map_err(|_| {
-            std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid port")
-        })?;
-
-        let dest_str = parts[1];
-        
-        // For now, create a simple hash of the destination string
-        // In a real implementation, you'd decode the Base64 I2P destination
-        let mut destination = [0u8; 32];
-        let hash = std::collections::hash_map::DefaultHasher::new();
-        use std::hash::{Hash, Hasher};
-        dest_str.hash(&mut hash.clone());
-        let hash_val = hash.finish();
-        destination[..8].copy_from_slice(&hash_val.to_le_bytes());
-        
-        Ok(Self::new(destination, port))
+    fn from_str(destination: &str) -> Result<Self, Self::Err> {
+        let bytes = I2P_BASE64
+            .decode(destination)
+            .map_err(|_| GarlicAddrParsingError::NonBase64Char)?;
+
+        Self::from_bytes(bytes)
     }
 }
 
-impl EpeeObjectBuilder<GarlicAddr> for () {
-    fn add_field<T: EpeeObject>(&mut self, name: &str, t: T) -> Result<()> {
-        Err(EpeeError::Format("Garlic address builder not implemented"))
+/// Encodes `hash` in lowercase, unpadded base32.
+fn encode_base32(hash: &[u8; 32]) -> String {
+    let mut domain = String::with_capacity(52);
+    let mut buffer = 0_u16;
+    let mut bits = 0;
+
+    for byte in hash {
+        buffer = (buffer << 8) | u16::from(*byte);
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            domain.push(char::from(
+                BASE32_ALPHABET[usize::from((buffer >> bits) & 0x1f)],
+            ));
+        }
     }
 
-    fn finish(self) -> Result<GarlicAddr> {
-        Err(EpeeError::Format("Garlic address builder not implemented"))
+    // 256 bits leave 1 bit, which is padded with zeros.
+    domain.push(char::from(
+        BASE32_ALPHABET[usize::from((buffer << (5 - bits)) & 0x1f)],
+    ));
+
+    domain
+}
+
+/// Decodes 52 lowercase base32 characters into a 32 byte hash.
+///
+/// Returns [`None`] if a character is not base32 or if the padding bits are not zero.
+fn decode_base32(domain: &[u8]) -> Option<[u8; 32]> {
+    let mut hash = [0; 32];
+    let mut buffer = 0_u16;
+    let mut bits = 0;
+    let mut i = 0;
+
+    for c in domain {
+        let value = match c {
+            b'a'..=b'z' => c - b'a',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+
+        buffer = (buffer << 5) | u16::from(value);
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            *hash.get_mut(i)? = (buffer >> bits).to_be_bytes()[1];
+            i += 1;
+        }
     }
+
+    // The remaining bit must be padding.
+    (i == 32 && buffer & ((1 << bits) - 1) == 0).then_some(hash)
 }
 
-impl EpeeObject for GarlicAddr {
-    type Builder = ();
+#[cfg(test)]
+mod tests {
+    use proptest::{collection::vec, prelude::*};
+
+    use cuprate_epee_encoding::{from_bytes, to_bytes};
+
+    use super::*;
+    use crate::NetworkAddress;
+
+    const VALID_GARLIC_ADDRESSES: &[&str] = &[
+        // monerod I2P mainnet seed nodes
+        "s3l6ke4ed3df466khuebb4poienoingwof7oxtbo6j4n56sghe3a.b32.i2p",
+        "sel36x6fibfzujwvt4hf5gxolz6kd3jpvbjqg6o3ud2xtionyl2q.b32.i2p",
+    ];
+
+    /// A destination with a null certificate, all keys set to `0x55`.
+    fn test_destination() -> I2pDestination {
+        let mut bytes = vec![0x55; MIN_DESTINATION_LEN];
+        bytes[384..].fill(0);
+        I2pDestination::from_bytes(bytes).unwrap()
+    }
+
+    #[test]
+    fn valid_garlic_address() {
+        for addr in VALID_GARLIC_ADDRESSES {
+            let garlic_addr = GarlicAddr::new(addr, 18080).unwrap();
+
+            assert_eq!(&garlic_addr.addr_string(), addr);
+            assert_eq!(garlic_addr.to_string(), format!("{addr}:18080"));
+            assert_eq!(
+                garlic_addr.to_string().parse::<GarlicAddr>().unwrap(),
+                garlic_addr
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_garlic_address() {
+        for addr in [
+            // Padding bits are set.
+            "s3l6ke4ed3df466khuebb4poienoingwof7oxtbo6j4n56sghe3b.b32.i2p",
+            // Not base32.
+            "s3l6ke4ed3df466khuebb4poienoingwof7oxtbo6j4n56sghe31.b32.i2p",
+            "S3L6KE4ED3DF466KHUEBB4POIENOINGWOF7OXTBO6J4N56SGHE3A.b32.i2p",
+            // Wrong TLD.
+            "s3l6ke4ed3df466khuebb4poienoingwof7oxtbo6j4n56sghe3a.b33.i2p",
+            // Encrypted leaseset (b33) addresses are not supported by monerod.
+            "s3l6ke4ed3df466khuebb4poienoingwof7oxtbo6j4n56sghe3as3l6ke4.b32.i2p",
+        ] {
+            assert!(
+                GarlicAddr::check_addr(addr).is_err(),
+                "Address {addr} has been reported as valid."
+            );
+        }
+    }
+
+    #[test]
+    fn destination_hash() {
+        let destination = test_destination();
+
+        // The b32 address is the SHA-256 hash of the binary destination.
+        assert_eq!(
+            destination.garlic_addr(0).addr_string(),
+            "25un3rgvygyqrstjot66v3rmnaks6we4gg2chxxfowh6q2nlm35q.b32.i2p"
+        );
+
+        let base64 = destination.to_string();
+        assert_eq!(base64.parse::<I2pDestination>().unwrap(), destination);
+        assert_eq!(
+            format!("{base64}:1").parse::<GarlicAddr>().unwrap(),
+            destination.garlic_addr(1)
+        );
+    }
+
+    #[test]
+    fn full_garlic_address() {
+        let destination = test_destination();
+
+        // The destination is kept when it is given.
+        let full_addr = format!("{destination}:1")
+            .parse::<FullGarlicAddr>()
+            .unwrap();
+        assert_eq!(full_addr.addr, destination.garlic_addr(1));
+        assert_eq!(full_addr.destination.as_ref(), Some(&destination));
+        assert_eq!(
+            full_addr.to_string().parse::<FullGarlicAddr>().unwrap(),
+            full_addr
+        );
+
+        // Only the hash is known for a garlic address.
+        let addr = format!("{}:1", VALID_GARLIC_ADDRESSES[0]);
+        let full_addr = addr.parse::<FullGarlicAddr>().unwrap();
+        assert_eq!(
+            full_addr,
+            FullGarlicAddr::from(addr.parse::<GarlicAddr>().unwrap())
+        );
+        assert_eq!(full_addr.to_string(), addr);
+    }
+
+    #[test]
+    fn invalid_destination() {
+        let mut bytes = test_destination().as_bytes().to_vec();
+
+        // The certificate is longer than the destination.
+        bytes[386] = 1;
+        assert!(I2pDestination::from_bytes(bytes.clone()).is_err());
+
+        bytes.push(0);
+        assert!(I2pDestination::from_bytes(bytes).is_ok());
+
+        assert!(I2pDestination::from_bytes(vec![0; MIN_DESTINATION_LEN - 1]).is_err());
+        assert!("not+i2p/base64".parse::<I2pDestination>().is_err());
+    }
+
+    /// Builds the epee encoding monerod uses for an I2P `network_address`.
+    fn monerod_tagged_i2p_address(host: &str, port: u16) -> Vec<u8> {
+        let mut bytes = vec![0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01];
 
-    fn number_of_fields(&self) -> u64 {
-        2
+        // 2 fields: `addr` and `type`, sorted by name like every `monerod` object.
+        bytes.push(2 << 2);
+        bytes.extend_from_slice(b"\x04addr\x0c");
+
+        // 2 fields: `host` and `port`.
+        bytes.push(2 << 2);
+        bytes.extend_from_slice(b"\x04host\x0a");
+        bytes.push(u8::try_from(host.len() << 2).unwrap());
+        bytes.extend_from_slice(host.as_bytes());
+        bytes.extend_from_slice(b"\x04port\x07");
+        bytes.extend_from_slice(&port.to_le_bytes());
+
+        bytes.extend_from_slice(b"\x04type\x08\x03");
+
+        bytes
+    }
+
+    #[test]
+    fn epee_monerod_tagged_address() {
+        for addr in VALID_GARLIC_ADDRESSES {
+            let bytes = monerod_tagged_i2p_address(addr, 18080);
+
+            let network_address: NetworkAddress = from_bytes(&mut bytes.as_slice()).unwrap();
+            assert_eq!(
+                network_address,
+                NetworkAddress::I2p(GarlicAddr::new(addr, 18080).unwrap())
+            );
+
+            assert_eq!(to_bytes(network_address).unwrap(), bytes);
+        }
     }
 
-    fn write_fields<B: cuprate_epee_encoding::bytes::BufMut>(
-        self,
-        w: &mut B,
-    ) -> cuprate_epee_encoding::Result<()> {
-        // Write destination as bytes
-        write_field(&self.destination.to_vec(), "destination", w)?;
-        // Write port
-        write_field(&self.port, "port", w)?;
-        Ok(())
+    proptest! {
+        #[test]
+        fn garlic_address_round_trip(hash in any::<[u8; 32]>(), port in any::<u16>()) {
+            let addr = GarlicAddr::from_hash(hash, port);
+
+            prop_assert_eq!(addr.to_string().parse::<GarlicAddr>().unwrap(), addr);
+
+            let network_address: NetworkAddress =
+                from_bytes(&mut to_bytes(NetworkAddress::I2p(addr)).unwrap()).unwrap();
+            prop_assert_eq!(network_address, NetworkAddress::I2p(addr));
+        }
+
+        #[test]
+        fn parse_invalid_garlic_address(addresses in vec("[a-z2-7]{51}.b32.i2p:[0-9]{1,5}", 250)) {
+            for addr in addresses {
+                prop_assert!(
+                    addr.parse::<GarlicAddr>().is_err(),
+                    "Address {addr} has been reported as valid."
+                );
+            }
+        }
     }
-}
\ No newline at end of file
+}
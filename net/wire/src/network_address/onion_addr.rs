@@ -14,6 +14,10 @@ use super::{NetworkAddress, NetworkAddressIncorrectZone};
 
 /// A v3, `Copy`able onion address.
 #[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 pub struct OnionAddr {
     /// 56 characters encoded onion v3 domain without the .onion suffix
     /// <https://spec.torproject.org/rend-spec/encoding-onion-addresses.html>
@@ -145,7 +149,7 @@ impl TryFrom<NetworkAddress> for OnionAddr {
     fn try_from(value: NetworkAddress) -> Result<Self, Self::Error> {
         match value {
             NetworkAddress::Tor(addr) => Ok(addr),
-            NetworkAddress::Clear(_) => Err(NetworkAddressIncorrectZone),
+            NetworkAddress::Clear(_) | NetworkAddress::I2p(_) => Err(NetworkAddressIncorrectZone),
         }
     }
 }
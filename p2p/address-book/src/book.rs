@@ -19,22 +19,75 @@ use tokio::{
 use tokio_util::time::DelayQueue;
 use tower::Service;
 
+use cuprate_p2p_bucket::{Bucket, Bucketable};
 use cuprate_p2p_core::{
     client::InternalPeerID,
     handles::ConnectionHandle,
     services::{AddressBookRequest, AddressBookResponse, ZoneSpecificPeerListEntryBase},
-    NetZoneAddress, NetworkZone,
+    types::{BanState, BanTarget, SetBan},
+    ConnectionDirection, NetZoneAddress, NetworkZone,
 };
 use cuprate_pruning::PruningSeed;
 
 use crate::{
-    peer_list::PeerList, store::save_peers_to_disk, AddressBookConfig, AddressBookError,
-    BorshNetworkZone,
+    peer_list::PeerList,
+    store::{save_peers_to_disk, PeerStore},
+    AddressBookConfig, AddressBookError, BorshNetworkZone,
 };
 
 #[cfg(test)]
 mod tests;
 
+/// The longest delay of an entry in the ban [`DelayQueue`], which panics on delays of over ~2 years.
+///
+/// Longer bans are queued again when the entry expires.
+const MAX_BAN_QUEUE_DELAY: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// The ban duration reported for subnets from the ban-list, which never expire.
+///
+/// This is the longest ban the RPC can report.
+const BAN_LIST_BAN_DURATION: Duration = Duration::from_secs(0xFFFF_FFFF);
+
+/// How long a peer handed out for an outbound connection counts towards its subnet's peers
+/// before it connects, this is longer than the P2P handshake timeout.
+const PENDING_PEER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A peer in the subnet [`Bucket`].
+#[derive(Clone, PartialEq, Eq)]
+struct SubnetPeer<A: NetZoneAddress> {
+    addr: A,
+    subnet: A::BanSubnet,
+}
+
+impl<A: NetZoneAddress> SubnetPeer<A> {
+    /// Returns a new [`SubnetPeer`], or [`None`] if the address is local or has no diversity subnet,
+    /// as these addresses are not limited.
+    fn new(addr: A) -> Option<Self> {
+        if addr.is_local() {
+            return None;
+        }
+
+        addr.diversity_subnet().map(|subnet| Self { addr, subnet })
+    }
+}
+
+/// Returns a subnet [`Bucket`] holding `max_peers_per_subnet` peers per subnet, `0` meaning no limit.
+const fn subnet_bucket<A: NetZoneAddress>(max_peers_per_subnet: usize) -> Bucket<SubnetPeer<A>> {
+    if max_peers_per_subnet == 0 {
+        Bucket::new(usize::MAX)
+    } else {
+        Bucket::new(max_peers_per_subnet)
+    }
+}
+
+impl<A: NetZoneAddress> Bucketable for SubnetPeer<A> {
+    type Discriminant = A::BanSubnet;
+
+    fn discriminant(&self) -> Self::Discriminant {
+        self.subnet
+    }
+}
+
 /// An entry in the connected list.
 pub(crate) struct ConnectionPeerEntry<Z: NetworkZone> {
     addr: Option<Z::Addr>,
@@ -53,9 +106,18 @@ pub struct AddressBook<Z: BorshNetworkZone> {
     white_list: PeerList<Z>,
     /// Our gray peers - the peers we have been told about but haven't connected to.
     gray_list: PeerList<Z>,
-    /// Our anchor peers - on start up will contain a list of peers we were connected to before shutting down
-    /// after that will contain a list of peers currently connected to that we can reach.
+    /// Our anchor peers - the peers we are currently connected to that we can reach.
     anchor_list: HashSet<Z::Addr>,
+    /// The peers we were connected to before shutting down, these are handed out first
+    /// when a white peer is requested.
+    startup_anchors: Vec<Z::Addr>,
+    /// Our anchor peers and the peers handed out for outbound connections, grouped by subnet.
+    subnet_peers: Bucket<SubnetPeer<Z::Addr>>,
+    /// The peers handed out for outbound connections, which are removed from `subnet_peers` once
+    /// they expire if they did not become an anchor.
+    pending_peers_queue: DelayQueue<Z::Addr>,
+    /// Our inbound peers, grouped by subnet.
+    inbound_subnet_peers: Bucket<SubnetPeer<Z::Addr>>,
     /// The currently connected peers.
     connected_peers: HashMap<InternalPeerID<Z::Addr>, ConnectionPeerEntry<Z>>,
     connected_peers_ban_id: HashMap<<Z::Addr as NetZoneAddress>::BanID, HashSet<Z::Addr>>,
@@ -63,6 +125,11 @@ pub struct AddressBook<Z: BorshNetworkZone> {
     banned_peers: HashMap<<Z::Addr as NetZoneAddress>::BanID, Instant>,
     banned_peers_queue: DelayQueue<<Z::Addr as NetZoneAddress>::BanID>,
 
+    /// The banned subnets and the [`Instant`] they will be unbanned.
+    banned_subnets: HashMap<<Z::Addr as NetZoneAddress>::BanSubnet, Instant>,
+    /// The subnets banned by the ban-list, see [`AddressBookRequest::SetBanList`].
+    ban_list: HashSet<<Z::Addr as NetZoneAddress>::BanSubnet>,
+
     peer_save_task_handle: Option<JoinHandle<std::io::Result<()>>>,
     peer_save_interval: Interval,
 
@@ -70,37 +137,59 @@ pub struct AddressBook<Z: BorshNetworkZone> {
 }
 
 impl<Z: BorshNetworkZone> AddressBook<Z> {
-    pub fn new(
-        cfg: AddressBookConfig<Z>,
-        white_peers: Vec<ZoneSpecificPeerListEntryBase<Z::Addr>>,
-        gray_peers: Vec<ZoneSpecificPeerListEntryBase<Z::Addr>>,
-        anchor_peers: Vec<Z::Addr>,
-    ) -> Self {
-        let white_list = PeerList::new(white_peers);
-        let gray_list = PeerList::new(gray_peers);
-        let anchor_list = HashSet::from_iter(anchor_peers);
-
-        // TODO: persist banned peers
-        let banned_peers = HashMap::new();
-        let banned_peers_queue = DelayQueue::new();
+    pub(crate) fn new(mut cfg: AddressBookConfig<Z>, store: PeerStore<Z>) -> Self {
+        let PeerStore {
+            white_list,
+            gray_list,
+            anchor_list,
+            ban_list,
+            banned_subnets,
+        } = store;
+
+        // Anchors are also stored in the white list, so only the addresses are needed.
+        let startup_anchors = anchor_list.into_iter().map(|peer| peer.adr).collect();
+
+        let white_list = PeerList::new(white_list);
+        let gray_list = PeerList::new(gray_list);
+
+        let mut banned_peers = HashMap::with_capacity(ban_list.len());
+        let mut banned_peers_queue = DelayQueue::with_capacity(ban_list.len());
+        for (ban_id, unban_at) in ban_list {
+            queue_unban(&mut banned_peers_queue, ban_id, unban_at);
+            banned_peers.insert(ban_id, unban_at);
+        }
 
         let connected_peers = HashMap::new();
 
+        let ban_list = std::mem::take(&mut cfg.ban_list);
+
         let mut peer_save_interval = interval(cfg.peer_save_period);
         peer_save_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
-        Self {
+        let mut address_book = Self {
             white_list,
             gray_list,
-            anchor_list,
+            anchor_list: HashSet::new(),
+            startup_anchors,
+            subnet_peers: subnet_bucket(cfg.max_peers_per_subnet),
+            inbound_subnet_peers: subnet_bucket(cfg.max_peers_per_subnet),
+            pending_peers_queue: DelayQueue::new(),
             connected_peers,
             connected_peers_ban_id: HashMap::new(),
             banned_peers,
             banned_peers_queue,
+            banned_subnets: banned_subnets.into_iter().collect(),
+            ban_list: HashSet::new(),
             peer_save_task_handle: None,
             peer_save_interval,
             cfg,
+        };
+
+        if !ban_list.is_empty() {
+            address_book.handle_set_ban_list(ban_list);
         }
+
+        address_book
     }
 
     fn poll_save_to_disk(&mut self, cx: &mut Context<'_>) {
@@ -130,13 +219,48 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
             &self.cfg,
             &self.white_list,
             &self.gray_list,
+            &self.anchor_list,
+            &self.banned_peers,
+            &self.banned_subnets,
         ));
     }
 
     fn poll_unban_peers(&mut self, cx: &mut Context<'_>) {
+        let now = Instant::now();
+
         while let Poll::Ready(Some(ban_id)) = self.banned_peers_queue.poll_expired(cx) {
-            tracing::debug!("Host {:?} is unbanned, ban has expired.", ban_id.get_ref(),);
-            self.banned_peers.remove(ban_id.get_ref());
+            let ban_id = ban_id.into_inner();
+
+            match self.banned_peers.get(&ban_id).copied() {
+                // The ban was extended or is longer than the queue allows.
+                Some(unban_at) if unban_at > now => {
+                    queue_unban(&mut self.banned_peers_queue, ban_id, unban_at);
+                }
+                Some(_) => {
+                    tracing::debug!("Host {ban_id:?} is unbanned, ban has expired.");
+                    self.banned_peers.remove(&ban_id);
+                }
+                // The host was already unbanned.
+                None => (),
+            }
+        }
+
+        self.banned_subnets.retain(|subnet, unban_at| {
+            let banned = *unban_at > now;
+            if !banned {
+                tracing::debug!("Subnet {subnet:?} is unbanned, ban has expired.");
+            }
+            banned
+        });
+    }
+
+    fn poll_pending_peers(&mut self, cx: &mut Context<'_>) {
+        while let Poll::Ready(Some(addr)) = self.pending_peers_queue.poll_expired(cx) {
+            let addr = addr.into_inner();
+
+            if !self.anchor_list.contains(&addr) {
+                remove_subnet_peer(&mut self.subnet_peers, addr);
+            }
         }
     }
 
@@ -178,6 +302,8 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
                 }
                 // remove the peer from the anchor list.
                 self.anchor_list.remove(&addr);
+                remove_subnet_peer(&mut self.subnet_peers, addr);
+                remove_subnet_peer(&mut self.inbound_subnet_peers, addr);
             }
         }
     }
@@ -187,28 +313,108 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
             tracing::error!("Tried to ban peer twice, this shouldn't happen.");
         }
 
-        if let Some(connected_peers_with_ban_id) = self.connected_peers_ban_id.get(&addr.ban_id()) {
-            for peer in connected_peers_with_ban_id.iter().map(|addr| {
-                tracing::debug!("Banning peer: {}, for: {:?}", addr, time);
+        self.ban_host(addr.ban_id(), time);
+    }
+
+    /// Bans a host, disconnecting any peers with this ban ID and removing them from the peer lists.
+    fn ban_host(&mut self, ban_id: <Z::Addr as NetZoneAddress>::BanID, time: Duration) {
+        tracing::debug!("Banning host: {ban_id:?}, for: {time:?}");
 
+        if let Some(connected_peers_with_ban_id) = self.connected_peers_ban_id.get(&ban_id) {
+            for addr in connected_peers_with_ban_id {
+                // The peer will get removed from our connected list once we disconnect
                 self.connected_peers
                     .get(&InternalPeerID::KnownAddr(*addr))
                     .expect("Peer must be in connected list if in connected_peers_with_ban_id")
-            }) {
-                // The peer will get removed from our connected list once we disconnect
-                peer.handle.send_close_signal();
+                    .handle
+                    .send_close_signal();
                 // Remove the peer now from anchors so we don't accidentally persist a bad anchor peer to disk.
-                self.anchor_list.remove(&addr);
+                self.anchor_list.remove(addr);
+                remove_subnet_peer(&mut self.subnet_peers, *addr);
             }
         }
 
-        self.white_list.remove_peers_with_ban_id(&addr.ban_id());
-        self.gray_list.remove_peers_with_ban_id(&addr.ban_id());
+        self.white_list.remove_peers_with_ban_id(&ban_id);
+        self.gray_list.remove_peers_with_ban_id(&ban_id);
 
         let unban_at = Instant::now() + time;
 
-        self.banned_peers_queue.insert_at(addr.ban_id(), unban_at);
-        self.banned_peers.insert(addr.ban_id(), unban_at);
+        queue_unban(&mut self.banned_peers_queue, ban_id, unban_at);
+        self.banned_peers.insert(ban_id, unban_at);
+    }
+
+    /// Disconnects any peers in a subnet and removes them from the peer lists.
+    fn remove_subnet(&mut self, subnet: &<Z::Addr as NetZoneAddress>::BanSubnet) {
+        #[expect(clippy::iter_over_hash_type, reason = "ordering doesn't matter here")]
+        for (internal_addr, peer) in &self.connected_peers {
+            if let InternalPeerID::KnownAddr(addr) = internal_addr {
+                if addr.in_subnet(subnet) {
+                    tracing::debug!("Disconnecting peer: {addr}, it is in a banned subnet.");
+                    peer.handle.send_close_signal();
+                    self.anchor_list.remove(addr);
+                    remove_subnet_peer(&mut self.subnet_peers, *addr);
+                }
+            }
+        }
+
+        self.white_list.remove_peers_in_subnet(subnet);
+        self.gray_list.remove_peers_in_subnet(subnet);
+    }
+
+    /// Handles a [`AddressBookRequest::SetBan`].
+    fn handle_set_ban(&mut self, set_ban: SetBan<Z::Addr>) {
+        match (set_ban.target, set_ban.ban) {
+            (BanTarget::Host(ban_id), Some(time)) => self.ban_host(ban_id, time),
+            (BanTarget::Host(ban_id), None) => {
+                tracing::debug!("Unbanning host: {ban_id:?}");
+                // The ban queue ignores entries that are no longer banned.
+                self.banned_peers.remove(&ban_id);
+            }
+            (BanTarget::Subnet(subnet), Some(time)) => {
+                tracing::debug!("Banning subnet: {subnet:?}, for: {time:?}");
+                self.remove_subnet(&subnet);
+                self.banned_subnets.insert(subnet, Instant::now() + time);
+            }
+            (BanTarget::Subnet(subnet), None) => {
+                tracing::debug!("Unbanning subnet: {subnet:?}");
+                self.banned_subnets.remove(&subnet);
+            }
+        }
+    }
+
+    /// Handles a [`AddressBookRequest::SetBanList`].
+    fn handle_set_ban_list(&mut self, ban_list: Vec<<Z::Addr as NetZoneAddress>::BanSubnet>) {
+        tracing::info!("Banning {} subnets from the ban-list.", ban_list.len());
+
+        for subnet in &ban_list {
+            self.remove_subnet(subnet);
+        }
+
+        self.ban_list = ban_list.into_iter().collect();
+    }
+
+    /// Returns the state of all bans.
+    fn get_bans(&self) -> Vec<BanState<Z::Addr>> {
+        let ban_list_unban_at = Instant::now() + BAN_LIST_BAN_DURATION;
+
+        self.banned_peers
+            .iter()
+            .map(|(ban_id, unban_at)| (BanTarget::Host(*ban_id), *unban_at))
+            .chain(
+                self.banned_subnets
+                    .iter()
+                    .map(|(subnet, unban_at)| (BanTarget::Subnet(*subnet), *unban_at)),
+            )
+            .chain(
+                self.ban_list
+                    .iter()
+                    .map(|subnet| (BanTarget::Subnet(*subnet), ban_list_unban_at)),
+            )
+            .map(|(target, unban_at)| BanState {
+                target,
+                unban_instant: Some(unban_at.into_std()),
+            })
+            .collect()
     }
 
     /// adds a peer to the gray list.
@@ -224,9 +430,14 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
         }
     }
 
-    /// Checks if a peer is banned.
+    /// Returns `true` if `addr` can be added to the peer lists.
+    fn should_add_to_peer_list(&self, addr: &Z::Addr) -> bool {
+        addr.should_add_to_peer_list() && (self.cfg.allow_local_ips || !addr.is_local())
+    }
+
+    /// Checks if a peer is banned, either directly or by a subnet ban.
     fn is_peer_banned(&self, peer: &Z::Addr) -> bool {
-        self.banned_peers.contains_key(&peer.ban_id())
+        self.peer_unban_instant(peer).is_some()
     }
 
     /// Checks when a peer will be unbanned.
@@ -235,7 +446,16 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
     ///   the [`Instant`] the peer will be unbanned
     /// - If the peer is not banned, this returns [`None`]
     fn peer_unban_instant(&self, peer: &Z::Addr) -> Option<Instant> {
-        self.banned_peers.get(&peer.ban_id()).copied()
+        if self.ban_list.iter().any(|subnet| peer.in_subnet(subnet)) {
+            return Some(Instant::now() + BAN_LIST_BAN_DURATION);
+        }
+
+        self.banned_subnets
+            .iter()
+            .filter(|&(subnet, _)| peer.in_subnet(subnet))
+            .map(|(_, unban_at)| *unban_at)
+            .chain(self.banned_peers.get(&peer.ban_id()).copied())
+            .max()
     }
 
     fn handle_incoming_peer_list(
@@ -247,7 +467,7 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
         peer_list.retain_mut(|peer| {
             peer.adr.make_canonical();
 
-            if peer.adr.should_add_to_peer_list() {
+            if self.should_add_to_peer_list(&peer.adr) {
                 !self.is_peer_banned(&peer.adr)
             } else {
                 false
@@ -268,8 +488,31 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
         block_needed: Option<usize>,
     ) -> Option<ZoneSpecificPeerListEntryBase<Z::Addr>> {
         tracing::debug!("Retrieving random white peer");
-        self.white_list
-            .take_random_peer(&mut rand::thread_rng(), block_needed, &self.anchor_list)
+
+        if block_needed.is_none() {
+            while let Some(anchor) = self.startup_anchors.pop() {
+                if subnet_peer_count(&self.subnet_peers, &anchor).is_none() {
+                    tracing::debug!("Skipping anchor peer: {anchor}, its subnet is full.");
+                    continue;
+                }
+
+                if let Some(peer) = self.white_list.remove_peer(&anchor) {
+                    tracing::debug!("Retrieved anchor peer: {anchor}");
+                    self.add_pending_peer(anchor);
+                    return Some(peer);
+                }
+            }
+        }
+
+        let peer = self.white_list.take_random_peer(
+            &mut rand::thread_rng(),
+            block_needed,
+            &self.anchor_list,
+            |addr| subnet_peer_count(&self.subnet_peers, addr),
+        )?;
+
+        self.add_pending_peer(peer.adr);
+        Some(peer)
     }
 
     fn take_random_gray_peer(
@@ -277,8 +520,24 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
         block_needed: Option<usize>,
     ) -> Option<ZoneSpecificPeerListEntryBase<Z::Addr>> {
         tracing::debug!("Retrieving random gray peer");
-        self.gray_list
-            .take_random_peer(&mut rand::thread_rng(), block_needed, &HashSet::new())
+        let peer = self.gray_list.take_random_peer(
+            &mut rand::thread_rng(),
+            block_needed,
+            &HashSet::new(),
+            |addr| subnet_peer_count(&self.subnet_peers, addr),
+        )?;
+
+        self.add_pending_peer(peer.adr);
+        Some(peer)
+    }
+
+    /// Counts a peer handed out for an outbound connection towards its subnet's peers, until it connects
+    /// or [`PENDING_PEER_TIMEOUT`] passes.
+    fn add_pending_peer(&mut self, addr: Z::Addr) {
+        if let Some(subnet_peer) = SubnetPeer::new(addr) {
+            self.subnet_peers.push(subnet_peer);
+            self.pending_peers_queue.insert(addr, PENDING_PEER_TIMEOUT);
+        }
     }
 
     fn get_white_peers(&self, len: usize) -> Vec<ZoneSpecificPeerListEntryBase<Z::Addr>> {
@@ -298,6 +557,11 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
             return Ok(());
         };
 
+        if !self.should_add_to_peer_list(addr) {
+            tracing::trace!("Not adding peer {addr} to white list, its address is not allowed.");
+            return Ok(());
+        }
+
         if let Some(peb) = self.white_list.get_peer_mut(addr) {
             if peb.pruning_seed != peer.pruning_seed {
                 return Err(AddressBookError::PeersDataChanged("Pruning seed"));
@@ -324,17 +588,35 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
     fn handle_new_connection(
         &mut self,
         internal_peer_id: InternalPeerID<Z::Addr>,
+        direction: ConnectionDirection,
         peer: ConnectionPeerEntry<Z>,
     ) -> Result<(), AddressBookError> {
         if self.connected_peers.contains_key(&internal_peer_id) {
             return Err(AddressBookError::PeerAlreadyConnected);
         }
 
+        // Inbound peers are limited per subnet.
+        let inbound_subnet_peer = match (&internal_peer_id, direction) {
+            (InternalPeerID::KnownAddr(addr), ConnectionDirection::Inbound) => {
+                SubnetPeer::new(*addr)
+            }
+            _ => None,
+        };
+
         // If we know the address then check if it's banned.
         if let InternalPeerID::KnownAddr(addr) = &internal_peer_id {
             if self.is_peer_banned(addr) {
                 return Err(AddressBookError::PeerIsBanned);
             }
+
+            if inbound_subnet_peer.as_ref().is_some_and(|subnet_peer| {
+                self.inbound_subnet_peers
+                    .is_bucket_full(&subnet_peer.subnet)
+            }) {
+                tracing::debug!("Rejecting inbound peer {addr}, its subnet is full.");
+                return Err(AddressBookError::SubnetFull);
+            }
+
             // although the peer may not be reachable still add it to the connected peers with ban ID.
             self.connected_peers_ban_id
                 .entry(addr.ban_id())
@@ -344,13 +626,27 @@ impl<Z: BorshNetworkZone> AddressBook<Z> {
 
         // if the address is Some that means we can reach it from our node.
         if let Some(addr) = peer.addr {
-            // The peer is reachable, update our white list and add it to the anchor connections.
+            // The peer is reachable, update our white list and add it to the anchor connections,
+            // unless we already have enough anchors in its subnet.
             self.update_white_list_peer_entry(&peer)?;
-            self.anchor_list.insert(addr);
+
+            if SubnetPeer::new(addr)
+                .and_then(|subnet_peer| self.subnet_peers.push(subnet_peer))
+                .is_some()
+            {
+                tracing::debug!("Not adding {addr} as an anchor, its subnet is full.");
+            } else {
+                self.anchor_list.insert(addr);
+            }
+
             self.white_list
                 .reduce_list(&self.anchor_list, self.cfg.max_white_list_length);
         }
 
+        if let Some(subnet_peer) = inbound_subnet_peer {
+            self.inbound_subnet_peers.push(subnet_peer);
+        }
+
         self.connected_peers.insert(internal_peer_id, peer);
         Ok(())
     }
@@ -363,6 +659,7 @@ impl<Z: BorshNetworkZone> Service<AddressBookRequest<Z>> for AddressBook<Z> {
 
     fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         self.poll_unban_peers(cx);
+        self.poll_pending_peers(cx);
         self.poll_save_to_disk(cx);
         self.poll_connected_peers();
         Poll::Ready(Ok(()))
@@ -375,6 +672,7 @@ impl<Z: BorshNetworkZone> Service<AddressBookRequest<Z>> for AddressBook<Z> {
         let response = match req {
             AddressBookRequest::NewConnection {
                 internal_peer_id,
+                direction,
                 public_address,
                 handle,
                 id,
@@ -384,6 +682,7 @@ impl<Z: BorshNetworkZone> Service<AddressBookRequest<Z>> for AddressBook<Z> {
             } => self
                 .handle_new_connection(
                     internal_peer_id,
+                    direction,
                     ConnectionPeerEntry {
                         addr: public_address,
                         id,
@@ -414,17 +713,24 @@ impl<Z: BorshNetworkZone> Service<AddressBookRequest<Z>> for AddressBook<Z> {
             AddressBookRequest::GetWhitePeers(len) => {
                 Ok(AddressBookResponse::Peers(self.get_white_peers(len)))
             }
+            AddressBookRequest::SetBan(set_ban) => {
+                self.handle_set_ban(set_ban);
+                Ok(AddressBookResponse::Ok)
+            }
+            AddressBookRequest::SetBanList(ban_list) => {
+                self.handle_set_ban_list(ban_list);
+                Ok(AddressBookResponse::Ok)
+            }
             AddressBookRequest::GetBan(addr) => Ok(AddressBookResponse::GetBan {
                 unban_instant: self.peer_unban_instant(&addr).map(Instant::into_std),
             }),
+            AddressBookRequest::GetBans => Ok(AddressBookResponse::GetBans(self.get_bans())),
             AddressBookRequest::OwnAddress => {
                 Ok(AddressBookResponse::OwnAddress(self.cfg.our_own_address))
             }
             AddressBookRequest::Peerlist
             | AddressBookRequest::PeerlistSize
             | AddressBookRequest::ConnectionCount
-            | AddressBookRequest::SetBan(_)
-            | AddressBookRequest::GetBans
             | AddressBookRequest::ConnectionInfo => {
                 todo!("finish https://github.com/Cuprate/cuprate/pull/297")
             }
@@ -433,3 +739,29 @@ impl<Z: BorshNetworkZone> Service<AddressBookRequest<Z>> for AddressBook<Z> {
         ready(response)
     }
 }
+
+/// Returns the amount of peers in `addr`'s subnet, or [`None`] if the subnet is full.
+///
+/// Local addresses and addresses without a diversity subnet are never limited.
+fn subnet_peer_count<A: NetZoneAddress>(
+    subnet_peers: &Bucket<SubnetPeer<A>>,
+    addr: &A,
+) -> Option<usize> {
+    let Some(SubnetPeer { subnet, .. }) = SubnetPeer::new(*addr) else {
+        return Some(0);
+    };
+
+    (!subnet_peers.is_bucket_full(&subnet)).then(|| subnet_peers.len_bucket(&subnet).unwrap_or(0))
+}
+
+/// Removes an address from the subnet [`Bucket`].
+fn remove_subnet_peer<A: NetZoneAddress>(subnet_peers: &mut Bucket<SubnetPeer<A>>, addr: A) {
+    if let Some(subnet_peer) = SubnetPeer::new(addr) {
+        subnet_peers.remove(&subnet_peer);
+    }
+}
+
+/// Queues the unban of `ban_id` at `unban_at`, or earlier if the ban is longer than [`MAX_BAN_QUEUE_DELAY`].
+fn queue_unban<B>(banned_peers_queue: &mut DelayQueue<B>, ban_id: B, unban_at: Instant) {
+    banned_peers_queue.insert_at(ban_id, unban_at.min(Instant::now() + MAX_BAN_QUEUE_DELAY));
+}
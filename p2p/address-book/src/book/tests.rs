@@ -1,13 +1,20 @@
 use std::{path::PathBuf, time::Duration};
 
 use futures::StreamExt;
-use tokio::time::interval;
+use tokio::time::{interval, Instant};
 
-use cuprate_p2p_core::{handles::HandleBuilder, NetworkZone};
+use cuprate_p2p_bucket::Bucket;
+use cuprate_p2p_core::{
+    handles::HandleBuilder,
+    types::{BanTarget, SetBan},
+    ConnectionDirection, NetworkZone,
+};
 use cuprate_pruning::PruningSeed;
 
-use super::{AddressBook, ConnectionPeerEntry, InternalPeerID};
-use crate::{peer_list::tests::make_fake_peer_list, AddressBookConfig, AddressBookError};
+use super::{subnet_bucket, AddressBook, ConnectionPeerEntry, InternalPeerID};
+use crate::{
+    peer_list::tests::make_fake_peer_list, store::PeerStore, AddressBookConfig, AddressBookError,
+};
 
 use cuprate_test_utils::test_netzone::{TestNetZone, TestNetZoneAddr};
 
@@ -17,6 +24,9 @@ fn test_cfg<Z: NetworkZone>() -> AddressBookConfig<Z> {
         max_gray_list_length: 500,
         peer_store_directory: PathBuf::new(),
         peer_save_period: Duration::from_secs(60),
+        max_peers_per_subnet: 2,
+        allow_local_ips: false,
+        ban_list: vec![],
         our_own_address: None,
     }
 }
@@ -29,10 +39,16 @@ fn make_fake_address_book(numb_white: u32, numb_gray: u32) -> AddressBook<TestNe
         white_list,
         gray_list,
         anchor_list: Default::default(),
+        startup_anchors: Default::default(),
+        subnet_peers: Bucket::new(2),
+        inbound_subnet_peers: Bucket::new(2),
+        pending_peers_queue: Default::default(),
         connected_peers: Default::default(),
         connected_peers_ban_id: Default::default(),
         banned_peers: Default::default(),
         banned_peers_queue: Default::default(),
+        banned_subnets: Default::default(),
+        ban_list: Default::default(),
         peer_save_task_handle: None,
         peer_save_interval: interval(Duration::from_secs(60)),
         cfg: test_cfg(),
@@ -51,6 +67,16 @@ async fn take_random_peers() {
     assert!(!address_book.gray_list.contains_peer(&peer.adr));
 }
 
+#[tokio::test]
+async fn take_random_peers_limited_per_subnet() {
+    // All the peers are in the same subnet.
+    let mut address_book = make_fake_address_book(0, 50);
+
+    assert!(address_book.take_random_gray_peer(None).is_some());
+    assert!(address_book.take_random_gray_peer(None).is_some());
+    assert!(address_book.take_random_gray_peer(None).is_none());
+}
+
 #[tokio::test]
 async fn get_white_peers() {
     let address_book = make_fake_address_book(100, 0);
@@ -81,6 +107,7 @@ async fn add_new_peer_already_connected() {
     address_book
         .handle_new_connection(
             InternalPeerID::KnownAddr(TestNetZoneAddr(1)),
+            ConnectionDirection::Outbound,
             ConnectionPeerEntry {
                 addr: None,
                 id: 0,
@@ -97,6 +124,7 @@ async fn add_new_peer_already_connected() {
     assert_eq!(
         address_book.handle_new_connection(
             InternalPeerID::KnownAddr(TestNetZoneAddr(1)),
+            ConnectionDirection::Outbound,
             ConnectionPeerEntry {
                 addr: None,
                 id: 0,
@@ -110,6 +138,102 @@ async fn add_new_peer_already_connected() {
     );
 }
 
+#[tokio::test]
+async fn anchors_limited_per_subnet() {
+    let mut address_book = make_fake_address_book(0, 0);
+
+    for id in [1, 2, 3, 300] {
+        let (_, handle) = HandleBuilder::default().build();
+
+        address_book
+            .handle_new_connection(
+                InternalPeerID::KnownAddr(TestNetZoneAddr(id)),
+                ConnectionDirection::Outbound,
+                ConnectionPeerEntry {
+                    addr: Some(TestNetZoneAddr(id)),
+                    id: 0,
+                    handle,
+                    pruning_seed: PruningSeed::decompress(385).unwrap(),
+                    rpc_port: 0,
+                    rpc_credits_per_hash: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    // The subnet of `0..256` is full, so `TestNetZoneAddr(3)` is connected but not an anchor.
+    assert_eq!(address_book.connected_peers.len(), 4);
+    assert_eq!(address_book.anchor_list.len(), 3);
+    assert!(!address_book.anchor_list.contains(&TestNetZoneAddr(3)));
+}
+
+#[tokio::test]
+async fn inbound_peers_limited_per_subnet() {
+    let mut address_book = make_fake_address_book(0, 0);
+
+    let mut connect = |id, direction| {
+        let (_, handle) = HandleBuilder::default().build();
+
+        address_book.handle_new_connection(
+            InternalPeerID::KnownAddr(TestNetZoneAddr(id)),
+            direction,
+            ConnectionPeerEntry {
+                addr: None,
+                id: 0,
+                handle,
+                pruning_seed: PruningSeed::decompress(385).unwrap(),
+                rpc_port: 0,
+                rpc_credits_per_hash: 0,
+            },
+        )
+    };
+
+    connect(1, ConnectionDirection::Inbound).unwrap();
+    connect(2, ConnectionDirection::Inbound).unwrap();
+    assert_eq!(
+        connect(3, ConnectionDirection::Inbound),
+        Err(AddressBookError::SubnetFull)
+    );
+
+    // Other subnets and outbound connections are not limited.
+    connect(300, ConnectionDirection::Inbound).unwrap();
+    connect(4, ConnectionDirection::Outbound).unwrap();
+}
+
+#[tokio::test]
+async fn zero_max_peers_per_subnet_is_no_limit() {
+    let mut address_book = make_fake_address_book(0, 0);
+    address_book.subnet_peers = subnet_bucket(0);
+    address_book.inbound_subnet_peers = subnet_bucket(0);
+
+    // All the peers are in the same subnet.
+    for (id, direction) in (1..5)
+        .map(|id| (id, ConnectionDirection::Inbound))
+        .chain((5..10).map(|id| (id, ConnectionDirection::Outbound)))
+    {
+        let (_, handle) = HandleBuilder::default().build();
+
+        address_book
+            .handle_new_connection(
+                InternalPeerID::KnownAddr(TestNetZoneAddr(id)),
+                direction,
+                ConnectionPeerEntry {
+                    addr: (direction == ConnectionDirection::Outbound)
+                        .then_some(TestNetZoneAddr(id)),
+                    id: 0,
+                    handle,
+                    pruning_seed: PruningSeed::decompress(385).unwrap(),
+                    rpc_port: 0,
+                    rpc_credits_per_hash: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    assert_eq!(address_book.connected_peers.len(), 9);
+    assert_eq!(address_book.anchor_list.len(), 5);
+}
+
 #[tokio::test]
 async fn banned_peer_removed_from_peer_lists() {
     let mut address_book = make_fake_address_book(100, 0);
@@ -143,3 +267,83 @@ async fn banned_peer_removed_from_peer_lists() {
         TestNetZoneAddr(1)
     );
 }
+
+#[tokio::test]
+async fn banned_subnet_removed_from_peer_lists() {
+    let mut address_book = make_fake_address_book(100, 0);
+
+    address_book.handle_set_ban(SetBan {
+        target: BanTarget::Subnet((10, 20)),
+        ban: Some(Duration::from_secs(60)),
+    });
+    assert_eq!(address_book.white_list.len(), 90);
+    assert!(address_book.is_peer_banned(&TestNetZoneAddr(15)));
+    assert!(!address_book.is_peer_banned(&TestNetZoneAddr(20)));
+
+    address_book.handle_set_ban(SetBan {
+        target: BanTarget::Subnet((10, 20)),
+        ban: None,
+    });
+    assert!(!address_book.is_peer_banned(&TestNetZoneAddr(15)));
+}
+
+#[tokio::test]
+async fn banned_subnets_restored_from_store() {
+    let address_book: AddressBook<TestNetZone<true>> = AddressBook::new(
+        test_cfg(),
+        PeerStore {
+            banned_subnets: vec![((10, 20), Instant::now() + Duration::from_secs(60))],
+            ..Default::default()
+        },
+    );
+
+    assert!(address_book.is_peer_banned(&TestNetZoneAddr(15)));
+    assert!(!address_book.is_peer_banned(&TestNetZoneAddr(20)));
+}
+
+#[tokio::test]
+async fn ban_list_set_from_config() {
+    let address_book: AddressBook<TestNetZone<true>> = AddressBook::new(
+        AddressBookConfig {
+            ban_list: vec![(10, 20)],
+            ..test_cfg()
+        },
+        PeerStore {
+            white_list: make_fake_peer_list(0, 50).peers.into_values().collect(),
+            ..Default::default()
+        },
+    );
+
+    assert!(address_book.is_peer_banned(&TestNetZoneAddr(15)));
+    assert!(!address_book.is_peer_banned(&TestNetZoneAddr(20)));
+    assert_eq!(address_book.white_list.len(), 40);
+    assert_eq!(address_book.get_bans().len(), 1);
+    assert!(address_book.cfg.ban_list.is_empty());
+}
+
+#[tokio::test]
+async fn ban_list_replaced() {
+    let mut address_book = make_fake_address_book(100, 0);
+
+    address_book.handle_set_ban_list(vec![(0, 10)]);
+    assert!(address_book.is_peer_banned(&TestNetZoneAddr(5)));
+
+    address_book.handle_set_ban_list(vec![(50, 60)]);
+    assert!(!address_book.is_peer_banned(&TestNetZoneAddr(5)));
+    assert!(address_book.is_peer_banned(&TestNetZoneAddr(55)));
+
+    assert_eq!(address_book.white_list.len(), 80);
+    assert_eq!(address_book.get_bans().len(), 1);
+}
+
+#[tokio::test]
+async fn startup_anchors_taken_first() {
+    let mut address_book = make_fake_address_book(50, 0);
+    address_book.startup_anchors = vec![TestNetZoneAddr(10), TestNetZoneAddr(100)];
+
+    // TestNetZoneAddr(100) is not in the white list so is skipped.
+    let peer = address_book.take_random_white_peer(None).unwrap();
+    assert_eq!(peer.adr, TestNetZoneAddr(10));
+    assert!(!address_book.white_list.contains_peer(&peer.adr));
+    assert!(address_book.startup_anchors.is_empty());
+}
@@ -33,6 +33,23 @@ pub struct AddressBookConfig<Z: NetworkZone> {
     pub peer_store_directory: PathBuf,
     /// The amount of time between saving the address book to disk.
     pub peer_save_period: Duration,
+    /// The maximum number of anchor peers in a single subnet, see [`NetZoneAddress::diversity_subnet`].
+    ///
+    /// Peers in subnets with this many anchor peers are not handed out for outbound connections and
+    /// inbound connections from subnets with this many inbound peers are rejected, local addresses are
+    /// not limited.
+    ///
+    /// `0` means no limit.
+    pub max_peers_per_subnet: usize,
+    /// Allow local addresses in the peer lists, see [`NetZoneAddress::is_local`].
+    ///
+    /// This should only be enabled on private test networks.
+    pub allow_local_ips: bool,
+    /// The ban-list to start with, see
+    /// [`AddressBookRequest::SetBanList`](cuprate_p2p_core::services::AddressBookRequest::SetBanList).
+    ///
+    /// This is set before any connections are made, so banned peers can't connect in the meantime.
+    pub ban_list: Vec<<Z::Addr as NetZoneAddress>::BanSubnet>,
 
     /// Our own address to advertise to peers. (Only set if `Z::BROADCAST_OWN_ADDR` = `true`)
     pub our_own_address: Option<Z::Addr>,
@@ -54,6 +71,9 @@ pub enum AddressBookError {
     /// The peer is banned.
     #[error("The peer is banned")]
     PeerIsBanned,
+    /// The peer's subnet already has the maximum number of inbound peers.
+    #[error("The peer's subnet has too many inbound connections")]
+    SubnetFull,
     /// The channel to the address book has closed unexpectedly.
     #[error("The address books channel has closed.")]
     AddressBooksChannelClosed,
@@ -66,37 +86,62 @@ pub enum AddressBookError {
 pub async fn init_address_book<Z: BorshNetworkZone>(
     cfg: AddressBookConfig<Z>,
 ) -> Result<book::AddressBook<Z>, std::io::Error> {
-    let (white_list, gray_list) = match store::read_peers_from_disk::<Z>(&cfg).await {
+    let store = match store::read_peers_from_disk::<Z>(&cfg).await {
         Ok(res) => res,
-        Err(e) if e.kind() == ErrorKind::NotFound => (vec![], vec![]),
+        Err(e) if e.kind() == ErrorKind::NotFound => store::PeerStore::default(),
         Err(e) => {
             tracing::error!(
                 "Error: Failed to open peer list,\n{},\nstarting with an empty list",
                 e
             );
-            (vec![], vec![])
+            store::PeerStore::default()
         }
     };
 
-    let address_book = book::AddressBook::<Z>::new(cfg, white_list, gray_list, Vec::new());
+    let address_book = book::AddressBook::<Z>::new(cfg, store);
 
     Ok(address_book)
 }
 
 use sealed::BorshNetworkZone;
 mod sealed {
+    use std::{fmt::Debug, hash::Hash};
+
     use super::*;
 
     /// An internal trait for the address book for a [`NetworkZone`] that adds the requirement of [`borsh`] traits
-    /// onto the network address.
+    /// onto the network address, its ban ID and its ban subnet.
     pub trait BorshNetworkZone: NetworkZone<Addr = Self::BorshAddr> {
-        type BorshAddr: NetZoneAddress + borsh::BorshDeserialize + borsh::BorshSerialize;
+        type BorshAddr: NetZoneAddress<BanID = Self::BorshBanID, BanSubnet = Self::BorshBanSubnet>
+            + borsh::BorshDeserialize
+            + borsh::BorshSerialize;
+        type BorshBanID: Debug
+            + Hash
+            + Eq
+            + Copy
+            + Send
+            + 'static
+            + borsh::BorshDeserialize
+            + borsh::BorshSerialize;
+        type BorshBanSubnet: Debug
+            + Hash
+            + Eq
+            + Ord
+            + Copy
+            + Send
+            + 'static
+            + borsh::BorshDeserialize
+            + borsh::BorshSerialize;
     }
 
     impl<T: NetworkZone> BorshNetworkZone for T
     where
         T::Addr: borsh::BorshDeserialize + borsh::BorshSerialize,
+        <T::Addr as NetZoneAddress>::BanID: borsh::BorshDeserialize + borsh::BorshSerialize,
+        <T::Addr as NetZoneAddress>::BanSubnet: borsh::BorshDeserialize + borsh::BorshSerialize,
     {
         type BorshAddr = T::Addr;
+        type BorshBanID = <T::Addr as NetZoneAddress>::BanID;
+        type BorshBanSubnet = <T::Addr as NetZoneAddress>::BanSubnet;
     }
 }
@@ -10,6 +10,9 @@ use cuprate_pruning::PruningSeed;
 #[cfg(test)]
 pub(crate) mod tests;
 
+/// The amount of random peers sampled when taking a peer from a [`PeerList`].
+const TAKE_RANDOM_PEER_SAMPLES: usize = 8;
+
 /// A Peer list in the address book.
 ///
 /// This could either be the white list or gray list.
@@ -84,18 +87,22 @@ impl<Z: NetworkZone> PeerList<Z> {
     /// that pruning seed otherwise we will just get a random peer in the whole
     /// list.
     ///
+    /// `subnet_peers` returns the amount of peers we have in a peer's subnet, or [`None`] if the peer's
+    /// subnet is full. A few random peers are sampled and the one in the least represented subnet is taken.
+    ///
     /// The given peer will be removed from the peer list.
     pub(crate) fn take_random_peer<R: Rng>(
         &mut self,
         r: &mut R,
         block_needed: Option<usize>,
         must_keep_peers: &HashSet<Z::Addr>,
+        subnet_peers: impl Fn(&Z::Addr) -> Option<usize>,
     ) -> Option<ZoneSpecificPeerListEntryBase<Z::Addr>> {
-        // Take a random peer and see if it's in the list of must_keep_peers, if it is try again.
-        // TODO: improve this
+        // Take random peers and skip any in the list of must_keep_peers or in full subnets.
+        let mut best_peer: Option<(Z::Addr, usize)> = None;
 
-        for _ in 0..3 {
-            if let Some(needed_height) = block_needed {
+        for _ in 0..TAKE_RANDOM_PEER_SAMPLES {
+            let peer = if let Some(needed_height) = block_needed {
                 let (_, addresses_with_block) = self.pruning_seeds.iter().find(|(seed, _)| {
                     // TODO: factor in peer blockchain height?
                     seed.get_next_unpruned_block(needed_height, MAX_BLOCK_HEIGHT_USIZE)
@@ -103,28 +110,37 @@ impl<Z: NetworkZone> PeerList<Z> {
                         == needed_height
                 })?;
                 let n = r.gen_range(0..addresses_with_block.len());
-                let peer = addresses_with_block[n];
-                if must_keep_peers.contains(&peer) {
-                    continue;
+                addresses_with_block[n]
+            } else {
+                let len = self.len();
+
+                if len == 0 {
+                    return None;
                 }
 
-                return self.remove_peer(&peer);
-            }
-            let len = self.len();
+                let n = r.gen_range(0..len);
+                *self.peers.get_index(n).unwrap().0
+            };
 
-            if len == 0 {
-                return None;
+            if must_keep_peers.contains(&peer) {
+                continue;
             }
 
-            let n = r.gen_range(0..len);
+            let Some(peers_in_subnet) = subnet_peers(&peer) else {
+                continue;
+            };
 
-            let (&key, _) = self.peers.get_index(n).unwrap();
-            if !must_keep_peers.contains(&key) {
-                return self.remove_peer(&key);
+            // A peer in a subnet we have no peers in can't be beaten.
+            if peers_in_subnet == 0 {
+                return self.remove_peer(&peer);
+            }
+
+            if !matches!(best_peer, Some((_, best)) if best <= peers_in_subnet) {
+                best_peer = Some((peer, peers_in_subnet));
             }
         }
 
-        None
+        best_peer.and_then(|(peer, _)| self.remove_peer(&peer))
     }
 
     pub(crate) fn get_random_peers<R: Rng>(
@@ -214,6 +230,23 @@ impl<Z: NetworkZone> PeerList<Z> {
         }
     }
 
+    /// Removes all peers in a subnet.
+    pub(crate) fn remove_peers_in_subnet(
+        &mut self,
+        subnet: &<Z::Addr as NetZoneAddress>::BanSubnet,
+    ) {
+        let addresses = self
+            .peers
+            .keys()
+            .filter(|addr| addr.in_subnet(subnet))
+            .copied()
+            .collect::<Vec<_>>();
+
+        for addr in addresses {
+            self.remove_peer(&addr);
+        }
+    }
+
     /// Tries to reduce the peer list to `new_len`.
     ///
     /// This function could keep the list bigger than `new_len` if `must_keep_peers`s length
@@ -83,7 +83,7 @@ fn peer_list_remove_specific_peer() {
     let mut peer_list = make_fake_peer_list_with_random_pruning_seeds(100);
 
     let peer = peer_list
-        .take_random_peer(&mut rand::thread_rng(), None, &HashSet::new())
+        .take_random_peer(&mut rand::thread_rng(), None, &HashSet::new(), |_| Some(0))
         .unwrap();
 
     let pruning_idxs = peer_list.pruning_seeds;
@@ -152,7 +152,7 @@ fn peer_list_get_peer_with_block() {
     peer_list.add_new_peer(make_fake_peer(101, Some(384)));
 
     let peer = peer_list
-        .take_random_peer(&mut r, Some(1), &HashSet::new())
+        .take_random_peer(&mut r, Some(1), &HashSet::new(), |_| Some(0))
         .expect("We just added a peer with the correct seed");
 
     assert!(peer
@@ -161,11 +161,34 @@ fn peer_list_get_peer_with_block() {
         .is_ok());
 }
 
+#[test]
+fn peer_list_skips_full_subnets() {
+    let mut peer_list = make_fake_peer_list(0, 10);
+    for i in 256..266 {
+        peer_list.add_new_peer(make_fake_peer(i, None));
+    }
+
+    // Only the subnet of `0..256` is full.
+    let subnet_peers = |addr: &TestNetZoneAddr| (addr.0 >= 256).then_some(1);
+
+    for _ in 0..10 {
+        if let Some(peer) =
+            peer_list.take_random_peer(&mut rand::thread_rng(), None, &HashSet::new(), subnet_peers)
+        {
+            assert!(peer.adr.0 >= 256);
+        }
+    }
+
+    assert!(peer_list
+        .take_random_peer(&mut rand::thread_rng(), None, &HashSet::new(), |_| None)
+        .is_none());
+}
+
 #[test]
 fn peer_list_ban_peers() {
     let mut peer_list = make_fake_peer_list_with_random_pruning_seeds(100);
     let peer = peer_list
-        .take_random_peer(&mut rand::thread_rng(), None, &HashSet::new())
+        .take_random_peer(&mut rand::thread_rng(), None, &HashSet::new(), |_| Some(0))
         .unwrap();
     let ban_id = peer.adr.ban_id();
 
@@ -1,43 +1,126 @@
 #![expect(
     single_use_lifetimes,
-    reason = "false positive on generated derive code on `SerPeerDataV1`"
+    reason = "false positive on generated derive code on `SerPeerDataV2`"
 )]
 
-use std::fs;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{Error, ErrorKind},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use borsh::{from_slice, to_vec, BorshDeserialize, BorshSerialize};
-use tokio::task::{spawn_blocking, JoinHandle};
+use tokio::{
+    task::{spawn_blocking, JoinHandle},
+    time::Instant,
+};
 
 use cuprate_p2p_core::{services::ZoneSpecificPeerListEntryBase, NetZoneAddress};
 
 use crate::{peer_list::PeerList, AddressBookConfig, BorshNetworkZone};
 
-// TODO: store anchor and ban list.
+/// The bytes at the start of a versioned peer store file.
+///
+/// V1 files are not versioned and start directly with the white list.
+const PEER_STORE_MAGIC: &[u8; 8] = b"cuprpeer";
+
+/// The version of the peer store format that is written.
+const PEER_STORE_VERSION: u8 = 2;
+
+#[cfg_attr(test, derive(BorshSerialize))]
+#[derive(BorshDeserialize)]
+struct DeserPeerDataV1<A: NetZoneAddress> {
+    white_list: Vec<ZoneSpecificPeerListEntryBase<A>>,
+    gray_list: Vec<ZoneSpecificPeerListEntryBase<A>>,
+}
 
 #[derive(BorshSerialize)]
-struct SerPeerDataV1<'a, A: NetZoneAddress> {
+struct SerPeerDataV2<'a, A: NetZoneAddress, B, S> {
     white_list: Vec<&'a ZoneSpecificPeerListEntryBase<A>>,
     gray_list: Vec<&'a ZoneSpecificPeerListEntryBase<A>>,
+    anchor_list: Vec<&'a ZoneSpecificPeerListEntryBase<A>>,
+    /// The ban IDs with the UNIX timestamp their ban expires at.
+    ban_list: Vec<(B, u64)>,
+    /// The banned subnets with the UNIX timestamp their ban expires at.
+    banned_subnets: Vec<(S, u64)>,
 }
 
 #[derive(BorshDeserialize)]
-struct DeserPeerDataV1<A: NetZoneAddress> {
+struct DeserPeerDataV2<A: NetZoneAddress, B, S> {
     white_list: Vec<ZoneSpecificPeerListEntryBase<A>>,
     gray_list: Vec<ZoneSpecificPeerListEntryBase<A>>,
+    anchor_list: Vec<ZoneSpecificPeerListEntryBase<A>>,
+    ban_list: Vec<(B, u64)>,
+    banned_subnets: Vec<(S, u64)>,
+}
+
+/// The peer data read from disk.
+pub(crate) struct PeerStore<Z: BorshNetworkZone> {
+    pub white_list: Vec<ZoneSpecificPeerListEntryBase<Z::Addr>>,
+    pub gray_list: Vec<ZoneSpecificPeerListEntryBase<Z::Addr>>,
+    /// The peers we were connected to before shutting down.
+    pub anchor_list: Vec<ZoneSpecificPeerListEntryBase<Z::Addr>>,
+    /// The bans that have not expired yet, with the [`Instant`] they expire at.
+    pub ban_list: Vec<(Z::BorshBanID, Instant)>,
+    /// The subnet bans that have not expired yet, with the [`Instant`] they expire at.
+    pub banned_subnets: Vec<(Z::BorshBanSubnet, Instant)>,
+}
+
+impl<Z: BorshNetworkZone> Default for PeerStore<Z> {
+    fn default() -> Self {
+        Self {
+            white_list: vec![],
+            gray_list: vec![],
+            anchor_list: vec![],
+            ban_list: vec![],
+            banned_subnets: vec![],
+        }
+    }
+}
+
+/// Returns the current UNIX timestamp.
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
 
 pub(crate) fn save_peers_to_disk<Z: BorshNetworkZone>(
     cfg: &AddressBookConfig<Z>,
     white_list: &PeerList<Z>,
     gray_list: &PeerList<Z>,
+    anchor_list: &HashSet<Z::Addr>,
+    banned_peers: &HashMap<Z::BorshBanID, Instant>,
+    banned_subnets: &HashMap<Z::BorshBanSubnet, Instant>,
 ) -> JoinHandle<std::io::Result<()>> {
+    let now = Instant::now();
+    let now_unix = unix_timestamp();
+    let to_unix = |unban_at: Instant| now_unix + unban_at.saturating_duration_since(now).as_secs();
+
     // maybe move this to another thread but that would require cloning the data ... this
     // happens so infrequently that it's probably not worth it.
-    let data = to_vec(&SerPeerDataV1 {
-        white_list: white_list.peers.values().collect::<Vec<_>>(),
-        gray_list: gray_list.peers.values().collect::<Vec<_>>(),
-    })
-    .unwrap();
+    let mut data = PEER_STORE_MAGIC.to_vec();
+    data.push(PEER_STORE_VERSION);
+    data.extend(
+        to_vec(&SerPeerDataV2 {
+            white_list: white_list.peers.values().collect::<Vec<_>>(),
+            gray_list: gray_list.peers.values().collect::<Vec<_>>(),
+            anchor_list: anchor_list
+                .iter()
+                .filter_map(|addr| white_list.peers.get(addr))
+                .collect::<Vec<_>>(),
+            ban_list: banned_peers
+                .iter()
+                .map(|(ban_id, unban_at)| (*ban_id, to_unix(*unban_at)))
+                .collect::<Vec<_>>(),
+            banned_subnets: banned_subnets
+                .iter()
+                .map(|(subnet, unban_at)| (*subnet, to_unix(*unban_at)))
+                .collect::<Vec<_>>(),
+        })
+        .unwrap(),
+    );
 
     let dir = cfg.peer_store_directory.clone();
     let file = dir.join(Z::NAME);
@@ -52,21 +135,62 @@ pub(crate) fn save_peers_to_disk<Z: BorshNetworkZone>(
 
 pub(crate) async fn read_peers_from_disk<Z: BorshNetworkZone>(
     cfg: &AddressBookConfig<Z>,
-) -> Result<
-    (
-        Vec<ZoneSpecificPeerListEntryBase<Z::Addr>>,
-        Vec<ZoneSpecificPeerListEntryBase<Z::Addr>>,
-    ),
-    std::io::Error,
-> {
+) -> Result<PeerStore<Z>, std::io::Error> {
     let file = cfg.peer_store_directory.join(Z::NAME);
 
     tracing::info!("Loading peers from file: {} ", file.display());
 
     let data = spawn_blocking(move || fs::read(file)).await.unwrap()?;
 
-    let de_ser: DeserPeerDataV1<Z::Addr> = from_slice(&data)?;
-    Ok((de_ser.white_list, de_ser.gray_list))
+    deserialize_peer_store(&data, Instant::now(), unix_timestamp())
+}
+
+/// Returns the bans that expire after `now_unix`, with the [`Instant`] they expire at.
+fn unexpired_bans<T>(bans: Vec<(T, u64)>, now: Instant, now_unix: u64) -> Vec<(T, Instant)> {
+    bans.into_iter()
+        .filter(|(_, unban_at)| *unban_at > now_unix)
+        .map(|(target, unban_at)| (target, now + Duration::from_secs(unban_at - now_unix)))
+        .collect()
+}
+
+/// Deserializes a peer store file of any version, `now` and `now_unix` are the current time.
+///
+/// Bans that have expired are dropped.
+fn deserialize_peer_store<Z: BorshNetworkZone>(
+    data: &[u8],
+    now: Instant,
+    now_unix: u64,
+) -> Result<PeerStore<Z>, std::io::Error> {
+    let Some(data) = data.strip_prefix(PEER_STORE_MAGIC) else {
+        tracing::info!("Migrating V1 peer store, anchors and bans will be stored from now on.");
+
+        let de_ser: DeserPeerDataV1<Z::Addr> = from_slice(data)?;
+        return Ok(PeerStore {
+            white_list: de_ser.white_list,
+            gray_list: de_ser.gray_list,
+            ..Default::default()
+        });
+    };
+
+    match data.split_first() {
+        Some((&PEER_STORE_VERSION, data)) => {
+            let de_ser: DeserPeerDataV2<Z::Addr, Z::BorshBanID, Z::BorshBanSubnet> =
+                from_slice(data)?;
+
+            Ok(PeerStore {
+                white_list: de_ser.white_list,
+                gray_list: de_ser.gray_list,
+                anchor_list: de_ser.anchor_list,
+                ban_list: unexpired_bans(de_ser.ban_list, now, now_unix),
+                banned_subnets: unexpired_bans(de_ser.banned_subnets, now, now_unix),
+            })
+        }
+        Some((version, _)) => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Unknown peer store version: {version}"),
+        )),
+        None => Err(ErrorKind::UnexpectedEof.into()),
+    }
 }
 
 #[cfg(test)]
@@ -81,13 +205,17 @@ mod tests {
         let white_list = make_fake_peer_list(0, 50);
         let gray_list = make_fake_peer_list(50, 100);
 
-        let data = to_vec(&SerPeerDataV1 {
+        let data = to_vec(&SerPeerDataV2 {
             white_list: white_list.peers.values().collect::<Vec<_>>(),
             gray_list: gray_list.peers.values().collect::<Vec<_>>(),
+            anchor_list: vec![],
+            ban_list: Vec::<(TestNetZoneAddr, u64)>::new(),
+            banned_subnets: Vec::<((u32, u32), u64)>::new(),
         })
         .unwrap();
 
-        let de_ser: DeserPeerDataV1<TestNetZoneAddr> = from_slice(&data).unwrap();
+        let de_ser: DeserPeerDataV2<TestNetZoneAddr, TestNetZoneAddr, (u32, u32)> =
+            from_slice(&data).unwrap();
 
         let white_list_2: PeerList<TestNetZone<true>> = PeerList::new(de_ser.white_list);
         let gray_list_2: PeerList<TestNetZone<true>> = PeerList::new(de_ser.gray_list);
@@ -103,4 +231,63 @@ mod tests {
             assert!(gray_list_2.contains_peer(addr));
         }
     }
+
+    #[test]
+    fn anchors_and_bans_restored() {
+        let now = Instant::now();
+        let now_unix = 1_000_000;
+
+        let white_list = make_fake_peer_list(0, 50);
+        let anchor = *white_list.peers.keys().next().unwrap();
+        let (banned, expired) = (TestNetZoneAddr(200), TestNetZoneAddr(201));
+
+        let mut data = PEER_STORE_MAGIC.to_vec();
+        data.push(PEER_STORE_VERSION);
+        data.extend(
+            to_vec(&SerPeerDataV2 {
+                white_list: white_list.peers.values().collect::<Vec<_>>(),
+                gray_list: vec![],
+                anchor_list: vec![&white_list.peers[&anchor]],
+                ban_list: vec![(banned, now_unix + 60), (expired, now_unix - 60)],
+                banned_subnets: vec![((0, 10), now_unix + 120), ((10, 20), now_unix - 1)],
+            })
+            .unwrap(),
+        );
+
+        let store: PeerStore<TestNetZone<true>> =
+            deserialize_peer_store(&data, now, now_unix).unwrap();
+
+        assert_eq!(store.white_list.len(), 50);
+        assert_eq!(store.anchor_list.len(), 1);
+        assert_eq!(store.anchor_list[0].adr, anchor);
+        assert_eq!(
+            store.ban_list,
+            vec![(banned, now + Duration::from_secs(60))]
+        );
+        assert_eq!(
+            store.banned_subnets,
+            vec![((0, 10), now + Duration::from_secs(120))]
+        );
+    }
+
+    #[test]
+    fn v1_peer_store_migrated() {
+        let white_list = make_fake_peer_list(0, 50);
+        let gray_list = make_fake_peer_list(50, 100);
+
+        let data = to_vec(&DeserPeerDataV1 {
+            white_list: white_list.peers.values().cloned().collect(),
+            gray_list: gray_list.peers.values().cloned().collect(),
+        })
+        .unwrap();
+
+        let store: PeerStore<TestNetZone<true>> =
+            deserialize_peer_store(&data, Instant::now(), 0).unwrap();
+
+        assert_eq!(store.white_list.len(), 50);
+        assert_eq!(store.gray_list.len(), 100);
+        assert!(store.anchor_list.is_empty());
+        assert!(store.ban_list.is_empty());
+        assert!(store.banned_subnets.is_empty());
+    }
 }
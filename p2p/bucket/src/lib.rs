@@ -4,10 +4,10 @@
 //!
 //! The item must implement the [`Bucketable`] trait that defines how to create the discriminant
 //! from the item type. The data structure will internally contain any item into "buckets" or vectors
-//! of sized capacity `N` that regroup all the stored items with this specific discriminant.
+//! with a capacity that regroup all the stored items with this specific discriminant.
 //!
-//! A practical example of this data structure is for storing `N` amount of IP discriminated by their subnets.
-//! You can store in each "buckets" corresponding to a `/16` subnet up to `N` IPs of that subnet.
+//! A practical example of this data structure is for storing a limited amount of IPs discriminated by their subnets.
+//! You can store in each "buckets" corresponding to a `/16` subnet up to a set amount of IPs of that subnet.
 //!
 //! # Example
 //!
@@ -16,7 +16,7 @@
 //! use std::net::Ipv4Addr;
 //!
 //! // Create a new bucket that can store at most 2 IPs in a particular `/16` subnet.
-//! let mut bucket = Bucket::<2,Ipv4Addr>::new();
+//! let mut bucket = Bucket::<Ipv4Addr>::new(2);
 //!
 //! // Fulfill the `96.96.0.0/16` bucket.
 //! bucket.push("96.96.0.1".parse().unwrap());
@@ -38,15 +38,14 @@
 //!
 //! ```
 
-use arrayvec::{ArrayVec, CapacityError};
-use rand::random;
+use rand::{thread_rng, Rng};
 
 use std::{collections::BTreeMap, net::Ipv4Addr};
 
 /// A discriminant that can be computed from the type.
 pub trait Bucketable: Sized + Eq + Clone {
     /// The type of the discriminant being used in the Binary tree.
-    type Discriminant: Ord + AsRef<[u8]>;
+    type Discriminant: Ord;
 
     /// Method that can compute the discriminant from the item.
     fn discriminant(&self) -> Self::Discriminant;
@@ -54,20 +53,28 @@ pub trait Bucketable: Sized + Eq + Clone {
 
 /// A collection data structure discriminating its unique items
 /// with a specified method. Limiting the amount of items stored
-/// with that discriminant to the const `N`.
-pub struct Bucket<const N: usize, I: Bucketable> {
+/// with that discriminant to the bucket's capacity.
+pub struct Bucket<I: Bucketable> {
     /// The storage of the bucket
-    storage: BTreeMap<I::Discriminant, ArrayVec<I, N>>,
+    storage: BTreeMap<I::Discriminant, Vec<I>>,
+    /// The maximum amount of items stored with the same discriminant.
+    capacity: usize,
 }
 
-impl<const N: usize, I: Bucketable> Bucket<N, I> {
-    /// Create a new Bucket
-    pub const fn new() -> Self {
+impl<I: Bucketable> Bucket<I> {
+    /// Create a new Bucket, storing at most `capacity` items with the same discriminant.
+    pub const fn new(capacity: usize) -> Self {
         Self {
             storage: BTreeMap::new(),
+            capacity,
         }
     }
 
+    /// Return the maximum amount of items stored with the same discriminant.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     /// Push a new element into the Bucket
     ///
     /// Will internally create a new vector for each new discriminant being
@@ -84,7 +91,7 @@ impl<const N: usize, I: Bucketable> Bucket<N, I> {
     /// use cuprate_p2p_bucket::Bucket;
     /// use std::net::Ipv4Addr;
     ///
-    /// let mut bucket = Bucket::<8,Ipv4Addr>::new();
+    /// let mut bucket = Bucket::<Ipv4Addr>::new(8);
     ///
     /// // Push a first IP address.
     /// bucket.push("127.0.0.1".parse().unwrap());
@@ -97,34 +104,41 @@ impl<const N: usize, I: Bucketable> Bucket<N, I> {
     pub fn push(&mut self, item: I) -> Option<I> {
         let discriminant = item.discriminant();
 
-        if let Some(vec) = self.storage.get_mut(&discriminant) {
-            // Push the item if it doesn't exist.
-            if !vec.contains(&item) {
-                return vec.try_push(item).err().map(CapacityError::element);
-            }
-        } else {
-            // Initialize the vector if not found.
-            let mut vec = ArrayVec::<I, N>::new();
-            vec.push(item);
-            self.storage.insert(discriminant, vec);
+        let len = match self.storage.get(&discriminant) {
+            // Ignore the item if it already exists.
+            Some(vec) if vec.contains(&item) => return None,
+            Some(vec) => vec.len(),
+            None => 0,
+        };
+
+        if len >= self.capacity {
+            return Some(item);
         }
 
+        // Will initialize the vector if not found.
+        self.storage.entry(discriminant).or_default().push(item);
         None
     }
 
     /// Will attempt to remove an item from the bucket.
     pub fn remove(&mut self, item: &I) -> Option<I> {
-        self.storage.get_mut(&item.discriminant()).and_then(|vec| {
-            vec.iter()
-                .enumerate()
-                .find_map(|(i, v)| (item == v).then_some(i))
-                .map(|index| vec.swap_remove(index))
-        })
+        let discriminant = item.discriminant();
+        let vec = self.storage.get_mut(&discriminant)?;
+
+        let index = vec.iter().position(|v| item == v)?;
+        let item = vec.swap_remove(index);
+
+        // Don't keep empty buckets around.
+        if vec.is_empty() {
+            self.storage.remove(&discriminant);
+        }
+
+        Some(item)
     }
 
     /// Return the number of item stored within the storage
     pub fn len(&self) -> usize {
-        self.storage.values().map(ArrayVec::len).sum()
+        self.storage.values().map(Vec::len).sum()
     }
 
     /// Return the number of item stored with a specific discriminant.
@@ -132,7 +146,12 @@ impl<const N: usize, I: Bucketable> Bucket<N, I> {
     /// This method returns None if the bucket with this discriminant
     /// doesn't exist.
     pub fn len_bucket(&self, discriminant: &I::Discriminant) -> Option<usize> {
-        self.storage.get(discriminant).map(ArrayVec::len)
+        self.storage.get(discriminant).map(Vec::len)
+    }
+
+    /// Return `true` if no more items can be stored with this discriminant.
+    pub fn is_bucket_full(&self, discriminant: &I::Discriminant) -> bool {
+        self.len_bucket(discriminant).unwrap_or(0) >= self.capacity
     }
 
     /// Return `true` if the storage contains no items
@@ -140,25 +159,21 @@ impl<const N: usize, I: Bucketable> Bucket<N, I> {
         self.len() == 0
     }
 
-    /// Return a reference to an item chosen at random.
+    /// Return a reference to an item chosen at random, or `None` if the bucket is empty.
     ///
     /// Repeated use of this function will provide a normal distribution of
     /// items based on their discriminants.
-    pub fn get_random(&mut self) -> Option<&I> {
-        // Get the total amount of discriminants to explore.
-        let len = self.storage.len();
+    pub fn get_random(&self) -> Option<&I> {
+        let mut rng = thread_rng();
 
-        // Get a random bucket.
-        let (_, vec) = self.storage.iter().nth(random::<usize>() / len).unwrap();
+        // Get a random bucket, empty buckets are never kept.
+        let (_, vec) = self
+            .storage
+            .iter()
+            .nth(rng.gen_range(0..self.storage.len().max(1)))?;
 
         // Return a reference chose at random.
-        vec.get(random::<usize>() / vec.len())
-    }
-}
-
-impl<const N: usize, I: Bucketable> Default for Bucket<N, I> {
-    fn default() -> Self {
-        Self::new()
+        vec.get(rng.gen_range(0..vec.len()))
     }
 }
 
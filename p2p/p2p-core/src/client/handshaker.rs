@@ -468,6 +468,7 @@ where
         .await?
         .call(AddressBookRequest::NewConnection {
             internal_peer_id: addr,
+            direction,
             public_address,
             handle: handle.clone(),
             id: peer_node_data.peer_id,
@@ -102,9 +102,9 @@ impl<N: NetworkZone> Service<AddressBookRequest<N>> for DummyAddressBook {
             | AddressBookRequest::TakeRandomWhitePeer { .. } => {
                 return ready(Err("dummy address book does not hold peers".into()));
             }
-            AddressBookRequest::NewConnection { .. } | AddressBookRequest::IncomingPeerList(_) => {
-                AddressBookResponse::Ok
-            }
+            AddressBookRequest::NewConnection { .. }
+            | AddressBookRequest::IncomingPeerList(_)
+            | AddressBookRequest::SetBanList(_) => AddressBookResponse::Ok,
             AddressBookRequest::GetBan(_) => AddressBookResponse::GetBan {
                 unban_instant: None,
             },
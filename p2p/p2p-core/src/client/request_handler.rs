@@ -153,7 +153,7 @@ where
                     adr: own_addr,
                     id: self.our_basic_node_data.peer_id,
                     last_seen: 0,
-                    pruning_seed: PruningSeed::NotPruned,
+                    pruning_seed: PruningSeed::decompress_p2p_rules(core_sync_data.pruning_seed)?,
                     rpc_port: self.our_basic_node_data.rpc_port,
                     rpc_credits_per_hash: self.our_basic_node_data.rpc_credits_per_hash,
                 },
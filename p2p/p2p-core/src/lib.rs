@@ -87,7 +87,7 @@ pub mod transports;
 pub mod types;
 
 pub use error::*;
-pub use network_zones::{ClearNet, Tor, I2p};
+pub use network_zones::{ClearNet, I2p, IpSubnet, IpSubnetParseError, NoSubnet, Tor};
 pub use protocol::*;
 use services::*;
 //re-export
@@ -121,10 +121,14 @@ pub trait NetZoneAddress:
     /// which for hidden services could just be the address it self but for clear net addresses will
     /// be the IP address.
     ///
-    /// - TODO: IP zone banning?
     /// - TODO: rename this to Host.
     type BanID: Debug + Hash + Eq + Clone + Copy + Send + 'static;
 
+    /// A group of hosts that can be banned together, for clear net addresses this is an IP subnet.
+    ///
+    /// Zones without such groups can use [`NoSubnet`].
+    type BanSubnet: Debug + Hash + Eq + Ord + Clone + Copy + Send + 'static;
+
     /// Changes the port of this address to `port`.
     fn set_port(&mut self, port: u16);
 
@@ -134,6 +138,25 @@ pub trait NetZoneAddress:
     /// Returns the [`Self::BanID`] for this address.
     fn ban_id(&self) -> Self::BanID;
 
+    /// Returns `true` if this address is in `subnet`.
+    fn in_subnet(&self, subnet: &Self::BanSubnet) -> bool;
+
+    /// Returns the subnet used to keep our peers diverse, we limit the amount of peers we connect to
+    /// in the same subnet so a single entity can't easily take all our connections.
+    ///
+    /// Zones without subnets should return [`None`].
+    fn diversity_subnet(&self) -> Option<Self::BanSubnet>;
+
+    /// Returns `true` if this address is only reachable from this machine or a local network.
+    ///
+    /// These addresses are only added to the peer list when local addresses are allowed, e.g. on
+    /// private test networks.
+    fn is_local(&self) -> bool;
+
+    /// Returns `true` if this address could be a peer, addresses that are never routable should
+    /// not be added to the peer list.
+    ///
+    /// Local addresses are checked separately with [`NetZoneAddress::is_local`].
     fn should_add_to_peer_list(&self) -> bool;
 }
 
@@ -2,6 +2,28 @@ mod clear;
 mod tor;
 mod i2p;
 
-pub use clear::ClearNet;
+pub use clear::{ClearNet, IpSubnet, IpSubnetParseError};
 pub use tor::Tor;
 pub use i2p::I2p;
+
+/// The [`NetZoneAddress::BanSubnet`](crate::NetZoneAddress::BanSubnet) of zones that have no
+/// subnets, this can never be constructed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NoSubnet {}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for NoSubnet {
+    fn serialize<W: std::io::Write>(&self, _: &mut W) -> std::io::Result<()> {
+        match *self {}
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for NoSubnet {
+    fn deserialize_reader<R: std::io::Read>(_: &mut R) -> std::io::Result<Self> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "This network zone has no subnets",
+        ))
+    }
+}
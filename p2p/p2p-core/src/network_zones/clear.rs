@@ -1,9 +1,14 @@
-use std::net::{IpAddr, SocketAddr};
+use std::{
+    fmt::{Display, Formatter},
+    net::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str::FromStr,
+};
 
 use crate::{NetZoneAddress, NetworkZone};
 
 impl NetZoneAddress for SocketAddr {
     type BanID = IpAddr;
+    type BanSubnet = IpSubnet;
 
     fn set_port(&mut self, port: u16) {
         Self::set_port(self, port);
@@ -13,14 +18,69 @@ impl NetZoneAddress for SocketAddr {
         self.ip()
     }
 
+    fn in_subnet(&self, subnet: &Self::BanSubnet) -> bool {
+        subnet.contains(self.ip())
+    }
+
+    fn diversity_subnet(&self) -> Option<Self::BanSubnet> {
+        // The `/16` for IPv4, like `monerod`, and the `/32` for IPv6 as that is a common allocation size.
+        let ip = self.ip().to_canonical();
+        let prefix_len = match ip {
+            IpAddr::V4(_) => 16,
+            IpAddr::V6(_) => 32,
+        };
+
+        IpSubnet::new(ip, prefix_len)
+    }
+
     fn make_canonical(&mut self) {
         let ip = self.ip().to_canonical();
         self.set_ip(ip);
     }
 
+    /// Matches `monerod`'s `is_ip_local` and `is_ip_loopback`, with link local addresses also
+    /// counted as local.
+    fn is_local(&self) -> bool {
+        match self.ip().to_canonical() {
+            IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+            IpAddr::V6(ip) => {
+                let first_segment = ip.segments()[0];
+
+                ip.is_loopback()
+                    // Unique local, `fc00::/7`.
+                    || first_segment & 0xfe00 == 0xfc00
+                    // Link local, `fe80::/10`.
+                    || first_segment & 0xffc0 == 0xfe80
+                    // Site local, `fec0::/10`, deprecated but still treated as local.
+                    || first_segment & 0xffc0 == 0xfec0
+            }
+        }
+    }
+
     fn should_add_to_peer_list(&self) -> bool {
-        // TODO
-        true
+        if self.port() == 0 {
+            return false;
+        }
+
+        match self.ip().to_canonical() {
+            IpAddr::V4(ip) => {
+                let first_octet = ip.octets()[0];
+
+                // `0.0.0.0/8` is "this network" and `240.0.0.0/4` is reserved, which includes broadcast.
+                !(first_octet == 0
+                    || first_octet >= 240
+                    || ip.is_multicast()
+                    || ip.is_documentation())
+            }
+            IpAddr::V6(ip) => {
+                let segments = ip.segments();
+
+                !(ip.is_unspecified()
+                    || ip.is_multicast()
+                    // Documentation, `2001:db8::/32`.
+                    || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+            }
+        }
     }
 }
 
@@ -36,3 +96,223 @@ impl NetworkZone for ClearNet {
 
     type Addr = SocketAddr;
 }
+
+/// An IP subnet in CIDR notation, e.g. `192.168.0.0/16`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IpSubnet {
+    /// The network address, all host bits are `0`.
+    addr: IpAddr,
+    /// The amount of leading bits that make up the network address.
+    prefix_len: u8,
+}
+
+impl IpSubnet {
+    /// Returns the subnet of `addr` with a `prefix_len` bit long prefix, host bits in `addr` are ignored.
+    ///
+    /// Returns [`None`] if `prefix_len` is longer than the address.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Option<Self> {
+        let addr = match addr {
+            IpAddr::V4(v4) if prefix_len <= 32 => {
+                // A shift of the whole width (a `/0` prefix) overflows, the mask is empty.
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(prefix_len))
+                    .unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(v4) & mask))
+            }
+            IpAddr::V6(v6) if prefix_len <= 128 => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(prefix_len))
+                    .unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(v6) & mask))
+            }
+            IpAddr::V4(_) | IpAddr::V6(_) => return None,
+        };
+
+        Some(Self { addr, prefix_len })
+    }
+
+    /// Returns the subnet that only contains `addr`.
+    pub const fn host(addr: IpAddr) -> Self {
+        let prefix_len = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+
+        Self { addr, prefix_len }
+    }
+
+    /// The network address of this subnet.
+    pub const fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// The length of this subnet's prefix in bits.
+    pub const fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns `true` if `ip` is in this subnet.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        Self::new(ip, self.prefix_len) == Some(*self)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for IpSubnet {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        (self.addr, self.prefix_len).serialize(writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for IpSubnet {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let (addr, prefix_len) = borsh::BorshDeserialize::deserialize_reader(reader)?;
+
+        Self::new(addr, prefix_len).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Invalid subnet prefix length",
+            )
+        })
+    }
+}
+
+impl Display for IpSubnet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+/// An error parsing an [`IpSubnet`].
+#[derive(Debug, thiserror::Error)]
+pub enum IpSubnetParseError {
+    /// The IP address is invalid.
+    #[error("Invalid IP address: {0}")]
+    Addr(#[from] AddrParseError),
+    /// The prefix length is invalid or longer than the address.
+    #[error("Invalid subnet prefix length")]
+    PrefixLen,
+}
+
+impl FromStr for IpSubnet {
+    type Err = IpSubnetParseError;
+
+    /// Parses a subnet in CIDR notation, a plain IP address is parsed as a subnet only containing that address.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((addr, prefix_len)) = s.split_once('/') else {
+            return Ok(Self::host(s.parse()?));
+        };
+
+        let prefix_len = prefix_len
+            .parse()
+            .map_err(|_| IpSubnetParseError::PrefixLen)?;
+
+        Self::new(addr.parse()?, prefix_len).ok_or(IpSubnetParseError::PrefixLen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ip_subnet_contains() {
+        let subnet: IpSubnet = "192.168.1.7/16".parse().unwrap();
+        assert_eq!(subnet.to_string(), "192.168.0.0/16");
+
+        assert!(subnet.contains("192.168.255.1".parse().unwrap()));
+        assert!(!subnet.contains("192.169.0.1".parse().unwrap()));
+        assert!(!subnet.contains("::1".parse().unwrap()));
+
+        let host: IpSubnet = "2001:db8::1".parse().unwrap();
+        assert_eq!(host.prefix_len(), 128);
+        assert!(host.contains("2001:db8::1".parse().unwrap()));
+        assert!(!host.contains("2001:db8::2".parse().unwrap()));
+
+        let all: IpSubnet = "0.0.0.0/0".parse().unwrap();
+        assert!(all.contains("1.2.3.4".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<IpSubnet>().is_err());
+        assert!("10.0.0/8".parse::<IpSubnet>().is_err());
+    }
+
+    /// Returns `(is_local, should_add_to_peer_list)` for `addr`.
+    fn check_addr(addr: &str) -> (bool, bool) {
+        let addr: SocketAddr = addr.parse().unwrap();
+        (addr.is_local(), addr.should_add_to_peer_list())
+    }
+
+    #[test]
+    fn ipv4_special_ranges() {
+        // Public.
+        assert_eq!(check_addr("176.9.0.187:18080"), (false, true));
+        assert_eq!(check_addr("172.32.0.1:18080"), (false, true));
+        assert_eq!(check_addr("176.9.0.187:0"), (false, false));
+
+        // Local.
+        for addr in [
+            "127.0.0.1:18080",
+            "127.255.0.1:18080",
+            "10.1.2.3:18080",
+            "172.16.0.1:18080",
+            "172.31.255.255:18080",
+            "192.168.1.1:18080",
+            "169.254.0.1:18080",
+            "[::ffff:192.168.1.1]:18080",
+        ] {
+            assert_eq!(check_addr(addr), (true, true), "{addr}");
+        }
+
+        // Never routable.
+        for addr in [
+            "0.0.0.0:18080",
+            "0.1.2.3:18080",
+            "224.0.0.1:18080",
+            "239.255.255.255:18080",
+            "240.0.0.1:18080",
+            "255.255.255.255:18080",
+            "192.0.2.1:18080",
+            "198.51.100.1:18080",
+            "203.0.113.1:18080",
+        ] {
+            assert_eq!(check_addr(addr), (false, false), "{addr}");
+        }
+    }
+
+    #[test]
+    fn ipv6_special_ranges() {
+        // Public.
+        assert_eq!(check_addr("[2a01:4f8::1]:18080"), (false, true));
+        assert_eq!(check_addr("[2a01:4f8::1]:0"), (false, false));
+
+        // Local.
+        for addr in [
+            "[::1]:18080",
+            "[fc00::1]:18080",
+            "[fd12:3456::1]:18080",
+            "[fe80::1]:18080",
+            "[febf::1]:18080",
+            "[fec0::1]:18080",
+        ] {
+            assert_eq!(check_addr(addr), (true, true), "{addr}");
+        }
+
+        // Never routable.
+        for addr in ["[::]:18080", "[ff02::1]:18080", "[2001:db8::1]:18080"] {
+            assert_eq!(check_addr(addr), (false, false), "{addr}");
+        }
+    }
+
+    #[test]
+    fn diversity_subnet() {
+        let addr: SocketAddr = "192.168.1.7:18080".parse().unwrap();
+        assert_eq!(addr.diversity_subnet(), "192.168.0.0/16".parse().ok());
+
+        let addr: SocketAddr = "[::ffff:192.168.1.7]:18080".parse().unwrap();
+        assert_eq!(addr.diversity_subnet(), "192.168.0.0/16".parse().ok());
+
+        let addr: SocketAddr = "[2001:db8:1::1]:18080".parse().unwrap();
+        assert_eq!(addr.diversity_subnet(), "2001:db8::/32".parse().ok());
+    }
+}
@@ -12,32 +12,43 @@
 //!
 //! ### Addressing
 //!
-//! The I2P zone uses [`GarlicAddr`] as its address type, representing I2P destinations.
+//! The I2P zone uses [`GarlicAddr`] as its address type, the SHA-256 hash of an I2P destination.
 //!
 
 use cuprate_wire::network_address::GarlicAddr;
 
-use crate::{NetZoneAddress, NetworkZone};
+use crate::{NetZoneAddress, NetworkZone, NoSubnet};
 
 impl NetZoneAddress for GarlicAddr {
     type BanID = [u8; 32];
+    type BanSubnet = NoSubnet;
 
     fn set_port(&mut self, port: u16) {
         self.port = port;
     }
 
     fn ban_id(&self) -> Self::BanID {
-        self.destination()
+        self.hash()
+    }
+
+    fn in_subnet(&self, subnet: &Self::BanSubnet) -> bool {
+        match *subnet {}
+    }
+
+    fn diversity_subnet(&self) -> Option<Self::BanSubnet> {
+        None
     }
 
     fn make_canonical(&mut self) {
-        // I2P destinations don't have canonical forms like IP addresses
-        // The destination hash is already in canonical form
+        // There are no canonical form of a garlic address...
+    }
+
+    fn is_local(&self) -> bool {
+        false
     }
 
     fn should_add_to_peer_list(&self) -> bool {
-        // For I2P, we generally want to add valid destinations to peer lists
-        // Additional validation could be added here if needed
+        // Validation of the garlic address has been done at the type construction...
         true
     }
 }
@@ -48,11 +59,9 @@ pub struct I2p;
 
 impl NetworkZone for I2p {
     const NAME: &'static str = "I2p";
-    
-    /// Don't check node IDs on I2P for anonymity
+
     const CHECK_NODE_ID: bool = false;
-    
-    /// Broadcast our own address for better connectivity in the I2P network
+
     const BROADCAST_OWN_ADDR: bool = true;
 
     type Addr = GarlicAddr;
@@ -15,10 +15,11 @@
 
 use cuprate_wire::network_address::OnionAddr;
 
-use crate::{NetZoneAddress, NetworkZone};
+use crate::{NetZoneAddress, NetworkZone, NoSubnet};
 
 impl NetZoneAddress for OnionAddr {
     type BanID = [u8; 56];
+    type BanSubnet = NoSubnet;
 
     fn set_port(&mut self, port: u16) {
         self.port = port;
@@ -28,10 +29,22 @@ impl NetZoneAddress for OnionAddr {
         self.domain()
     }
 
+    fn in_subnet(&self, subnet: &Self::BanSubnet) -> bool {
+        match *subnet {}
+    }
+
+    fn diversity_subnet(&self) -> Option<Self::BanSubnet> {
+        None
+    }
+
     fn make_canonical(&mut self) {
         // There are no canonical form of an onion address...
     }
 
+    fn is_local(&self) -> bool {
+        false
+    }
+
     fn should_add_to_peer_list(&self) -> bool {
         // Validation of the onion address has been done at the type construction...
         true
@@ -7,7 +7,7 @@ use crate::{
     client::InternalPeerID,
     handles::ConnectionHandle,
     types::{BanState, ConnectionInfo, Peerlist, SetBan},
-    NetZoneAddress, NetworkAddressIncorrectZone, NetworkZone,
+    ConnectionDirection, NetZoneAddress, NetworkAddressIncorrectZone, NetworkZone,
 };
 
 /// A request to the core sync service for our node's [`CoreSyncData`].
@@ -77,6 +77,8 @@ pub enum AddressBookRequest<Z: NetworkZone> {
     NewConnection {
         /// The [`InternalPeerID`] of this connection.
         internal_peer_id: InternalPeerID<Z::Addr>,
+        /// The direction of this connection.
+        direction: ConnectionDirection,
         /// The public address of the peer, if this peer has a reachable public address.
         public_address: Option<Z::Addr>,
         /// The [`ConnectionHandle`] to this peer.
@@ -130,10 +132,15 @@ pub enum AddressBookRequest<Z: NetworkZone> {
     /// Get the amount of incoming & outgoing connections.
     ConnectionCount,
 
-    /// (Un)ban a peer.
+    /// (Un)ban a peer or a subnet.
     SetBan(SetBan<Z::Addr>),
 
-    /// Checks if the given peer is banned.
+    /// Replaces the subnets banned by the ban-list file.
+    ///
+    /// These bans do not expire, they are only lifted by another [`AddressBookRequest::SetBanList`].
+    SetBanList(Vec<<Z::Addr as NetZoneAddress>::BanSubnet>),
+
+    /// Checks if the given peer is banned, either directly or by a subnet ban.
     GetBan(Z::Addr),
 
     /// Get the state of all bans.
@@ -147,6 +154,8 @@ pub enum AddressBookResponse<Z: NetworkZone> {
     /// Response to:
     /// - [`AddressBookRequest::NewConnection`]
     /// - [`AddressBookRequest::IncomingPeerList`]
+    /// - [`AddressBookRequest::SetBan`]
+    /// - [`AddressBookRequest::SetBanList`]
     Ok,
 
     /// Response to:
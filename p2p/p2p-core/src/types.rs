@@ -7,21 +7,29 @@ use cuprate_types::{AddressType, ConnectionState};
 
 use crate::{NetZoneAddress, ZoneSpecificPeerListEntryBase};
 
+/// A host or a group of hosts that can be banned.
+pub enum BanTarget<A: NetZoneAddress> {
+    /// A single host, see [`NetZoneAddress::BanID`].
+    Host(A::BanID),
+    /// A group of hosts, see [`NetZoneAddress::BanSubnet`].
+    Subnet(A::BanSubnet),
+}
+
 /// Data within [`crate::services::AddressBookRequest::SetBan`].
 pub struct SetBan<A: NetZoneAddress> {
-    /// Address of the peer.
-    pub address: A,
-    /// - If [`Some`], how long this peer should be banned for
-    /// - If [`None`], the peer will be unbanned
+    /// The host(s) to (un)ban.
+    pub target: BanTarget<A>,
+    /// - If [`Some`], how long the target should be banned for
+    /// - If [`None`], the target will be unbanned
     pub ban: Option<Duration>,
 }
 
 /// Data within [`crate::services::AddressBookResponse::GetBans`].
 pub struct BanState<A: NetZoneAddress> {
-    /// Address of the peer.
-    pub address: A,
-    /// - If [`Some`], the peer is banned until this [`Instant`]
-    /// - If [`None`], the peer is not currently banned
+    /// The banned host(s).
+    pub target: BanTarget<A>,
+    /// - If [`Some`], the target is banned until this [`Instant`]
+    /// - If [`None`], the target is not currently banned
     pub unban_instant: Option<Instant>,
 }
 
@@ -1,134 +1,165 @@
-//! I2P Transport Implementation
+//! I2P Transport
 //!
-//! This module provides the I2P transport layer for Cuprate's P2P networking.
-//! It implements the `Transport` trait to enable communication over the I2P network.
+//! This module defines a transport method for the I2P network zone, using the SAM v3 bridge of a
+//! local I2P router (i2pd or Java I2P).
+//!
+//! Outbound connections are opened with `STREAM CONNECT` over a transient session shared by all
+//! outbound connections. Inbound connections are accepted with `STREAM ACCEPT` over a separate
+//! session using our own (persistent) destination, so the two can not be linked together.
+//!
+//! Once a stream has been opened the SAM socket carries the raw stream data, which is framed
+//! with [`MoneroWireCodec`] like TCP connections.
 //!
-//! This is a basic implementation that would need to be enhanced with actual
-//! I2P client library integration (like i2p-rs or similar).
 
 use std::{
+    io,
+    path::{Path, PathBuf},
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
 
-use async_trait::async_trait;
-use futures::{Sink, Stream};
+use futures::Stream;
+use tokio::{
+    net::tcp::{OwnedReadHalf, OwnedWriteHalf},
+    sync::{mpsc, Mutex},
+    task::JoinHandle,
+    time::sleep,
+};
 use tokio_util::codec::{FramedRead, FramedWrite};
 
-use cuprate_wire::{network_address::GarlicAddr, BucketError, LevinMessage, Message, MoneroWireCodec};
+use cuprate_p2p_core::{NetworkZone, Transport};
+use cuprate_wire::{
+    network_address::{GarlicAddr, I2pDestination},
+    MoneroWireCodec,
+};
 
-use crate::{NetworkZone, Transport};
+mod sam;
+#[cfg(test)]
+mod tests;
 
-/// I2P transport implementation.
-///
-/// This transport enables P2P communication over the I2P anonymous network.
-/// Currently this is a stub implementation that would need to be connected
-/// to an actual I2P router/client.
+use sam::{SamSession, SamSocket};
+
+/// The default address of the SAM bridge of I2P routers.
+pub const DEFAULT_SAM_ADDRESS: &str = "127.0.0.1:7656";
+
+/// The time to wait before trying to accept a new connection after a failure.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// The amount of accepted connections that can be waiting to be handled by the inbound server.
+const ACCEPTED_CONNECTIONS_QUEUE: usize = 8;
+
+/// The stream (incoming data) of an I2P connection.
+pub type I2pStream = FramedRead<OwnedReadHalf, MoneroWireCodec>;
+
+/// The sink (outgoing data) of an I2P connection.
+pub type I2pSink = FramedWrite<OwnedWriteHalf, MoneroWireCodec>;
+
+/// I2P transport using a router's SAM v3 bridge.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct I2pTransport;
 
-/// Configuration for I2P client connections.
-///
-/// This would typically contain I2P-specific settings like:
-/// - Router address and port
-/// - Tunnel configuration
-/// - Authentication settings
-#[derive(Debug, Clone, Default)]
-pub struct I2pClientConfig {
-    /// I2P router address (typically localhost:7656)
-    pub router_address: String,
-    /// Connection timeout
-    pub connect_timeout: Duration,
-    /// I2P destination lease duration
-    pub lease_duration: Duration,
-}
-
-/// Configuration for I2P server (listening for incoming connections).
-///
-/// This would contain settings for creating an I2P destination and
-/// listening for incoming connections.
+/// Parameters for making outbound I2P connections.
 #[derive(Debug, Clone)]
-pub struct I2pServerConfig {
-    /// Our I2P destination (if we have one)
-    pub destination: Option<GarlicAddr>,
-    /// Key pair for our destination (would be actual I2P keys)
-    pub private_key: Option<Vec<u8>>,
-    /// Number of tunnels to create
-    pub tunnel_count: u8,
-    /// Tunnel length (hops)
-    pub tunnel_length: u8,
+pub struct I2pClientConfig {
+    /// The address of the router's SAM bridge.
+    pub sam_address: String,
+    /// The session used for all outbound connections, created on first use.
+    session: Arc<Mutex<Option<Arc<SamSession>>>>,
 }
 
-impl Default for I2pServerConfig {
-    fn default() -> Self {
+impl I2pClientConfig {
+    /// Creates a new [`I2pClientConfig`] using the SAM bridge at `sam_address`.
+    pub fn new(sam_address: String) -> Self {
         Self {
-            destination: None,
-            private_key: None,
-            tunnel_count: 3,
-            tunnel_length: 3,
+            sam_address,
+            session: Arc::default(),
         }
     }
-}
 
-/// Placeholder for I2P stream - would be replaced with actual I2P stream implementation
-pub struct I2pStream {
-    // This would contain the actual I2P stream/socket
-    _inner: (),
-}
-
-impl Stream for I2pStream {
-    type Item = Result<Message, BucketError>;
+    /// Returns the outbound session, creating it if needed.
+    async fn session(&self) -> io::Result<Arc<SamSession>> {
+        let mut session = self.session.lock().await;
 
-    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        // Placeholder implementation
-        Poll::Pending
-    }
-}
+        if let Some(session) = session.as_ref() {
+            return Ok(Arc::clone(session));
+        }
 
-/// Placeholder for I2P sink - would be replaced with actual I2P sink implementation
-pub struct I2pSink {
-    // This would contain the actual I2P stream/socket for writing
-    _inner: (),
-}
+        tracing::debug!("Creating outbound I2P session.");
 
-impl Sink<LevinMessage<Message>> for I2pSink {
-    type Error = BucketError;
+        // Outbound connections always use a transient destination, we don't need the keys.
+        let (new_session, _) = SamSession::new(&self.sam_address, None).await?;
+        let new_session = Arc::new(new_session);
+        *session = Some(Arc::clone(&new_session));
+        drop(session);
 
-    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+        Ok(new_session)
     }
 
-    fn start_send(self: Pin<&mut Self>, _item: LevinMessage<Message>) -> Result<(), Self::Error> {
-        // Placeholder implementation
-        Ok(())
+    /// Forgets the outbound session if it is still `failed`, so the next connection creates a new one.
+    async fn reset_session(&self, failed: &Arc<SamSession>) {
+        let mut session = self.session.lock().await;
+
+        if session.as_ref().is_some_and(|s| Arc::ptr_eq(s, failed)) {
+            *session = None;
+        }
     }
+}
 
-    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+impl Default for I2pClientConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAM_ADDRESS.to_owned())
     }
+}
+
+/// Parameters for accepting inbound I2P connections.
+#[derive(Debug, Clone)]
+pub struct I2pServerConfig {
+    /// The address of the router's SAM bridge.
+    pub sam_address: String,
+    /// The file holding the private keys of our destination.
+    ///
+    /// If the file does not exist, a new destination is generated and its keys are saved to this file.
+    /// If this is [`None`] a transient destination is used, which changes every time the listener is started.
+    pub keys_file: Option<PathBuf>,
+}
 
-    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+impl Default for I2pServerConfig {
+    fn default() -> Self {
+        Self {
+            sam_address: DEFAULT_SAM_ADDRESS.to_owned(),
+            keys_file: None,
+        }
     }
 }
 
-/// I2P listener for incoming connections
+/// A listener for inbound I2P connections.
+///
+/// Connections are accepted on a background task, which keeps our inbound session open and is
+/// stopped when this is dropped.
 pub struct I2pListener {
-    // This would contain the actual I2P listener/server socket
-    _inner: (),
+    /// The connections accepted by the background task.
+    connections: mpsc::Receiver<io::Result<(Option<GarlicAddr>, I2pStream, I2pSink)>>,
+    /// The task accepting connections.
+    accept_task: JoinHandle<()>,
+}
+
+impl Drop for I2pListener {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
 }
 
 impl Stream for I2pListener {
-    type Item = Result<(Option<GarlicAddr>, I2pStream, I2pSink), std::io::Error>;
+    type Item = io::Result<(Option<GarlicAddr>, I2pStream, I2pSink)>;
 
-    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        // Placeholder implementation - would poll for incoming I2P connections
-        Poll::Pending
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.connections.poll_recv(cx)
     }
 }
 
-#[async_trait]
+#[async_trait::async_trait]
 impl<Z: NetworkZone<Addr = GarlicAddr>> Transport<Z> for I2pTransport {
     type ClientConfig = I2pClientConfig;
     type ServerConfig = I2pServerConfig;
@@ -140,128 +171,136 @@ impl<Z: NetworkZone<Addr = GarlicAddr>> Transport<Z> for I2pTransport {
     async fn connect_to_peer(
         addr: Z::Addr,
         config: &Self::ClientConfig,
-    ) -> Result<(Self::Stream, Self::Sink), std::io::Error> {
-        // Placeholder implementation
-        // In a real implementation, this would:
-        // 1. Connect to I2P router using config.router_address
-        // 2. Create an outbound tunnel to the destination addr
-        // 3. Establish the connection
-        // 4. Return wrapped stream/sink
-        
-        tracing::info!("Attempting to connect to I2P destination: {}", addr);
-        tracing::debug!("Using I2P router at: {}", config.router_address);
-        
-        // For now, return an error since this is a stub
-        Err(std::io::Error::new(
-            std::io::ErrorKind::NotConnected,
-            "I2P transport not yet implemented - needs actual I2P client library integration",
+    ) -> Result<(Self::Stream, Self::Sink), io::Error> {
+        let session = config.session().await?;
+
+        let mut socket = SamSocket::connect(&config.sam_address).await?;
+        let destination = socket.naming_lookup(&addr.addr_string()).await?;
+
+        let stream = match socket.stream_connect(&session.id, &destination).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                // The router does not know about our session anymore, make a new one next time.
+                if e.kind() == io::ErrorKind::InvalidInput {
+                    config.reset_session(&session).await;
+                }
+
+                return Err(e);
+            }
+        };
+
+        let (read, write) = stream.into_split();
+        Ok((
+            FramedRead::new(read, MoneroWireCodec::default()),
+            FramedWrite::new(write, MoneroWireCodec::default()),
         ))
     }
 
     async fn incoming_connection_listener(
         config: Self::ServerConfig,
-    ) -> Result<Self::Listener, std::io::Error> {
-        // Placeholder implementation
-        // In a real implementation, this would:
-        // 1. Connect to I2P router
-        // 2. Create or load our destination identity
-        // 3. Set up inbound tunnels
-        // 4. Start listening for connections
-        // 5. Return the listener
-        
-        tracing::info!("Setting up I2P destination listener");
-        
-        if let Some(dest) = &config.destination {
-            tracing::info!("Using existing I2P destination: {}", dest);
-        } else {
-            tracing::info!("Creating new I2P destination");
+    ) -> Result<Self::Listener, io::Error> {
+        let private_key = match &config.keys_file {
+            Some(keys_file) => match tokio::fs::read_to_string(keys_file).await {
+                Ok(keys) => Some(keys.trim().to_owned()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e),
+            },
+            None => None,
+        };
+
+        let (session, new_keys) =
+            SamSession::new(&config.sam_address, private_key.as_deref()).await?;
+
+        match config.keys_file {
+            Some(keys_file) if private_key.is_none() => {
+                tracing::info!(
+                    "Saving new I2P destination keys to: {}",
+                    keys_file.display()
+                );
+                tokio::task::spawn_blocking(move || save_keys(&keys_file, &new_keys)).await??;
+            }
+            Some(_) => (),
+            None => tracing::warn!(
+                "Using a transient I2P destination, our address will change on restart."
+            ),
         }
-        
-        // For now, return an error since this is a stub
-        Err(std::io::Error::new(
-            std::io::ErrorKind::NotConnected,
-            "I2P transport not yet implemented - needs actual I2P client library integration",
-        ))
+
+        let our_addr = parse_destination(&session.destination)?.garlic_addr(0);
+        tracing::info!("Accepting I2P connections on: {}", our_addr.addr_string());
+
+        let (connections_tx, connections) = mpsc::channel(ACCEPTED_CONNECTIONS_QUEUE);
+        let accept_task = tokio::spawn(accept_connections(
+            config.sam_address,
+            session,
+            connections_tx,
+        ));
+
+        Ok(I2pListener {
+            connections,
+            accept_task,
+        })
     }
 }
 
-/*
-## I2P-RS Integration Guide
-
-To complete this I2P transport implementation, you'll need to:
-
-### 1. Verify i2p-rs API
-Check the actual API at https://github.com/i2p/i2p-rs for:
-- SamConnection creation
-- Session management
-- Stream operations
-- Error handling
-
-### 2. Key Implementation Areas
-
-#### A. Client Connection (`connect_to_peer`)
-```rust
-async fn connect_to_peer(addr: Z::Addr, config: &Self::ClientConfig) -> Result<(Self::Stream, Self::Sink), std::io::Error> {
-    // 1. Connect to SAM bridge
-    let sam = SamConnection::connect(&config.sam_address).await?;
-    
-    // 2. Create session
-    let session = sam.stream_session_create("cuprate-client", "EDDSA_SHA512_ED25519").await?;
-    
-    // 3. Convert GarlicAddr to I2P destination format
-    let destination = garlic_addr_to_i2p_destination(addr)?;
-    
-    // 4. Connect to destination
-    let stream = session.connect(&destination).await?;
-    
-    // 5. Wrap in MoneroWireCodec
-    Ok((I2pStream::new(stream), I2pSink::new(stream)))
+/// Accepts connections on `session` forever, sending them down `connections_tx`.
+async fn accept_connections(
+    sam_address: String,
+    session: SamSession,
+    connections_tx: mpsc::Sender<io::Result<(Option<GarlicAddr>, I2pStream, I2pSink)>>,
+) {
+    loop {
+        let connection = accept_connection(&sam_address, &session).await;
+        let failed = connection.is_err();
+
+        if connections_tx.send(connection).await.is_err() {
+            return;
+        }
+
+        if failed {
+            sleep(ACCEPT_RETRY_DELAY).await;
+        }
+    }
 }
-```
-
-#### B. Server Listener (`incoming_connection_listener`)
-```rust
-async fn incoming_connection_listener(config: Self::ServerConfig) -> Result<Self::Listener, std::io::Error> {
-    // 1. Connect to SAM bridge
-    let sam = SamConnection::connect(&config.sam_address).await?;
-    
-    // 2. Create or load destination
-    let (destination, private_key) = if let Some(dest) = config.destination {
-        (dest, config.private_key.unwrap())
-    } else {
-        sam.generate_destination().await?
-    };
-    
-    // 3. Create listening session
-    let session = sam.stream_session(&config.session_name, &destination, &private_key).await?;
-    
-    Ok(I2pListener { session, destination })
+
+/// Waits for a single inbound connection on `session`.
+async fn accept_connection(
+    sam_address: &str,
+    session: &SamSession,
+) -> io::Result<(Option<GarlicAddr>, I2pStream, I2pSink)> {
+    let (destination, stream) = SamSocket::connect(sam_address)
+        .await?
+        .stream_accept(&session.id)
+        .await?;
+
+    let addr = parse_destination(&destination)?.garlic_addr(0);
+
+    let (read, write) = stream.into_split();
+    Ok((
+        Some(addr),
+        FramedRead::new(read, MoneroWireCodec::default()),
+        FramedWrite::new(write, MoneroWireCodec::default()),
+    ))
 }
-```
 
-### 3. Address Conversion
-Implement proper conversion between GarlicAddr and I2P destination format:
+/// Saves the private keys of our destination to `path`, only readable by the current user on unix.
+fn save_keys(path: &Path, keys: &str) -> io::Result<()> {
+    use std::io::Write;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
 
-```rust
-fn garlic_addr_to_i2p_destination(addr: GarlicAddr) -> Result<String, Error> {
-    // Convert from our internal GarlicAddr format to I2P destination string
-    // This depends on how GarlicAddr stores the destination data
+    options.open(path)?.write_all(keys.as_bytes())
 }
 
-fn i2p_destination_to_garlic_addr(dest: &str) -> Result<GarlicAddr, Error> {
-    // Convert from I2P destination string to our GarlicAddr format
+/// Parses a base64 destination received from the SAM bridge.
+fn parse_destination(destination: &str) -> io::Result<I2pDestination> {
+    destination
+        .parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
 }
-```
-
-### 4. Testing
-- Test with local I2P router
-- Verify SAM bridge connectivity
-- Test peer discovery and connection
-- Ensure proper error handling
-
-### 5. Configuration
-Complete the configuration integration in cuprated to support:
-- I2P router/SAM bridge settings
-- Destination persistence
-- Network zone selection
-*/
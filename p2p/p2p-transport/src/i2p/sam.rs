@@ -0,0 +1,333 @@
+//! SAM v3 Client
+//!
+//! A minimal client for the [SAM v3 bridge](https://geti2p.net/en/docs/api/samv3) exposed by I2P routers.
+//!
+//! Only the commands needed to run a `STREAM` session are implemented:
+//! - `HELLO VERSION`
+//! - `SESSION CREATE STYLE=STREAM`
+//! - `STREAM CONNECT`
+//! - `STREAM ACCEPT`
+//! - `NAMING LOOKUP`
+//!
+
+use std::{collections::HashMap, io};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// The minimum SAM version we accept, `3.1` is the first version to support `SIGNATURE_TYPE`.
+const SAM_MIN_VERSION: &str = "3.1";
+
+/// The maximum SAM version we ask for.
+const SAM_MAX_VERSION: &str = "3.3";
+
+/// The signature type used for newly generated destinations.
+const SIGNATURE_TYPE: &str = "EdDSA_SHA512_Ed25519";
+
+/// The maximum length of a single line we accept from the bridge.
+///
+/// The longest lines sent by the bridge contain a full set of private keys which are well under 1 KiB.
+const MAX_LINE_LEN: usize = 16 * 1024;
+
+/// A parsed reply from the bridge, e.g. `HELLO REPLY RESULT=OK VERSION=3.1`.
+#[derive(Debug)]
+struct SamReply {
+    /// The first word of the reply, e.g. `HELLO`.
+    topic: String,
+    /// The second word of the reply, e.g. `REPLY`.
+    kind: String,
+    /// The `KEY=VALUE` pairs of the reply.
+    values: HashMap<String, String>,
+}
+
+impl SamReply {
+    /// Parses a line received from the bridge.
+    fn parse(line: &str) -> io::Result<Self> {
+        let mut tokens = tokenize(line).into_iter();
+
+        let (Some(topic), Some(kind)) = (tokens.next(), tokens.next()) else {
+            return Err(invalid_data(format!("Malformed SAM reply: {line}")));
+        };
+
+        let values = tokens
+            .map(|token| match token.split_once('=') {
+                Some((key, value)) => (key.to_owned(), value.to_owned()),
+                None => (token, String::new()),
+            })
+            .collect();
+
+        Ok(Self {
+            topic,
+            kind,
+            values,
+        })
+    }
+
+    /// Removes and returns the value of `key`.
+    fn take_value(&mut self, key: &str) -> io::Result<String> {
+        self.values
+            .remove(key)
+            .ok_or_else(|| invalid_data(format!("SAM reply is missing the {key} field")))
+    }
+
+    /// Checks the `RESULT` field of the reply, mapping SAM errors to [`io::Error`]s.
+    fn check_result(&self) -> io::Result<()> {
+        let result = self.values.get("RESULT").map_or("", String::as_str);
+
+        let kind = match result {
+            "OK" => return Ok(()),
+            "CANT_REACH_PEER" | "PEER_NOT_FOUND" => io::ErrorKind::ConnectionRefused,
+            "TIMEOUT" => io::ErrorKind::TimedOut,
+            "INVALID_ID" | "INVALID_KEY" | "KEY_NOT_FOUND" => io::ErrorKind::InvalidInput,
+            "DUPLICATED_ID" | "DUPLICATED_DEST" => io::ErrorKind::AddrInUse,
+            "NOVERSION" => io::ErrorKind::Unsupported,
+            _ => io::ErrorKind::Other,
+        };
+
+        let message = self.values.get("MESSAGE").map_or("", String::as_str);
+
+        Err(io::Error::new(
+            kind,
+            format!(
+                "SAM {} {} failed with {result}: {message}",
+                self.topic, self.kind
+            ),
+        ))
+    }
+}
+
+/// Splits a SAM line into whitespace separated tokens, keeping quoted values together.
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_ascii_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Returns an [`io::ErrorKind::InvalidData`] error with the given message.
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// A socket to the SAM bridge that has completed the `HELLO` handshake.
+#[derive(Debug)]
+pub(crate) struct SamSocket {
+    stream: TcpStream,
+}
+
+impl SamSocket {
+    /// Connects to the bridge at `sam_address` and negotiates the protocol version.
+    pub(crate) async fn connect(sam_address: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(sam_address).await?;
+        stream.set_nodelay(true)?;
+
+        let mut socket = Self { stream };
+        socket
+            .command(
+                &format!("HELLO VERSION MIN={SAM_MIN_VERSION} MAX={SAM_MAX_VERSION}"),
+                "HELLO",
+            )
+            .await?;
+
+        Ok(socket)
+    }
+
+    /// Resolves `name` to a full base64 destination.
+    ///
+    /// `name` can be a `.b32.i2p` address, a host name known to the router or `ME` for the
+    /// destination of the session opened on this socket.
+    pub(crate) async fn naming_lookup(&mut self, name: &str) -> io::Result<String> {
+        self.command(&format!("NAMING LOOKUP NAME={name}"), "NAMING")
+            .await?
+            .take_value("VALUE")
+    }
+
+    /// Creates a `STREAM` session with the nickname `id`, returning the base64 private keys of the
+    /// session's destination.
+    ///
+    /// `private_key` is the base64 private keys of the destination to use, if this is [`None`] a
+    /// transient destination will be generated by the router.
+    ///
+    /// The session stays open until this socket is closed.
+    pub(crate) async fn create_stream_session(
+        &mut self,
+        id: &str,
+        private_key: Option<&str>,
+    ) -> io::Result<String> {
+        let destination = private_key.unwrap_or("TRANSIENT");
+
+        self.command(
+            &format!(
+                "SESSION CREATE STYLE=STREAM ID={id} DESTINATION={destination} SIGNATURE_TYPE={SIGNATURE_TYPE}"
+            ),
+            "SESSION",
+        )
+        .await?
+        .take_value("DESTINATION")
+    }
+
+    /// Opens a stream to `destination` over the session `id`.
+    ///
+    /// Returns the underlying socket, which only carries the stream's data from now on.
+    pub(crate) async fn stream_connect(
+        mut self,
+        id: &str,
+        destination: &str,
+    ) -> io::Result<TcpStream> {
+        self.command(
+            &format!("STREAM CONNECT ID={id} DESTINATION={destination} SILENT=false"),
+            "STREAM",
+        )
+        .await?;
+
+        Ok(self.stream)
+    }
+
+    /// Waits for a peer to open a stream to the session `id`.
+    ///
+    /// Returns the base64 destination of the peer and the underlying socket, which only carries the
+    /// stream's data from now on.
+    pub(crate) async fn stream_accept(mut self, id: &str) -> io::Result<(String, TcpStream)> {
+        self.command(&format!("STREAM ACCEPT ID={id} SILENT=false"), "STREAM")
+            .await?;
+
+        // When a peer connects the bridge sends their destination, followed by
+        // `FROM_PORT`/`TO_PORT` on SAM 3.2+.
+        let line = self.read_line().await?;
+        let destination = line
+            .split_ascii_whitespace()
+            .next()
+            .ok_or_else(|| invalid_data(String::from("SAM bridge sent an empty destination")))?
+            .to_owned();
+
+        Ok((destination, self.stream))
+    }
+
+    /// Sends `command` and waits for the reply, checking that it is for `topic` and that it succeeded.
+    async fn command(&mut self, command: &str, topic: &str) -> io::Result<SamReply> {
+        let mut line = String::with_capacity(command.len() + 1);
+        line.push_str(command);
+        line.push('\n');
+
+        self.stream.write_all(line.as_bytes()).await?;
+
+        let reply = SamReply::parse(&self.read_line().await?)?;
+
+        if reply.topic != topic {
+            return Err(invalid_data(format!(
+                "Expected a {topic} reply from the SAM bridge, got: {} {}",
+                reply.topic, reply.kind
+            )));
+        }
+
+        reply.check_result()?;
+
+        Ok(reply)
+    }
+
+    /// Reads a single line from the bridge.
+    ///
+    /// This reads a byte at a time so nothing after the newline is consumed, as after a `STREAM`
+    /// command the rest of the socket belongs to the peer.
+    async fn read_line(&mut self) -> io::Result<String> {
+        let mut line = Vec::new();
+
+        loop {
+            let byte = self.stream.read_u8().await?;
+            if byte == b'\n' {
+                break;
+            }
+
+            if line.len() >= MAX_LINE_LEN {
+                return Err(invalid_data(String::from("SAM line is too long")));
+            }
+
+            line.push(byte);
+        }
+
+        String::from_utf8(line).map_err(|e| invalid_data(e.to_string()))
+    }
+}
+
+/// An open SAM `STREAM` session.
+///
+/// The bridge closes the session when this is dropped.
+#[derive(Debug)]
+pub(crate) struct SamSession {
+    /// The nickname of the session, used to open streams on it.
+    pub(crate) id: String,
+    /// Our base64 destination.
+    pub(crate) destination: String,
+    /// The socket the session was created on, the session lives as long as this is open.
+    _control: SamSocket,
+}
+
+impl SamSession {
+    /// Creates a new `STREAM` session on the bridge at `sam_address`, returning the session and the
+    /// base64 private keys of its destination.
+    ///
+    /// See [`SamSocket::create_stream_session`] for `private_key`.
+    pub(crate) async fn new(
+        sam_address: &str,
+        private_key: Option<&str>,
+    ) -> io::Result<(Self, String)> {
+        let id = format!("cuprate-{:016x}", rand::random::<u64>());
+
+        let mut control = SamSocket::connect(sam_address).await?;
+        let private_key = control.create_stream_session(&id, private_key).await?;
+        let destination = control.naming_lookup("ME").await?;
+
+        Ok((
+            Self {
+                id,
+                destination,
+                _control: control,
+            },
+            private_key,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reply() {
+        let reply =
+            SamReply::parse("STREAM STATUS RESULT=I2P_ERROR MESSAGE=\"Stream closed\"").unwrap();
+
+        assert_eq!(reply.topic, "STREAM");
+        assert_eq!(reply.kind, "STATUS");
+        assert_eq!(reply.values["RESULT"], "I2P_ERROR");
+        assert_eq!(reply.values["MESSAGE"], "Stream closed");
+        assert_eq!(
+            reply.check_result().unwrap_err().kind(),
+            io::ErrorKind::Other
+        );
+
+        let reply = SamReply::parse("HELLO REPLY RESULT=OK VERSION=3.1").unwrap();
+        assert!(reply.check_result().is_ok());
+
+        assert!(SamReply::parse("HELLO").is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use std::{collections::HashMap, sync::Arc};
+
+use futures::{SinkExt, StreamExt};
+use rand::Rng;
+use tokio::{
+    io::{copy_bidirectional, AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::Mutex,
+};
+
+use cuprate_p2p_core::I2p;
+use cuprate_wire::{
+    admin::{PingResponse, PING_OK_RESPONSE_STATUS_TEXT},
+    AdminRequestMessage, AdminResponseMessage, Message,
+};
+
+use super::*;
+
+/// A session on the [`FakeSamBridge`].
+struct FakeSession {
+    /// The base64 destination of the session.
+    destination: String,
+    /// Sockets waiting in `STREAM ACCEPT`.
+    accepting: Vec<TcpStream>,
+}
+
+/// A fake SAM bridge, which connects streams opened between its sessions over loopback TCP.
+#[derive(Clone, Default)]
+struct FakeSamBridge {
+    sessions: Arc<Mutex<HashMap<String, FakeSession>>>,
+}
+
+impl FakeSamBridge {
+    /// Starts the bridge, returning the address it listens on.
+    async fn spawn() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let bridge = Self::default();
+
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(bridge.clone().handle_socket(socket));
+            }
+        });
+
+        address
+    }
+
+    /// Handles the commands sent on a single socket.
+    async fn handle_socket(self, mut socket: TcpStream) {
+        // The session created on this socket, if any.
+        let mut own_session = None;
+
+        while let Some(line) = read_line(&mut socket).await {
+            let tokens = line.split_ascii_whitespace().collect::<Vec<_>>();
+            let value = |key: &str| {
+                tokens
+                    .iter()
+                    .find_map(|t| t.strip_prefix(key)?.strip_prefix('='))
+                    .unwrap()
+                    .to_owned()
+            };
+
+            let reply = match (tokens[0], tokens[1]) {
+                ("HELLO", "VERSION") => String::from("HELLO REPLY RESULT=OK VERSION=3.1"),
+                ("SESSION", "CREATE") => {
+                    // The fake "private keys" of a destination are just the destination.
+                    let destination = match value("DESTINATION").as_str() {
+                        "TRANSIENT" => random_destination().to_string(),
+                        keys => keys.to_owned(),
+                    };
+
+                    let id = value("ID");
+                    self.sessions.lock().await.insert(
+                        id.clone(),
+                        FakeSession {
+                            destination: destination.clone(),
+                            accepting: Vec::new(),
+                        },
+                    );
+                    own_session = Some(id);
+
+                    format!("SESSION STATUS RESULT=OK DESTINATION={destination}")
+                }
+                ("NAMING", "LOOKUP") => {
+                    let name = value("NAME");
+                    let sessions = self.sessions.lock().await;
+
+                    let destination = if name == "ME" {
+                        own_session
+                            .as_ref()
+                            .map(|id| sessions[id].destination.clone())
+                    } else {
+                        sessions.values().map(|s| s.destination.clone()).find(|d| {
+                            parse_destination(d).unwrap().garlic_addr(0).addr_string() == name
+                        })
+                    };
+
+                    match destination {
+                        Some(destination) => {
+                            format!("NAMING REPLY RESULT=OK NAME={name} VALUE={destination}")
+                        }
+                        None => format!("NAMING REPLY RESULT=KEY_NOT_FOUND NAME={name}"),
+                    }
+                }
+                ("STREAM", "CONNECT") => {
+                    let mut sessions = self.sessions.lock().await;
+                    let our_destination = sessions[&value("ID")].destination.clone();
+
+                    let peer = sessions
+                        .values_mut()
+                        .find(|s| s.destination == value("DESTINATION"))
+                        .and_then(|s| s.accepting.pop());
+
+                    let Some(mut peer) = peer else {
+                        drop(sessions);
+                        socket
+                            .write_all(b"STREAM STATUS RESULT=CANT_REACH_PEER\n")
+                            .await
+                            .unwrap();
+                        return;
+                    };
+                    drop(sessions);
+
+                    socket
+                        .write_all(b"STREAM STATUS RESULT=OK\n")
+                        .await
+                        .unwrap();
+                    peer.write_all(format!("{our_destination} FROM_PORT=0 TO_PORT=0\n").as_bytes())
+                        .await
+                        .unwrap();
+
+                    drop(copy_bidirectional(&mut socket, &mut peer).await);
+                    return;
+                }
+                ("STREAM", "ACCEPT") => {
+                    socket
+                        .write_all(b"STREAM STATUS RESULT=OK\n")
+                        .await
+                        .unwrap();
+
+                    self.sessions
+                        .lock()
+                        .await
+                        .get_mut(&value("ID"))
+                        .unwrap()
+                        .accepting
+                        .push(socket);
+                    return;
+                }
+                _ => panic!("Unexpected SAM command: {line}"),
+            };
+
+            socket
+                .write_all(format!("{reply}\n").as_bytes())
+                .await
+                .unwrap();
+        }
+    }
+}
+
+/// Reads a line from `socket`, returning [`None`] if the socket was closed.
+async fn read_line(socket: &mut TcpStream) -> Option<String> {
+    let mut line = Vec::new();
+
+    loop {
+        match socket.read_u8().await.ok()? {
+            b'\n' => return Some(String::from_utf8(line).unwrap()),
+            byte => line.push(byte),
+        }
+    }
+}
+
+/// Returns a new destination with random keys and a null certificate.
+fn random_destination() -> I2pDestination {
+    let mut bytes = vec![0; 387];
+    rand::thread_rng().fill(&mut bytes[..384]);
+
+    I2pDestination::from_bytes(bytes).unwrap()
+}
+
+#[tokio::test]
+async fn connect_and_accept_over_sam() {
+    let sam_address = FakeSamBridge::spawn().await;
+
+    let keys_dir = tempfile::tempdir().unwrap();
+    let keys_file = keys_dir.path().join("i2p.keys");
+
+    let mut listener =
+        <I2pTransport as Transport<I2p>>::incoming_connection_listener(I2pServerConfig {
+            sam_address: sam_address.clone(),
+            keys_file: Some(keys_file.clone()),
+        })
+        .await
+        .unwrap();
+
+    // A new destination must have been generated and saved.
+    let keys = std::fs::read_to_string(&keys_file).unwrap();
+    let server_addr = parse_destination(&keys).unwrap().garlic_addr(0);
+
+    let client_config = I2pClientConfig::new(sam_address);
+
+    // The listener accepts connections on a background task, retry until it is waiting for one.
+    let (mut stream, mut sink) = loop {
+        match <I2pTransport as Transport<I2p>>::connect_to_peer(server_addr, &client_config).await {
+            Ok(connection) => break connection,
+            Err(e) => {
+                assert_eq!(e.kind(), io::ErrorKind::ConnectionRefused);
+                sleep(Duration::from_millis(10)).await;
+            }
+        }
+    };
+
+    let (peer_addr, mut peer_stream, mut peer_sink) = listener.next().await.unwrap().unwrap();
+
+    let client_session = client_config.session().await.unwrap();
+    assert_eq!(
+        peer_addr,
+        Some(
+            parse_destination(&client_session.destination)
+                .unwrap()
+                .garlic_addr(0)
+        )
+    );
+
+    sink.send(Message::Request(AdminRequestMessage::Ping).into())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        peer_stream.next().await,
+        Some(Ok(Message::Request(AdminRequestMessage::Ping)))
+    ));
+
+    peer_sink
+        .send(
+            Message::Response(AdminResponseMessage::Ping(PingResponse {
+                status: PING_OK_RESPONSE_STATUS_TEXT,
+                peer_id: 1,
+            }))
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(Message::Response(AdminResponseMessage::Ping(
+            PingResponse { peer_id: 1, .. }
+        ))))
+    ));
+
+    // Restarting the listener must reuse the saved destination.
+    drop(listener);
+    drop(
+        <I2pTransport as Transport<I2p>>::incoming_connection_listener(I2pServerConfig {
+            sam_address: client_config.sam_address.clone(),
+            keys_file: Some(keys_file.clone()),
+        })
+        .await
+        .unwrap(),
+    );
+    assert_eq!(std::fs::read_to_string(&keys_file).unwrap(), keys);
+}
+
+#[tokio::test]
+async fn connect_to_unknown_destination() {
+    let sam_address = FakeSamBridge::spawn().await;
+    let client_config = I2pClientConfig::new(sam_address);
+
+    let err = <I2pTransport as Transport<I2p>>::connect_to_peer(
+        GarlicAddr::from_hash([1; 32], 0),
+        &client_config,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
@@ -3,5 +3,8 @@
 //! This crate contains additional transport implementations for Cuprate.
 
 pub mod i2p;
+mod socks;
+pub mod tor;
 
-pub use i2p::{I2pTransport, I2pClientConfig, I2pServerConfig};
+pub use i2p::{I2pClientConfig, I2pServerConfig, I2pTransport};
+pub use tor::{TorClientConfig, TorServerConfig, TorTransport};
@@ -0,0 +1,166 @@
+//! SOCKS5 Client
+//!
+//! A minimal [SOCKS5](https://www.rfc-editor.org/rfc/rfc1928) client, only supporting the `CONNECT`
+//! command to a domain name without authentication.
+//!
+//! This is all that is needed to reach `.onion` addresses through a Tor daemon, which resolves them itself.
+//!
+
+use std::{io, net::SocketAddr};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// The SOCKS protocol version.
+const SOCKS_VERSION: u8 = 5;
+
+/// The "no authentication required" method.
+const NO_AUTHENTICATION: u8 = 0;
+
+/// The `CONNECT` command.
+const CMD_CONNECT: u8 = 1;
+
+/// The IPv4 address type.
+const ATYP_IPV4: u8 = 1;
+
+/// The domain name address type.
+const ATYP_DOMAIN_NAME: u8 = 3;
+
+/// The IPv6 address type.
+const ATYP_IPV6: u8 = 4;
+
+/// Opens a connection to `host:port` through the SOCKS5 proxy at `proxy`.
+///
+/// Returns the socket to the proxy, which only carries the connection's data from now on.
+pub(crate) async fn connect(proxy: SocketAddr, host: &str, port: u16) -> io::Result<TcpStream> {
+    let host_len = u8::try_from(host.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "SOCKS5 host is too long"))?;
+
+    let mut stream = TcpStream::connect(proxy).await?;
+    stream.set_nodelay(true)?;
+
+    // Method selection, we only offer no authentication.
+    stream
+        .write_all(&[SOCKS_VERSION, 1, NO_AUTHENTICATION])
+        .await?;
+
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply).await?;
+    check_version(reply[0])?;
+
+    if reply[1] != NO_AUTHENTICATION {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "SOCKS5 proxy requires authentication",
+        ));
+    }
+
+    // The connect request.
+    let mut request = Vec::with_capacity(7 + host.len());
+    request.extend_from_slice(&[SOCKS_VERSION, CMD_CONNECT, 0, ATYP_DOMAIN_NAME, host_len]);
+    request.extend_from_slice(host.as_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply = [0; 4];
+    stream.read_exact(&mut reply).await?;
+    check_version(reply[0])?;
+    check_reply(reply[1])?;
+
+    // Skip the bound address and port, which are meaningless for Tor.
+    let address_len = match reply[3] {
+        ATYP_IPV4 => 4,
+        ATYP_IPV6 => 16,
+        ATYP_DOMAIN_NAME => usize::from(stream.read_u8().await?),
+        atyp => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("SOCKS5 proxy replied with an unknown address type: {atyp}"),
+            ))
+        }
+    };
+
+    let mut bound_address = vec![0; address_len + 2];
+    stream.read_exact(&mut bound_address).await?;
+
+    Ok(stream)
+}
+
+/// Checks the version of a reply is SOCKS5.
+fn check_version(version: u8) -> io::Result<()> {
+    if version == SOCKS_VERSION {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Proxy replied with SOCKS version: {version}"),
+        ))
+    }
+}
+
+/// Maps the reply field of a SOCKS5 reply to an [`io::Error`].
+///
+/// Tor's extended errors for onion services are documented in the `SocksPort` section of the tor manual.
+fn check_reply(reply: u8) -> io::Result<()> {
+    let (kind, message) = match reply {
+        0x00 => return Ok(()),
+        0x02 => (io::ErrorKind::PermissionDenied, "connection not allowed"),
+        0x03 => (io::ErrorKind::NetworkUnreachable, "network unreachable"),
+        0x04 => (io::ErrorKind::HostUnreachable, "host unreachable"),
+        0x05 => (io::ErrorKind::ConnectionRefused, "connection refused"),
+        0x06 => (io::ErrorKind::TimedOut, "TTL expired"),
+        0x07 | 0x08 => (io::ErrorKind::Unsupported, "request not supported"),
+        0xF0 => (
+            io::ErrorKind::HostUnreachable,
+            "onion service descriptor not found",
+        ),
+        0xF1 => (
+            io::ErrorKind::InvalidData,
+            "onion service descriptor is invalid",
+        ),
+        0xF2 => (
+            io::ErrorKind::HostUnreachable,
+            "onion service introduction failed",
+        ),
+        0xF3 => (
+            io::ErrorKind::HostUnreachable,
+            "onion service rendezvous failed",
+        ),
+        0xF4..=0xF5 => (
+            io::ErrorKind::PermissionDenied,
+            "onion service requires client authorization",
+        ),
+        0xF6 => (io::ErrorKind::InvalidInput, "invalid onion service address"),
+        0xF7 => (
+            io::ErrorKind::TimedOut,
+            "onion service introduction timed out",
+        ),
+        _ => (io::ErrorKind::Other, "general failure"),
+    };
+
+    Err(io::Error::new(
+        kind,
+        format!("SOCKS5 connect failed: {message}"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reply_errors() {
+        assert!(check_reply(0).is_ok());
+        assert_eq!(
+            check_reply(5).unwrap_err().kind(),
+            io::ErrorKind::ConnectionRefused
+        );
+        assert_eq!(
+            check_reply(0xF6).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(check_reply(0x42).unwrap_err().kind(), io::ErrorKind::Other);
+    }
+}
@@ -0,0 +1,150 @@
+//! Tor Transport
+//!
+//! This module defines a transport method for the Tor network zone, using a local Tor daemon.
+//!
+//! Outbound connections are opened through the daemon's SOCKS5 proxy, which resolves `.onion`
+//! addresses itself.
+//!
+//! Inbound connections are accepted on a local TCP port that an onion service forwards to, e.g.
+//! with `HiddenServicePort 18083 127.0.0.1:18083` in the `torrc`. All inbound peers appear to come
+//! from the daemon, so their addresses are unknown.
+//!
+
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+use tokio::net::{
+    tcp::{OwnedReadHalf, OwnedWriteHalf},
+    TcpListener,
+};
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+use cuprate_p2p_core::{NetworkZone, Transport};
+use cuprate_wire::{MoneroWireCodec, OnionAddr};
+
+use crate::socks;
+
+#[cfg(test)]
+mod tests;
+
+/// The default address of Tor's SOCKS5 proxy.
+pub const DEFAULT_SOCKS_PROXY: SocketAddr =
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 9050));
+
+/// The default address to accept connections forwarded by our onion service on.
+pub const DEFAULT_INBOUND_ADDRESS: SocketAddr =
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 18083));
+
+/// The stream (incoming data) of a Tor connection.
+pub type TorStream = FramedRead<OwnedReadHalf, MoneroWireCodec>;
+
+/// The sink (outgoing data) of a Tor connection.
+pub type TorSink = FramedWrite<OwnedWriteHalf, MoneroWireCodec>;
+
+/// Tor transport using a Tor daemon's SOCKS5 proxy and onion service.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TorTransport;
+
+/// Parameters for making outbound Tor connections.
+#[derive(Debug, Clone)]
+pub struct TorClientConfig {
+    /// The address of Tor's SOCKS5 proxy.
+    pub socks_proxy: SocketAddr,
+}
+
+impl Default for TorClientConfig {
+    fn default() -> Self {
+        Self {
+            socks_proxy: DEFAULT_SOCKS_PROXY,
+        }
+    }
+}
+
+/// Parameters for accepting inbound Tor connections.
+#[derive(Debug, Clone)]
+pub struct TorServerConfig {
+    /// The local address our onion service forwards connections to.
+    pub listen_on: SocketAddr,
+}
+
+impl Default for TorServerConfig {
+    fn default() -> Self {
+        Self {
+            listen_on: DEFAULT_INBOUND_ADDRESS,
+        }
+    }
+}
+
+/// A listener for connections forwarded by our onion service.
+pub struct TorListener {
+    listener: TcpListener,
+}
+
+impl TorListener {
+    /// Returns the local address this listener is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+}
+
+impl Stream for TorListener {
+    type Item = io::Result<(Option<OnionAddr>, TorStream, TorSink)>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.listener.poll_accept(cx).map(|res| {
+            Some(res.map(|(stream, _)| {
+                let (read, write) = stream.into_split();
+
+                // The connection comes from the Tor daemon, we don't know the peer's address.
+                (
+                    None,
+                    FramedRead::new(read, MoneroWireCodec::default()),
+                    FramedWrite::new(write, MoneroWireCodec::default()),
+                )
+            }))
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl<Z: NetworkZone<Addr = OnionAddr>> Transport<Z> for TorTransport {
+    type ClientConfig = TorClientConfig;
+    type ServerConfig = TorServerConfig;
+
+    type Stream = TorStream;
+    type Sink = TorSink;
+    type Listener = TorListener;
+
+    async fn connect_to_peer(
+        addr: Z::Addr,
+        config: &Self::ClientConfig,
+    ) -> Result<(Self::Stream, Self::Sink), io::Error> {
+        let stream = socks::connect(config.socks_proxy, &addr.addr_string(), addr.port()).await?;
+
+        let (read, write) = stream.into_split();
+        Ok((
+            FramedRead::new(read, MoneroWireCodec::default()),
+            FramedWrite::new(write, MoneroWireCodec::default()),
+        ))
+    }
+
+    async fn incoming_connection_listener(
+        config: Self::ServerConfig,
+    ) -> Result<Self::Listener, io::Error> {
+        if !config.listen_on.ip().is_loopback() {
+            tracing::warn!(
+                "Accepting Tor connections on a non-loopback address: {}, anyone reaching it will appear to be a Tor peer.",
+                config.listen_on
+            );
+        }
+
+        let listener = TcpListener::bind(config.listen_on).await?;
+
+        Ok(TorListener { listener })
+    }
+}
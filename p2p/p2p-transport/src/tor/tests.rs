@@ -0,0 +1,153 @@
+use std::{collections::HashMap, sync::Arc};
+
+use futures::{SinkExt, StreamExt};
+use tokio::{
+    io::{copy_bidirectional, AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use cuprate_p2p_core::Tor;
+use cuprate_wire::{
+    admin::{PingResponse, PING_OK_RESPONSE_STATUS_TEXT},
+    AdminRequestMessage, AdminResponseMessage, Message,
+};
+
+use super::*;
+
+const ONION_ADDR: &str = "monerotoruzizulg5ttgat2emf4d6fbmiea25detrmmy7erypseyteyd.onion:18083";
+
+/// A fake SOCKS5 proxy, which forwards connections to `host:port` to a local address.
+struct FakeSocksProxy {
+    routes: Arc<HashMap<String, SocketAddr>>,
+}
+
+impl FakeSocksProxy {
+    /// Starts the proxy, returning the address it listens on.
+    async fn spawn(routes: HashMap<String, SocketAddr>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let proxy = Self {
+            routes: Arc::new(routes),
+        };
+
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(Self::handle_socket(Arc::clone(&proxy.routes), socket));
+            }
+        });
+
+        address
+    }
+
+    /// Handles a single SOCKS5 connection.
+    async fn handle_socket(routes: Arc<HashMap<String, SocketAddr>>, mut socket: TcpStream) {
+        // Method selection.
+        let mut greeting = [0; 3];
+        socket.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(greeting, [5, 1, 0]);
+        socket.write_all(&[5, 0]).await.unwrap();
+
+        // Connect request, always to a domain name.
+        let mut request = [0; 5];
+        socket.read_exact(&mut request).await.unwrap();
+        assert_eq!(request[..4], [5, 1, 0, 3]);
+
+        let mut host = vec![0; usize::from(request[4])];
+        socket.read_exact(&mut host).await.unwrap();
+        let port = socket.read_u16().await.unwrap();
+
+        let target = format!("{}:{port}", String::from_utf8(host).unwrap());
+
+        let peer = match routes.get(&target) {
+            Some(addr) => TcpStream::connect(addr).await.ok(),
+            None => None,
+        };
+
+        let Some(mut peer) = peer else {
+            // Tor's "onion service descriptor can not be found" error.
+            socket
+                .write_all(&[5, 0xF0, 0, 1, 0, 0, 0, 0, 0, 0])
+                .await
+                .unwrap();
+            return;
+        };
+
+        socket
+            .write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0])
+            .await
+            .unwrap();
+
+        drop(copy_bidirectional(&mut socket, &mut peer).await);
+    }
+}
+
+#[tokio::test]
+async fn connect_and_accept_over_socks() {
+    let mut listener =
+        <TorTransport as Transport<Tor>>::incoming_connection_listener(TorServerConfig {
+            listen_on: "127.0.0.1:0".parse().unwrap(),
+        })
+        .await
+        .unwrap();
+
+    let socks_proxy = FakeSocksProxy::spawn(HashMap::from([(
+        ONION_ADDR.to_string(),
+        listener.local_addr().unwrap(),
+    )]))
+    .await;
+
+    let (mut stream, mut sink) = <TorTransport as Transport<Tor>>::connect_to_peer(
+        ONION_ADDR.parse().unwrap(),
+        &TorClientConfig { socks_proxy },
+    )
+    .await
+    .unwrap();
+
+    let (peer_addr, mut peer_stream, mut peer_sink) = listener.next().await.unwrap().unwrap();
+
+    // Inbound Tor peers are anonymous.
+    assert_eq!(peer_addr, None);
+
+    sink.send(Message::Request(AdminRequestMessage::Ping).into())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        peer_stream.next().await,
+        Some(Ok(Message::Request(AdminRequestMessage::Ping)))
+    ));
+
+    peer_sink
+        .send(
+            Message::Response(AdminResponseMessage::Ping(PingResponse {
+                status: PING_OK_RESPONSE_STATUS_TEXT,
+                peer_id: 1,
+            }))
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(Message::Response(AdminResponseMessage::Ping(
+            PingResponse { peer_id: 1, .. }
+        ))))
+    ));
+}
+
+#[tokio::test]
+async fn connect_to_unknown_onion() {
+    let socks_proxy = FakeSocksProxy::spawn(HashMap::new()).await;
+
+    let err = <TorTransport as Transport<Tor>>::connect_to_peer(
+        ONION_ADDR.parse().unwrap(),
+        &TorClientConfig { socks_proxy },
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::HostUnreachable);
+}
@@ -6,14 +6,14 @@ use std::sync::Arc;
 
 use futures::FutureExt;
 use tokio::{sync::mpsc, task::JoinSet};
-use tower::{buffer::Buffer, util::BoxCloneService, Service, ServiceExt};
+use tower::{buffer::Buffer, service_fn, util::BoxCloneService, Service, ServiceExt};
 use tracing::{instrument, Instrument, Span};
 
 use cuprate_async_buffer::BufferStream;
 use cuprate_p2p_core::{
     client::Connector,
     services::{AddressBookRequest, AddressBookResponse},
-    CoreSyncSvc, NetworkZone, ProtocolRequestHandlerMaker, Transport,
+    CoreSyncSvc, NetZoneAddress, NetworkZone, ProtocolRequestHandlerMaker, Transport,
 };
 
 pub mod block_downloader;
@@ -50,6 +50,8 @@ where
     Z: NetworkZone,
     T: Transport<Z>,
     Z::Addr: borsh::BorshDeserialize + borsh::BorshSerialize,
+    <Z::Addr as NetZoneAddress>::BanID: borsh::BorshDeserialize + borsh::BorshSerialize,
+    <Z::Addr as NetZoneAddress>::BanSubnet: borsh::BorshDeserialize + borsh::BorshSerialize,
     PR: ProtocolRequestHandlerMaker<Z> + Clone,
     CS: CoreSyncSvc + Clone,
 {
@@ -162,6 +164,35 @@ pub struct NetworkInterface<N: NetworkZone> {
 }
 
 impl<N: NetworkZone> NetworkInterface<N> {
+    /// Create a mock [`NetworkInterface`] with no connected peers, useful for testing.
+    ///
+    /// Requests to the address book return an error.
+    pub fn mock() -> Self {
+        let peer_set = service_fn(|req: PeerSetRequest| {
+            futures::future::ready(Ok(match req {
+                PeerSetRequest::MostPoWSeen => PeerSetResponse::MostPoWSeen {
+                    cumulative_difficulty: 0,
+                    height: 0,
+                    top_hash: [0; 32],
+                },
+                PeerSetRequest::PeersWithMorePoW(_) => PeerSetResponse::PeersWithMorePoW(vec![]),
+                PeerSetRequest::StemPeer => PeerSetResponse::StemPeer(None),
+            }))
+        });
+
+        let address_book = service_fn(|_: AddressBookRequest<N>| {
+            futures::future::ready(Err("The mock network interface has no address book".into()))
+        });
+
+        Self {
+            peer_set: peer_set.boxed_clone(),
+            broadcast_svc: BroadcastSvc::mock(),
+            make_connection_tx: mpsc::channel(1).0,
+            address_book: address_book.boxed_clone(),
+            _background_tasks: Arc::new(JoinSet::new()),
+        }
+    }
+
     /// Returns a service which allows broadcasting messages to all the connected peers in a specific [`NetworkZone`].
     pub fn broadcast_svc(&self) -> BroadcastSvc<N> {
         self.broadcast_svc.clone()
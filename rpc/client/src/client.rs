@@ -0,0 +1,366 @@
+//! RPC client.
+
+//---------------------------------------------------------------------------------------------------- Import
+use std::sync::Arc;
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::Incoming,
+    header::{self, HeaderValue},
+    Method, Request, Response, StatusCode, Uri,
+};
+use hyper_util::{
+    client::legacy::{connect::HttpConnector, Client},
+    rt::TokioExecutor,
+};
+use serde::de::DeserializeOwned;
+
+use cuprate_epee_encoding::{from_bytes, to_bytes, EpeeObject};
+use cuprate_json_rpc::Id;
+use cuprate_rpc_types::{bin, json, json::JsonRpcRequest, other, other::OtherRequest};
+
+use crate::{digest_auth::DigestAuth, RpcClientError};
+
+//---------------------------------------------------------------------------------------------------- Constants
+/// The `Content-Type` of JSON requests.
+const CONTENT_TYPE_JSON: &str = "application/json";
+
+/// The `Content-Type` of binary (epee) requests.
+const CONTENT_TYPE_BINARY: &str = "application/octet-stream";
+
+//---------------------------------------------------------------------------------------------------- RpcClient
+/// An async client for `monerod` and `cuprated`'s RPC server.
+///
+/// There is one method per endpoint, prefixed by the module of its types in [`cuprate_rpc_types`]:
+/// - `json_` methods are JSON-RPC methods sent to `/json_rpc`, e.g. [`RpcClient::json_get_block_count`]
+/// - `other_` methods are sent to other JSON endpoints, e.g. [`RpcClient::other_get_height`]
+/// - `bin_` methods are sent to `.bin` endpoints, e.g. [`RpcClient::bin_get_blocks`]
+///
+/// Methods without inputs take no request.
+///
+/// Connections are kept alive and reused between requests,
+/// cloning an [`RpcClient`] is cheap and shares the connections.
+///
+/// Only `http` URLs are supported.
+#[derive(Clone, Debug)]
+pub struct RpcClient {
+    /// The HTTP client, this holds the connection pool.
+    client: Client<HttpConnector, Full<Bytes>>,
+    /// The URL of the RPC server, without a trailing `/`.
+    url: Arc<str>,
+    /// Digest authentication, if credentials were set.
+    auth: Option<Arc<DigestAuth>>,
+}
+
+impl RpcClient {
+    /// Create a new [`RpcClient`] connecting to the RPC server at `url`, e.g. `http://127.0.0.1:18081`.
+    ///
+    /// No connection is made until the first request.
+    ///
+    /// # Errors
+    /// This returns an error if `url` is invalid.
+    pub fn new(url: &str) -> Result<Self, RpcClientError> {
+        let url = url.trim_end_matches('/');
+        url.parse::<Uri>()?;
+
+        Ok(Self {
+            client: Client::builder(TokioExecutor::new()).build_http(),
+            url: url.into(),
+            auth: None,
+        })
+    }
+
+    /// Use HTTP digest authentication with these credentials, like `monerod`'s `--rpc-login`.
+    #[must_use]
+    pub fn with_digest_auth(mut self, username: String, password: String) -> Self {
+        self.auth = Some(Arc::new(DigestAuth::new(username, password)));
+        self
+    }
+
+    /// The URL of the RPC server.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Send a JSON-RPC request to `/json_rpc` and return the result.
+    async fn json_rpc<T: DeserializeOwned>(
+        &self,
+        request: JsonRpcRequest,
+    ) -> Result<T, RpcClientError> {
+        let request = cuprate_json_rpc::Request::new_with_id(Id::Num(0), request);
+        let body = serde_json::to_vec(&request)?;
+
+        let response = self
+            .send("/json_rpc", CONTENT_TYPE_JSON, body.into())
+            .await?;
+        let response: cuprate_json_rpc::Response<T> = serde_json::from_slice(&response)?;
+
+        Ok(response.payload?)
+    }
+
+    /// Send a request to a JSON `endpoint` that isn't `/json_rpc`.
+    async fn other<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        request: OtherRequest,
+    ) -> Result<T, RpcClientError> {
+        let body = serde_json::to_vec(&request)?;
+        let response = self.send(endpoint, CONTENT_TYPE_JSON, body.into()).await?;
+
+        Ok(serde_json::from_slice(&response)?)
+    }
+
+    /// Send a request to a binary `endpoint`.
+    async fn bin<Req: EpeeObject, T: EpeeObject>(
+        &self,
+        endpoint: &str,
+        request: Req,
+    ) -> Result<T, RpcClientError> {
+        let body = to_bytes(request)?.freeze();
+        let mut response = self.send(endpoint, CONTENT_TYPE_BINARY, body).await?;
+
+        Ok(from_bytes(&mut response)?)
+    }
+
+    /// Send a `POST` request to `endpoint` and return the response body.
+    ///
+    /// If the server requires authentication, the request is retried once with the new challenge.
+    async fn send(
+        &self,
+        endpoint: &str,
+        content_type: &'static str,
+        body: Bytes,
+    ) -> Result<Bytes, RpcClientError> {
+        let uri: Uri = format!("{}{endpoint}", self.url).parse()?;
+
+        let mut response = self
+            .request(uri.clone(), content_type, body.clone())
+            .await?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            if let Some(auth) = &self.auth {
+                if auth.update_challenge(response.headers()) {
+                    response = self.request(uri, content_type, body).await?;
+                }
+            }
+        }
+
+        match response.status() {
+            StatusCode::OK => Ok(response.into_body().collect().await?.to_bytes()),
+            status => Err(RpcClientError::Status(status)),
+        }
+    }
+
+    /// Send a single `POST` request, authenticated if there is a challenge.
+    async fn request(
+        &self,
+        uri: Uri,
+        content_type: &'static str,
+        body: Bytes,
+    ) -> Result<Response<Incoming>, RpcClientError> {
+        let mut request = Request::new(Full::new(body));
+        *request.method_mut() = Method::POST;
+
+        let headers = request.headers_mut();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+        if let Some(authorization) = self
+            .auth
+            .as_ref()
+            .and_then(|auth| auth.authorization(&Method::POST, &uri))
+        {
+            headers.insert(header::AUTHORIZATION, authorization);
+        }
+
+        *request.uri_mut() = uri;
+
+        Ok(self.client.request(request).await?)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Methods
+/// Returns the request if there is one, else the (empty) request type's default.
+macro_rules! request_or_default {
+    ($request_type:ty, $request:ident) => {
+        $request
+    };
+    ($request_type:ty) => {
+        <$request_type>::default()
+    };
+}
+
+/// This macro generates the JSON-RPC methods.
+///
+/// See below for usage.
+macro_rules! generate_json_rpc_methods {
+    ($(
+        // Syntax:
+        // Function name => Request variant (input)
+        //
+        // Methods with no input have no request argument, e.g. `GetBlockCount()`.
+        $fn:ident => $variant:ident($($request:ident)?)
+    ),* $(,)?) => { paste::paste! {
+        impl RpcClient {
+            $(
+                #[doc = concat!(
+                    "Send a [`JsonRpcRequest::",
+                    stringify!($variant),
+                    "`] to `/json_rpc`.",
+                )]
+                ///
+                /// # Errors
+                /// This returns an error if the request failed or the server responded with a JSON-RPC error.
+                pub async fn $fn(
+                    &self,
+                    $($request: json::[<$variant Request>],)?
+                ) -> Result<json::[<$variant Response>], RpcClientError> {
+                    let request = request_or_default!(json::[<$variant Request>] $(, $request)?);
+                    self.json_rpc(JsonRpcRequest::$variant(request)).await
+                }
+            )*
+        }
+    }};
+}
+
+/// This macro generates the methods for other JSON endpoints.
+///
+/// See below for usage.
+macro_rules! generate_other_methods {
+    ($(
+        // Syntax:
+        // Function name => Endpoint => Request variant (input)
+        $fn:ident => $endpoint:literal => $variant:ident($($request:ident)?)
+    ),* $(,)?) => { paste::paste! {
+        impl RpcClient {
+            $(
+                #[doc = concat!(
+                    "Send a [`OtherRequest::",
+                    stringify!($variant),
+                    "`] to `",
+                    $endpoint,
+                    "`.",
+                )]
+                ///
+                /// # Errors
+                /// This returns an error if the request failed.
+                pub async fn $fn(
+                    &self,
+                    $($request: other::[<$variant Request>],)?
+                ) -> Result<other::[<$variant Response>], RpcClientError> {
+                    let request = request_or_default!(other::[<$variant Request>] $(, $request)?);
+                    self.other($endpoint, OtherRequest::$variant(request)).await
+                }
+            )*
+        }
+    }};
+}
+
+/// This macro generates the methods for binary endpoints.
+///
+/// See below for usage.
+macro_rules! generate_bin_methods {
+    ($(
+        // Syntax:
+        // Function name => Endpoint => Type module::Request variant (input)
+        $fn:ident => $endpoint:literal => $module:ident::$variant:ident($($request:ident)?)
+    ),* $(,)?) => { paste::paste! {
+        impl RpcClient {
+            $(
+                #[doc = concat!(
+                    "Send a [`BinRequest::",
+                    stringify!($variant),
+                    "`](cuprate_rpc_types::bin::BinRequest::",
+                    stringify!($variant),
+                    ") to `",
+                    $endpoint,
+                    "`.",
+                )]
+                ///
+                /// # Errors
+                /// This returns an error if the request failed.
+                pub async fn $fn(
+                    &self,
+                    $($request: $module::[<$variant Request>],)?
+                ) -> Result<$module::[<$variant Response>], RpcClientError> {
+                    let request = request_or_default!($module::[<$variant Request>] $(, $request)?);
+                    self.bin($endpoint, request).await
+                }
+            )*
+        }
+    }};
+}
+
+generate_json_rpc_methods! {
+    json_get_block_template           => GetBlockTemplate(request),
+    json_get_block_count              => GetBlockCount(),
+    json_on_get_block_hash            => OnGetBlockHash(request),
+    json_submit_block                 => SubmitBlock(request),
+    json_generate_blocks              => GenerateBlocks(request),
+    json_get_last_block_header        => GetLastBlockHeader(request),
+    json_get_block_header_by_hash     => GetBlockHeaderByHash(request),
+    json_get_block_header_by_height   => GetBlockHeaderByHeight(request),
+    json_get_block_headers_range      => GetBlockHeadersRange(request),
+    json_get_block                    => GetBlock(request),
+    json_get_connections              => GetConnections(),
+    json_get_info                     => GetInfo(),
+    json_hard_fork_info               => HardForkInfo(request),
+    json_set_bans                     => SetBans(request),
+    json_get_bans                     => GetBans(),
+    json_banned                       => Banned(request),
+    json_flush_transaction_pool       => FlushTransactionPool(request),
+    json_get_output_histogram         => GetOutputHistogram(request),
+    json_get_coinbase_tx_sum          => GetCoinbaseTxSum(request),
+    json_get_version                  => GetVersion(),
+    json_get_fee_estimate             => GetFeeEstimate(request),
+    json_get_alternate_chains         => GetAlternateChains(),
+    json_relay_tx                     => RelayTx(request),
+    json_sync_info                    => SyncInfo(),
+    json_get_transaction_pool_backlog => GetTransactionPoolBacklog(),
+    json_get_miner_data               => GetMinerData(),
+    json_prune_blockchain             => PruneBlockchain(request),
+    json_calc_pow                     => CalcPow(request),
+    json_flush_cache                  => FlushCache(request),
+    json_add_aux_pow                  => AddAuxPow(request),
+    json_get_tx_ids_loose             => GetTxIdsLoose(request),
+}
+
+generate_other_methods! {
+    other_get_height                  => "/get_height"                  => GetHeight(),
+    other_get_transactions            => "/get_transactions"            => GetTransactions(request),
+    other_get_alt_blocks_hashes       => "/get_alt_blocks_hashes"       => GetAltBlocksHashes(),
+    other_is_key_image_spent          => "/is_key_image_spent"          => IsKeyImageSpent(request),
+    other_send_raw_transaction        => "/send_raw_transaction"        => SendRawTransaction(request),
+    other_start_mining                => "/start_mining"                => StartMining(request),
+    other_stop_mining                 => "/stop_mining"                 => StopMining(),
+    other_mining_status               => "/mining_status"               => MiningStatus(),
+    other_save_bc                     => "/save_bc"                     => SaveBc(),
+    other_get_peer_list               => "/get_peer_list"               => GetPeerList(request),
+    other_get_public_nodes            => "/get_public_nodes"            => GetPublicNodes(request),
+    other_set_log_hash_rate           => "/set_log_hash_rate"           => SetLogHashRate(request),
+    other_set_log_level               => "/set_log_level"               => SetLogLevel(request),
+    other_set_log_categories          => "/set_log_categories"          => SetLogCategories(request),
+    other_get_transaction_pool        => "/get_transaction_pool"        => GetTransactionPool(),
+    other_get_transaction_pool_hashes => "/get_transaction_pool_hashes" => GetTransactionPoolHashes(),
+    other_get_transaction_pool_stats  => "/get_transaction_pool_stats"  => GetTransactionPoolStats(),
+    other_set_bootstrap_daemon        => "/set_bootstrap_daemon"        => SetBootstrapDaemon(request),
+    other_stop_daemon                 => "/stop_daemon"                 => StopDaemon(),
+    other_get_net_stats               => "/get_net_stats"               => GetNetStats(),
+    other_get_limit                   => "/get_limit"                   => GetLimit(),
+    other_set_limit                   => "/set_limit"                   => SetLimit(request),
+    other_out_peers                   => "/out_peers"                   => OutPeers(request),
+    other_in_peers                    => "/in_peers"                    => InPeers(request),
+    other_get_outs                    => "/get_outs"                    => GetOuts(request),
+    other_update                      => "/update"                      => Update(request),
+    other_pop_blocks                  => "/pop_blocks"                  => PopBlocks(request),
+}
+
+generate_bin_methods! {
+    bin_get_blocks                  => "/get_blocks.bin"                  => bin::GetBlocks(request),
+    bin_get_blocks_by_height        => "/get_blocks_by_height.bin"        => bin::GetBlocksByHeight(request),
+    bin_get_hashes                  => "/get_hashes.bin"                  => bin::GetHashes(request),
+    bin_get_o_indexes               => "/get_o_indexes.bin"               => bin::GetOutputIndexes(request),
+    bin_get_outs                    => "/get_outs.bin"                    => bin::GetOuts(request),
+    bin_get_transaction_pool_hashes => "/get_transaction_pool_hashes.bin" => bin::GetTransactionPoolHashes(),
+    bin_get_output_distribution     => "/get_output_distribution.bin"     => json::GetOutputDistribution(request),
+}
@@ -0,0 +1,275 @@
+//! HTTP digest authentication.
+//!
+//! This is the client side of the HTTP digest authentication (RFC 2617) `monerod` uses with `--rpc-login`,
+//! see `cuprate_rpc_interface::DigestAuthLayer` for the server side.
+
+//---------------------------------------------------------------------------------------------------- Import
+use std::{collections::HashMap, fmt, sync::Mutex};
+
+use hyper::{
+    header::{self, HeaderMap, HeaderValue},
+    Method, Uri,
+};
+use md5::{Digest, Md5};
+
+//---------------------------------------------------------------------------------------------------- Challenge
+/// The algorithm used to hash the credentials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Algorithm {
+    Md5,
+    Md5Sess,
+}
+
+/// A challenge from a `WWW-Authenticate` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Challenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: Algorithm,
+    /// Is `true` if the `auth` `qop` was offered, otherwise no `qop` is used.
+    qop_auth: bool,
+    /// The nonce count, the amount of requests sent with this nonce.
+    nc: u32,
+}
+
+//---------------------------------------------------------------------------------------------------- DigestAuth
+/// Digest authentication credentials and the latest challenge from the server.
+///
+/// Requests are authenticated up-front with the latest challenge,
+/// so only the first request (or one with a stale nonce) needs to be retried.
+pub(crate) struct DigestAuth {
+    username: String,
+    password: String,
+    challenge: Mutex<Option<Challenge>>,
+}
+
+impl DigestAuth {
+    /// Create a new [`DigestAuth`], no challenge has been received yet.
+    pub(crate) const fn new(username: String, password: String) -> Self {
+        Self {
+            username,
+            password,
+            challenge: Mutex::new(None),
+        }
+    }
+
+    /// Replace the challenge with one from the `WWW-Authenticate` headers of a `401 Unauthorized` response.
+    ///
+    /// The first supported challenge is used, returns `false` if there were none.
+    pub(crate) fn update_challenge(&self, headers: &HeaderMap) -> bool {
+        let challenge = headers
+            .get_all(header::WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|challenge| challenge.to_str().ok())
+            .find_map(parse_challenge);
+
+        let Some(challenge) = challenge else {
+            return false;
+        };
+
+        *self.challenge.lock().unwrap() = Some(challenge);
+        true
+    }
+
+    /// Returns the `Authorization` header for a request to `uri`.
+    ///
+    /// Returns [`None`] if no challenge has been received yet.
+    pub(crate) fn authorization(&self, method: &Method, uri: &Uri) -> Option<HeaderValue> {
+        let challenge = {
+            let mut challenge = self.challenge.lock().unwrap();
+            let challenge = challenge.as_mut()?;
+            challenge.nc += 1;
+            challenge.clone()
+        };
+
+        let Challenge {
+            realm,
+            nonce,
+            opaque,
+            algorithm,
+            qop_auth,
+            nc,
+        } = challenge;
+
+        let username = &self.username;
+        let uri = uri.path_and_query().map_or(uri.path(), |p| p.as_str());
+        let cnonce = format!("{:016x}", rand::random::<u64>());
+        let nc = format!("{nc:08x}");
+
+        let mut ha1 = md5_hex(&format!("{username}:{realm}:{}", self.password));
+
+        let algorithm = match algorithm {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Md5Sess => {
+                ha1 = md5_hex(&format!("{ha1}:{nonce}:{cnonce}"));
+                "MD5-sess"
+            }
+        };
+
+        let ha2 = md5_hex(&format!("{method}:{uri}"));
+
+        let mut authorization = format!(
+            "Digest username=\"{username}\",realm=\"{realm}\",nonce=\"{nonce}\",uri=\"{uri}\",algorithm={algorithm},cnonce=\"{cnonce}\""
+        );
+
+        let response = if qop_auth {
+            authorization.push_str(&format!(",qop=auth,nc={nc}"));
+            md5_hex(&format!("{ha1}:{nonce}:{nc}:{cnonce}:auth:{ha2}"))
+        } else {
+            md5_hex(&format!("{ha1}:{nonce}:{ha2}"))
+        };
+
+        authorization.push_str(&format!(",response=\"{response}\""));
+
+        if let Some(opaque) = opaque {
+            authorization.push_str(&format!(",opaque=\"{opaque}\""));
+        }
+
+        HeaderValue::from_str(&authorization).ok()
+    }
+}
+
+impl fmt::Debug for DigestAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestAuth")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Free functions
+/// Returns the lowercase hex MD5 hash of `data`.
+fn md5_hex(data: &str) -> String {
+    format!("{:x}", Md5::digest(data))
+}
+
+/// Parse a digest `WWW-Authenticate` challenge.
+///
+/// Returns [`None`] if the challenge is invalid or uses an unsupported algorithm or `qop`.
+fn parse_challenge(challenge: &str) -> Option<Challenge> {
+    let params = challenge
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("digest "))
+        .and_then(|_| parse_params(&challenge[7..]))?;
+
+    let algorithm = match params.get("algorithm").copied() {
+        None | Some("MD5") => Algorithm::Md5,
+        Some("MD5-sess") => Algorithm::Md5Sess,
+        Some(_) => return None,
+    };
+
+    let qop_auth = match params.get("qop") {
+        None => false,
+        Some(qop) if qop.split(',').any(|qop| qop.trim() == "auth") => true,
+        Some(_) => return None,
+    };
+
+    Some(Challenge {
+        realm: (*params.get("realm")?).to_string(),
+        nonce: (*params.get("nonce")?).to_string(),
+        opaque: params.get("opaque").map(|opaque| (*opaque).to_string()),
+        algorithm,
+        qop_auth,
+        nc: 0,
+    })
+}
+
+/// Parse the comma separated `key=value` parameters of a digest challenge.
+///
+/// Values may be quoted, the quotes are not included in the returned value.
+fn parse_params(mut params: &str) -> Option<HashMap<&str, &str>> {
+    let mut map = HashMap::new();
+
+    loop {
+        params = params.trim_start();
+
+        if params.is_empty() {
+            return Some(map);
+        }
+
+        let (key, rest) = params.split_once('=')?;
+        let rest = rest.trim_start();
+
+        let (value, rest) = if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"')?;
+            (&quoted[..end], &quoted[end + 1..])
+        } else {
+            let end = rest.find(',').unwrap_or(rest.len());
+            (rest[..end].trim_end(), &rest[end..])
+        };
+
+        map.insert(key.trim(), value);
+
+        let rest = rest.trim_start();
+        params = match rest.strip_prefix(',') {
+            Some(rest) => rest,
+            None if rest.is_empty() => rest,
+            None => return None,
+        };
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Tests
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// `monerod`'s challenges are parsed, unsupported ones are skipped.
+    #[test]
+    fn challenge() {
+        let mut headers = HeaderMap::new();
+        for challenge in [
+            r#"Basic realm="monero-rpc""#,
+            r#"Digest qop="auth-int",algorithm=MD5,realm="monero-rpc",nonce="a""#,
+            r#"Digest qop="auth",algorithm=MD5-sess,realm="monero-rpc",nonce="b",stale=false"#,
+        ] {
+            headers.append(
+                header::WWW_AUTHENTICATE,
+                HeaderValue::from_static(challenge),
+            );
+        }
+
+        let auth = DigestAuth::new("user".into(), "pass".into());
+        assert!(auth
+            .authorization(&Method::POST, &Uri::from_static("/"))
+            .is_none());
+        assert!(auth.update_challenge(&headers));
+
+        assert_eq!(
+            *auth.challenge.lock().unwrap(),
+            Some(Challenge {
+                realm: "monero-rpc".into(),
+                nonce: "b".into(),
+                opaque: None,
+                algorithm: Algorithm::Md5Sess,
+                qop_auth: true,
+                nc: 0,
+            })
+        );
+    }
+
+    /// The nonce count increases with each request.
+    #[test]
+    fn nonce_count() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::WWW_AUTHENTICATE,
+            HeaderValue::from_static(r#"Digest qop="auth",realm="monero-rpc",nonce="a""#),
+        );
+
+        let auth = DigestAuth::new("user".into(), "pass".into());
+        assert!(auth.update_challenge(&headers));
+
+        let uri = Uri::from_static("/json_rpc");
+        for nc in ["00000001", "00000002"] {
+            let authorization = auth.authorization(&Method::POST, &uri).unwrap();
+            let authorization = authorization.to_str().unwrap();
+
+            let params = parse_params(&authorization[7..]).unwrap();
+            assert_eq!(params["nc"], nc);
+            assert_eq!(params["uri"], "/json_rpc");
+            assert_eq!(params["algorithm"], "MD5");
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Error type.
+
+//---------------------------------------------------------------------------------------------------- Use
+use hyper::{http::uri::InvalidUri, StatusCode};
+
+use cuprate_json_rpc::error::ErrorObject;
+
+//---------------------------------------------------------------------------------------------------- RpcClientError
+/// An error returned by [`RpcClient`](crate::RpcClient).
+#[derive(Debug, thiserror::Error)]
+pub enum RpcClientError {
+    /// The URL of the RPC server is invalid.
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(#[from] InvalidUri),
+
+    /// The request could not be sent, e.g. the server could not be connected to.
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] hyper_util::client::legacy::Error),
+
+    /// The response body could not be read.
+    #[error("Failed to read the response body: {0}")]
+    Body(#[from] hyper::Error),
+
+    /// The server responded with a status code other than `200 OK`.
+    ///
+    /// Servers respond with `403 Forbidden` to restricted endpoints
+    /// if they are restricted, and `401 Unauthorized` if authentication failed.
+    #[error("Unexpected HTTP status: {0}")]
+    Status(StatusCode),
+
+    /// JSON (de)serialization failed.
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Epee (de)serialization failed.
+    #[error("Epee error: {0}")]
+    Epee(#[from] cuprate_epee_encoding::Error),
+
+    /// The server responded with a JSON-RPC error.
+    #[error("JSON-RPC error: {0}")]
+    JsonRpc(#[from] ErrorObject),
+}
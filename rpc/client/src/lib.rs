@@ -0,0 +1,17 @@
+#![doc = include_str!("../README.md")]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+mod client;
+mod digest_auth;
+mod error;
+
+pub use client::RpcClient;
+pub use error::RpcClientError;
+
+// false-positive: used in `tests/`.
+#[cfg(test)]
+mod test {
+    extern crate axum;
+    extern crate cuprate_rpc_interface;
+    extern crate tokio;
+}
@@ -0,0 +1,206 @@
+//! Tests for [`RpcClient`] against an in-process RPC server using [`RpcHandlerDummy`].
+
+#![expect(unused_crate_dependencies, reason = "external test module")]
+
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request},
+    middleware::{self, Next},
+    Router,
+};
+use hyper::StatusCode;
+use tokio::net::TcpListener;
+
+use cuprate_json_rpc::error::ErrorCode;
+use cuprate_rpc_interface::{DigestAuthLayer, DigestCredentials, RouterBuilder, RpcHandlerDummy};
+use cuprate_rpc_types::{bin, json, other};
+
+use cuprate_rpc_client::{RpcClient, RpcClientError};
+
+/// Start an RPC server with all endpoints, the `router` function can add layers.
+///
+/// Returns the server's URL.
+async fn start_server(restricted: bool, router: impl FnOnce(Router) -> Router) -> String {
+    let state = RpcHandlerDummy {
+        restricted,
+        max_batch_size: 0,
+    };
+    let router = router(RouterBuilder::new().all().build().with_state(state));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn json_rpc() {
+    let client = RpcClient::new(&start_server(false, |r| r).await).unwrap();
+
+    assert_eq!(
+        client.json_get_block_count().await.unwrap(),
+        json::GetBlockCountResponse::default()
+    );
+
+    assert_eq!(
+        client
+            .json_get_block_header_by_height(json::GetBlockHeaderByHeightRequest {
+                height: 1,
+                fill_pow_hash: false,
+            })
+            .await
+            .unwrap(),
+        json::GetBlockHeaderByHeightResponse::default()
+    );
+
+    assert_eq!(
+        client
+            .json_generate_blocks(json::GenerateBlocksRequest::default())
+            .await
+            .unwrap(),
+        json::GenerateBlocksResponse::default()
+    );
+}
+
+#[tokio::test]
+async fn other() {
+    let client = RpcClient::new(&start_server(false, |r| r).await).unwrap();
+
+    assert_eq!(
+        client.other_get_height().await.unwrap(),
+        other::GetHeightResponse::default()
+    );
+
+    assert_eq!(
+        client
+            .other_get_transactions(other::GetTransactionsRequest::default())
+            .await
+            .unwrap(),
+        other::GetTransactionsResponse::default()
+    );
+}
+
+#[tokio::test]
+async fn bin() {
+    let client = RpcClient::new(&start_server(false, |r| r).await).unwrap();
+
+    assert_eq!(
+        client
+            .bin_get_blocks(bin::GetBlocksRequest::default())
+            .await
+            .unwrap(),
+        bin::GetBlocksResponse::default()
+    );
+
+    assert_eq!(
+        client.bin_get_transaction_pool_hashes().await.unwrap(),
+        bin::GetTransactionPoolHashesResponse::default()
+    );
+
+    assert_eq!(
+        client
+            .bin_get_output_distribution(json::GetOutputDistributionRequest::default())
+            .await
+            .unwrap(),
+        json::GetOutputDistributionResponse::default()
+    );
+}
+
+/// Restricted endpoints and methods return errors on restricted servers.
+#[tokio::test]
+async fn restricted() {
+    let client = RpcClient::new(&start_server(true, |r| r).await).unwrap();
+
+    let Err(RpcClientError::JsonRpc(error)) = client
+        .json_generate_blocks(json::GenerateBlocksRequest::default())
+        .await
+    else {
+        panic!("expected a JSON-RPC error");
+    };
+    assert_eq!(error.code, ErrorCode::MethodNotFound);
+
+    assert!(matches!(
+        client.other_stop_daemon().await,
+        Err(RpcClientError::Status(StatusCode::FORBIDDEN))
+    ));
+
+    // Unrestricted endpoints still work.
+    client.json_get_block_count().await.unwrap();
+    client.other_get_height().await.unwrap();
+}
+
+#[tokio::test]
+async fn digest_auth() {
+    let url = start_server(false, |r| {
+        r.layer(DigestAuthLayer::new(DigestCredentials::from_login(
+            "user:pass",
+        )))
+    })
+    .await;
+
+    // The nonce is reused, with an increasing nonce count.
+    let client = RpcClient::new(&url)
+        .unwrap()
+        .with_digest_auth("user".into(), "pass".into());
+
+    for _ in 0..3 {
+        client.json_get_block_count().await.unwrap();
+        client.other_get_height().await.unwrap();
+        client.bin_get_transaction_pool_hashes().await.unwrap();
+    }
+
+    for client in [
+        RpcClient::new(&url).unwrap(),
+        RpcClient::new(&url)
+            .unwrap()
+            .with_digest_auth("user".into(), "wrong".into()),
+    ] {
+        assert!(matches!(
+            client.other_get_height().await,
+            Err(RpcClientError::Status(StatusCode::UNAUTHORIZED))
+        ));
+    }
+}
+
+/// All requests, including from clones, are sent over the same connection.
+#[tokio::test]
+async fn connection_reuse() {
+    let peers = Arc::new(Mutex::new(HashSet::new()));
+
+    let url = {
+        let peers = Arc::clone(&peers);
+        start_server(false, move |r| {
+            r.layer(middleware::from_fn(
+                move |ConnectInfo(addr): ConnectInfo<SocketAddr>, request: Request, next: Next| {
+                    peers.lock().unwrap().insert(addr);
+                    next.run(request)
+                },
+            ))
+        })
+        .await
+    };
+
+    let client = RpcClient::new(&url).unwrap();
+
+    for client in [client.clone(), client] {
+        client.json_get_block_count().await.unwrap();
+        client.other_get_height().await.unwrap();
+        client.bin_get_transaction_pool_hashes().await.unwrap();
+    }
+
+    assert_eq!(peers.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,562 @@
+//! HTTP digest authentication.
+//!
+//! This is the same HTTP digest authentication (RFC 2617) `monerod` uses with `--rpc-login`, see:
+//! <https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454/contrib/epee/src/http_auth.cpp>.
+
+//---------------------------------------------------------------------------------------------------- Import
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    http::{header, HeaderValue, Method, Request, StatusCode, Uri},
+    response::{IntoResponse, Response},
+};
+use futures::future::{ready, Either, Ready};
+use md5::{Digest, Md5};
+use subtle::ConstantTimeEq;
+use tower::{Layer, Service};
+
+//---------------------------------------------------------------------------------------------------- Constants
+/// The realm sent in challenges, this is the same as `monerod`.
+const REALM: &str = "monero-rpc";
+
+/// How long a nonce can be used for, after which clients are told it is stale.
+const NONCE_LIFETIME: Duration = Duration::from_secs(60 * 10);
+
+/// The maximum amount of nonces that are tracked at once.
+///
+/// If this is reached the oldest nonce is removed, see [`Nonces::insert`].
+const MAX_NONCES: usize = 1024;
+
+//---------------------------------------------------------------------------------------------------- DigestCredentials
+/// The username and password required by [`DigestAuthLayer`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct DigestCredentials {
+    /// The username.
+    pub username: String,
+    /// The password.
+    pub password: String,
+}
+
+impl DigestCredentials {
+    /// Parse credentials in the `username[:password]` format of `monerod`'s `--rpc-login`.
+    ///
+    /// If there is no `:` the password is empty.
+    ///
+    /// ```rust
+    /// use cuprate_rpc_interface::DigestCredentials;
+    ///
+    /// let credentials = DigestCredentials::from_login("user:pass:word");
+    /// assert_eq!(credentials.username, "user");
+    /// assert_eq!(credentials.password, "pass:word");
+    ///
+    /// let credentials = DigestCredentials::from_login("user");
+    /// assert_eq!(credentials.username, "user");
+    /// assert_eq!(credentials.password, "");
+    /// ```
+    pub fn from_login(login: &str) -> Self {
+        let (username, password) = login.split_once(':').unwrap_or((login, ""));
+
+        Self {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for DigestCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestCredentials")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- DigestAuthLayer
+/// A [`Layer`] that requires HTTP digest authentication for all requests.
+///
+/// Both the `MD5` and `MD5-sess` algorithms are supported, with the `auth` `qop` or none at all.
+///
+/// Requests that fail authentication are answered with `401 Unauthorized`
+/// along with a new challenge, the inner service is not called.
+///
+/// ```rust
+/// use cuprate_rpc_interface::{DigestAuthLayer, DigestCredentials, RouterBuilder, RpcHandlerDummy};
+///
+/// let router = RouterBuilder::<RpcHandlerDummy>::new()
+///     .all()
+///     .build()
+///     .layer(DigestAuthLayer::new(DigestCredentials::from_login("user:pass")));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DigestAuthLayer {
+    state: Arc<DigestAuthState>,
+}
+
+impl DigestAuthLayer {
+    /// Create a new [`DigestAuthLayer`] requiring `credentials`.
+    pub fn new(credentials: DigestCredentials) -> Self {
+        Self {
+            state: Arc::new(DigestAuthState {
+                credentials,
+                nonces: Mutex::new(Nonces::default()),
+            }),
+        }
+    }
+}
+
+impl<S> Layer<S> for DigestAuthLayer {
+    type Service = DigestAuth<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DigestAuth {
+            inner,
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- DigestAuth
+/// The [`Service`] created by [`DigestAuthLayer`].
+#[derive(Clone, Debug)]
+pub struct DigestAuth<S> {
+    inner: S,
+    state: Arc<DigestAuthState>,
+}
+
+impl<S, B> Service<Request<B>> for DigestAuth<S>
+where
+    S: Service<Request<B>, Response = Response>,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Response, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let authenticated =
+            request
+                .headers()
+                .get(header::AUTHORIZATION)
+                .map_or(Err(false), |authorization| {
+                    self.state
+                        .authenticate(request.method(), request.uri(), authorization)
+                });
+
+        match authenticated {
+            Ok(()) => Either::Right(self.inner.call(request)),
+            Err(stale) => Either::Left(ready(Ok(self.state.challenge(stale)))),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- DigestAuthState
+/// The state of an issued nonce.
+#[derive(Debug)]
+struct NonceState {
+    /// When the nonce was issued.
+    issued: Instant,
+    /// The highest nonce count used with this nonce.
+    nc: u32,
+}
+
+/// The nonces we have issued, that have not expired.
+#[derive(Debug, Default)]
+struct Nonces {
+    /// The state of each nonce.
+    states: HashMap<String, NonceState>,
+    /// The nonces in the order they were issued, oldest first.
+    ///
+    /// This can contain nonces already removed from `states`.
+    issued: VecDeque<(String, Instant)>,
+}
+
+impl Nonces {
+    /// Add a newly issued nonce.
+    ///
+    /// Expired nonces are removed and if [`MAX_NONCES`] are tracked the oldest one is too.
+    fn insert(&mut self, nonce: String) {
+        while let Some((oldest, issued)) = self.issued.front() {
+            if issued.elapsed() < NONCE_LIFETIME && self.issued.len() < MAX_NONCES {
+                break;
+            }
+
+            self.states.remove(oldest);
+            self.issued.pop_front();
+        }
+
+        let issued = Instant::now();
+
+        self.states
+            .insert(nonce.clone(), NonceState { issued, nc: 0 });
+        self.issued.push_back((nonce, issued));
+    }
+}
+
+/// State shared between all [`DigestAuth`] services of a [`DigestAuthLayer`].
+#[derive(Debug)]
+struct DigestAuthState {
+    credentials: DigestCredentials,
+    nonces: Mutex<Nonces>,
+}
+
+impl DigestAuthState {
+    /// Create a `401 Unauthorized` response with a new nonce.
+    ///
+    /// `stale` should be `true` if the client knows the credentials but
+    /// used an invalid nonce, so it can retry without asking the user.
+    fn challenge(&self, stale: bool) -> Response {
+        let nonce = format!("{:032x}", rand::random::<u128>());
+
+        self.nonces.lock().unwrap().insert(nonce.clone());
+
+        let mut response = StatusCode::UNAUTHORIZED.into_response();
+
+        // `monerod` offers `MD5-sess` first.
+        for algorithm in ["MD5-sess", "MD5"] {
+            let challenge = format!(
+                "Digest qop=\"auth\",algorithm={algorithm},realm=\"{REALM}\",nonce=\"{nonce}\",stale={stale}"
+            );
+
+            response.headers_mut().append(
+                header::WWW_AUTHENTICATE,
+                HeaderValue::from_str(&challenge).unwrap(),
+            );
+        }
+
+        response
+    }
+
+    /// Check the `Authorization` header of a request.
+    ///
+    /// # Errors
+    /// On failure, this returns if the nonce was the problem, i.e. if the challenge is `stale`.
+    fn authenticate(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        authorization: &HeaderValue,
+    ) -> Result<(), bool> {
+        let authorization = authorization.to_str().map_err(|_| false)?;
+
+        let params = authorization
+            .get(..7)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("digest "))
+            .and_then(|_| parse_params(&authorization[7..]))
+            .ok_or(false)?;
+
+        let param = |key: &str| params.get(key).copied().ok_or(false);
+
+        let username = param("username")?;
+        let nonce = param("nonce")?;
+        let digest_uri = param("uri")?;
+        let response = param("response")?;
+
+        if username != self.credentials.username || param("realm")? != REALM {
+            return Err(false);
+        }
+
+        let request_uri = uri.path_and_query().map_or(uri.path(), |p| p.as_str());
+        if digest_uri != request_uri {
+            return Err(false);
+        }
+
+        let cnonce = params.get("cnonce").copied();
+        let qop = params.get("qop").copied();
+
+        let mut ha1 = md5_hex(&format!("{username}:{REALM}:{}", self.credentials.password));
+
+        match params.get("algorithm").copied() {
+            None | Some("MD5") => (),
+            Some("MD5-sess") => ha1 = md5_hex(&format!("{ha1}:{nonce}:{}", cnonce.ok_or(false)?)),
+            Some(_) => return Err(false),
+        }
+
+        let ha2 = md5_hex(&format!("{method}:{digest_uri}"));
+
+        let nc = match qop {
+            None => {
+                if !responses_eq(response, &md5_hex(&format!("{ha1}:{nonce}:{ha2}"))) {
+                    return Err(false);
+                }
+
+                None
+            }
+            Some("auth") => {
+                let nc_str = param("nc")?;
+                let nc = u32::from_str_radix(nc_str, 16).map_err(|_| false)?;
+                let cnonce = cnonce.ok_or(false)?;
+
+                let expected = md5_hex(&format!("{ha1}:{nonce}:{nc_str}:{cnonce}:auth:{ha2}"));
+                if !responses_eq(response, &expected) {
+                    return Err(false);
+                }
+
+                Some(nc)
+            }
+            Some(_) => return Err(false),
+        };
+
+        // The response is correct, from here on only the nonce can be wrong.
+        let mut nonces = self.nonces.lock().unwrap();
+
+        let Some(state) = nonces.states.get_mut(nonce) else {
+            return Err(true);
+        };
+
+        if state.issued.elapsed() >= NONCE_LIFETIME {
+            nonces.states.remove(nonce);
+            return Err(true);
+        }
+
+        // The nonce count must always increase, otherwise this could be a replayed request.
+        if let Some(nc) = nc {
+            if nc <= state.nc {
+                return Err(true);
+            }
+
+            state.nc = nc;
+        }
+
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Free functions
+/// Returns the lowercase hex MD5 hash of `data`.
+fn md5_hex(data: &str) -> String {
+    format!("{:x}", Md5::digest(data))
+}
+
+/// Compare a client's digest `response` with the expected one in constant time,
+/// so the time taken doesn't leak how much of it was correct.
+fn responses_eq(response: &str, expected: &str) -> bool {
+    response.as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+/// Parse the comma separated `key=value` parameters of a digest `Authorization` header.
+///
+/// Values may be quoted, the quotes are not included in the returned value.
+fn parse_params(mut params: &str) -> Option<HashMap<&str, &str>> {
+    let mut map = HashMap::new();
+
+    loop {
+        params = params.trim_start();
+
+        if params.is_empty() {
+            return Some(map);
+        }
+
+        let (key, rest) = params.split_once('=')?;
+        let rest = rest.trim_start();
+
+        let (value, rest) = if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"')?;
+            (&quoted[..end], &quoted[end + 1..])
+        } else {
+            let end = rest.find(',').unwrap_or(rest.len());
+            (rest[..end].trim_end(), &rest[end..])
+        };
+
+        map.insert(key.trim(), value);
+
+        let rest = rest.trim_start();
+        params = match rest.strip_prefix(',') {
+            Some(rest) => rest,
+            None if rest.is_empty() => rest,
+            None => return None,
+        };
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Tests
+#[cfg(test)]
+mod test {
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    /// A router with a single `/` route, behind [`DigestAuthLayer`].
+    fn router() -> Router {
+        Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(DigestAuthLayer::new(DigestCredentials::from_login(
+                "user:pass",
+            )))
+    }
+
+    /// Send a `GET /` request, with an optional `Authorization` header.
+    async fn send(authorization: Option<String>) -> Response {
+        let mut request = Request::get("/");
+
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+
+        router()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    /// Returns the nonce and `stale` of the `WWW-Authenticate` challenges.
+    fn challenge(response: &Response) -> (String, bool) {
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let challenges = response
+            .headers()
+            .get_all(header::WWW_AUTHENTICATE)
+            .iter()
+            .map(|c| c.to_str().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(challenges.len(), 2);
+        assert!(challenges[0].contains("algorithm=MD5-sess"));
+        assert!(challenges[1].contains("algorithm=MD5,"));
+
+        let params = parse_params(challenges[0].strip_prefix("Digest ").unwrap()).unwrap();
+        assert_eq!(params["realm"], REALM);
+        assert_eq!(params["qop"], "auth");
+
+        (params["nonce"].to_string(), params["stale"] == "true")
+    }
+
+    /// Create an `Authorization` header for `GET /`.
+    fn authorization(password: &str, nonce: &str, algorithm: &str, nc: u32) -> String {
+        let cnonce = "0a4f113b";
+        let nc = format!("{nc:08x}");
+
+        let mut ha1 = md5_hex(&format!("user:{REALM}:{password}"));
+        if algorithm == "MD5-sess" {
+            ha1 = md5_hex(&format!("{ha1}:{nonce}:{cnonce}"));
+        }
+        let ha2 = md5_hex("GET:/");
+        let response = md5_hex(&format!("{ha1}:{nonce}:{nc}:{cnonce}:auth:{ha2}"));
+
+        format!(
+            r#"Digest username="user", realm="{REALM}", nonce="{nonce}", uri="/", algorithm={algorithm}, qop=auth, nc={nc}, cnonce="{cnonce}", response="{response}""#
+        )
+    }
+
+    #[test]
+    fn credentials() {
+        let credentials = DigestCredentials::from_login("user:pass");
+        assert_eq!(credentials.username, "user");
+        assert_eq!(credentials.password, "pass");
+        assert!(!format!("{credentials:?}").contains("pass"));
+    }
+
+    #[test]
+    fn params() {
+        let params =
+            parse_params(r#"username="a, b", nc=00000001 , qop=auth,uri="/json_rpc""#).unwrap();
+
+        assert_eq!(params["username"], "a, b");
+        assert_eq!(params["nc"], "00000001");
+        assert_eq!(params["qop"], "auth");
+        assert_eq!(params["uri"], "/json_rpc");
+
+        assert!(parse_params(r#"username="a"#).is_none());
+        assert!(parse_params(r#"username="a" nc=1"#).is_none());
+    }
+
+    #[test]
+    fn nonces_removed_oldest_first() {
+        let mut nonces = Nonces::default();
+
+        for i in 0..=MAX_NONCES {
+            nonces.insert(i.to_string());
+        }
+
+        assert_eq!(nonces.states.len(), MAX_NONCES);
+        assert_eq!(nonces.issued.len(), MAX_NONCES);
+        assert!(!nonces.states.contains_key("0"));
+        assert!(nonces.states.contains_key("1"));
+        assert!(nonces.states.contains_key(&MAX_NONCES.to_string()));
+    }
+
+    #[tokio::test]
+    async fn no_authorization() {
+        let (_, stale) = challenge(&send(None).await);
+        assert!(!stale);
+    }
+
+    #[tokio::test]
+    async fn authorized() {
+        let router = router();
+
+        for algorithm in ["MD5", "MD5-sess"] {
+            let response = router
+                .clone()
+                .oneshot(Request::get("/").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let (nonce, _) = challenge(&response);
+
+            for nc in 1..=3 {
+                let request = Request::get("/")
+                    .header(
+                        header::AUTHORIZATION,
+                        authorization("pass", &nonce, algorithm, nc),
+                    )
+                    .body(Body::empty())
+                    .unwrap();
+
+                let response = router.clone().oneshot(request).await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+            }
+
+            // Replaying a nonce count is rejected, but the nonce is only stale.
+            let request = Request::get("/")
+                .header(
+                    header::AUTHORIZATION,
+                    authorization("pass", &nonce, algorithm, 2),
+                )
+                .body(Body::empty())
+                .unwrap();
+
+            let response = router.clone().oneshot(request).await.unwrap();
+            let (_, stale) = challenge(&response);
+            assert!(stale);
+        }
+    }
+
+    #[tokio::test]
+    async fn wrong_password() {
+        let router = router();
+
+        let response = router
+            .clone()
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let (nonce, _) = challenge(&response);
+
+        let request = Request::get("/")
+            .header(
+                header::AUTHORIZATION,
+                authorization("wrong", &nonce, "MD5", 1),
+            )
+            .body(Body::empty())
+            .unwrap();
+
+        let (_, stale) = challenge(&router.oneshot(request).await.unwrap());
+        assert!(!stale);
+    }
+
+    #[tokio::test]
+    async fn unknown_nonce() {
+        let response = send(Some(authorization("pass", "1234", "MD5", 1))).await;
+        let (_, stale) = challenge(&response);
+        assert!(stale);
+    }
+}
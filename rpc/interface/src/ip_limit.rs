@@ -0,0 +1,376 @@
+//! Per-IP limits.
+
+//---------------------------------------------------------------------------------------------------- Import
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    http::{header, HeaderValue, Request, StatusCode},
+    response::{IntoResponse, Response},
+    serve::IncomingStream,
+};
+use futures::future::{ready, Either, Ready};
+use tower::Service;
+
+//---------------------------------------------------------------------------------------------------- Constants
+/// The window [`IpLimits::requests_per_minute`] is counted over.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+//---------------------------------------------------------------------------------------------------- IpLimits
+/// Limits applied to each IP address by [`IpLimitMakeService`].
+///
+/// A limit of `0` disables that limit.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct IpLimits {
+    /// The maximum amount of requests an IP address can make per minute.
+    ///
+    /// Requests over this limit are answered with `429 Too Many Requests`.
+    ///
+    /// Each request in a JSON-RPC batch counts as a request, see [`RequestCharge`].
+    pub requests_per_minute: u32,
+
+    /// The maximum amount of connections an IP address can have open at once.
+    ///
+    /// Requests on connections over this limit are answered
+    /// with `503 Service Unavailable` and the connection is closed.
+    pub max_connections: u32,
+}
+
+//---------------------------------------------------------------------------------------------------- IpLimitMakeService
+/// A make-service for [`axum::serve`] that applies [`IpLimits`] to a service.
+///
+/// ```rust,no_run
+/// use cuprate_rpc_interface::{IpLimitMakeService, IpLimits, RouterBuilder, RpcHandlerDummy};
+///
+/// # async {
+/// let router = RouterBuilder::new()
+///     .all()
+///     .build()
+///     .with_state(RpcHandlerDummy { restricted: true, max_batch_size: 0 });
+///
+/// let limits = IpLimits {
+///     requests_per_minute: 60,
+///     max_connections: 3,
+/// };
+///
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:18089").await.unwrap();
+/// axum::serve(listener, IpLimitMakeService::new(router, limits))
+///     .await
+///     .unwrap();
+/// # };
+/// ```
+#[derive(Clone, Debug)]
+pub struct IpLimitMakeService<S> {
+    inner: S,
+    limits: IpLimits,
+    ips: Arc<Mutex<HashMap<IpAddr, IpState>>>,
+}
+
+impl<S> IpLimitMakeService<S> {
+    /// Create a new [`IpLimitMakeService`], applying `limits` to `inner`.
+    pub fn new(inner: S, limits: IpLimits) -> Self {
+        Self {
+            inner,
+            limits,
+            ips: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<S: Clone> Service<IncomingStream<'_>> for IpLimitMakeService<S> {
+    type Response = IpLimit<S>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Infallible>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, stream: IncomingStream<'_>) -> Self::Future {
+        let ip = stream.remote_addr().ip();
+
+        let connections = {
+            let mut ips = self.ips.lock().unwrap();
+
+            if !ips.contains_key(&ip) {
+                // Forget IPs that can no longer be over a limit.
+                ips.retain(|_, state| {
+                    state.connections != 0 || state.window_start.elapsed() < RATE_LIMIT_WINDOW
+                });
+            }
+
+            let state = ips.entry(ip).or_insert_with(|| IpState {
+                connections: 0,
+                window_start: Instant::now(),
+                requests: 0,
+            });
+
+            state.connections += 1;
+            state.connections
+        };
+
+        let accepted =
+            self.limits.max_connections == 0 || connections <= self.limits.max_connections;
+
+        ready(Ok(IpLimit {
+            inner: self.inner.clone(),
+            connection: Arc::new(Connection {
+                ip,
+                accepted,
+                limits: self.limits,
+                ips: Arc::clone(&self.ips),
+            }),
+        }))
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- IpLimit
+/// The per-connection [`Service`] created by [`IpLimitMakeService`].
+#[derive(Clone, Debug)]
+pub struct IpLimit<S> {
+    inner: S,
+    connection: Arc<Connection>,
+}
+
+impl<S, B> Service<Request<B>> for IpLimit<S>
+where
+    S: Service<Request<B>, Response = Response>,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Response, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        if !self.connection.accepted {
+            let mut response = StatusCode::SERVICE_UNAVAILABLE.into_response();
+            response
+                .headers_mut()
+                .insert(header::CONNECTION, HeaderValue::from_static("close"));
+
+            return Either::Left(ready(Ok(response)));
+        }
+
+        if !self.connection.allow_requests(1) {
+            return Either::Left(ready(Ok(StatusCode::TOO_MANY_REQUESTS.into_response())));
+        }
+
+        request
+            .extensions_mut()
+            .insert(RequestCharge(Arc::clone(&self.connection)));
+
+        Either::Right(self.inner.call(request))
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- RequestCharge
+/// Charges extra requests to the IP address of a request.
+///
+/// [`IpLimit`] adds this to the extensions of every request it lets through, so a request
+/// that does the work of many, like a JSON-RPC batch, can be counted as such.
+#[derive(Clone, Debug)]
+pub struct RequestCharge(Arc<Connection>);
+
+impl RequestCharge {
+    /// Count `requests` more requests against [`IpLimits::requests_per_minute`],
+    /// returns `false` if the IP address is over the limit.
+    pub fn charge(&self, requests: u32) -> bool {
+        self.0.allow_requests(requests)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- State
+/// The state of a single IP address.
+#[derive(Debug)]
+struct IpState {
+    /// The amount of open connections.
+    connections: u32,
+    /// The start of the current rate limit window.
+    window_start: Instant,
+    /// The amount of requests made in the current rate limit window.
+    requests: u32,
+}
+
+/// A single connection, the IP's connection count is decremented when this is dropped.
+#[derive(Debug)]
+struct Connection {
+    ip: IpAddr,
+    /// If this connection was within [`IpLimits::max_connections`].
+    accepted: bool,
+    limits: IpLimits,
+    ips: Arc<Mutex<HashMap<IpAddr, IpState>>>,
+}
+
+impl Connection {
+    /// Count `requests` requests against [`IpLimits::requests_per_minute`],
+    /// returns `false` if they are over the limit.
+    fn allow_requests(&self, requests: u32) -> bool {
+        if self.limits.requests_per_minute == 0 {
+            return true;
+        }
+
+        let mut ips = self.ips.lock().unwrap();
+        let Some(state) = ips.get_mut(&self.ip) else {
+            return true;
+        };
+
+        if state.window_start.elapsed() >= RATE_LIMIT_WINDOW {
+            state.window_start = Instant::now();
+            state.requests = 0;
+        }
+
+        state.requests = state.requests.saturating_add(requests);
+        state.requests <= self.limits.requests_per_minute
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let mut ips = self.ips.lock().unwrap();
+
+        if let Some(state) = ips.get_mut(&self.ip) {
+            state.connections -= 1;
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Tests
+#[cfg(test)]
+mod test {
+    use axum::{routing::get, Router};
+    use serde_json::json;
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::*;
+    use crate::{RouterBuilder, RpcHandlerDummy};
+
+    /// Start a server with a single `/` route, returning its port.
+    async fn serve(limits: IpLimits) -> u16 {
+        serve_router(Router::new().route("/", get(|| async { "ok" })), limits).await
+    }
+
+    /// Start a server with `router`, returning its port.
+    async fn serve_router(router: Router, limits: IpLimits) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::task::spawn(async move {
+            axum::serve(listener, IpLimitMakeService::new(router, limits))
+                .await
+                .unwrap();
+        });
+
+        port
+    }
+
+    /// Send a `GET /` request on a new connection, returning the status code.
+    async fn status(port: u16) -> u16 {
+        tokio::task::spawn_blocking(move || {
+            match ureq::get(&format!("http://127.0.0.1:{port}/")).call() {
+                Ok(response) => response.status(),
+                Err(ureq::Error::Status(status, _)) => status,
+                Err(e) => panic!("{e}"),
+            }
+        })
+        .await
+        .unwrap()
+    }
+
+    /// Send a JSON-RPC batch of `len` requests to `/json_rpc` on a new connection, returning the status code.
+    async fn json_rpc_batch_status(port: u16, len: u64) -> u16 {
+        let batch = (0..len)
+            .map(
+                |id| json!({"jsonrpc": "2.0", "id": id, "method": "get_block_count", "params": {}}),
+            )
+            .collect::<Vec<_>>();
+
+        tokio::task::spawn_blocking(move || {
+            match ureq::post(&format!("http://127.0.0.1:{port}/json_rpc")).send_json(batch) {
+                Ok(response) => response.status(),
+                Err(ureq::Error::Status(status, _)) => status,
+                Err(e) => panic!("{e}"),
+            }
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn no_limits() {
+        let port = serve(IpLimits::default()).await;
+
+        for _ in 0..10 {
+            assert_eq!(status(port).await, 200);
+        }
+    }
+
+    #[tokio::test]
+    async fn requests_per_minute() {
+        let port = serve(IpLimits {
+            requests_per_minute: 3,
+            max_connections: 0,
+        })
+        .await;
+
+        for _ in 0..3 {
+            assert_eq!(status(port).await, 200);
+        }
+
+        assert_eq!(status(port).await, 429);
+    }
+
+    #[tokio::test]
+    async fn max_connections() {
+        let port = serve(IpLimits {
+            requests_per_minute: 0,
+            max_connections: 2,
+        })
+        .await;
+
+        let first = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let second = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+
+        assert_eq!(status(port).await, 503);
+
+        // Closing a connection frees a slot.
+        drop(first);
+        drop(second);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(status(port).await, 200);
+    }
+
+    /// Each request in a JSON-RPC batch counts against the request limit.
+    #[tokio::test]
+    async fn json_rpc_batch_requests_per_minute() {
+        let router = RouterBuilder::new()
+            .json_rpc()
+            .build()
+            .with_state(RpcHandlerDummy {
+                restricted: false,
+                max_batch_size: 0,
+            });
+
+        let port = serve_router(
+            router,
+            IpLimits {
+                requests_per_minute: 3,
+                max_connections: 0,
+            },
+        )
+        .await;
+
+        assert_eq!(json_rpc_batch_status(port, 2).await, 200);
+        // 4 requests are over the limit.
+        assert_eq!(json_rpc_batch_status(port, 2).await, 429);
+    }
+}
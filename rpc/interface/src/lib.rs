@@ -1,6 +1,8 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+mod digest_auth;
+mod ip_limit;
 mod route;
 mod router_builder;
 mod rpc_handler;
@@ -8,6 +10,8 @@ mod rpc_handler;
 mod rpc_handler_dummy;
 mod rpc_service;
 
+pub use digest_auth::{DigestAuth, DigestAuthLayer, DigestCredentials};
+pub use ip_limit::{IpLimit, IpLimitMakeService, IpLimits, RequestCharge};
 pub use router_builder::RouterBuilder;
 pub use rpc_handler::RpcHandler;
 #[cfg(feature = "dummy")]
@@ -1,23 +1,95 @@
 //! JSON-RPC 2.0 endpoint route functions.
 
 //---------------------------------------------------------------------------------------------------- Import
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use futures::future::join_all;
 use tower::ServiceExt;
 
-use cuprate_json_rpc::{Id, Response};
+use cuprate_json_rpc::{
+    error::{ErrorCode, ErrorObject},
+    BatchRequest, Id, Request, RequestOrBatch, Response, ResponseOrBatch,
+};
 use cuprate_rpc_types::{
     json::{JsonRpcRequest, JsonRpcResponse},
     RpcCallValue,
 };
 
-use crate::rpc_handler::RpcHandler;
+use crate::{ip_limit::RequestCharge, rpc_handler::RpcHandler};
 
 //---------------------------------------------------------------------------------------------------- Routes
 /// The `/json_rpc` route function used in [`crate::RouterBuilder`].
+///
+/// Behind an [`crate::IpLimit`], each request in a batch is counted against the IP's request limit.
 pub(crate) async fn json_rpc<H: RpcHandler>(
     State(handler): State<H>,
-    Json(request): Json<cuprate_json_rpc::Request<JsonRpcRequest>>,
-) -> Result<Json<Response<JsonRpcResponse>>, StatusCode> {
+    request_charge: Option<Extension<RequestCharge>>,
+    Json(request): Json<RequestOrBatch<JsonRpcRequest>>,
+) -> Result<Json<ResponseOrBatch<JsonRpcResponse>>, StatusCode> {
+    let batch = match request {
+        RequestOrBatch::Single(request) => {
+            let Ok(response) = handle_request(handler, request).await else {
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            };
+
+            return Ok(Json(ResponseOrBatch::Single(response)));
+        }
+        RequestOrBatch::Batch(batch) => batch,
+    };
+
+    // JSON-RPC 2.0 rule:
+    // An empty batch is invalid and must be responded to with a single response.
+    if batch.is_empty() {
+        return Ok(Json(ResponseOrBatch::Single(Response::invalid_request(
+            Id::Null,
+        ))));
+    }
+
+    let max_batch_size = handler.max_batch_size();
+    if max_batch_size != 0 && batch.len() > max_batch_size {
+        return Ok(Json(ResponseOrBatch::Single(Response::err(
+            Id::Null,
+            ErrorObject {
+                code: ErrorCode::InvalidRequest,
+                message: format!("Batch size limit exceeded: {max_batch_size}").into(),
+                data: None,
+            },
+        ))));
+    }
+
+    // The HTTP request was already counted as one request, count the rest of the batch.
+    if let Some(Extension(request_charge)) = request_charge {
+        let extra_requests = u32::try_from(batch.len() - 1).unwrap_or(u32::MAX);
+
+        if !request_charge.charge(extra_requests) {
+            return Err(StatusCode::TOO_MANY_REQUESTS);
+        }
+    }
+
+    // Each request is handled on its own, an error only fails that request's response.
+    let responses = join_all(batch.into_iter().map(|request| {
+        let handler = handler.clone();
+
+        async move {
+            match request {
+                BatchRequest::Valid(request) => handle_request(handler, request)
+                    .await
+                    .unwrap_or_else(Response::internal_error),
+                BatchRequest::Invalid(id) => Response::invalid_request(id),
+            }
+        }
+    }))
+    .await;
+
+    Ok(Json(ResponseOrBatch::Batch(responses)))
+}
+
+/// Handles a single JSON-RPC request.
+///
+/// If the [`RpcHandler`] returned an error, the request's [`Id`] is returned as the error.
+async fn handle_request<H: RpcHandler>(
+    handler: H,
+    request: Request<JsonRpcRequest>,
+) -> Result<Response<JsonRpcResponse>, Id> {
     // TODO: <https://www.jsonrpc.org/specification#notification>
     //
     // JSON-RPC notifications (requests without `id`)
@@ -43,19 +115,102 @@ pub(crate) async fn json_rpc<H: RpcHandler>(
         //
         // - <https://github.com/monero-project/monero/blob/893916ad091a92e765ce3241b94e706ad012b62a/contrib/epee/include/net/http_server_handlers_map2.h#L244-L252>
         // - <https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454/src/rpc/core_rpc_server.h#L188>
-        return Ok(Json(Response::method_not_found(id)));
+        return Ok(Response::method_not_found(id));
     }
 
     // Send request.
-    let Ok(response) = handler.oneshot(request.body).await else {
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    };
-
-    Ok(Json(Response::ok(id, response)))
+    match handler.oneshot(request.body).await {
+        Ok(response) => Ok(Response::ok(id, response)),
+        Err(_) => Err(id),
+    }
 }
 
 //---------------------------------------------------------------------------------------------------- Tests
 #[cfg(test)]
 mod test {
-    // use super::*;
+    use serde_json::json;
+
+    use cuprate_rpc_types::json::GetBlockCountResponse;
+
+    use super::*;
+    use crate::RpcHandlerDummy;
+
+    /// Send `request` to the `/json_rpc` route of a [`RpcHandlerDummy`].
+    async fn call(
+        max_batch_size: usize,
+        request: serde_json::Value,
+    ) -> ResponseOrBatch<JsonRpcResponse> {
+        let handler = RpcHandlerDummy {
+            restricted: false,
+            max_batch_size,
+        };
+
+        json_rpc(
+            State(handler),
+            None,
+            Json(serde_json::from_value(request).unwrap()),
+        )
+        .await
+        .unwrap()
+        .0
+    }
+
+    /// A `get_block_count` request with the ID `id`.
+    fn get_block_count(id: u64) -> serde_json::Value {
+        json!({"jsonrpc": "2.0", "id": id, "method": "get_block_count", "params": {}})
+    }
+
+    fn get_block_count_response(id: u64) -> Response<JsonRpcResponse> {
+        Response::ok(
+            Id::Num(id),
+            JsonRpcResponse::GetBlockCount(GetBlockCountResponse::default()),
+        )
+    }
+
+    /// A batch over the limit is rejected as a whole with a single response.
+    #[tokio::test]
+    async fn batch_over_limit() {
+        let batch = json!([get_block_count(0), get_block_count(1), get_block_count(2)]);
+
+        let ResponseOrBatch::Single(response) = call(2, batch.clone()).await else {
+            panic!("an over-limit batch must get a single response");
+        };
+        assert_eq!(response.id, Id::Null);
+        assert_eq!(
+            response.payload.unwrap_err().code,
+            ErrorCode::InvalidRequest
+        );
+
+        // At the limit, or without one, the batch is handled.
+        for max_batch_size in [3, 0] {
+            let ResponseOrBatch::Batch(responses) = call(max_batch_size, batch.clone()).await
+            else {
+                panic!("a batch within the limit must get a batch response");
+            };
+            assert_eq!(responses.len(), 3);
+        }
+    }
+
+    /// Invalid requests in a batch only fail their own response, and responses keep the batch's order.
+    #[tokio::test]
+    async fn mixed_batch_keeps_order() {
+        let batch = json!([
+            get_block_count(0),
+            {"jsonrpc": "2.0", "id": 1, "method": "not_a_method"},
+            get_block_count(2),
+            {"jsonrpc": "2.0", "id": 3},
+            get_block_count(4),
+        ]);
+
+        assert_eq!(
+            call(0, batch).await,
+            ResponseOrBatch::Batch(vec![
+                get_block_count_response(0),
+                Response::invalid_request(Id::Num(1)),
+                get_block_count_response(2),
+                Response::invalid_request(Id::Num(3)),
+                get_block_count_response(4),
+            ])
+        );
+    }
 }
@@ -3,6 +3,8 @@
 //---------------------------------------------------------------------------------------------------- Use
 use axum::Router;
 
+use cuprate_rpc_types::{self as types, RpcCall};
+
 use crate::{
     route::{bin, fallback, json_rpc, other_json},
     rpc_handler::RpcHandler,
@@ -16,10 +18,12 @@ macro_rules! generate_router_builder {
         // $BUILDER_FUNCTION_NAME =>
         // $ACTUAL_ENDPOINT_STRING =>
         // $ENDPOINT_FUNCTION_MODULE::$ENDPOINT_FUNCTION =>
+        // $REQUEST_TYPE =>
         // ($HTTP_METHOD(s))
         $endpoint_ident:ident =>
         $endpoint_string:literal =>
         $endpoint_module:ident::$endpoint_fn:ident =>
+        $request:ty =>
         ($($http_method:ident),*)
     ),* $(,)?) => {
         /// Builder for creating the RPC router.
@@ -47,6 +51,9 @@ macro_rules! generate_router_builder {
         ///
         /// If desired, you can enable `/get_height` but not `/getheight`.
         ///
+        /// [`all`](RouterBuilder::all) and [`all_restricted`](RouterBuilder::all_restricted)
+        /// always enable both.
+        ///
         /// # Example
         /// ```rust
         /// use cuprate_rpc_interface::{RouterBuilder, RpcHandlerDummy};
@@ -68,6 +75,11 @@ macro_rules! generate_router_builder {
         /// let all = RouterBuilder::<RpcHandlerDummy>::new()
         ///     .all()
         ///     .build();
+        ///
+        /// // Create a router with all endpoints allowed on restricted RPC enabled.
+        /// let all_restricted = RouterBuilder::<RpcHandlerDummy>::new()
+        ///     .all_restricted()
+        ///     .build();
         /// ```
         #[derive(Clone)]
         pub struct RouterBuilder<H: RpcHandler> {
@@ -94,6 +106,8 @@ macro_rules! generate_router_builder {
             /// Enable all endpoints, including [`Self::fallback`].
             #[must_use]
             pub fn all(mut self) -> Self {
+                self = self.json_rpc();
+
                 $(
                     self = self.$endpoint_ident();
                 )*
@@ -101,6 +115,37 @@ macro_rules! generate_router_builder {
                 self.fallback()
             }
 
+            /// Enable all endpoints that are allowed on restricted RPC servers,
+            /// including [`Self::fallback`].
+            ///
+            /// An endpoint is enabled if its request type's [`RpcCall::IS_RESTRICTED`] is `false`.
+            ///
+            /// `/json_rpc` is always enabled, restricted methods are denied within it.
+            #[must_use]
+            pub fn all_restricted(mut self) -> Self {
+                self = self.json_rpc();
+
+                $(
+                    if !<$request as RpcCall>::IS_RESTRICTED {
+                        self = self.$endpoint_ident();
+                    }
+                )*
+
+                self.fallback()
+            }
+
+            /// Enable the `/json_rpc` endpoint.
+            #[must_use]
+            pub fn json_rpc(self) -> Self {
+                Self {
+                    router: self.router.route(
+                        "/json_rpc",
+                        ::axum::routing::method_routing::get(json_rpc::json_rpc::<H>)
+                            .post(json_rpc::json_rpc::<H>),
+                    ),
+                }
+            }
+
             /// Enable the catch-all fallback route.
             ///
             /// Any unknown or disabled route will route here, e.g.:
@@ -132,56 +177,61 @@ macro_rules! generate_router_builder {
                 }
             )*
         }
+
+        /// Every endpoint and whether its request type is restricted.
+        #[cfg(test)]
+        const ROUTES: &[(&str, bool)] = &[
+            $(
+                ($endpoint_string, <$request as RpcCall>::IS_RESTRICTED),
+            )*
+        ];
     };
 }
 
 generate_router_builder! {
-    // JSON-RPC 2.0 route.
-    json_rpc => "/json_rpc" => json_rpc::json_rpc => (get, post),
-
     // Other JSON routes.
-    other_get_height                  => "/get_height"                  => other_json::get_height                  => (get, post),
-    other_getheight                   => "/getheight"                   => other_json::get_height                  => (get, post),
-    other_get_transactions            => "/get_transactions"            => other_json::get_transactions            => (get, post),
-    other_gettransactions             => "/gettransactions"             => other_json::get_transactions            => (get, post),
-    other_get_alt_blocks_hashes       => "/get_alt_blocks_hashes"       => other_json::get_alt_blocks_hashes       => (get, post),
-    other_is_key_image_spent          => "/is_key_image_spent"          => other_json::is_key_image_spent          => (get, post),
-    other_send_raw_transaction        => "/send_raw_transaction"        => other_json::send_raw_transaction        => (get, post),
-    other_sendrawtransaction          => "/sendrawtransaction"          => other_json::send_raw_transaction        => (get, post),
-    other_start_mining                => "/start_mining"                => other_json::start_mining                => (get, post),
-    other_stop_mining                 => "/stop_mining"                 => other_json::stop_mining                 => (get, post),
-    other_mining_status               => "/mining_status"               => other_json::mining_status               => (get, post),
-    other_save_bc                     => "/save_bc"                     => other_json::save_bc                     => (get, post),
-    other_get_peer_list               => "/get_peer_list"               => other_json::get_peer_list               => (get, post),
-    other_get_public_nodes            => "/get_public_nodes"            => other_json::get_public_nodes            => (get, post),
-    other_set_log_hash_rate           => "/set_log_hash_rate"           => other_json::set_log_hash_rate           => (get, post),
-    other_set_log_level               => "/set_log_level"               => other_json::set_log_level               => (get, post),
-    other_set_log_categories          => "/set_log_categories"          => other_json::set_log_categories          => (get, post),
-    other_get_transaction_pool        => "/get_transaction_pool"        => other_json::get_transaction_pool        => (get, post),
-    other_get_transaction_pool_hashes => "/get_transaction_pool_hashes" => other_json::get_transaction_pool_hashes => (get, post),
-    other_get_transaction_pool_stats  => "/get_transaction_pool_stats"  => other_json::get_transaction_pool_stats  => (get, post),
-    other_set_bootstrap_daemon        => "/set_bootstrap_daemon"        => other_json::set_bootstrap_daemon        => (get, post),
-    other_stop_daemon                 => "/stop_daemon"                 => other_json::stop_daemon                 => (get, post),
-    other_get_net_stats               => "/get_net_stats"               => other_json::get_net_stats               => (get, post),
-    other_get_limit                   => "/get_limit"                   => other_json::get_limit                   => (get, post),
-    other_set_limit                   => "/set_limit"                   => other_json::set_limit                   => (get, post),
-    other_out_peers                   => "/out_peers"                   => other_json::out_peers                   => (get, post),
-    other_in_peers                    => "/in_peers"                    => other_json::in_peers                    => (get, post),
-    other_get_outs                    => "/get_outs"                    => other_json::get_outs                    => (get, post),
-    other_update                      => "/update"                      => other_json::update                      => (get, post),
-    other_pop_blocks                  => "/pop_blocks"                  => other_json::pop_blocks                  => (get, post),
+    other_get_height                  => "/get_height"                  => other_json::get_height                  => types::other::GetHeightRequest                => (get, post),
+    other_getheight                   => "/getheight"                   => other_json::get_height                  => types::other::GetHeightRequest                => (get, post),
+    other_get_transactions            => "/get_transactions"            => other_json::get_transactions            => types::other::GetTransactionsRequest          => (get, post),
+    other_gettransactions             => "/gettransactions"             => other_json::get_transactions            => types::other::GetTransactionsRequest          => (get, post),
+    other_get_alt_blocks_hashes       => "/get_alt_blocks_hashes"       => other_json::get_alt_blocks_hashes       => types::other::GetAltBlocksHashesRequest       => (get, post),
+    other_is_key_image_spent          => "/is_key_image_spent"          => other_json::is_key_image_spent          => types::other::IsKeyImageSpentRequest          => (get, post),
+    other_send_raw_transaction        => "/send_raw_transaction"        => other_json::send_raw_transaction        => types::other::SendRawTransactionRequest       => (get, post),
+    other_sendrawtransaction          => "/sendrawtransaction"          => other_json::send_raw_transaction        => types::other::SendRawTransactionRequest       => (get, post),
+    other_start_mining                => "/start_mining"                => other_json::start_mining                => types::other::StartMiningRequest              => (get, post),
+    other_stop_mining                 => "/stop_mining"                 => other_json::stop_mining                 => types::other::StopMiningRequest               => (get, post),
+    other_mining_status               => "/mining_status"               => other_json::mining_status               => types::other::MiningStatusRequest             => (get, post),
+    other_save_bc                     => "/save_bc"                     => other_json::save_bc                     => types::other::SaveBcRequest                   => (get, post),
+    other_get_peer_list               => "/get_peer_list"               => other_json::get_peer_list               => types::other::GetPeerListRequest              => (get, post),
+    other_get_public_nodes            => "/get_public_nodes"            => other_json::get_public_nodes            => types::other::GetPublicNodesRequest           => (get, post),
+    other_set_log_hash_rate           => "/set_log_hash_rate"           => other_json::set_log_hash_rate           => types::other::SetLogHashRateRequest           => (get, post),
+    other_set_log_level               => "/set_log_level"               => other_json::set_log_level               => types::other::SetLogLevelRequest              => (get, post),
+    other_set_log_categories          => "/set_log_categories"          => other_json::set_log_categories          => types::other::SetLogCategoriesRequest         => (get, post),
+    other_get_transaction_pool        => "/get_transaction_pool"        => other_json::get_transaction_pool        => types::other::GetTransactionPoolRequest       => (get, post),
+    other_get_transaction_pool_hashes => "/get_transaction_pool_hashes" => other_json::get_transaction_pool_hashes => types::other::GetTransactionPoolHashesRequest => (get, post),
+    other_get_transaction_pool_stats  => "/get_transaction_pool_stats"  => other_json::get_transaction_pool_stats  => types::other::GetTransactionPoolStatsRequest  => (get, post),
+    other_set_bootstrap_daemon        => "/set_bootstrap_daemon"        => other_json::set_bootstrap_daemon        => types::other::SetBootstrapDaemonRequest       => (get, post),
+    other_stop_daemon                 => "/stop_daemon"                 => other_json::stop_daemon                 => types::other::StopDaemonRequest               => (get, post),
+    other_get_net_stats               => "/get_net_stats"               => other_json::get_net_stats               => types::other::GetNetStatsRequest              => (get, post),
+    other_get_limit                   => "/get_limit"                   => other_json::get_limit                   => types::other::GetLimitRequest                 => (get, post),
+    other_set_limit                   => "/set_limit"                   => other_json::set_limit                   => types::other::SetLimitRequest                 => (get, post),
+    other_out_peers                   => "/out_peers"                   => other_json::out_peers                   => types::other::OutPeersRequest                 => (get, post),
+    other_in_peers                    => "/in_peers"                    => other_json::in_peers                    => types::other::InPeersRequest                  => (get, post),
+    other_get_outs                    => "/get_outs"                    => other_json::get_outs                    => types::other::GetOutsRequest                  => (get, post),
+    other_update                      => "/update"                      => other_json::update                      => types::other::UpdateRequest                   => (get, post),
+    other_pop_blocks                  => "/pop_blocks"                  => other_json::pop_blocks                  => types::other::PopBlocksRequest                => (get, post),
 
     // Binary routes.
-    bin_get_blocks                  => "/get_blocks.bin"                  => bin::get_blocks                  => (get, post),
-    bin_getblocks                   => "/getblocks.bin"                   => bin::get_blocks                  => (get, post),
-    bin_get_blocks_by_height        => "/get_blocks_by_height.bin"        => bin::get_blocks_by_height        => (get, post),
-    bin_getblocks_by_height         => "/getblocks_by_height.bin"         => bin::get_blocks_by_height        => (get, post),
-    bin_get_hashes                  => "/get_hashes.bin"                  => bin::get_hashes                  => (get, post),
-    bin_gethashes                   => "/gethashes.bin"                   => bin::get_hashes                  => (get, post),
-    bin_get_o_indexes               => "/get_o_indexes.bin"               => bin::get_o_indexes               => (get, post),
-    bin_get_outs                    => "/get_outs.bin"                    => bin::get_outs                    => (get, post),
-    bin_get_transaction_pool_hashes => "/get_transaction_pool_hashes.bin" => bin::get_transaction_pool_hashes => (get, post),
-    bin_get_output_distribution     => "/get_output_distribution.bin"     => bin::get_output_distribution     => (get, post),
+    bin_get_blocks                  => "/get_blocks.bin"                  => bin::get_blocks                  => types::bin::GetBlocksRequest                => (get, post),
+    bin_getblocks                   => "/getblocks.bin"                   => bin::get_blocks                  => types::bin::GetBlocksRequest                => (get, post),
+    bin_get_blocks_by_height        => "/get_blocks_by_height.bin"        => bin::get_blocks_by_height        => types::bin::GetBlocksByHeightRequest        => (get, post),
+    bin_getblocks_by_height         => "/getblocks_by_height.bin"         => bin::get_blocks_by_height        => types::bin::GetBlocksByHeightRequest        => (get, post),
+    bin_get_hashes                  => "/get_hashes.bin"                  => bin::get_hashes                  => types::bin::GetHashesRequest                => (get, post),
+    bin_gethashes                   => "/gethashes.bin"                   => bin::get_hashes                  => types::bin::GetHashesRequest                => (get, post),
+    bin_get_o_indexes               => "/get_o_indexes.bin"               => bin::get_o_indexes               => types::bin::GetOutputIndexesRequest         => (get, post),
+    bin_get_outs                    => "/get_outs.bin"                    => bin::get_outs                    => types::bin::GetOutsRequest                  => (get, post),
+    bin_get_transaction_pool_hashes => "/get_transaction_pool_hashes.bin" => bin::get_transaction_pool_hashes => types::bin::GetTransactionPoolHashesRequest => (get, post),
+    bin_get_output_distribution     => "/get_output_distribution.bin"     => bin::get_output_distribution     => types::json::GetOutputDistributionRequest   => (get, post),
 }
 
 impl<H: RpcHandler> Default for RouterBuilder<H> {
@@ -190,3 +240,39 @@ impl<H: RpcHandler> Default for RouterBuilder<H> {
         Self::new().all()
     }
 }
+
+//---------------------------------------------------------------------------------------------------- Tests
+#[cfg(test)]
+mod test {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::RpcHandlerDummy;
+
+    /// Tests [`RouterBuilder::all_restricted`] enables every unrestricted
+    /// endpoint and leaves every restricted endpoint to the fallback.
+    #[tokio::test]
+    async fn all_restricted() {
+        let router = RouterBuilder::new()
+            .all_restricted()
+            .build()
+            .with_state(RpcHandlerDummy {
+                restricted: false,
+                max_batch_size: 0,
+            });
+
+        assert!(ROUTES.iter().any(|(_, restricted)| *restricted));
+
+        for (endpoint, restricted) in ROUTES {
+            let request = Request::post(*endpoint).body(Body::empty()).unwrap();
+            let status = router.clone().oneshot(request).await.unwrap().status();
+
+            // Only the fallback returns `404`.
+            assert_eq!(status == StatusCode::NOT_FOUND, *restricted, "{endpoint}");
+        }
+    }
+}
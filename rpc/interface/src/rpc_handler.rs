@@ -47,4 +47,13 @@ pub trait RpcHandler:
     /// will automatically be denied access when using the
     /// [`axum::Router`] provided by [`RouterBuilder`](crate::RouterBuilder).
     fn is_restricted(&self) -> bool;
+
+    /// The maximum amount of requests in a JSON-RPC batch request.
+    ///
+    /// Larger batches are rejected as a whole, `0` means there is no limit.
+    ///
+    /// By default there is no limit.
+    fn max_batch_size(&self) -> usize {
+        0
+    }
 }
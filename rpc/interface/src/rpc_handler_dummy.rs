@@ -36,12 +36,19 @@ pub struct RpcHandlerDummy {
     /// The dummy will honor this [`bool`]
     /// on restricted methods/endpoints.
     pub restricted: bool,
+
+    /// The [maximum batch size](RpcHandler::max_batch_size).
+    pub max_batch_size: usize,
 }
 
 impl RpcHandler for RpcHandlerDummy {
     fn is_restricted(&self) -> bool {
         self.restricted
     }
+
+    fn max_batch_size(&self) -> usize {
+        self.max_batch_size
+    }
 }
 
 impl Service<JsonRpcRequest> for RpcHandlerDummy {
@@ -0,0 +1,149 @@
+//! JSON-RPC 2.0 batch objects.
+
+//---------------------------------------------------------------------------------------------------- Use
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::{id::Id, request::Request, response::Response};
+
+//---------------------------------------------------------------------------------------------------- RequestOrBatch
+/// Either a single [`Request`] or a [batch](https://www.jsonrpc.org/specification#batch) of them.
+///
+/// This is the body of a request sent to a JSON-RPC 2.0 server.
+///
+/// ```rust
+/// use cuprate_json_rpc::{BatchRequest, Id, RequestOrBatch};
+/// use serde_json::{from_str, Value};
+///
+/// let single: RequestOrBatch<Value> = from_str(r#"{"jsonrpc":"2.0","id":0,"method":"a"}"#).unwrap();
+/// assert!(matches!(single, RequestOrBatch::Single(_)));
+///
+/// // Invalid elements don't fail the whole batch.
+/// let batch: RequestOrBatch<Value> = from_str(r#"[{"jsonrpc":"2.0","id":0,"method":"a"},{"id":1}]"#).unwrap();
+/// let RequestOrBatch::Batch(batch) = batch else { panic!() };
+/// assert!(matches!(batch[0], BatchRequest::Valid(_)));
+/// assert_eq!(batch[1], BatchRequest::Invalid(Id::Num(1)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged, bound = "T: DeserializeOwned")]
+pub enum RequestOrBatch<T> {
+    /// A single request.
+    Single(Request<T>),
+    /// A batch of requests.
+    ///
+    /// ### JSON-RPC 2.0 rules
+    /// - An empty batch is an invalid request
+    /// - The responses should be in a batch, although not necessarily in the same order
+    Batch(Vec<BatchRequest<T>>),
+}
+
+//---------------------------------------------------------------------------------------------------- BatchRequest
+/// An element of a batch request.
+///
+/// Elements that are not valid [`Request`]s are kept so they can be responded to
+/// with [`Response::invalid_request`] instead of failing the whole batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchRequest<T> {
+    /// A valid request.
+    Valid(Request<T>),
+    /// An invalid request, with its [`Id`] or [`Id::Null`] if it could not be found.
+    Invalid(Id),
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for BatchRequest<T> {
+    fn deserialize<D: Deserializer<'de>>(der: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(der)?;
+
+        // Find the ID before the value is consumed, in case the request is invalid.
+        let id = value
+            .get("id")
+            .and_then(|id| Id::deserialize(id).ok())
+            .unwrap_or(Id::Null);
+
+        Ok(serde_json::from_value(value).map_or(Self::Invalid(id), Self::Valid))
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- ResponseOrBatch
+/// Either a single [`Response`] or a [batch](https://www.jsonrpc.org/specification#batch) of them.
+///
+/// ```rust
+/// use cuprate_json_rpc::{Id, Response, ResponseOrBatch};
+///
+/// let batch = ResponseOrBatch::Batch(vec![
+///     Response::ok(Id::Num(0), "OK"),
+///     Response::invalid_request(Id::Null),
+/// ]);
+///
+/// let json = serde_json::to_string(&batch).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"[{"jsonrpc":"2.0","id":0,"result":"OK"},{"jsonrpc":"2.0","id":null,"error":{"code":-32600,"message":"Invalid Request"}}]"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResponseOrBatch<T> {
+    /// A single response.
+    Single(Response<T>),
+    /// A batch of responses.
+    Batch(Vec<Response<T>>),
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::Body;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::{from_value, json};
+
+    /// Batches keep their order and invalid elements.
+    #[test]
+    fn batch_request() {
+        let batch: RequestOrBatch<Body<[u8; 1]>> = from_value(json!([
+            {"jsonrpc": "2.0", "id": 0, "method": "a", "params": [0]},
+            {"jsonrpc": "2.0", "id": "b", "method": "b"},
+            1,
+            {"jsonrpc": "2.0", "method": "c", "params": [2]},
+        ]))
+        .unwrap();
+
+        let RequestOrBatch::Batch(batch) = batch else {
+            panic!("expected a batch");
+        };
+
+        assert_eq!(
+            batch,
+            vec![
+                BatchRequest::Valid(Request::new_with_id(
+                    Id::Num(0),
+                    Body {
+                        method: "a".into(),
+                        params: [0]
+                    }
+                )),
+                BatchRequest::Invalid(Id::Str("b".into())),
+                BatchRequest::Invalid(Id::Null),
+                BatchRequest::Valid(Request::new(Body {
+                    method: "c".into(),
+                    params: [2]
+                })),
+            ]
+        );
+    }
+
+    /// An empty batch is still a batch, the server must reject it.
+    #[test]
+    fn empty_batch() {
+        let batch: RequestOrBatch<Body<()>> = from_value(json!([])).unwrap();
+        assert_eq!(batch, RequestOrBatch::Batch(vec![]));
+    }
+
+    /// A single invalid request is an error, not a batch.
+    #[test]
+    fn invalid_single_request() {
+        assert!(from_value::<RequestOrBatch<Body<()>>>(json!({"id": 0})).is_err());
+    }
+}
@@ -14,5 +14,8 @@ pub use request::Request;
 mod response;
 pub use response::Response;
 
+mod batch;
+pub use batch::{BatchRequest, RequestOrBatch, ResponseOrBatch};
+
 #[cfg(test)]
 mod tests;
@@ -3,15 +3,15 @@
 //! Only non-crate types are imported, all crate types use `crate::`.
 
 use std::{
-    net::{SocketAddr, SocketAddrV4},
+    net::{IpAddr, SocketAddr},
     time::Duration,
 };
 
 use cuprate_helper::{fmt::HexPrefix, map::ipv4_from_u32};
 use cuprate_hex::{Hex, HexVec};
 use cuprate_p2p_core::{
-    types::{ConnectionId, ConnectionInfo, SetBan, Span},
-    NetZoneAddress,
+    types::{BanTarget, ConnectionId, ConnectionInfo, SetBan, Span},
+    IpSubnetParseError, NetZoneAddress,
 };
 use cuprate_types::rpc::{BlockHeader, ChainInfo, HistogramEntry, SpentKeyImageInfo, TxInfo};
 
@@ -85,9 +85,19 @@ impl<A: NetZoneAddress> From<ConnectionInfo<A>> for crate::misc::ConnectionInfo
 }
 
 // TODO: support non-clearnet addresses.
-impl From<crate::misc::SetBan> for SetBan<SocketAddr> {
-    fn from(x: crate::misc::SetBan) -> Self {
-        let address = SocketAddr::V4(SocketAddrV4::new(ipv4_from_u32(x.ip), 0));
+impl TryFrom<crate::misc::SetBan> for SetBan<SocketAddr> {
+    type Error = IpSubnetParseError;
+
+    /// Like `monerod`, `host` is used if it is set, it can be an IP address or a subnet.
+    /// Otherwise, `ip` is used.
+    fn try_from(x: crate::misc::SetBan) -> Result<Self, Self::Error> {
+        let target = if x.host.is_empty() {
+            BanTarget::Host(IpAddr::V4(ipv4_from_u32(x.ip)))
+        } else if x.host.contains('/') {
+            BanTarget::Subnet(x.host.parse()?)
+        } else {
+            BanTarget::Host(x.host.parse()?)
+        };
 
         let ban = if x.ban {
             Some(Duration::from_secs(x.seconds.into()))
@@ -95,7 +105,7 @@ impl From<crate::misc::SetBan> for SetBan<SocketAddr> {
             None
         };
 
-        Self { address, ban }
+        Ok(Self { target, ban })
     }
 }
 
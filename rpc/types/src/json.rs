@@ -733,6 +733,9 @@ define_request_and_response! {
 ///
 /// See also: [`JsonRpcResponse`].
 ///
+/// The `method` of each variant is the same as `monerod`'s,
+/// e.g. [`JsonRpcRequest::GenerateBlocks`] is `generateblocks`.
+///
 /// TODO: document and test (de)serialization behavior after figuring out `method/params`.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
@@ -745,6 +748,7 @@ pub enum JsonRpcRequest {
     GetBlockCount(GetBlockCountRequest),
     OnGetBlockHash(OnGetBlockHashRequest),
     SubmitBlock(SubmitBlockRequest),
+    #[cfg_attr(feature = "serde", serde(rename = "generateblocks"))]
     GenerateBlocks(GenerateBlocksRequest),
     GetLastBlockHeader(GetLastBlockHeaderRequest),
     GetBlockHeaderByHash(GetBlockHeaderByHashRequest),
@@ -757,6 +761,7 @@ pub enum JsonRpcRequest {
     SetBans(SetBansRequest),
     GetBans(GetBansRequest),
     Banned(BannedRequest),
+    #[cfg_attr(feature = "serde", serde(rename = "flush_txpool"))]
     FlushTransactionPool(FlushTransactionPoolRequest),
     GetOutputHistogram(GetOutputHistogramRequest),
     GetCoinbaseTxSum(GetCoinbaseTxSumRequest),
@@ -765,12 +770,14 @@ pub enum JsonRpcRequest {
     GetAlternateChains(GetAlternateChainsRequest),
     RelayTx(RelayTxRequest),
     SyncInfo(SyncInfoRequest),
+    #[cfg_attr(feature = "serde", serde(rename = "get_txpool_backlog"))]
     GetTransactionPoolBacklog(GetTransactionPoolBacklogRequest),
     GetMinerData(GetMinerDataRequest),
     PruneBlockchain(PruneBlockchainRequest),
     CalcPow(CalcPowRequest),
     FlushCache(FlushCacheRequest),
     AddAuxPow(AddAuxPowRequest),
+    #[cfg_attr(feature = "serde", serde(rename = "get_txids_loose"))]
     GetTxIdsLoose(GetTxIdsLooseRequest),
 }
 
@@ -1969,4 +1976,35 @@ mod test {
             merkle_tree_depth: 0,
         });
     }
+
+    /// [`JsonRpcRequest`] methods are the same as `monerod`'s.
+    #[test]
+    fn json_rpc_request_method() {
+        for (request, method) in [
+            (
+                JsonRpcRequest::GetBlockCount(GetBlockCountRequest {}),
+                "get_block_count",
+            ),
+            (
+                JsonRpcRequest::GenerateBlocks(GenerateBlocksRequest::default()),
+                "generateblocks",
+            ),
+            (
+                JsonRpcRequest::FlushTransactionPool(FlushTransactionPoolRequest::default()),
+                "flush_txpool",
+            ),
+            (
+                JsonRpcRequest::GetTransactionPoolBacklog(GetTransactionPoolBacklogRequest {}),
+                "get_txpool_backlog",
+            ),
+            (
+                JsonRpcRequest::GetTxIdsLoose(GetTxIdsLooseRequest::default()),
+                "get_txids_loose",
+            ),
+        ] {
+            let json = serde_json::to_value(&request).unwrap();
+            assert_eq!(json["method"], method);
+            assert_eq!(from_value::<JsonRpcRequest>(json).unwrap(), request);
+        }
+    }
 }
@@ -6,13 +6,14 @@ use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "epee")]
 use cuprate_epee_encoding::{
+    container_as_blob::ContainerAsBlob,
     epee_object, error,
     macros::bytes::{Buf, BufMut},
-    read_epee_value, write_field, EpeeObject, EpeeObjectBuilder, EpeeValue,
+    read_epee_value, write_field, EpeeObject, EpeeObjectBuilder, EpeeValue, Marker,
 };
 
 //---------------------------------------------------------------------------------------------------- Free
-/// TODO: <https://github.com/Cuprate/cuprate/pull/229#discussion_r1690531904>.
+/// Compresses an integer array, by writing each integer as a varint.
 ///
 /// Used for [`Distribution::CompressedBinary::distribution`].
 #[doc = crate::macros::monero_definition_link!(
@@ -21,21 +22,67 @@ use cuprate_epee_encoding::{
     45..=55
 )]
 #[cfg(any(feature = "epee", feature = "serde"))]
-fn compress_integer_array(_: &[u64]) -> Vec<u8> {
-    todo!()
+fn compress_integer_array(array: &[u64]) -> Vec<u8> {
+    // A varint is at most 10 bytes, 1 is enough for the small integers that make up most distributions.
+    let mut bytes = Vec::with_capacity(array.len());
+
+    for &int in array {
+        let mut int = int;
+
+        while int >= 0x80 {
+            #[expect(clippy::cast_possible_truncation, reason = "masked to 7 bits")]
+            bytes.push((int & 0x7f) as u8 | 0x80);
+            int >>= 7;
+        }
+
+        #[expect(clippy::cast_possible_truncation, reason = "int < 0x80")]
+        bytes.push(int as u8);
+    }
+
+    bytes
 }
 
-/// TODO: <https://github.com/Cuprate/cuprate/pull/229#discussion_r1690531904>.
+/// Decompresses an integer array created with [`compress_integer_array`].
 ///
 /// Used for [`Distribution::CompressedBinary::distribution`].
+///
+/// Returns [`None`] if `bytes` contains an invalid varint.
 #[doc = crate::macros::monero_definition_link!(
     "cc73fe71162d564ffda8e549b79a350bca53c454",
     "rpc/core_rpc_server_commands_defs.h",
     57..=72
 )]
 #[cfg(any(feature = "epee", feature = "serde"))]
-fn decompress_integer_array(_: &[u8]) -> Vec<u64> {
-    todo!()
+fn decompress_integer_array(bytes: &[u8]) -> Option<Vec<u64>> {
+    let mut array = Vec::with_capacity(bytes.len());
+
+    let mut int = 0_u64;
+    let mut shift = 0;
+
+    for &byte in bytes {
+        let bits = u64::from(byte & 0x7f);
+
+        // The varint would overflow a `u64`.
+        if shift >= u64::BITS || (bits << shift) >> shift != bits {
+            return None;
+        }
+
+        int |= bits << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            array.push(int);
+            int = 0;
+            shift = 0;
+        }
+    }
+
+    // The last varint must be complete.
+    if shift != 0 {
+        return None;
+    }
+
+    Some(array)
 }
 
 //---------------------------------------------------------------------------------------------------- Distribution
@@ -148,10 +195,36 @@ fn deserialize_compressed_data_as_distribution<'de, D>(d: D) -> Result<Vec<u64>,
 where
     D: serde::Deserializer<'de>,
 {
-    Vec::<u8>::deserialize(d).map(|v| decompress_integer_array(&v))
+    let bytes = Vec::<u8>::deserialize(d)?;
+    decompress_integer_array(&bytes)
+        .ok_or_else(|| serde::de::Error::custom("invalid compressed distribution"))
 }
 
 //---------------------------------------------------------------------------------------------------- Epee
+/// The `distribution` field, which `monerod` writes as an array of integers,
+/// or as a blob of little-endian [`u64`]s when `binary` is `true`.
+#[cfg(feature = "epee")]
+struct DistributionData(Vec<u64>);
+
+#[cfg(feature = "epee")]
+impl EpeeValue for DistributionData {
+    const MARKER: Marker = <Vec<u64>>::MARKER;
+
+    fn read<B: Buf>(r: &mut B, marker: &Marker) -> error::Result<Self> {
+        let distribution = if marker.is_seq {
+            Vec::<u64>::read(r, marker)?
+        } else {
+            ContainerAsBlob::<u64>::read(r, marker)?.into()
+        };
+
+        Ok(Self(distribution))
+    }
+
+    fn write<B: BufMut>(self, w: &mut B) -> error::Result<()> {
+        self.0.write(w)
+    }
+}
+
 #[cfg(feature = "epee")]
 /// [`EpeeObjectBuilder`] for [`Distribution`].
 ///
@@ -177,6 +250,9 @@ impl EpeeObjectBuilder<Distribution> for __DistributionEpeeBuilder {
                     $(
                         stringify!($field) => { self.$field = Some(read_epee_value(r)?); },
                     )*
+                    "distribution" => {
+                        self.distribution = Some(read_epee_value::<DistributionData, B>(r)?.0);
+                    }
                     _ => return Ok(false),
                 }
             };
@@ -188,8 +264,7 @@ impl EpeeObjectBuilder<Distribution> for __DistributionEpeeBuilder {
             amount,
             binary,
             compress,
-            compressed_data,
-            distribution
+            compressed_data
         }
 
         Ok(true)
@@ -202,48 +277,84 @@ impl EpeeObjectBuilder<Distribution> for __DistributionEpeeBuilder {
         let base = self.base.ok_or(ELSE)?;
         let amount = self.amount.ok_or(ELSE)?;
 
+        // INVARIANT:
+        // Empty containers are not serialized, so a missing
+        // `compressed_data` or `distribution` is an empty distribution.
+        let binary = self.binary.unwrap_or_default();
+        let compress = self.compress.unwrap_or_default();
+
         let distribution = if let Some(compressed_data) = self.compressed_data {
-            let distribution = decompress_integer_array(&compressed_data);
+            let distribution = decompress_integer_array(&compressed_data).ok_or(
+                error::Error::Value("Invalid compressed distribution".into()),
+            )?;
             Distribution::CompressedBinary(DistributionCompressedBinary {
                 start_height,
                 base,
                 distribution,
                 amount,
             })
-        } else if let Some(distribution) = self.distribution {
-            Distribution::Uncompressed(DistributionUncompressed {
-                binary: self.binary.ok_or(ELSE)?,
-                distribution,
+        } else if binary && compress && self.distribution.is_none() {
+            Distribution::CompressedBinary(DistributionCompressedBinary {
                 start_height,
                 base,
+                distribution: vec![],
                 amount,
             })
         } else {
-            return Err(ELSE);
+            Distribution::Uncompressed(DistributionUncompressed {
+                binary,
+                distribution: self.distribution.unwrap_or_default(),
+                start_height,
+                base,
+                amount,
+            })
         };
 
         Ok(distribution)
     }
 }
 
+// Custom epee implementation, as `monerod` writes the
+// distribution differently depending on `binary` and `compress`.
+//
+// The encoder sorts the fields by name, like `monerod`, so the order here does not matter.
 #[cfg(feature = "epee")]
 impl EpeeObject for Distribution {
     type Builder = __DistributionEpeeBuilder;
 
     fn number_of_fields(&self) -> u64 {
-        match self {
-            // Inner struct fields + `compress`.
-            Self::Uncompressed(s) => s.number_of_fields() + 1,
-            // Inner struct fields + `compress` + `binary`.
-            Self::CompressedBinary(s) => s.number_of_fields() + 2,
-        }
+        // `amount`, `start_height`, `binary`, `compress`, `base`.
+        const FIELDS: u64 = 5;
+
+        let data_written = match self {
+            Self::Uncompressed(s) => !s.distribution.is_empty(),
+            Self::CompressedBinary(s) => !s.distribution.is_empty(),
+        };
+
+        FIELDS + u64::from(data_written)
     }
 
     fn write_fields<B: BufMut>(self, w: &mut B) -> error::Result<()> {
         match self {
-            Self::Uncompressed(s) => {
-                s.write_fields(w)?;
+            Self::Uncompressed(DistributionUncompressed {
+                start_height,
+                base,
+                distribution,
+                amount,
+                binary,
+            }) => {
+                write_field(amount, "amount", w)?;
+                write_field(start_height, "start_height", w)?;
+                write_field(binary, "binary", w)?;
                 write_field(false, "compress", w)?;
+
+                if binary {
+                    write_field(ContainerAsBlob::from(distribution), "distribution", w)?;
+                } else {
+                    write_field(distribution, "distribution", w)?;
+                }
+
+                write_field(base, "base", w)?;
             }
 
             Self::CompressedBinary(DistributionCompressedBinary {
@@ -252,15 +363,12 @@ impl EpeeObject for Distribution {
                 distribution,
                 amount,
             }) => {
-                let compressed_data = compress_integer_array(&distribution);
-
-                start_height.write(w)?;
-                base.write(w)?;
-                compressed_data.write(w)?;
-                amount.write(w)?;
-
+                write_field(amount, "amount", w)?;
+                write_field(start_height, "start_height", w)?;
                 write_field(true, "binary", w)?;
                 write_field(true, "compress", w)?;
+                write_field(compress_integer_array(&distribution), "compressed_data", w)?;
+                write_field(base, "base", w)?;
             }
         }
 
@@ -271,29 +379,79 @@ impl EpeeObject for Distribution {
 //---------------------------------------------------------------------------------------------------- Tests
 #[cfg(test)]
 mod tests {
-    // use pretty_assertions::assert_eq;
-
-    // use super::*;
-
-    // TODO: re-enable tests after (de)compression functions are implemented.
-
-    // /// Tests that [`compress_integer_array`] outputs as expected.
-    // #[test]
-    // fn compress() {
-    //     let varints = &[16_384, 16_383, 16_382, 16_381];
-    //     let bytes = compress_integer_array(varints).unwrap();
-
-    //     let expected = [2, 0, 1, 0, 253, 255, 249, 255, 245, 255];
-    //     assert_eq!(expected, *bytes);
-    // }
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Tests that [`compress_integer_array`] outputs as expected.
+    #[test]
+    fn compress() {
+        let varints = &[16_384, 16_383, 0, 127, 128, u64::MAX];
+        let bytes = compress_integer_array(varints);
+
+        let expected = [
+            0x80, 0x80, 0x01, // 16_384
+            0xff, 0x7f, // 16_383
+            0x00, // 0
+            0x7f, // 127
+            0x80, 0x01, // 128
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, // u64::MAX
+        ];
+        assert_eq!(expected, *bytes);
+    }
 
-    // /// Tests that [`decompress_integer_array`] outputs as expected.
-    // #[test]
-    // fn decompress() {
-    //     let bytes = &[2, 0, 1, 0, 253, 255, 249, 255, 245, 255];
-    //     let varints = decompress_integer_array(bytes);
+    /// Tests that [`decompress_integer_array`] outputs as expected.
+    #[test]
+    fn decompress() {
+        let bytes = &[0x80, 0x80, 0x01, 0xff, 0x7f, 0x00, 0x80, 0x01];
+        let varints = decompress_integer_array(bytes).unwrap();
+
+        let expected = vec![16_384, 16_383, 0, 128];
+        assert_eq!(expected, varints);
+
+        // Incomplete varint.
+        assert_eq!(decompress_integer_array(&[0x80]), None);
+        // Overflows a `u64`.
+        assert_eq!(decompress_integer_array(&[0xff; 10]), None);
+        assert_eq!(
+            decompress_integer_array(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]),
+            None
+        );
+    }
 
-    //     let expected = vec![16_384, 16_383, 16_382, 16_381];
-    //     assert_eq!(expected, varints);
-    // }
+    /// Tests that every kind of [`Distribution`] survives an epee round-trip.
+    #[cfg(feature = "epee")]
+    #[test]
+    fn epee_round_trip() {
+        let distributions = [
+            Distribution::Uncompressed(DistributionUncompressed {
+                start_height: 1_462_078,
+                base: 0,
+                distribution: vec![1, 2, 3],
+                amount: 0,
+                binary: false,
+            }),
+            Distribution::Uncompressed(DistributionUncompressed {
+                start_height: 1_462_078,
+                base: 5,
+                distribution: vec![1, 2, u64::MAX],
+                amount: 0,
+                binary: true,
+            }),
+            Distribution::CompressedBinary(DistributionCompressedBinary {
+                start_height: 1_462_078,
+                base: 5,
+                distribution: vec![16_384, 16_383, 0],
+                amount: 2_628_780_000,
+            }),
+            Distribution::CompressedBinary(DistributionCompressedBinary::default()),
+            Distribution::Uncompressed(DistributionUncompressed::default()),
+        ];
+
+        for distribution in distributions {
+            let bytes = cuprate_epee_encoding::to_bytes(distribution.clone()).unwrap();
+            let got: Distribution = cuprate_epee_encoding::from_bytes(&mut bytes.freeze()).unwrap();
+            assert_eq!(got, distribution);
+        }
+    }
 }
@@ -8,11 +8,16 @@
 /// Returned by [`crate::ops::property::db_version`].
 ///
 /// This is incremented by 1 when `cuprate_blockchain`'s
-/// structure/schema/tables change.
+/// structure/schema/tables change, a migration from the
+/// previous version must be added to `src/migrations.rs`.
+///
+/// Version history:
+/// - `0`: databases created before the version was stored
+/// - `1`: the version is stored in [`Properties`](crate::tables::Properties)
 ///
 /// This is akin to `VERSION` in `monerod`:
 /// <https://github.com/monero-project/monero/blob/c8214782fb2a769c57382a999eaf099691c836e7/src/blockchain_db/lmdb/db_lmdb.cpp#L57>
-pub const DATABASE_VERSION: u64 = 0;
+pub const DATABASE_VERSION: u64 = 1;
 
 //---------------------------------------------------------------------------------------------------- Tests
 #[cfg(test)]
@@ -22,6 +22,7 @@ compile_error!("Cuprate is only compatible with 64-bit CPUs");
 
 mod constants;
 mod free;
+mod migrations;
 
 pub use constants::DATABASE_VERSION;
 pub use cuprate_database;
@@ -0,0 +1,138 @@
+//! Database schema migrations.
+//!
+//! [`migrate`] upgrades a database from one version to the next,
+//! it is run by [`cuprate_database::migration::migrate`] in [`crate::open`].
+//!
+//! See [`crate::DATABASE_VERSION`] for the version history.
+
+//---------------------------------------------------------------------------------------------------- Import
+use cuprate_database::{DbResult, EnvInner};
+
+//---------------------------------------------------------------------------------------------------- Free functions
+/// Migrate the database from `version` to `version + 1`.
+///
+/// # Errors
+/// This returns the [`cuprate_database::RuntimeError`] of a failed migration.
+///
+/// # Panics
+/// This panics if there is no migration from `version`,
+/// i.e. `version >= DATABASE_VERSION`.
+#[expect(
+    clippy::unnecessary_wraps,
+    reason = "Migrations can fail, the only current one is a no-op"
+)]
+pub(crate) fn migrate<'env, Ei: EnvInner<'env>>(
+    version: u64,
+    _env_inner: &Ei,
+    _tx_rw: &Ei::Rw<'_>,
+) -> DbResult<()> {
+    match version {
+        // Version 0 only lacks the `Properties` table,
+        // which is created with all other tables on open.
+        0 => Ok(()),
+        _ => unreachable!("no migration from database version {version}"),
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Tests
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use cuprate_database::{
+        migration, ConcreteEnv, DatabaseRo, DatabaseRw, Env, EnvInner, InitError, TxRw,
+    };
+    use cuprate_test_utils::data::BLOCK_V1_TX2;
+
+    use crate::{
+        config::{Config, ConfigBuilder},
+        ops::{block::add_block, property::db_version},
+        tables::{OpenTables, Tables, TablesMut},
+        DATABASE_VERSION,
+    };
+
+    /// Create a [`Config`] in a temporary directory.
+    fn tmp_config() -> (Config, tempfile::TempDir) {
+        let tempdir = tempfile::tempdir().unwrap();
+        let config = ConfigBuilder::new()
+            .data_directory(tempdir.path().into())
+            .low_power()
+            .build();
+
+        (config, tempdir)
+    }
+
+    /// New databases are created at [`DATABASE_VERSION`].
+    #[test]
+    fn new_database() {
+        let (config, _tmp) = tmp_config();
+        let env = crate::open(config).unwrap();
+
+        let env_inner = env.env_inner();
+        let tx_ro = env_inner.tx_ro().unwrap();
+        let tables = env_inner.open_tables(&tx_ro).unwrap();
+
+        assert_eq!(db_version(tables.properties()).unwrap(), DATABASE_VERSION);
+    }
+
+    /// A version 0 database, with blocks but without a
+    /// stored version, is migrated to [`DATABASE_VERSION`].
+    #[test]
+    fn v0_database() {
+        let (config, _tmp) = tmp_config();
+
+        // Create the version 0 database.
+        {
+            let env = ConcreteEnv::open(config.db_config.clone()).unwrap();
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+            OpenTables::create_tables(&env_inner, &tx_rw).unwrap();
+
+            let mut block = BLOCK_V1_TX2.clone();
+            block.height = 0;
+
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+            add_block(&block, &mut tables).unwrap();
+            assert!(tables.properties().is_empty().unwrap());
+
+            drop(tables);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        let env = crate::open(config).unwrap();
+
+        let env_inner = env.env_inner();
+        let tx_ro = env_inner.tx_ro().unwrap();
+        let tables = env_inner.open_tables(&tx_ro).unwrap();
+
+        assert_eq!(db_version(tables.properties()).unwrap(), DATABASE_VERSION);
+        assert_eq!(tables.block_infos().len().unwrap(), 1);
+    }
+
+    /// Databases from a newer version are refused.
+    #[test]
+    fn newer_database() {
+        let (config, _tmp) = tmp_config();
+
+        {
+            let env = crate::open(config.clone()).unwrap();
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+            tables
+                .properties_mut()
+                .put(&migration::VERSION_KEY, &(DATABASE_VERSION + 1))
+                .unwrap();
+
+            drop(tables);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        assert!(matches!(
+            crate::open(config),
+            Err(InitError::UnsupportedVersion { found, supported })
+                if found == DATABASE_VERSION + 1 && supported == DATABASE_VERSION
+        ));
+    }
+}
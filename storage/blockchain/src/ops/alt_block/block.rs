@@ -203,6 +203,7 @@ pub fn get_alt_block_extended_header_from_height(
         ),
         block_weight: block_info.weight,
         long_term_weight: block_info.long_term_weight,
+        cumulative_generated_coins: None,
     })
 }
 
@@ -307,6 +308,7 @@ mod tests {
                     alt_block.block.header.hardfork_version
                 );
                 assert_eq!(header.vote, alt_block.block.header.hardfork_signal);
+                assert_eq!(header.cumulative_generated_coins, None);
 
                 let block_hash = get_alt_block_hash(&height, chain_id, &tables).unwrap();
 
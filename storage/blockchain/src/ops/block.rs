@@ -1,6 +1,8 @@
 //! Block functions.
 
 //---------------------------------------------------------------------------------------------------- Import
+use std::io;
+
 use bytemuck::TransparentWrapper;
 use bytes::Bytes;
 use monero_serai::{
@@ -16,18 +18,20 @@ use cuprate_helper::{
     map::{combine_low_high_bits_to_u128, split_u128_into_low_high_bits},
     tx::tx_fee,
 };
+use cuprate_pruning::PruningSeed;
 use cuprate_types::{
     AltBlockInformation, BlockCompleteEntry, ChainId, ExtendedBlockHeader, HardFork,
-    TransactionBlobs, VerifiedBlockInformation, VerifiedTransactionInformation,
+    PrunedTxBlobEntry, TransactionBlobs, VerifiedBlockInformation, VerifiedTransactionInformation,
 };
 
 use crate::{
     ops::{
         alt_block,
-        blockchain::{chain_height, cumulative_generated_coins},
+        blockchain::{chain_height, cumulative_generated_coins, prune_blocks},
         macros::doc_error,
         output::get_rct_num_outputs,
-        tx::{add_tx, remove_tx},
+        property::{get_pruned_height, set_pruned_height},
+        tx::{add_tx, prune_tx, remove_tx},
     },
     tables::{BlockHeights, BlockInfos, Tables, TablesIter, TablesMut},
     types::{BlockHash, BlockHeight, BlockInfo},
@@ -134,6 +138,10 @@ pub fn add_block(block: &VerifiedBlockInformation, tables: &mut impl TablesMut)
         .block_heights_mut()
         .put(&block.block_hash, &block.height)?;
 
+    //------------------------------------------------------ Pruning
+    // If the database is pruned, prune the block that just left the blockchain tip.
+    prune_blocks(1, tables)?;
+
     Ok(())
 }
 
@@ -174,6 +182,18 @@ pub fn pop_block(
         transactions: block_txs_hashes,
     };
 
+    // Pruned transactions have lost their prunable data, so can not be added to an alt chain.
+    if move_to_alt_chain.is_some()
+        && !block.transactions.is_empty()
+        && !tables
+            .tx_blobs()
+            .contains(&(block_info.mining_tx_index + 1))?
+    {
+        return Err(RuntimeError::Io(io::Error::other(
+            "a pruned block can not be moved to an alt chain",
+        )));
+    }
+
     //------------------------------------------------------ Transaction / Outputs / Key Images
     remove_tx(&block.miner_transaction.hash(), tables)?;
 
@@ -221,9 +241,45 @@ pub fn pop_block(
         }
     }
 
+    //------------------------------------------------------ Pruning
+    // The chain height is now `block_height`, the pruned height can not be above it.
+    if get_pruned_height(tables.properties())? > block_height {
+        set_pruned_height(&block_height, tables.properties_mut())?;
+    }
+
     Ok((block_height, block_info.block_hash, block))
 }
 
+//---------------------------------------------------------------------------------------------------- `prune_block`
+/// Prune a block's transactions, if the block should be pruned with this [`PruningSeed`].
+///
+/// Blocks in the seed's stripe and blocks in the blockchain tip are kept, see
+/// [`PruningSeed::has_full_block`]. The miner transaction is never pruned.
+///
+/// This does not update the pruned height, use
+/// [`prune_blocks`](crate::ops::blockchain::prune_blocks) to prune the blockchain.
+#[doc = doc_error!()]
+pub fn prune_block(
+    block_height: &BlockHeight,
+    pruning_seed: &PruningSeed,
+    tables: &mut impl TablesMut,
+) -> DbResult<()> {
+    let chain_height = chain_height(tables.block_heights())?;
+    if pruning_seed.has_full_block(*block_height, chain_height) {
+        return Ok(());
+    }
+
+    let miner_tx_idx = tables.block_infos().get(block_height)?.mining_tx_index;
+    let numb_txs = usize_to_u64(tables.block_txs_hashes().get(block_height)?.len());
+
+    // The block's transactions directly follow the miner transaction.
+    for tx_id in miner_tx_idx + 1..=miner_tx_idx + numb_txs {
+        prune_tx(&tx_id, tables)?;
+    }
+
+    Ok(())
+}
+
 //---------------------------------------------------------------------------------------------------- `get_block_blob_with_tx_indexes`
 /// Retrieve a block's raw bytes, the index of the miner transaction and the number of non miner-txs in the block.
 ///
@@ -277,8 +333,31 @@ pub fn get_block_complete_entry_from_height(
         get_block_blob_with_tx_indexes(block_height, tables)?;
 
     let first_tx_idx = miner_tx_idx + 1;
+    let tx_ids = first_tx_idx..(usize_to_u64(numb_non_miner_txs) + first_tx_idx);
 
-    let tx_blobs = (first_tx_idx..(usize_to_u64(numb_non_miner_txs) + first_tx_idx))
+    // All of a block's transactions are pruned together, see `prune_block`.
+    let pruned = numb_non_miner_txs != 0 && !tables.tx_blobs().contains(&first_tx_idx)?;
+
+    if pruned {
+        let txs = tx_ids
+            .map(|idx| {
+                Ok(PrunedTxBlobEntry {
+                    blob: Bytes::from(tables.pruned_tx_blobs().get(&idx)?.0),
+                    prunable_hash: tables.prunable_hashes().get(&idx)?.into(),
+                })
+            })
+            .collect::<Result<_, RuntimeError>>()?;
+
+        return Ok(BlockCompleteEntry {
+            block: Bytes::from(block_blob),
+            txs: TransactionBlobs::Pruned(txs),
+            pruned: true,
+            // The weight can not be calculated from pruned transactions, so it must be sent.
+            block_weight: usize_to_u64(tables.block_infos().get(block_height)?.weight),
+        });
+    }
+
+    let tx_blobs = tx_ids
         .map(|idx| {
             let tx_blob = tables.tx_blobs().get(&idx)?.0;
 
@@ -340,6 +419,7 @@ pub fn get_block_extended_header_from_height(
         timestamp: block_header.timestamp,
         block_weight: block_info.weight,
         long_term_weight: block_info.long_term_weight,
+        cumulative_generated_coins: Some(block_info.cumulative_generated_coins),
     })
 }
 
@@ -421,6 +501,8 @@ pub fn block_exists(
 #[cfg(test)]
 #[expect(clippy::too_many_lines)]
 mod test {
+    use std::num::NonZero;
+
     use pretty_assertions::assert_eq;
 
     use cuprate_database::{Env, EnvInner, TxRw};
@@ -587,6 +669,120 @@ mod test {
         assert_all_tables_are_empty(&env);
     }
 
+    /// Tests [`get_block_complete_entry_from_height`] returns pruned blocks.
+    #[test]
+    fn pruned_block_complete_entry() {
+        let (env, _tmp) = tmp_concrete_env();
+        let env_inner = env.env_inner();
+
+        let mut blocks = [BLOCK_V1_TX2.clone(), BLOCK_V9_TX3.clone()];
+
+        let tx_rw = env_inner.tx_rw().unwrap();
+        let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+        for (height, block) in blocks.iter_mut().enumerate() {
+            block.height = height;
+            add_block(block, &mut tables).unwrap();
+        }
+
+        // Blocks in the blockchain tip are never pruned.
+        let pruning_seed = PruningSeed::new_pruned(1, 3).unwrap();
+        prune_block(&0, &pruning_seed, &mut tables).unwrap();
+        assert_eq!(tables.pruned_tx_blobs().len().unwrap(), 0);
+
+        // Prune the first block's transactions.
+        let miner_tx_idx = tables.block_infos().get(&0).unwrap().mining_tx_index;
+        for i in 1..=usize_to_u64(blocks[0].txs.len()) {
+            prune_tx(&(miner_tx_idx + i), &mut tables).unwrap();
+        }
+
+        drop(tables);
+        TxRw::commit(tx_rw).unwrap();
+
+        let tx_ro = env_inner.tx_ro().unwrap();
+        let tables = env_inner.open_tables(&tx_ro).unwrap();
+
+        let entry = get_block_complete_entry_from_height(&0, &tables).unwrap();
+        assert!(entry.pruned);
+        assert_eq!(entry.block, blocks[0].block_blob);
+        assert_eq!(entry.block_weight, usize_to_u64(blocks[0].weight));
+
+        let TransactionBlobs::Pruned(txs) = entry.txs else {
+            panic!("Block should be pruned");
+        };
+        assert_eq!(txs.len(), blocks[0].txs.len());
+
+        for (entry, tx) in txs.iter().zip(&blocks[0].txs) {
+            let (pruned_blob, _, prunable_hash) =
+                crate::ops::tx::tx_pruned_prunable_blobs(&tx.tx, &tx.tx_blob);
+
+            assert_eq!(entry.blob, pruned_blob);
+            assert_eq!(*entry.prunable_hash, prunable_hash);
+        }
+
+        // The other block is still complete.
+        let entry = get_block_complete_entry_from_height(&1, &tables).unwrap();
+        assert!(!entry.pruned);
+        assert_eq!(entry.txs.take_normal().unwrap().len(), blocks[1].txs.len());
+    }
+
+    /// Tests [`pop_block`] removes pruned blocks and clamps the pruned height to the chain height.
+    #[test]
+    fn pop_pruned_blocks() {
+        let (env, _tmp) = tmp_concrete_env();
+        let env_inner = env.env_inner();
+
+        let mut blocks = [BLOCK_V1_TX2.clone(), BLOCK_V9_TX3.clone()];
+
+        {
+            let tx_rw = env_inner.tx_rw().unwrap();
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+            for (height, block) in blocks.iter_mut().enumerate() {
+                block.height = height;
+                add_block(block, &mut tables).unwrap();
+            }
+
+            for (height, block) in blocks.iter().enumerate() {
+                let miner_tx_idx = tables.block_infos().get(&height).unwrap().mining_tx_index;
+                for i in 1..=usize_to_u64(block.txs.len()) {
+                    prune_tx(&(miner_tx_idx + i), &mut tables).unwrap();
+                }
+            }
+            set_pruned_height(&2, tables.properties_mut()).unwrap();
+
+            drop(tables);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        // Pruned blocks can not be moved to an alt chain.
+        {
+            let tx_rw = env_inner.tx_rw().unwrap();
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+            let chain_id = ChainId(NonZero::new(1).unwrap());
+            assert!(pop_block(Some(chain_id), &mut tables).is_err());
+
+            drop(tables);
+            TxRw::abort(tx_rw).unwrap();
+        }
+
+        let tx_rw = env_inner.tx_rw().unwrap();
+        let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+        for height in (0..blocks.len()).rev() {
+            let (popped_height, block_hash, _) = pop_block(None, &mut tables).unwrap();
+            assert_eq!(popped_height, height);
+            assert_eq!(block_hash, blocks[height].block_hash);
+            assert_eq!(get_pruned_height(tables.properties()).unwrap(), height);
+        }
+
+        assert!(tables.block_infos().is_empty().unwrap());
+        assert!(tables.tx_blobs().is_empty().unwrap());
+        assert!(tables.pruned_tx_blobs().is_empty().unwrap());
+        assert!(tables.prunable_hashes().is_empty().unwrap());
+    }
+
     /// We should panic if: `block.height` > `u32::MAX`
     #[test]
     #[should_panic(expected = "block.height (4294967296) > u32::MAX")]
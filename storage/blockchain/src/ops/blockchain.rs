@@ -1,11 +1,18 @@
-//! Blockchain functions - chain height, generated coins, etc.
+//! Blockchain functions - chain height, generated coins, pruning, etc.
 
 //---------------------------------------------------------------------------------------------------- Import
+use std::cmp::min;
+
 use cuprate_database::{DatabaseRo, DbResult, RuntimeError};
+use cuprate_pruning::{PruningSeed, CRYPTONOTE_PRUNING_TIP_BLOCKS};
 
 use crate::{
-    ops::{block, macros::doc_error},
-    tables::{AltBlockHeights, BlockHeights, BlockInfos},
+    ops::{
+        block,
+        macros::doc_error,
+        property::{get_blockchain_pruning_seed, get_pruned_height, set_pruned_height},
+    },
+    tables::{AltBlockHeights, BlockHeights, BlockInfos, TablesMut},
     types::{BlockHash, BlockHeight},
 };
 
@@ -122,6 +129,41 @@ pub fn find_split_point(
     Ok(idx)
 }
 
+/// Prune the blocks that have left the blockchain tip, up to `max_blocks` of them.
+///
+/// Blocks are pruned in order starting at [`get_pruned_height`], until every block more
+/// than [`CRYPTONOTE_PRUNING_TIP_BLOCKS`] below the chain height has been pruned.
+/// Blocks in the database's pruning stripe are kept, see [`block::prune_block`].
+///
+/// This does nothing if the database is not pruned.
+///
+/// Returns `true` if there are no more blocks to prune.
+#[doc = doc_error!()]
+pub fn prune_blocks(max_blocks: usize, tables: &mut impl TablesMut) -> DbResult<bool> {
+    let pruning_seed = get_blockchain_pruning_seed(tables.properties())?;
+    if pruning_seed == PruningSeed::NotPruned {
+        return Ok(true);
+    }
+
+    let prune_until =
+        chain_height(tables.block_heights())?.saturating_sub(CRYPTONOTE_PRUNING_TIP_BLOCKS);
+    let pruned_height = get_pruned_height(tables.properties())?;
+
+    if pruned_height >= prune_until {
+        return Ok(true);
+    }
+
+    let end = min(prune_until, pruned_height.saturating_add(max_blocks));
+
+    for height in pruned_height..end {
+        block::prune_block(&height, &pruning_seed, tables)?;
+    }
+
+    set_pruned_height(&end, tables.properties_mut())?;
+
+    Ok(end == prune_until)
+}
+
 //---------------------------------------------------------------------------------------------------- Tests
 #[cfg(test)]
 mod test {
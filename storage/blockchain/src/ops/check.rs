@@ -0,0 +1,1129 @@
+//! Database integrity checks.
+//!
+//! [`check`] walks the main-chain and alt-chain tables and reports data that is
+//! inconsistent between them, [`repair`] then pops blocks with [`pop_block`]
+//! until only the consistent blocks are left.
+
+//---------------------------------------------------------------------------------------------------- Import
+use std::{collections::HashMap, fmt};
+
+use monero_serai::{
+    block::{Block, BlockHeader},
+    transaction::{Input, Pruned, Timelock, Transaction, TransactionPrefix},
+};
+
+use cuprate_database::{DatabaseIter, DatabaseRo, DbResult, RuntimeError, Table};
+use cuprate_helper::{
+    cast::{u32_to_usize, u64_to_usize, usize_to_u64},
+    map::{combine_low_high_bits_to_u128, u64_to_timelock},
+};
+use cuprate_types::Chain;
+
+use crate::{
+    ops::{block::pop_block, macros::doc_error},
+    tables::{
+        AltBlockBlobs, AltBlockHeights, AltBlocksInfo, AltChainInfos, AltTransactionBlobs,
+        AltTransactionInfos, BlockHeaderBlobs, BlockHeights, BlockInfos, BlockTxsHashes, KeyImages,
+        NumOutputs, Outputs, PrunableHashes, PrunedTxBlobs, RctOutputs, TablesIter, TablesMut,
+        TxBlobs, TxHeights, TxIds, TxOutputs, TxUnlockTime,
+    },
+    types::{Amount, BlockHash, BlockHeight, BlockInfo, PreRctOutputId, RawChainId, TxHash},
+};
+
+//---------------------------------------------------------------------------------------------------- Report
+/// Where a [`Problem`] was found.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Location {
+    /// The data of the main-chain block at this height.
+    Block(BlockHeight),
+    /// Main-chain data that does not belong to a single block,
+    /// e.g. entries left in a table above the top block.
+    MainChain,
+    /// The alt-chain tables.
+    AltChain,
+}
+
+/// An inconsistency found by [`check`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// Where the problem was found.
+    pub location: Location,
+    /// The name of the table with the inconsistent data.
+    pub table: &'static str,
+    /// What is inconsistent.
+    pub description: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Location::Block(height) => write!(f, "block {height}")?,
+            Location::MainChain => f.write_str("main chain")?,
+            Location::AltChain => f.write_str("alt chain")?,
+        }
+
+        write!(f, ", {}: {}", self.table, self.description)
+    }
+}
+
+/// The result of [`check`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CheckReport {
+    /// The amount of blocks in [`BlockInfos`].
+    pub chain_height: BlockHeight,
+    /// The problems found, in the order they were found.
+    pub problems: Vec<Problem>,
+}
+
+impl CheckReport {
+    /// Returns `true` if no problems were found.
+    pub const fn is_consistent(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// The chain height all blocks below are consistent at,
+    /// i.e. the chain height [`repair`] pops blocks back to.
+    ///
+    /// This is [`CheckReport::chain_height`] if no
+    /// problems were found at a [`Location::Block`].
+    pub fn consistent_chain_height(&self) -> BlockHeight {
+        self.problems
+            .iter()
+            .filter_map(|problem| match problem.location {
+                Location::Block(height) => Some(height),
+                Location::MainChain | Location::AltChain => None,
+            })
+            .min()
+            .unwrap_or(self.chain_height)
+    }
+
+    /// Returns `true` if problems were found in the alt-chain tables.
+    pub fn has_alt_chain_problems(&self) -> bool {
+        self.problems
+            .iter()
+            .any(|problem| problem.location == Location::AltChain)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Free functions
+/// Check the database for inconsistencies between tables.
+///
+/// This checks, for every main-chain block:
+/// - its [`BlockInfos`], [`BlockHeights`], [`BlockHeaderBlobs`] and [`BlockTxsHashes`] entries agree
+/// - its cumulative difficulty is above and its cumulative generated coins are not below the previous block's
+/// - its transactions have the expected [`TxId`](crate::types::TxId)s in [`TxIds`], [`TxHeights`] and [`TxBlobs`]/[`PrunedTxBlobs`]
+/// - their key images are in [`KeyImages`] and their outputs are in [`TxOutputs`] and [`Outputs`]/[`RctOutputs`]
+///
+/// that no table has entries not belonging to a block, and that
+/// the alt-chain tables only reference data that exists.
+///
+/// The whole database is read, so this can take a long time.
+#[doc = doc_error!()]
+pub fn check(tables: &impl TablesIter) -> DbResult<CheckReport> {
+    let mut checker = Checker::default();
+
+    checker.check_blocks(tables)?;
+    checker.check_table_lens(tables)?;
+    checker.check_alt_blocks(tables)?;
+
+    Ok(checker.report)
+}
+
+/// Pop blocks with [`pop_block`] until only the blocks
+/// below [`CheckReport::consistent_chain_height`] are left.
+///
+/// This returns the heights and hashes of the popped blocks, top block first.
+///
+/// Problems at [`Location::MainChain`] and [`Location::AltChain`] are not repaired, the
+/// alt-chain tables can be emptied with [`flush_alt_blocks`](crate::ops::alt_block::flush_alt_blocks).
+#[doc = doc_error!()]
+///
+/// [`pop_block`] needs the data of the popped blocks, so this also errors if the data it
+/// reads is missing or the blocks are pruned, the transaction should then be aborted.
+pub fn repair(
+    report: &CheckReport,
+    tables: &mut impl TablesMut,
+) -> DbResult<Vec<(BlockHeight, BlockHash)>> {
+    let consistent_chain_height = usize_to_u64(report.consistent_chain_height());
+    let mut popped = Vec::new();
+
+    while tables.block_infos().len()? > consistent_chain_height {
+        let (height, hash, _) = pop_block(None, tables)?;
+        popped.push((height, hash));
+    }
+
+    Ok(popped)
+}
+
+//---------------------------------------------------------------------------------------------------- Checker
+/// The state of a running [`check`].
+#[derive(Default)]
+struct Checker {
+    /// The report being built.
+    report: CheckReport,
+    /// The [`TxId`](crate::types::TxId) of the next transaction.
+    num_txs: u64,
+    /// The amount of transactions with an unlock time.
+    num_unlock_times: u64,
+    /// The amount of key images.
+    num_key_images: u64,
+    /// The amount of pre-RCT outputs of each amount.
+    num_outputs: HashMap<Amount, u64>,
+    /// The amount of RCT outputs.
+    num_rct_outputs: u64,
+}
+
+/// The parts of a stored transaction the checks need.
+struct StoredTx {
+    /// The hash, [`None`] if the transaction is pruned.
+    hash: Option<TxHash>,
+    /// If this is a v1 transaction, i.e. its outputs are in [`Outputs`].
+    v1: bool,
+    /// The prefix.
+    prefix: TransactionPrefix,
+}
+
+impl Checker {
+    /// Record a problem.
+    fn problem(&mut self, location: Location, table: &'static str, description: String) {
+        self.report.problems.push(Problem {
+            location,
+            table,
+            description,
+        });
+    }
+
+    /// Check every main-chain block, in order.
+    fn check_blocks(&mut self, tables: &impl TablesIter) -> DbResult<()> {
+        let mut previous = None;
+
+        for (i, result) in tables.block_infos_iter().iter()?.enumerate() {
+            let (height, block_info) = result?;
+
+            if height != i {
+                self.problem(
+                    Location::Block(i),
+                    BlockInfos::NAME,
+                    format!("block {i} is missing, the next block is {height}"),
+                );
+            }
+
+            self.check_block(height, &block_info, previous.as_ref(), tables)?;
+            previous = Some(block_info);
+        }
+
+        self.report.chain_height = u64_to_usize(tables.block_infos().len()?);
+
+        Ok(())
+    }
+
+    /// Check a main-chain block and its transactions.
+    fn check_block(
+        &mut self,
+        height: BlockHeight,
+        block_info: &BlockInfo,
+        previous: Option<&BlockInfo>,
+        tables: &impl TablesIter,
+    ) -> DbResult<()> {
+        let location = Location::Block(height);
+        let block_hash = Hex(&block_info.block_hash);
+
+        //------------------------------------------------------ Block data
+        match get_opt(tables.block_heights(), &block_info.block_hash)? {
+            Some(h) if h == height => (),
+            Some(h) => self.problem(
+                location,
+                BlockHeights::NAME,
+                format!("block {block_hash} is at height {h}"),
+            ),
+            None => self.problem(
+                location,
+                BlockHeights::NAME,
+                format!("block {block_hash} is missing"),
+            ),
+        }
+
+        match get_opt(tables.block_header_blobs(), &height)? {
+            Some(blob) => match BlockHeader::read(&mut blob.0.as_slice()) {
+                Ok(header) => {
+                    if header.timestamp != block_info.timestamp {
+                        self.problem(
+                            location,
+                            BlockInfos::NAME,
+                            format!(
+                                "timestamp {} is not the header's timestamp {}",
+                                block_info.timestamp, header.timestamp
+                            ),
+                        );
+                    }
+
+                    let previous_hash = previous.map_or([0; 32], |previous| previous.block_hash);
+                    if header.previous != previous_hash {
+                        self.problem(
+                            location,
+                            BlockHeaderBlobs::NAME,
+                            format!(
+                                "previous block {} is not the block below {}",
+                                Hex(&header.previous),
+                                Hex(&previous_hash)
+                            ),
+                        );
+                    }
+                }
+                Err(e) => self.problem(
+                    location,
+                    BlockHeaderBlobs::NAME,
+                    format!("block header is invalid: {e}"),
+                ),
+            },
+            None => self.problem(
+                location,
+                BlockHeaderBlobs::NAME,
+                "block header is missing".to_string(),
+            ),
+        }
+
+        //------------------------------------------------------ Cumulative values
+        if let Some(previous) = previous {
+            let cumulative_difficulty = combine_low_high_bits_to_u128(
+                block_info.cumulative_difficulty_low,
+                block_info.cumulative_difficulty_high,
+            );
+            let previous_cumulative_difficulty = combine_low_high_bits_to_u128(
+                previous.cumulative_difficulty_low,
+                previous.cumulative_difficulty_high,
+            );
+
+            if cumulative_difficulty <= previous_cumulative_difficulty {
+                self.problem(
+                    location,
+                    BlockInfos::NAME,
+                    format!("cumulative difficulty {cumulative_difficulty} is not above the previous block's {previous_cumulative_difficulty}"),
+                );
+            }
+
+            // Not strictly increasing as this saturates, see `add_block`.
+            if block_info.cumulative_generated_coins < previous.cumulative_generated_coins {
+                self.problem(
+                    location,
+                    BlockInfos::NAME,
+                    format!(
+                        "cumulative generated coins {} is below the previous block's {}",
+                        block_info.cumulative_generated_coins, previous.cumulative_generated_coins
+                    ),
+                );
+            }
+        }
+
+        //------------------------------------------------------ Transactions
+        // The block's transactions directly follow the miner transaction.
+        if block_info.mining_tx_index != self.num_txs {
+            self.problem(
+                location,
+                BlockInfos::NAME,
+                format!(
+                    "miner transaction {} is not the next transaction {}",
+                    block_info.mining_tx_index, self.num_txs
+                ),
+            );
+            self.num_txs = block_info.mining_tx_index;
+        }
+
+        self.check_tx(height, None, tables)?;
+
+        match get_opt(tables.block_txs_hashes(), &height)? {
+            Some(tx_hashes) => {
+                for tx_hash in &tx_hashes.0 {
+                    self.check_tx(height, Some(tx_hash), tables)?;
+                }
+            }
+            None => self.problem(
+                location,
+                BlockTxsHashes::NAME,
+                "transaction hashes are missing".to_string(),
+            ),
+        }
+
+        if block_info.cumulative_rct_outs != self.num_rct_outputs {
+            self.problem(
+                location,
+                BlockInfos::NAME,
+                format!(
+                    "cumulative RCT outputs {} is not the RCT output count {}",
+                    block_info.cumulative_rct_outs, self.num_rct_outputs
+                ),
+            );
+            self.num_rct_outputs = block_info.cumulative_rct_outs;
+        }
+
+        Ok(())
+    }
+
+    /// Check the next transaction, in the block at `height`.
+    ///
+    /// `tx_hash` is [`None`] for miner transactions, their
+    /// hash is taken from their blob as they are never pruned.
+    fn check_tx(
+        &mut self,
+        height: BlockHeight,
+        tx_hash: Option<&TxHash>,
+        tables: &impl TablesIter,
+    ) -> DbResult<()> {
+        let location = Location::Block(height);
+        let tx_id = self.num_txs;
+        self.num_txs += 1;
+
+        match get_opt(tables.tx_heights(), &tx_id)? {
+            Some(h) if h == height => (),
+            Some(h) => self.problem(
+                location,
+                TxHeights::NAME,
+                format!("transaction {tx_id} is at height {h}"),
+            ),
+            None => self.problem(
+                location,
+                TxHeights::NAME,
+                format!("transaction {tx_id} is missing"),
+            ),
+        }
+
+        //------------------------------------------------------ Blob
+        let pruned_blob = get_opt(tables.pruned_tx_blobs(), &tx_id)?;
+
+        if pruned_blob.is_some() != tables.prunable_hashes().contains(&tx_id)? {
+            self.problem(
+                location,
+                PrunableHashes::NAME,
+                format!("prunable hash of transaction {tx_id} does not match its pruned state"),
+            );
+        }
+
+        let tx = match (get_opt(tables.tx_blobs(), &tx_id)?, pruned_blob) {
+            (Some(blob), pruned_blob) => {
+                if pruned_blob.is_some() {
+                    self.problem(
+                        location,
+                        PrunedTxBlobs::NAME,
+                        format!("transaction {tx_id} is both pruned and not pruned"),
+                    );
+                }
+
+                let tx = Transaction::read(&mut blob.0.as_slice()).map(|tx| StoredTx {
+                    hash: Some(tx.hash()),
+                    v1: matches!(tx, Transaction::V1 { .. }),
+                    prefix: tx.prefix().clone(),
+                });
+
+                Some((TxBlobs::NAME, tx))
+            }
+            (None, Some(blob)) => {
+                if tx_hash.is_none() {
+                    self.problem(
+                        location,
+                        PrunedTxBlobs::NAME,
+                        format!("miner transaction {tx_id} is pruned"),
+                    );
+                }
+
+                let tx = Transaction::<Pruned>::read(&mut blob.0.as_slice()).map(|tx| StoredTx {
+                    hash: None,
+                    v1: matches!(tx, Transaction::V1 { .. }),
+                    prefix: tx.prefix().clone(),
+                });
+
+                Some((PrunedTxBlobs::NAME, tx))
+            }
+            (None, None) => {
+                self.problem(
+                    location,
+                    TxBlobs::NAME,
+                    format!("transaction {tx_id} is missing"),
+                );
+                None
+            }
+        };
+
+        let tx = match tx {
+            Some((_, Ok(tx))) => Some(tx),
+            Some((table, Err(e))) => {
+                self.problem(
+                    location,
+                    table,
+                    format!("transaction {tx_id} is invalid: {e}"),
+                );
+                None
+            }
+            None => None,
+        };
+
+        //------------------------------------------------------ Hash
+        if let Some(tx_hash) = tx_hash
+            .copied()
+            .or_else(|| tx.as_ref().and_then(|tx| tx.hash))
+        {
+            match get_opt(tables.tx_ids(), &tx_hash)? {
+                Some(id) if id == tx_id => (),
+                Some(id) => self.problem(
+                    location,
+                    TxIds::NAME,
+                    format!(
+                        "transaction {} has id {id}, expected {tx_id}",
+                        Hex(&tx_hash)
+                    ),
+                ),
+                None => self.problem(
+                    location,
+                    TxIds::NAME,
+                    format!("transaction {} is missing", Hex(&tx_hash)),
+                ),
+            }
+        }
+
+        // The remaining checks need the transaction.
+        let Some(tx) = tx else {
+            return Ok(());
+        };
+
+        //------------------------------------------------------ Unlock time
+        if tx.prefix.additional_timelock != Timelock::None {
+            self.num_unlock_times += 1;
+        }
+
+        let unlock_time =
+            get_opt(tables.tx_unlock_time(), &tx_id)?.map_or(Timelock::None, u64_to_timelock);
+        if unlock_time != tx.prefix.additional_timelock {
+            self.problem(
+                location,
+                TxUnlockTime::NAME,
+                format!(
+                    "transaction {tx_id} has unlock time {unlock_time:?}, expected {:?}",
+                    tx.prefix.additional_timelock
+                ),
+            );
+        }
+
+        //------------------------------------------------------ Key images
+        for input in &tx.prefix.inputs {
+            if let Input::ToKey { key_image, .. } = input {
+                self.num_key_images += 1;
+
+                if !tables.key_images().contains(key_image.as_bytes())? {
+                    self.problem(
+                        location,
+                        KeyImages::NAME,
+                        format!(
+                            "key image {} of transaction {tx_id} is missing",
+                            Hex(key_image.as_bytes())
+                        ),
+                    );
+                }
+            }
+        }
+
+        //------------------------------------------------------ Outputs
+        let amount_indices = get_opt(tables.tx_outputs(), &tx_id)?.map(|indices| indices.0);
+
+        match &amount_indices {
+            Some(amount_indices) if amount_indices.len() == tx.prefix.outputs.len() => (),
+            Some(amount_indices) => self.problem(
+                location,
+                TxOutputs::NAME,
+                format!(
+                    "transaction {tx_id} has {} outputs, expected {}",
+                    amount_indices.len(),
+                    tx.prefix.outputs.len()
+                ),
+            ),
+            None => self.problem(
+                location,
+                TxOutputs::NAME,
+                format!("outputs of transaction {tx_id} are missing"),
+            ),
+        }
+
+        for (i, output) in tx.prefix.outputs.iter().enumerate() {
+            // Where `add_tx` stores the output.
+            let (table, amount, amount_index, stored) = if tx.v1 {
+                let amount = output.amount.unwrap_or(0);
+                let num_outputs = self.num_outputs.entry(amount).or_default();
+                let amount_index = *num_outputs;
+                *num_outputs += 1;
+
+                let stored = get_opt(
+                    tables.outputs(),
+                    &PreRctOutputId {
+                        amount,
+                        amount_index,
+                    },
+                )?
+                .map(|output| (output.key, output.height, output.tx_idx));
+
+                (Outputs::NAME, amount, amount_index, stored)
+            } else {
+                let amount_index = self.num_rct_outputs;
+                self.num_rct_outputs += 1;
+
+                let stored = get_opt(tables.rct_outputs(), &amount_index)?
+                    .map(|output| (output.key, output.height, output.tx_idx));
+
+                (RctOutputs::NAME, 0, amount_index, stored)
+            };
+
+            if let Some(index) = amount_indices
+                .as_ref()
+                .and_then(|indices| indices.get(i))
+                .filter(|index| **index != amount_index)
+            {
+                self.problem(
+                    location,
+                    TxOutputs::NAME,
+                    format!(
+                        "output {i} of transaction {tx_id} has amount index {index}, expected {amount_index}"
+                    ),
+                );
+            }
+
+            match stored {
+                Some((key, h, idx))
+                    if key == output.key.0 && u32_to_usize(h) == height && idx == tx_id =>
+                {
+                    ()
+                }
+                Some(_) => self.problem(
+                    location,
+                    table,
+                    format!(
+                        "output {amount}/{amount_index} is not output {i} of transaction {tx_id}"
+                    ),
+                ),
+                None => self.problem(
+                    location,
+                    table,
+                    format!("output {amount}/{amount_index} of transaction {tx_id} is missing"),
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the main-chain tables have no entries not belonging to a block.
+    fn check_table_lens(&mut self, tables: &impl TablesIter) -> DbResult<()> {
+        let location = Location::MainChain;
+        let chain_height = usize_to_u64(self.report.chain_height);
+        let num_outputs = self.num_outputs.values().sum::<u64>();
+
+        let pruned_txs = tables.pruned_tx_blobs().len()?;
+        let unpruned_txs = tables.tx_blobs().len()?;
+
+        for (table, len, expected) in [
+            (
+                BlockHeights::NAME,
+                tables.block_heights().len()?,
+                chain_height,
+            ),
+            (
+                BlockHeaderBlobs::NAME,
+                tables.block_header_blobs().len()?,
+                chain_height,
+            ),
+            (
+                BlockTxsHashes::NAME,
+                tables.block_txs_hashes().len()?,
+                chain_height,
+            ),
+            (TxIds::NAME, tables.tx_ids().len()?, self.num_txs),
+            (TxHeights::NAME, tables.tx_heights().len()?, self.num_txs),
+            (TxOutputs::NAME, tables.tx_outputs().len()?, self.num_txs),
+            (TxBlobs::NAME, unpruned_txs + pruned_txs, self.num_txs),
+            (
+                PrunableHashes::NAME,
+                tables.prunable_hashes().len()?,
+                pruned_txs,
+            ),
+            (
+                TxUnlockTime::NAME,
+                tables.tx_unlock_time().len()?,
+                self.num_unlock_times,
+            ),
+            (
+                KeyImages::NAME,
+                tables.key_images().len()?,
+                self.num_key_images,
+            ),
+            (Outputs::NAME, tables.outputs().len()?, num_outputs),
+            (
+                RctOutputs::NAME,
+                tables.rct_outputs().len()?,
+                self.num_rct_outputs,
+            ),
+        ] {
+            if len != expected {
+                self.problem(
+                    location,
+                    table,
+                    format!("{len} entries, expected {expected}"),
+                );
+            }
+        }
+
+        let mut num_outputs = self.num_outputs.clone();
+
+        for result in tables.num_outputs_iter().iter()? {
+            let (amount, count) = result?;
+            let expected = num_outputs.remove(&amount).unwrap_or(0);
+
+            if count != expected {
+                self.problem(
+                    location,
+                    NumOutputs::NAME,
+                    format!("{count} outputs of amount {amount}, expected {expected}"),
+                );
+            }
+        }
+
+        for (amount, expected) in num_outputs {
+            self.problem(
+                location,
+                NumOutputs::NAME,
+                format!("outputs of amount {amount} are missing, expected {expected}"),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Check the alt-chain tables only reference data that exists.
+    fn check_alt_blocks(&mut self, tables: &impl TablesIter) -> DbResult<()> {
+        let location = Location::AltChain;
+
+        for result in tables.alt_block_heights_iter().iter()? {
+            let (block_hash, alt_block_height) = result?;
+
+            if !tables.alt_blocks_info().contains(&alt_block_height)? {
+                self.problem(
+                    location,
+                    AltBlocksInfo::NAME,
+                    format!("alt block {} is missing", Hex(&block_hash)),
+                );
+            }
+        }
+
+        for result in tables.alt_blocks_info_iter().iter()? {
+            let (alt_block_height, alt_block_info) = result?;
+            let block_hash = Hex(&alt_block_info.block_hash);
+
+            if get_opt(tables.alt_block_heights(), &alt_block_info.block_hash)?
+                != Some(alt_block_height)
+            {
+                self.problem(
+                    location,
+                    AltBlockHeights::NAME,
+                    format!("alt block {block_hash} is missing or at another height"),
+                );
+            }
+
+            if alt_block_info.height != alt_block_height.height {
+                self.problem(
+                    location,
+                    AltBlocksInfo::NAME,
+                    format!(
+                        "alt block {block_hash} has height {}, expected {}",
+                        alt_block_info.height, alt_block_height.height
+                    ),
+                );
+            }
+
+            match get_opt(tables.alt_chain_infos(), &alt_block_height.chain_id)? {
+                Some(alt_chain_info) if alt_block_height.height < alt_chain_info.chain_height => (),
+                Some(alt_chain_info) => self.problem(
+                    location,
+                    AltChainInfos::NAME,
+                    format!(
+                        "alt block {block_hash} is above the chain height {} of alt chain {:?}",
+                        alt_chain_info.chain_height, alt_block_height.chain_id
+                    ),
+                ),
+                None => self.problem(
+                    location,
+                    AltChainInfos::NAME,
+                    format!(
+                        "alt chain {:?} of alt block {block_hash} is missing",
+                        alt_block_height.chain_id
+                    ),
+                ),
+            }
+
+            let Some(blob) = get_opt(tables.alt_block_blobs(), &alt_block_height)? else {
+                self.problem(
+                    location,
+                    AltBlockBlobs::NAME,
+                    format!("alt block {block_hash} is missing"),
+                );
+                continue;
+            };
+
+            let alt_block = match Block::read(&mut blob.0.as_slice()) {
+                Ok(alt_block) => alt_block,
+                Err(e) => {
+                    self.problem(
+                        location,
+                        AltBlockBlobs::NAME,
+                        format!("alt block {block_hash} is invalid: {e}"),
+                    );
+                    continue;
+                }
+            };
+
+            // The transactions are read like `get_alt_block` does.
+            for tx_hash in &alt_block.transactions {
+                if !tables.alt_transaction_infos().contains(tx_hash)? {
+                    self.problem(
+                        location,
+                        AltTransactionInfos::NAME,
+                        format!(
+                            "transaction {} of alt block {block_hash} is missing",
+                            Hex(tx_hash)
+                        ),
+                    );
+                } else if !tables.alt_transaction_blobs().contains(tx_hash)?
+                    && !tables.tx_ids().contains(tx_hash)?
+                {
+                    self.problem(
+                        location,
+                        AltTransactionBlobs::NAME,
+                        format!(
+                            "transaction {} of alt block {block_hash} is missing",
+                            Hex(tx_hash)
+                        ),
+                    );
+                }
+            }
+        }
+
+        for result in tables.alt_block_blobs_iter().keys()? {
+            let alt_block_height = result?;
+
+            if !tables.alt_blocks_info().contains(&alt_block_height)? {
+                self.problem(
+                    location,
+                    AltBlocksInfo::NAME,
+                    format!("alt block at {alt_block_height:?} is missing"),
+                );
+            }
+        }
+
+        for result in tables.alt_chain_infos_iter().iter()? {
+            let (chain_id, alt_chain_info) = result?;
+
+            if alt_chain_info.chain_height <= alt_chain_info.common_ancestor_height + 1 {
+                self.problem(
+                    location,
+                    AltChainInfos::NAME,
+                    format!(
+                        "alt chain {chain_id:?} has chain height {} at or below its split point {}",
+                        alt_chain_info.chain_height, alt_chain_info.common_ancestor_height
+                    ),
+                );
+            }
+
+            if let Chain::Alt(parent_chain_id) = Chain::from(alt_chain_info.parent_chain) {
+                let parent_chain_id = RawChainId::from(parent_chain_id);
+
+                if !tables.alt_chain_infos().contains(&parent_chain_id)? {
+                    self.problem(
+                        location,
+                        AltChainInfos::NAME,
+                        format!(
+                            "parent chain {parent_chain_id:?} of alt chain {chain_id:?} is missing"
+                        ),
+                    );
+                }
+            }
+        }
+
+        for result in tables.alt_transaction_blobs_iter().keys()? {
+            let tx_hash = result?;
+
+            if !tables.alt_transaction_infos().contains(&tx_hash)? {
+                self.problem(
+                    location,
+                    AltTransactionInfos::NAME,
+                    format!("alt transaction {} is missing", Hex(&tx_hash)),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Private
+/// [`DatabaseRo::get`], with [`RuntimeError::KeyNotFound`] as [`None`].
+fn get_opt<T: Table>(table: &impl DatabaseRo<T>, key: &T::Key) -> DbResult<Option<T::Value>> {
+    match table.get(key) {
+        Ok(value) => Ok(Some(value)),
+        Err(RuntimeError::KeyNotFound) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Formats a hash as hex.
+struct Hex<'a>(&'a [u8; 32]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Tests
+#[cfg(test)]
+mod test {
+    use std::num::NonZero;
+
+    use pretty_assertions::assert_eq;
+
+    use cuprate_database::{DatabaseRw, Env, EnvInner, TxRw};
+    use cuprate_test_utils::data::{BLOCK_V16_TX0, BLOCK_V1_TX2, BLOCK_V9_TX3};
+    use cuprate_types::{ChainId, VerifiedBlockInformation};
+
+    use crate::{
+        ops::block::add_block,
+        tables::{OpenTables, Tables},
+        tests::tmp_concrete_env,
+    };
+
+    use super::*;
+
+    /// The test blocks, with their heights and previous block hashes set to form a chain.
+    fn blocks() -> Vec<VerifiedBlockInformation> {
+        let mut blocks = vec![
+            BLOCK_V1_TX2.clone(),
+            BLOCK_V9_TX3.clone(),
+            BLOCK_V16_TX0.clone(),
+        ];
+
+        let mut previous = [0; 32];
+        for (height, block) in blocks.iter_mut().enumerate() {
+            block.height = height;
+            block.block.header.previous = previous;
+            block.block_blob = block.block.serialize();
+            block.block_hash = block.block.hash();
+            previous = block.block_hash;
+        }
+
+        blocks
+    }
+
+    /// Create an `Env` with [`blocks`] added.
+    fn env_with_blocks() -> (impl Env, tempfile::TempDir, Vec<VerifiedBlockInformation>) {
+        let (env, tmp) = tmp_concrete_env();
+        let blocks = blocks();
+
+        {
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+            for block in &blocks {
+                add_block(block, &mut tables).unwrap();
+            }
+
+            drop(tables);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        (env, tmp, blocks)
+    }
+
+    /// [`check`] the database.
+    fn check_env(env: &impl Env) -> CheckReport {
+        let env_inner = env.env_inner();
+        let tx_ro = env_inner.tx_ro().unwrap();
+        let tables = env_inner.open_tables(&tx_ro).unwrap();
+
+        check(&tables).unwrap()
+    }
+
+    /// [`repair`] the database, returns the popped blocks.
+    fn repair_env(env: &impl Env, report: &CheckReport) -> Vec<(BlockHeight, BlockHash)> {
+        let env_inner = env.env_inner();
+        let tx_rw = env_inner.tx_rw().unwrap();
+        let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+        let popped = repair(report, &mut tables).unwrap();
+
+        drop(tables);
+        TxRw::commit(tx_rw).unwrap();
+        popped
+    }
+
+    /// A database written by `add_block` is consistent.
+    #[test]
+    fn consistent() {
+        let (env, _tmp, _) = env_with_blocks();
+
+        let report = check_env(&env);
+        assert_eq!(report.problems, vec![]);
+        assert!(report.is_consistent());
+        assert_eq!(report.chain_height, 3);
+        assert_eq!(report.consistent_chain_height(), 3);
+
+        assert_eq!(repair_env(&env, &report), vec![]);
+    }
+
+    /// A transaction at the wrong height is found,
+    /// and its block and the blocks above it are popped.
+    #[test]
+    fn tx_height() {
+        let (env, _tmp, blocks) = env_with_blocks();
+
+        {
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+            let mining_tx_index = tables.block_infos().get(&1).unwrap().mining_tx_index;
+            tables.tx_heights_mut().put(&mining_tx_index, &0).unwrap();
+
+            drop(tables);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        let report = check_env(&env);
+        assert_eq!(
+            report.problems,
+            vec![Problem {
+                location: Location::Block(1),
+                table: TxHeights::NAME,
+                description: "transaction 3 is at height 0".to_string(),
+            }]
+        );
+        assert_eq!(report.consistent_chain_height(), 1);
+
+        assert_eq!(
+            repair_env(&env, &report),
+            vec![(2, blocks[2].block_hash), (1, blocks[1].block_hash)]
+        );
+
+        let report = check_env(&env);
+        assert!(report.is_consistent());
+        assert_eq!(report.chain_height, 1);
+    }
+
+    /// A cumulative difficulty that does not increase is found.
+    #[test]
+    fn cumulative_difficulty() {
+        let (env, _tmp, _) = env_with_blocks();
+
+        {
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+            let mut block_info = tables.block_infos().get(&2).unwrap();
+            block_info.cumulative_difficulty_low = 0;
+            block_info.cumulative_difficulty_high = 0;
+            tables.block_infos_mut().put(&2, &block_info).unwrap();
+
+            drop(tables);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        let report = check_env(&env);
+        assert_eq!(report.problems.len(), 1);
+        assert_eq!(report.problems[0].location, Location::Block(2));
+        assert_eq!(report.problems[0].table, BlockInfos::NAME);
+        assert_eq!(report.consistent_chain_height(), 2);
+    }
+
+    /// A missing key image is found, in its block and in the table length.
+    #[test]
+    fn key_image() {
+        let (env, _tmp, blocks) = env_with_blocks();
+
+        {
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+            let Input::ToKey { key_image, .. } = blocks[0].txs[0].tx.prefix().inputs[0] else {
+                panic!("not a key image");
+            };
+            tables
+                .key_images_mut()
+                .delete(key_image.as_bytes())
+                .unwrap();
+
+            drop(tables);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        let report = check_env(&env);
+        let problems = report
+            .problems
+            .iter()
+            .map(|problem| (problem.location, problem.table))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            vec![
+                (Location::Block(0), KeyImages::NAME),
+                (Location::MainChain, KeyImages::NAME)
+            ]
+        );
+        assert_eq!(report.consistent_chain_height(), 0);
+
+        assert_eq!(repair_env(&env, &report).len(), 3);
+        assert!(check_env(&env).is_consistent());
+    }
+
+    /// Alt blocks are checked, but not repaired.
+    #[test]
+    fn alt_chain() {
+        let (env, _tmp, _) = env_with_blocks();
+        let chain_id = ChainId(NonZero::new(1).unwrap());
+
+        {
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+            pop_block(Some(chain_id), &mut tables).unwrap();
+
+            drop(tables);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        assert!(check_env(&env).is_consistent());
+
+        {
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+            tables
+                .alt_chain_infos_mut()
+                .delete(&chain_id.into())
+                .unwrap();
+
+            drop(tables);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        let report = check_env(&env);
+        assert!(!report.is_consistent());
+        assert!(report.has_alt_chain_problems());
+        assert!(report
+            .problems
+            .iter()
+            .all(|problem| problem.location == Location::AltChain));
+        assert_eq!(report.consistent_chain_height(), 2);
+        assert_eq!(repair_env(&env, &report), vec![]);
+    }
+}
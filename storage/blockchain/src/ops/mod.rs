@@ -105,6 +105,7 @@
 pub mod alt_block;
 pub mod block;
 pub mod blockchain;
+pub mod check;
 pub mod key_image;
 pub mod output;
 pub mod property;
@@ -1,41 +1,140 @@
 //! Database properties functions - version, pruning, etc.
-//!
-//! SOMEDAY: the database `properties` table is not yet implemented.
 
 //---------------------------------------------------------------------------------------------------- Import
-use cuprate_database::DbResult;
+use std::borrow::Cow;
+
+use cuprate_database::{migration, DatabaseRo, DatabaseRw, DbResult, RuntimeError, StorableStr};
+use cuprate_helper::cast::{u64_to_usize, usize_to_u64};
 use cuprate_pruning::PruningSeed;
 
-use crate::ops::macros::doc_error;
+use crate::{
+    ops::macros::doc_error,
+    tables::Properties,
+    types::{BlockHeight, PropertyKey},
+};
+
+//---------------------------------------------------------------------------------------------------- Keys
+/// The [`Properties`] key of the compressed [`PruningSeed`].
+///
+/// This only exists if the database is pruned.
+const PRUNING_SEED: PropertyKey = StorableStr(Cow::Borrowed("pruning_seed"));
+
+/// The [`Properties`] key of the height below which all blocks have been pruned.
+const PRUNED_HEIGHT: PropertyKey = StorableStr(Cow::Borrowed("pruned_height"));
 
 //---------------------------------------------------------------------------------------------------- Free Functions
-/// SOMEDAY
+/// Retrieve the [`PruningSeed`] of the database.
 ///
+/// This returns [`PruningSeed::NotPruned`] if the database is not pruned.
 #[doc = doc_error!()]
+#[expect(
+    clippy::missing_panics_doc,
+    reason = "The panic is only possible with a corrupt DB"
+)]
+#[inline]
+pub fn get_blockchain_pruning_seed(
+    table_properties: &impl DatabaseRo<Properties>,
+) -> DbResult<PruningSeed> {
+    match table_properties.get(&PRUNING_SEED) {
+        Ok(seed) => Ok(u32::try_from(seed)
+            .ok()
+            .and_then(|seed| PruningSeed::decompress(seed).ok())
+            .expect("Stored pruning seed must be valid")),
+        Err(RuntimeError::KeyNotFound) => Ok(PruningSeed::NotPruned),
+        Err(e) => Err(e),
+    }
+}
+
+/// Set the [`PruningSeed`] of the database.
 ///
-/// # Example
-/// ```rust
-/// # use cuprate_blockchain::{*, tables::*, ops::block::*, ops::tx::*};
-/// // SOMEDAY
-/// ```
+/// This only records the seed, blocks are pruned with
+/// [`prune_blocks`](crate::ops::blockchain::prune_blocks).
+#[doc = doc_error!()]
 #[inline]
-pub const fn get_blockchain_pruning_seed() -> DbResult<PruningSeed> {
-    // SOMEDAY: impl pruning.
-    // We need a DB properties table.
-    Ok(PruningSeed::NotPruned)
+pub fn set_blockchain_pruning_seed(
+    pruning_seed: &PruningSeed,
+    table_properties: &mut impl DatabaseRw<Properties>,
+) -> DbResult<()> {
+    table_properties.put(&PRUNING_SEED, &u64::from(pruning_seed.compress()))
 }
 
-/// SOMEDAY
+/// Retrieve the height below which all blocks have been pruned.
 ///
+/// This returns `0` if no blocks have been pruned.
 #[doc = doc_error!()]
+#[inline]
+pub fn get_pruned_height(table_properties: &impl DatabaseRo<Properties>) -> DbResult<BlockHeight> {
+    match table_properties.get(&PRUNED_HEIGHT) {
+        Ok(height) => Ok(u64_to_usize(height)),
+        Err(RuntimeError::KeyNotFound) => Ok(0),
+        Err(e) => Err(e),
+    }
+}
+
+/// Set the height below which all blocks have been pruned.
+#[doc = doc_error!()]
+#[inline]
+pub fn set_pruned_height(
+    pruned_height: &BlockHeight,
+    table_properties: &mut impl DatabaseRw<Properties>,
+) -> DbResult<()> {
+    table_properties.put(&PRUNED_HEIGHT, &usize_to_u64(*pruned_height))
+}
+
+/// Retrieve the schema version of the database.
 ///
-/// # Example
-/// ```rust
-/// # use cuprate_blockchain::{*, tables::*, ops::block::*, ops::tx::*};
-/// // SOMEDAY
-/// ```
+/// This is [`DATABASE_VERSION`](crate::DATABASE_VERSION)
+/// for databases opened with [`open`](crate::open).
+#[doc = doc_error!()]
 #[inline]
-pub const fn db_version() -> DbResult<u64> {
-    // SOMEDAY: We need a DB properties table.
-    Ok(crate::constants::DATABASE_VERSION)
+pub fn db_version(table_properties: &impl DatabaseRo<Properties>) -> DbResult<u64> {
+    table_properties.get(&migration::VERSION_KEY)
+}
+
+//---------------------------------------------------------------------------------------------------- Tests
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use cuprate_database::{Env, EnvInner, TxRw};
+
+    use super::*;
+
+    use crate::{
+        tables::{OpenTables, Tables, TablesMut},
+        tests::tmp_concrete_env,
+    };
+
+    /// Tests the pruning seed and pruned height are stored.
+    #[test]
+    fn pruning_properties() {
+        let (env, _tmp) = tmp_concrete_env();
+        let env_inner = env.env_inner();
+
+        let tx_rw = env_inner.tx_rw().unwrap();
+        let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+        // Nothing is stored in a new database.
+        assert_eq!(
+            get_blockchain_pruning_seed(tables.properties()).unwrap(),
+            PruningSeed::NotPruned
+        );
+        assert_eq!(get_pruned_height(tables.properties()).unwrap(), 0);
+
+        let pruning_seed = PruningSeed::new_pruned(3, 3).unwrap();
+        set_blockchain_pruning_seed(&pruning_seed, tables.properties_mut()).unwrap();
+        set_pruned_height(&123, tables.properties_mut()).unwrap();
+
+        drop(tables);
+        TxRw::commit(tx_rw).unwrap();
+
+        let tx_ro = env_inner.tx_ro().unwrap();
+        let tables = env_inner.open_tables(&tx_ro).unwrap();
+
+        assert_eq!(
+            get_blockchain_pruning_seed(tables.properties()).unwrap(),
+            pruning_seed
+        );
+        assert_eq!(get_pruned_height(tables.properties()).unwrap(), 123);
+    }
 }
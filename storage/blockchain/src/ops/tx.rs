@@ -1,8 +1,14 @@
 //! Transaction functions.
 
 //---------------------------------------------------------------------------------------------------- Import
+use std::io;
+
 use bytemuck::TransparentWrapper;
-use monero_serai::transaction::{Input, Timelock, Transaction};
+use monero_serai::{
+    io::read_varint,
+    primitives::keccak256,
+    transaction::{Input, Timelock, Transaction, TransactionPrefix},
+};
 
 use cuprate_database::{DatabaseRo, DatabaseRw, DbResult, RuntimeError, StorableVec};
 use cuprate_helper::crypto::compute_zero_commitment;
@@ -58,6 +64,8 @@ pub fn add_tx(
     //------------------------------------------------------ Transaction data
     tables.tx_ids_mut().put(tx_hash, &tx_id)?;
     tables.tx_heights_mut().put(&tx_id, block_height)?;
+    // Transactions are always added unpruned, they are pruned
+    // once their block leaves the blockchain tip, see `prune_tx`.
     tables
         .tx_blobs_mut()
         .put(&tx_id, StorableVec::wrap_ref(tx_blob))?;
@@ -74,12 +82,6 @@ pub fn add_tx(
         Timelock::Time(time) => tables.tx_unlock_time_mut().put(&tx_id, &time)?,
     }
 
-    //------------------------------------------------------ Pruning
-    // SOMEDAY: implement pruning after `monero-serai` does.
-    // if let PruningSeed::Pruned(decompressed_pruning_seed) = get_blockchain_pruning_seed()? {
-    // SOMEDAY: what to store here? which table?
-    // }
-
     //------------------------------------------------------
     let Ok(height) = u32::try_from(*block_height) else {
         panic!("add_tx(): block_height ({block_height}) > u32::MAX");
@@ -184,23 +186,27 @@ pub fn add_tx(
 /// Thus, after [`remove_tx`], those values (outputs and key images)
 /// will be remove from database tables as well.
 ///
+/// If the transaction has been [pruned](prune_tx) its pruned blob and prunable hash are
+/// removed instead of its blob, and the returned [`Transaction`] only has its prefix,
+/// see [`tx_from_pruned_blob`].
+///
 #[doc = doc_error!()]
 #[inline]
 pub fn remove_tx(tx_hash: &TxHash, tables: &mut impl TablesMut) -> DbResult<(TxId, Transaction)> {
     //------------------------------------------------------ Transaction data
     let tx_id = tables.tx_ids_mut().take(tx_hash)?;
-    let tx_blob = tables.tx_blobs_mut().take(&tx_id)?;
+    let tx = match tables.tx_blobs_mut().take(&tx_id) {
+        Ok(tx_blob) => Transaction::read(&mut tx_blob.0.as_slice())?,
+        Err(RuntimeError::KeyNotFound) => {
+            let pruned_blob = tables.pruned_tx_blobs_mut().take(&tx_id)?.0;
+            tables.prunable_hashes_mut().delete(&tx_id)?;
+            tx_from_pruned_blob(&pruned_blob)?
+        }
+        Err(e) => return Err(e),
+    };
     tables.tx_heights_mut().delete(&tx_id)?;
     tables.tx_outputs_mut().delete(&tx_id)?;
 
-    //------------------------------------------------------ Pruning
-    // SOMEDAY: implement pruning after `monero-serai` does.
-    // table_prunable_hashes.delete(&tx_id)?;
-    // table_prunable_tx_blobs.delete(&tx_id)?;
-    // if let PruningSeed::Pruned(decompressed_pruning_seed) = get_blockchain_pruning_seed()? {
-    // SOMEDAY: what to remove here? which table?
-    // }
-
     //------------------------------------------------------ Unlock Time
     match tables.tx_unlock_time_mut().delete(&tx_id) {
         Ok(()) | Err(RuntimeError::KeyNotFound) => (),
@@ -208,10 +214,6 @@ pub fn remove_tx(tx_hash: &TxHash, tables: &mut impl TablesMut) -> DbResult<(TxI
         Err(e) => return Err(e),
     }
 
-    //------------------------------------------------------
-    // Refer to the inner transaction type from now on.
-    let tx = Transaction::read(&mut tx_blob.0.as_slice())?;
-
     //------------------------------------------------------ Key Images
     // Is this a miner transaction?
     let mut miner_tx = false;
@@ -256,6 +258,38 @@ pub fn remove_tx(tx_hash: &TxHash, tables: &mut impl TablesMut) -> DbResult<(TxI
     Ok((tx_id, tx))
 }
 
+/// Prune a transaction, removing its prunable data from the database.
+///
+/// The transaction's blob is moved from [`TxBlobs`] into
+/// [`PrunedTxBlobs`](crate::tables::PrunedTxBlobs) (without the prunable data)
+/// and its prunable hash is stored in [`PrunableHashes`](crate::tables::PrunableHashes).
+///
+/// This does nothing if the transaction is already pruned.
+///
+/// Miner transactions have no prunable data so should not be pruned, [`get_tx`]
+/// and other functions reading miner transactions expect them in [`TxBlobs`].
+#[doc = doc_error!()]
+#[inline]
+pub fn prune_tx(tx_id: &TxId, tables: &mut impl TablesMut) -> DbResult<()> {
+    let tx_blob = match tables.tx_blobs_mut().take(tx_id) {
+        Ok(tx_blob) => tx_blob.0,
+        Err(RuntimeError::KeyNotFound) if tables.pruned_tx_blobs().contains(tx_id)? => {
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    let tx = Transaction::read(&mut tx_blob.as_slice())?;
+    let (pruned_blob, _, prunable_hash) = tx_pruned_prunable_blobs(&tx, &tx_blob);
+
+    tables
+        .pruned_tx_blobs_mut()
+        .put(tx_id, &StorableVec(pruned_blob.to_vec()))?;
+    tables.prunable_hashes_mut().put(tx_id, &prunable_hash)?;
+
+    Ok(())
+}
+
 //---------------------------------------------------------------------------------------------------- `get_tx_*`
 /// Retrieve a [`Transaction`] from the database with its [`TxHash`].
 #[doc = doc_error!()]
@@ -279,6 +313,69 @@ pub fn get_tx_from_id(
     Ok(Transaction::read(&mut tx_blob.as_slice())?)
 }
 
+//----------------------------------------------------------------------------------------------------
+/// Split a [`Transaction`]'s blob into its pruned and prunable parts.
+///
+/// This returns `(pruned_blob, prunable_blob, prunable_hash)`.
+///
+/// The prunable part is the ring signatures of v1 transactions and the
+/// RCT prunable proofs of v2 transactions. The `prunable_hash` is all `0`s
+/// for v1 and v2 miner transactions, as `monerod` does not store one for them.
+///
+/// `tx_blob` must be the serialized `tx`.
+pub fn tx_pruned_prunable_blobs<'a>(
+    tx: &Transaction,
+    tx_blob: &'a [u8],
+) -> (&'a [u8], &'a [u8], [u8; 32]) {
+    let mut prunable = Vec::new();
+
+    // Writing to a `Vec` cannot fail.
+    let prunable_hash = match tx {
+        Transaction::V1 { signatures, .. } => {
+            for signature in signatures {
+                signature.write(&mut prunable).unwrap();
+            }
+            [0; 32]
+        }
+        Transaction::V2 {
+            proofs: Some(proofs),
+            ..
+        } => {
+            proofs
+                .prunable
+                .write(&mut prunable, proofs.rct_type())
+                .unwrap();
+            keccak256(&prunable)
+        }
+        Transaction::V2 { proofs: None, .. } => [0; 32],
+    };
+
+    let (pruned_blob, prunable_blob) = tx_blob.split_at(tx_blob.len() - prunable.len());
+
+    (pruned_blob, prunable_blob, prunable_hash)
+}
+
+/// Read a [`Transaction`] from its pruned blob, see [`tx_pruned_prunable_blobs`].
+///
+/// Only the prefix is read, the returned transaction has no signatures or RCT proofs,
+/// so it must not be used for anything needing them, e.g. its hash.
+fn tx_from_pruned_blob(mut pruned_blob: &[u8]) -> io::Result<Transaction> {
+    let version: u64 = read_varint(&mut pruned_blob)?;
+    let prefix = TransactionPrefix::read(&mut pruned_blob, version)?;
+
+    match version {
+        1 => Ok(Transaction::V1 {
+            prefix,
+            signatures: vec![],
+        }),
+        2 => Ok(Transaction::V2 {
+            prefix,
+            proofs: None,
+        }),
+        _ => Err(io::Error::other("invalid transaction version")),
+    }
+}
+
 //----------------------------------------------------------------------------------------------------
 /// How many [`Transaction`]s are there?
 ///
@@ -418,4 +515,50 @@ mod test {
 
         assert_all_tables_are_empty(&env);
     }
+
+    /// Tests [`prune_tx`] moves transactions into the pruned tables.
+    #[test]
+    fn prune_tx_tables() {
+        let (env, _tmp) = tmp_concrete_env();
+        let env_inner = env.env_inner();
+
+        let txs = [&*TX_V1_SIG2, &*TX_V2_RCT3];
+
+        let tx_rw = env_inner.tx_rw().unwrap();
+        let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+
+        for tx in txs {
+            let tx_id = add_tx(&tx.tx, &tx.tx_blob, &tx.tx_hash, &0, &mut tables).unwrap();
+            prune_tx(&tx_id, &mut tables).unwrap();
+
+            // Pruning twice does nothing.
+            prune_tx(&tx_id, &mut tables).unwrap();
+
+            let (pruned_blob, prunable_blob, prunable_hash) =
+                tx_pruned_prunable_blobs(&tx.tx, &tx.tx_blob);
+            assert!(!prunable_blob.is_empty());
+
+            assert!(matches!(
+                get_tx_from_id(&tx_id, tables.tx_blobs()),
+                Err(RuntimeError::KeyNotFound)
+            ));
+            assert_eq!(tables.pruned_tx_blobs().get(&tx_id).unwrap().0, pruned_blob);
+            assert_eq!(tables.prunable_hashes().get(&tx_id).unwrap(), prunable_hash);
+        }
+
+        assert_eq!(tables.tx_blobs().len().unwrap(), 0);
+        assert_eq!(tables.pruned_tx_blobs().len().unwrap(), 2);
+        assert_eq!(tables.prunable_hashes().len().unwrap(), 2);
+
+        // Pruned transactions can still be removed.
+        for tx in txs.iter().rev() {
+            let (_, removed_tx) = remove_tx(&tx.tx_hash, &mut tables).unwrap();
+            assert_eq!(removed_tx.prefix(), tx.tx.prefix());
+        }
+
+        drop(tables);
+        TxRw::commit(tx_rw).unwrap();
+
+        assert_all_tables_are_empty(&env);
+    }
 }
@@ -1,22 +1,15 @@
 //! Database reader thread-pool definitions and logic.
 
-#![expect(
-    unreachable_code,
-    unused_variables,
-    clippy::unnecessary_wraps,
-    clippy::needless_pass_by_value,
-    reason = "TODO: finish implementing the signatures from <https://github.com/Cuprate/cuprate/pull/297>"
-)]
-
 //---------------------------------------------------------------------------------------------------- Import
 use std::{
-    cmp::min,
+    cmp::{max, min},
     collections::{HashMap, HashSet},
     ops::Range,
     sync::Arc,
 };
 
 use indexmap::{IndexMap, IndexSet};
+use monero_serai::transaction::Transaction;
 use rayon::{
     iter::{Either, IntoParallelIterator, ParallelIterator},
     prelude::*,
@@ -24,14 +17,24 @@ use rayon::{
 };
 use thread_local::ThreadLocal;
 
-use cuprate_database::{ConcreteEnv, DatabaseRo, DbResult, Env, EnvInner, RuntimeError};
+use cuprate_database::{
+    ConcreteEnv, DatabaseIter, DatabaseRo, DbResult, Env, EnvInner, RuntimeError,
+};
 use cuprate_database_service::{init_thread_pool, DatabaseReadService, ReaderThreads};
-use cuprate_helper::map::combine_low_high_bits_to_u128;
+use cuprate_helper::{
+    cast::{u32_to_usize, u64_to_usize, usize_to_u64},
+    fs::free_space,
+    map::{combine_low_high_bits_to_u128, split_u128_into_low_high_bits},
+};
 use cuprate_types::{
     blockchain::{BlockchainReadRequest, BlockchainResponse},
     output_cache::OutputCache,
-    rpc::OutputHistogramInput,
-    Chain, ChainId, ExtendedBlockHeader, OutputDistributionInput, TxsInBlock,
+    rpc::{
+        ChainInfo, CoinbaseTxSum, OutputDistributionData, OutputHistogramEntry,
+        OutputHistogramInput,
+    },
+    Chain, ChainId, ExtendedBlockHeader, OutputDistributionInput, OutputOnChain, TxInBlockchain,
+    TxsInBlock,
 };
 
 use crate::{
@@ -48,14 +51,15 @@ use crate::{
         blockchain::{cumulative_generated_coins, find_split_point, top_block_height},
         key_image::key_image_exists,
         output::id_to_output_on_chain,
+        tx::{get_num_tx, get_tx_from_id, tx_pruned_prunable_blobs},
     },
     service::{
         free::{compact_history_genesis_not_included, compact_history_index_to_height_offset},
         types::{BlockchainReadHandle, ResponseResult},
     },
     tables::{
-        AltBlockHeights, BlockHeights, BlockInfos, OpenTables, RctOutputs, Tables, TablesIter,
-        TxIds, TxOutputs,
+        AltBlockHeights, AltChainInfos, BlockHeights, BlockInfos, OpenTables, RctOutputs, Tables,
+        TablesIter, TxIds, TxOutputs,
     },
     types::{
         AltBlockHeight, Amount, AmountIndex, BlockHash, BlockHeight, KeyImage, PreRctOutputId,
@@ -149,6 +153,7 @@ fn map_request(
         R::Transactions { tx_hashes } => transactions(env, tx_hashes),
         R::TotalRctOutputs => total_rct_outputs(env),
         R::TxOutputIndexes { tx_hash } => tx_output_indexes(env, &tx_hash),
+        R::TxsOutputIndexes { tx_hashes } => txs_output_indexes(env, &tx_hashes),
         R::OutputDistribution(input) => output_distribution(env, input),
     }
 
@@ -561,7 +566,40 @@ fn outputs_vec(
     outputs: Vec<(Amount, AmountIndex)>,
     get_txid: bool,
 ) -> ResponseResult {
-    Ok(BlockchainResponse::OutputsVec(todo!()))
+    // Prepare tx/tables in `ThreadLocal`.
+    let env_inner = env.env_inner();
+    let tx_ro = thread_local(env);
+    let tables = thread_local(env);
+
+    // Collect results using `rayon`, this keeps the order of the request.
+    let outputs = outputs
+        .into_par_iter()
+        .map(|(amount, amount_index)| {
+            let tx_ro = tx_ro.get_or_try(|| env_inner.tx_ro())?;
+            let tables = get_tables!(env_inner, tx_ro, tables)?.as_ref();
+
+            let id = PreRctOutputId {
+                amount,
+                amount_index,
+            };
+
+            Ok((
+                amount,
+                (amount_index, id_to_output_on_chain(&id, get_txid, tables)?),
+            ))
+        })
+        .collect::<DbResult<Vec<_>>>()?;
+
+    // Group consecutive outputs with the same amount.
+    let mut vec = Vec::<(Amount, Vec<(AmountIndex, OutputOnChain)>)>::new();
+    for (amount, output) in outputs {
+        match vec.last_mut() {
+            Some((last_amount, outputs)) if *last_amount == amount => outputs.push(output),
+            _ => vec.push((amount, vec![output])),
+        }
+    }
+
+    Ok(BlockchainResponse::OutputsVec(vec))
 }
 
 /// [`BlockchainReadRequest::NumberOutputsWithAmount`].
@@ -836,7 +874,14 @@ fn txs_in_block(env: &ConcreteEnv, block_hash: [u8; 32], missing_txs: Vec<u64>)
     let txs = missing_txs
         .into_iter()
         .map(|index_offset| Ok(tables.tx_blobs().get(&(first_tx_index + index_offset))?.0))
-        .collect::<DbResult<_>>()?;
+        .collect::<DbResult<_>>();
+
+    let txs = match txs {
+        Ok(txs) => txs,
+        // The block has been pruned, we do not have the full transactions.
+        Err(RuntimeError::KeyNotFound) => return Ok(BlockchainResponse::TxsInBlock(None)),
+        Err(e) => return Err(e),
+    };
 
     Ok(BlockchainResponse::TxsInBlock(Some(TxsInBlock {
         block,
@@ -914,43 +959,370 @@ fn block_by_hash(env: &ConcreteEnv, block_hash: BlockHash) -> ResponseResult {
 
 /// [`BlockchainReadRequest::TotalTxCount`]
 fn total_tx_count(env: &ConcreteEnv) -> ResponseResult {
-    Ok(BlockchainResponse::TotalTxCount(todo!()))
+    // Single-threaded, no `ThreadLocal` required.
+    let env_inner = env.env_inner();
+    let tx_ro = env_inner.tx_ro()?;
+    let num_tx = get_num_tx(&env_inner.open_db_ro::<TxIds>(&tx_ro)?)?;
+
+    Ok(BlockchainResponse::TotalTxCount(u64_to_usize(num_tx)))
 }
 
 /// [`BlockchainReadRequest::DatabaseSize`]
 fn database_size(env: &ConcreteEnv) -> ResponseResult {
     Ok(BlockchainResponse::DatabaseSize {
-        database_size: todo!(),
-        free_space: todo!(),
+        database_size: env.disk_size_bytes()?,
+        free_space: free_space(env.config().db_directory())?,
     })
 }
 
 /// [`BlockchainReadRequest::OutputHistogram`]
+///
+/// For each amount this counts:
+/// - all outputs
+/// - outputs that are past the [`SPENDABLE_AGE`], if `unlocked` is set
+/// - outputs in blocks with a timestamp `>= recent_cutoff`, if `recent_cutoff` is non-zero
+///
+/// These are counted from the newest output backwards, like `monerod`:
+/// <https://github.com/monero-project/monero/blob/893916ad091a92e765ce3241b94e706ad012b62a/src/blockchain_db/lmdb/db_lmdb.cpp#L4222-L4299>
 fn output_histogram(env: &ConcreteEnv, input: OutputHistogramInput) -> ResponseResult {
-    Ok(BlockchainResponse::OutputHistogram(todo!()))
+    let OutputHistogramInput {
+        amounts,
+        min_count,
+        max_count,
+        unlocked,
+        recent_cutoff,
+    } = input;
+
+    // Prepare tx/tables in `ThreadLocal`.
+    let env_inner = env.env_inner();
+    let tx_ro = thread_local(env);
+    let tables = thread_local(env);
+
+    // Get the amount of outputs for each amount.
+    let (chain_height, total_instances) = {
+        let tx_ro = env_inner.tx_ro()?;
+        let tables = env_inner.open_tables(&tx_ro)?;
+
+        let chain_height = crate::ops::blockchain::chain_height(tables.block_heights())?;
+        let num_rct_outputs = tables.rct_outputs().len()?;
+
+        let total_instances = if amounts.is_empty() {
+            // All amounts that exist.
+            let mut total_instances = tables
+                .num_outputs_iter()
+                .iter()?
+                .collect::<DbResult<Vec<_>>>()?;
+
+            if num_rct_outputs != 0 {
+                total_instances.push((0, num_rct_outputs));
+            }
+
+            total_instances
+        } else {
+            amounts
+                .into_iter()
+                .map(|amount| {
+                    if amount == 0 {
+                        return Ok((amount, num_rct_outputs));
+                    }
+
+                    match tables.num_outputs().get(&amount) {
+                        Ok(count) => Ok((amount, count)),
+                        // If we get a request for an `amount` that doesn't exist,
+                        // we return `0` instead of an error.
+                        Err(RuntimeError::KeyNotFound) => Ok((amount, 0)),
+                        Err(e) => Err(e),
+                    }
+                })
+                .collect::<DbResult<Vec<_>>>()?
+        };
+
+        (chain_height, total_instances)
+    };
+
+    // Collect results using `rayon`.
+    let mut histogram = total_instances
+        .into_par_iter()
+        .filter(|(_, total_instances)| {
+            *total_instances >= min_count && (max_count == 0 || *total_instances <= max_count)
+        })
+        .map(|(amount, total_instances)| {
+            let tx_ro = tx_ro.get_or_try(|| env_inner.tx_ro())?;
+            let tables = get_tables!(env_inner, tx_ro, tables)?.as_ref();
+
+            let mut unlocked_instances = 0;
+            let mut recent_instances = 0;
+
+            if unlocked || recent_cutoff != 0 {
+                // Skip the outputs that are still locked.
+                let mut amount_index = total_instances;
+                while amount_index > 0 {
+                    let height = output_height(amount, amount_index - 1, tables)?;
+                    if height + SPENDABLE_AGE <= chain_height {
+                        break;
+                    }
+                    amount_index -= 1;
+                }
+
+                unlocked_instances = amount_index;
+
+                if recent_cutoff != 0 {
+                    while amount_index > 0 {
+                        let height = output_height(amount, amount_index - 1, tables)?;
+                        if get_block_info(&height, tables.block_infos())?.timestamp < recent_cutoff
+                        {
+                            break;
+                        }
+                        amount_index -= 1;
+                        recent_instances += 1;
+                    }
+                }
+            }
+
+            Ok(OutputHistogramEntry {
+                amount,
+                total_instances,
+                unlocked_instances,
+                recent_instances,
+            })
+        })
+        .collect::<DbResult<Vec<_>>>()?;
+
+    histogram.sort_unstable_by_key(|entry| entry.amount);
+
+    Ok(BlockchainResponse::OutputHistogram(histogram))
+}
+
+/// The amount of blocks an output must be buried under before it can be spent.
+///
+/// This is `CRYPTONOTE_DEFAULT_TX_SPENDABLE_AGE` in `monerod`.
+const SPENDABLE_AGE: usize = 10;
+
+/// Returns the height of the block the output was created in.
+///
+/// This supports RCT outputs, in which case `amount` should be `0`.
+fn output_height(
+    amount: Amount,
+    amount_index: AmountIndex,
+    tables: &impl Tables,
+) -> DbResult<BlockHeight> {
+    let height = if amount == 0 {
+        tables.rct_outputs().get(&amount_index)?.height
+    } else {
+        let id = PreRctOutputId {
+            amount,
+            amount_index,
+        };
+        tables.outputs().get(&id)?.height
+    };
+
+    Ok(u32_to_usize(height))
 }
 
 /// [`BlockchainReadRequest::CoinbaseTxSum`]
+///
+/// The emission of a block is the amount of coins it generated, the fees are
+/// the amount its miner transaction created minus the emission. This does not
+/// read the block's transactions, as they may have been pruned.
 fn coinbase_tx_sum(env: &ConcreteEnv, height: usize, count: u64) -> ResponseResult {
-    Ok(BlockchainResponse::CoinbaseTxSum(todo!()))
+    // Prepare tx/tables in `ThreadLocal`.
+    let env_inner = env.env_inner();
+    let tx_ro = thread_local(env);
+    let tables = thread_local(env);
+
+    let chain_height = {
+        let tx_ro = env_inner.tx_ro()?;
+        crate::ops::blockchain::chain_height(&env_inner.open_db_ro::<BlockHeights>(&tx_ro)?)?
+    };
+
+    let end_height = min(height.saturating_add(u64_to_usize(count)), chain_height);
+
+    // Collect results using `rayon`.
+    let (emission_amount, fee_amount) = (height..end_height)
+        .into_par_iter()
+        .map(|height| {
+            let tx_ro = tx_ro.get_or_try(|| env_inner.tx_ro())?;
+            let tables = get_tables!(env_inner, tx_ro, tables)?.as_ref();
+
+            let miner_tx_index = get_block_info(&height, tables.block_infos())?.mining_tx_index;
+
+            let coinbase_amount = get_tx_from_id(&miner_tx_index, tables.tx_blobs())?
+                .prefix()
+                .outputs
+                .iter()
+                .map(|output| u128::from(output.amount.unwrap_or(0)))
+                .sum::<u128>();
+
+            let generated_coins = cumulative_generated_coins(&height, tables.block_infos())?
+                - match height.checked_sub(1) {
+                    Some(parent) => cumulative_generated_coins(&parent, tables.block_infos())?,
+                    None => 0,
+                };
+            let emission_amount = u128::from(generated_coins);
+
+            Ok((
+                emission_amount,
+                coinbase_amount.saturating_sub(emission_amount),
+            ))
+        })
+        .try_reduce(
+            || (0, 0),
+            |(emission_a, fee_a), (emission_b, fee_b)| Ok((emission_a + emission_b, fee_a + fee_b)),
+        )?;
+
+    let (emission_amount, emission_amount_top64) = split_u128_into_low_high_bits(emission_amount);
+    let (fee_amount, fee_amount_top64) = split_u128_into_low_high_bits(fee_amount);
+
+    Ok(BlockchainResponse::CoinbaseTxSum(CoinbaseTxSum {
+        emission_amount_top64,
+        emission_amount,
+        fee_amount_top64,
+        fee_amount,
+    }))
 }
 
 /// [`BlockchainReadRequest::AltChains`]
+///
+/// Each alt-chain includes the blocks of any alt-chains it forked
+/// from, up until the point where it joins the main-chain.
 fn alt_chains(env: &ConcreteEnv) -> ResponseResult {
-    Ok(BlockchainResponse::AltChains(todo!()))
+    // Single-threaded, no `ThreadLocal` required.
+    let env_inner = env.env_inner();
+    let tx_ro = env_inner.tx_ro()?;
+    let tables = env_inner.open_tables(&tx_ro)?;
+
+    let chains = tables
+        .alt_chain_infos_iter()
+        .iter()?
+        .map(|result| {
+            let (chain_id, chain_info) = result?;
+            let chain_id = ChainId::from(chain_id);
+
+            let history = get_alt_chain_history_ranges(
+                0..chain_info.chain_height,
+                chain_id,
+                tables.alt_chain_infos(),
+            )?;
+
+            // The alt-blocks, from the top block down.
+            let mut block_hashes = Vec::new();
+            for (chain, range) in &history {
+                let Chain::Alt(chain_id) = chain else {
+                    continue;
+                };
+
+                for height in range.clone().rev() {
+                    let alt_block_height = AltBlockHeight {
+                        chain_id: (*chain_id).into(),
+                        height,
+                    };
+
+                    block_hashes.push(tables.alt_blocks_info().get(&alt_block_height)?.block_hash);
+                }
+            }
+
+            let top_block_info = tables.alt_blocks_info().get(&AltBlockHeight {
+                chain_id: chain_id.into(),
+                height: chain_info.chain_height - 1,
+            })?;
+
+            // The main-chain block this chain (or one of its parent alt-chains) forked from.
+            let length = block_hashes.len();
+            let main_chain_parent_height = chain_info.chain_height - length - 1;
+            let main_chain_parent_block =
+                get_block_info(&main_chain_parent_height, tables.block_infos())?.block_hash;
+
+            Ok(ChainInfo {
+                block_hash: top_block_info.block_hash,
+                block_hashes,
+                difficulty_top64: top_block_info.cumulative_difficulty_high,
+                difficulty: top_block_info.cumulative_difficulty_low,
+                height: usize_to_u64(top_block_info.height),
+                length: usize_to_u64(length),
+                main_chain_parent_block,
+            })
+        })
+        .collect::<DbResult<_>>()?;
+
+    Ok(BlockchainResponse::AltChains(chains))
 }
 
 /// [`BlockchainReadRequest::AltChainCount`]
 fn alt_chain_count(env: &ConcreteEnv) -> ResponseResult {
-    Ok(BlockchainResponse::AltChainCount(todo!()))
+    // Single-threaded, no `ThreadLocal` required.
+    let env_inner = env.env_inner();
+    let tx_ro = env_inner.tx_ro()?;
+    let len = env_inner.open_db_ro::<AltChainInfos>(&tx_ro)?.len()?;
+
+    Ok(BlockchainResponse::AltChainCount(u64_to_usize(len)))
 }
 
 /// [`BlockchainReadRequest::Transactions`]
 fn transactions(env: &ConcreteEnv, tx_hashes: HashSet<[u8; 32]>) -> ResponseResult {
-    Ok(BlockchainResponse::Transactions {
-        txs: todo!(),
-        missed_txs: todo!(),
-    })
+    // Prepare tx/tables in `ThreadLocal`.
+    let env_inner = env.env_inner();
+    let tx_ro = thread_local(env);
+    let tables = thread_local(env);
+
+    let chain_height = {
+        let tx_ro = tx_ro.get_or_try(|| env_inner.tx_ro())?;
+        let tables = get_tables!(env_inner, tx_ro, tables)?.as_ref();
+        crate::ops::blockchain::chain_height(tables.block_heights())?
+    };
+
+    // Collect results using `rayon`.
+    let (txs, missed_txs) = tx_hashes
+        .into_par_iter()
+        .map(|tx_hash| {
+            let tx_ro = tx_ro.get_or_try(|| env_inner.tx_ro())?;
+            let tables = get_tables!(env_inner, tx_ro, tables)?.as_ref();
+
+            let tx_id = match tables.tx_ids().get(&tx_hash) {
+                Ok(tx_id) => tx_id,
+                Err(RuntimeError::KeyNotFound) => return Ok(Either::Right(tx_hash)),
+                Err(e) => return Err(e),
+            };
+
+            let (tx_blob, pruned_blob, prunable_blob, prunable_hash) =
+                match tables.tx_blobs().get(&tx_id) {
+                    Ok(tx_blob) => {
+                        let tx_blob = tx_blob.0;
+                        let tx = Transaction::read(&mut tx_blob.as_slice())?;
+                        let (pruned_blob, prunable_blob, prunable_hash) =
+                            tx_pruned_prunable_blobs(&tx, &tx_blob);
+
+                        let (pruned_blob, prunable_blob) =
+                            (pruned_blob.to_vec(), prunable_blob.to_vec());
+                        (tx_blob, pruned_blob, prunable_blob, prunable_hash)
+                    }
+                    // The transaction has been pruned, only the pruned blob is available.
+                    Err(RuntimeError::KeyNotFound) => (
+                        vec![],
+                        tables.pruned_tx_blobs().get(&tx_id)?.0,
+                        vec![],
+                        tables.prunable_hashes().get(&tx_id)?,
+                    ),
+                    Err(e) => return Err(e),
+                };
+
+            let block_height = tables.tx_heights().get(&tx_id)?;
+            let block_timestamp = get_block_info(&block_height, tables.block_infos())?.timestamp;
+
+            Ok(Either::Left(TxInBlockchain {
+                block_height: usize_to_u64(block_height),
+                block_timestamp,
+                confirmations: usize_to_u64(chain_height - block_height),
+                output_indices: tables.tx_outputs().get(&tx_id)?.0,
+                tx_hash,
+                pruned_blob,
+                prunable_blob,
+                prunable_hash,
+                tx_blob,
+            }))
+        })
+        .collect::<DbResult<_>>()?;
+
+    Ok(BlockchainResponse::Transactions { txs, missed_txs })
 }
 
 /// [`BlockchainReadRequest::TotalRctOutputs`]
@@ -974,7 +1346,130 @@ fn tx_output_indexes(env: &ConcreteEnv, tx_hash: &[u8; 32]) -> ResponseResult {
     Ok(BlockchainResponse::TxOutputIndexes(o_indexes.0))
 }
 
+/// [`BlockchainReadRequest::TxsOutputIndexes`]
+fn txs_output_indexes(env: &ConcreteEnv, tx_hashes: &[[u8; 32]]) -> ResponseResult {
+    // Single-threaded, no `ThreadLocal` required.
+    let env_inner = env.env_inner();
+    let tx_ro = env_inner.tx_ro()?;
+    let tx_ids = env_inner.open_db_ro::<TxIds>(&tx_ro)?;
+    let tx_outputs = env_inner.open_db_ro::<TxOutputs>(&tx_ro)?;
+
+    let o_indexes = tx_hashes
+        .iter()
+        .map(|tx_hash| Ok(tx_outputs.get(&tx_ids.get(tx_hash)?)?.0))
+        .collect::<DbResult<_>>()?;
+
+    Ok(BlockchainResponse::TxsOutputIndexes(o_indexes))
+}
+
 /// [`BlockchainReadRequest::OutputDistribution`]
+///
+/// The distributions are cumulative over the whole chain, the first entry also counts every
+/// output created before `start_height`, so `base` is always `0`, like `monerod`:
+/// <https://github.com/monero-project/monero/blob/893916ad091a92e765ce3241b94e706ad012b62a/src/blockchain_db/lmdb/db_lmdb.cpp#L3554-L3600>
+///
+/// For RCT outputs, `start_height` is at least the height of the first RCT output.
 fn output_distribution(env: &ConcreteEnv, input: OutputDistributionInput) -> ResponseResult {
-    Ok(BlockchainResponse::OutputDistribution(todo!()))
+    let OutputDistributionInput {
+        amounts,
+        cumulative,
+        from_height,
+        to_height,
+    } = input;
+
+    // Prepare tx/tables in `ThreadLocal`.
+    let env_inner = env.env_inner();
+    let tx_ro = thread_local(env);
+    let tables = thread_local(env);
+
+    let chain_height = {
+        let tx_ro = tx_ro.get_or_try(|| env_inner.tx_ro())?;
+        let tables = get_tables!(env_inner, tx_ro, tables)?.as_ref();
+        crate::ops::blockchain::chain_height(tables.block_heights())?
+    };
+
+    let from_height = u64_to_usize(from_height);
+    // `to_height` is inclusive.
+    let end_height = to_height.map_or(chain_height, |to_height| {
+        min(
+            u64_to_usize(to_height.get()).saturating_add(1),
+            chain_height,
+        )
+    });
+
+    // Collect results using `rayon`.
+    let distributions = amounts
+        .into_par_iter()
+        .map(|amount| {
+            let tx_ro = tx_ro.get_or_try(|| env_inner.tx_ro())?;
+            let tables = get_tables!(env_inner, tx_ro, tables)?.as_ref();
+
+            let (start_height, mut distribution) = if amount == 0 {
+                // RCT outputs, the block infos already contain the cumulative amount.
+                let first_rct_output_height = match tables.rct_outputs().first() {
+                    Ok((_, rct_output)) => u32_to_usize(rct_output.height),
+                    Err(RuntimeError::KeyNotFound) => from_height,
+                    Err(e) => return Err(e),
+                };
+
+                let start_height = max(from_height, first_rct_output_height);
+
+                let distribution = (start_height..end_height)
+                    .map(|height| {
+                        Ok(get_block_info(&height, tables.block_infos())?.cumulative_rct_outs)
+                    })
+                    .collect::<DbResult<Vec<_>>>()?;
+
+                (start_height, distribution)
+            } else {
+                // Pre-RCT outputs are stored in the order they were created.
+                let mut distribution = vec![0; end_height.saturating_sub(from_height)];
+                let mut base = 0;
+
+                let num_outputs = match tables.num_outputs().get(&amount) {
+                    Ok(count) => count,
+                    Err(RuntimeError::KeyNotFound) => 0,
+                    Err(e) => return Err(e),
+                };
+
+                for amount_index in 0..num_outputs {
+                    let height = output_height(amount, amount_index, tables)?;
+
+                    if height >= end_height {
+                        break;
+                    }
+
+                    match height.checked_sub(from_height) {
+                        Some(i) => distribution[i] += 1,
+                        None => base += 1,
+                    }
+                }
+
+                // Make the distribution cumulative, including the outputs before `from_height`.
+                let mut sum = base;
+                for count in &mut distribution {
+                    sum += *count;
+                    *count = sum;
+                }
+
+                (from_height, distribution)
+            };
+
+            if !cumulative {
+                let mut previous = 0;
+                for count in &mut distribution {
+                    (*count, previous) = (*count - previous, *count);
+                }
+            }
+
+            Ok(OutputDistributionData {
+                amount,
+                distribution,
+                start_height: usize_to_u64(start_height),
+                base: 0,
+            })
+        })
+        .collect::<DbResult<_>>()?;
+
+    Ok(BlockchainResponse::OutputDistribution(distributions))
 }
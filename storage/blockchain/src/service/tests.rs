@@ -6,7 +6,11 @@
 #![allow(clippy::await_holding_lock, clippy::too_many_lines)]
 
 //---------------------------------------------------------------------------------------------------- Use
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZero,
+    sync::Arc,
+};
 
 use indexmap::{IndexMap, IndexSet};
 use pretty_assertions::assert_eq;
@@ -14,10 +18,13 @@ use rand::Rng;
 use tower::{Service, ServiceExt};
 
 use cuprate_database::{ConcreteEnv, DatabaseIter, DatabaseRo, Env, EnvInner, RuntimeError};
+use cuprate_helper::map::combine_low_high_bits_to_u128;
+use cuprate_pruning::PruningSeed;
 use cuprate_test_utils::data::{BLOCK_V16_TX0, BLOCK_V1_TX2, BLOCK_V9_TX3};
 use cuprate_types::{
     blockchain::{BlockchainReadRequest, BlockchainResponse, BlockchainWriteRequest},
-    Chain, ChainId, OutputOnChain, VerifiedBlockInformation,
+    rpc::{CoinbaseTxSum, OutputDistributionData, OutputHistogramInput},
+    Chain, ChainId, OutputDistributionInput, OutputOnChain, VerifiedBlockInformation,
 };
 
 use crate::{
@@ -415,6 +422,281 @@ async fn v16_tx0() {
     .await;
 }
 
+/// Tests the read requests that are not covered by [`test_template`],
+/// against the known mainnet data of [`BLOCK_V1_TX2`], [`BLOCK_V9_TX3`] and [`BLOCK_V16_TX0`].
+#[tokio::test]
+async fn mainnet_data_requests() {
+    let (reader, mut writer, env, _tempdir) = init_service();
+
+    let blocks = [&*BLOCK_V1_TX2, &*BLOCK_V9_TX3, &*BLOCK_V16_TX0];
+
+    for (i, block) in blocks.iter().enumerate() {
+        let mut block = (*block).clone();
+        block.height = i;
+
+        let request = BlockchainWriteRequest::WriteBlock(block);
+        let response = writer.ready().await.unwrap().call(request).await.unwrap();
+        assert_eq!(response, BlockchainResponse::Ok);
+    }
+
+    let read = |request| reader.clone().oneshot(request);
+
+    //----------------------------------------------------------------------- TotalTxCount
+    // 1 miner transaction per block + 2 + 3 + 0 transactions.
+    assert_eq!(
+        read(BlockchainReadRequest::TotalTxCount).await.unwrap(),
+        BlockchainResponse::TotalTxCount(8)
+    );
+
+    //----------------------------------------------------------------------- DatabaseSize
+    let BlockchainResponse::DatabaseSize {
+        database_size,
+        free_space,
+    } = read(BlockchainReadRequest::DatabaseSize).await.unwrap()
+    else {
+        panic!("Wrong response type was returned");
+    };
+    assert_eq!(database_size, env.disk_size_bytes().unwrap());
+    assert_ne!(free_space, 0);
+
+    //----------------------------------------------------------------------- CoinbaseTxSum
+    let mut total_emission = 0;
+    let mut total_fees = 0;
+
+    for (height, block) in blocks.iter().enumerate() {
+        let fees = block.txs.iter().map(|tx| tx.fee).sum::<u64>();
+        let coinbase_amount = block
+            .block
+            .miner_transaction
+            .prefix()
+            .outputs
+            .iter()
+            .map(|output| output.amount.unwrap())
+            .sum::<u64>();
+
+        // The miner transaction must pay out exactly the generated coins plus the fees.
+        assert_eq!(coinbase_amount, block.generated_coins + fees);
+
+        let request = BlockchainReadRequest::CoinbaseTxSum { height, count: 1 };
+        assert_eq!(
+            read(request).await.unwrap(),
+            BlockchainResponse::CoinbaseTxSum(CoinbaseTxSum {
+                emission_amount_top64: 0,
+                emission_amount: block.generated_coins,
+                fee_amount_top64: 0,
+                fee_amount: fees,
+            })
+        );
+
+        total_emission += block.generated_coins;
+        total_fees += fees;
+    }
+
+    // A `count` past the top block stops at the top block.
+    let request = BlockchainReadRequest::CoinbaseTxSum {
+        height: 0,
+        count: 100,
+    };
+    assert_eq!(
+        read(request).await.unwrap(),
+        BlockchainResponse::CoinbaseTxSum(CoinbaseTxSum {
+            emission_amount_top64: 0,
+            emission_amount: total_emission,
+            fee_amount_top64: 0,
+            fee_amount: total_fees,
+        })
+    );
+
+    //----------------------------------------------------------------------- Transactions
+    let missing_tx = [1; 32];
+    let tx_hashes = blocks
+        .iter()
+        .flat_map(|block| block.txs.iter().map(|tx| tx.tx_hash))
+        .chain([missing_tx])
+        .collect::<HashSet<_>>();
+
+    let BlockchainResponse::Transactions { txs, missed_txs } =
+        read(BlockchainReadRequest::Transactions { tx_hashes })
+            .await
+            .unwrap()
+    else {
+        panic!("Wrong response type was returned");
+    };
+
+    assert_eq!(missed_txs, vec![missing_tx]);
+    assert_eq!(txs.len(), 5);
+
+    for tx in txs {
+        let (height, block) = blocks
+            .iter()
+            .enumerate()
+            .find(|(_, block)| block.block.transactions.contains(&tx.tx_hash))
+            .unwrap();
+        let expected = block
+            .txs
+            .iter()
+            .find(|expected| expected.tx_hash == tx.tx_hash)
+            .unwrap();
+
+        assert_eq!(tx.tx_blob, expected.tx_blob);
+        assert_eq!(tx.block_height, height as u64);
+        assert_eq!(tx.block_timestamp, block.block.header.timestamp);
+        assert_eq!(tx.confirmations, (blocks.len() - height) as u64);
+        assert_eq!(
+            [tx.pruned_blob, tx.prunable_blob].concat(),
+            expected.tx_blob
+        );
+
+        // Only v2 transactions have a prunable hash.
+        assert_eq!(tx.prunable_hash == [0; 32], expected.tx.version() == 1);
+    }
+
+    //----------------------------------------------------------------------- TxsOutputIndexes
+    let tx_hashes = blocks
+        .iter()
+        .flat_map(|block| {
+            std::iter::once(block.block.miner_transaction.hash())
+                .chain(block.block.transactions.iter().copied())
+        })
+        .collect::<Vec<_>>();
+
+    let mut expected = Vec::with_capacity(tx_hashes.len());
+    for &tx_hash in &tx_hashes {
+        let BlockchainResponse::TxOutputIndexes(o_indexes) =
+            read(BlockchainReadRequest::TxOutputIndexes { tx_hash })
+                .await
+                .unwrap()
+        else {
+            panic!("Wrong response type was returned");
+        };
+        expected.push(o_indexes);
+    }
+
+    // The batched request returns the same indexes, in request order.
+    assert_eq!(
+        read(BlockchainReadRequest::TxsOutputIndexes {
+            tx_hashes: tx_hashes.clone()
+        })
+        .await
+        .unwrap(),
+        BlockchainResponse::TxsOutputIndexes(expected)
+    );
+
+    assert!(read(BlockchainReadRequest::TxsOutputIndexes {
+        tx_hashes: vec![tx_hashes[0], missing_tx]
+    })
+    .await
+    .is_err());
+
+    //----------------------------------------------------------------------- OutputsVec
+    let outputs = vec![(0, 3), (0, 0), (0, 7)];
+    let BlockchainResponse::OutputsVec(response) = read(BlockchainReadRequest::OutputsVec {
+        outputs: outputs.clone(),
+        get_txid: true,
+    })
+    .await
+    .unwrap() else {
+        panic!("Wrong response type was returned");
+    };
+
+    let env_inner = env.env_inner();
+    let tx_ro = env_inner.tx_ro().unwrap();
+    let tables = env_inner.open_tables(&tx_ro).unwrap();
+
+    let expected = outputs
+        .iter()
+        .map(|(amount, amount_index)| {
+            let id = PreRctOutputId {
+                amount: *amount,
+                amount_index: *amount_index,
+            };
+            (
+                *amount_index,
+                id_to_output_on_chain(&id, true, &tables).unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(response, vec![(0, expected)]);
+
+    //----------------------------------------------------------------------- OutputHistogram
+    let BlockchainResponse::OutputHistogram(histogram) = read(
+        BlockchainReadRequest::OutputHistogram(OutputHistogramInput {
+            amounts: vec![],
+            min_count: 0,
+            max_count: 0,
+            unlocked: true,
+            recent_cutoff: 0,
+        }),
+    )
+    .await
+    .unwrap() else {
+        panic!("Wrong response type was returned");
+    };
+
+    // Every amount + RCT outputs.
+    assert_eq!(
+        histogram.len() as u64,
+        tables.num_outputs().len().unwrap() + 1
+    );
+    for entry in histogram {
+        let expected = if entry.amount == 0 {
+            tables.rct_outputs().len().unwrap()
+        } else {
+            tables.num_outputs().get(&entry.amount).unwrap()
+        };
+        assert_eq!(entry.total_instances, expected);
+        // All outputs are younger than 10 blocks.
+        assert_eq!(entry.unlocked_instances, 0);
+        assert_eq!(entry.recent_instances, 0);
+    }
+
+    //----------------------------------------------------------------------- OutputDistribution
+    // The RCT outputs start at the v9 block.
+    for (cumulative, distribution) in [(true, vec![7, 8]), (false, vec![7, 1])] {
+        let request = BlockchainReadRequest::OutputDistribution(OutputDistributionInput {
+            amounts: vec![0],
+            cumulative,
+            from_height: 0,
+            to_height: None,
+        });
+        assert_eq!(
+            read(request).await.unwrap(),
+            BlockchainResponse::OutputDistribution(vec![OutputDistributionData {
+                amount: 0,
+                distribution,
+                start_height: 1,
+                base: 0,
+            }])
+        );
+    }
+
+    // Pre-RCT outputs only exist in the v1 block.
+    let amount = tables
+        .outputs_iter()
+        .keys()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .amount;
+    let count = tables.num_outputs().get(&amount).unwrap();
+    let request = BlockchainReadRequest::OutputDistribution(OutputDistributionInput {
+        amounts: vec![amount],
+        cumulative: true,
+        from_height: 0,
+        to_height: NonZero::new(1),
+    });
+    assert_eq!(
+        read(request).await.unwrap(),
+        BlockchainResponse::OutputDistribution(vec![OutputDistributionData {
+            amount,
+            distribution: vec![count, count],
+            start_height: 0,
+            base: 0,
+        }])
+    );
+}
+
 /// Tests the alt-chain requests and responses.
 #[tokio::test]
 async fn alt_chain_requests() {
@@ -434,6 +716,7 @@ async fn alt_chain_requests() {
     // Generate the alt-blocks.
     let mut prev_hash = BLOCK_V9_TX3.block_hash;
     let mut chain_id = 1;
+    let mut chain_ids = HashSet::new();
     let alt_blocks = [&BLOCK_V16_TX0, &BLOCK_V9_TX3, &BLOCK_V1_TX2]
         .into_iter()
         .enumerate()
@@ -446,6 +729,7 @@ async fn alt_chain_requests() {
             prev_hash = block.block_hash;
             // Randomly either keep the [`ChainId`] the same or change it to a new value.
             chain_id += rand::thread_rng().gen_range(0..=1);
+            chain_ids.insert(chain_id);
 
             map_verified_block_to_alt(block, ChainId(chain_id.try_into().unwrap()))
         })
@@ -475,6 +759,42 @@ async fn alt_chain_requests() {
         assert_eq!(got_block.txs, alt_block.txs);
     }
 
+    // Each [`ChainId`] used is a separate alt-chain.
+    let request = BlockchainReadRequest::AltChainCount;
+    let response = reader.clone().oneshot(request).await.unwrap();
+    assert_eq!(response, BlockchainResponse::AltChainCount(chain_ids.len()));
+
+    let request = BlockchainReadRequest::AltChains;
+    let response = reader.clone().oneshot(request).await.unwrap();
+
+    let BlockchainResponse::AltChains(chains) = response else {
+        panic!("Wrong response type was returned");
+    };
+
+    // The top alt-chain contains every alt-block, from the top block down.
+    assert_eq!(chains.len(), chain_ids.len());
+    let top_block = alt_blocks.last().unwrap();
+    let chain = chains
+        .into_iter()
+        .find(|chain| chain.block_hash == top_block.block_hash)
+        .unwrap();
+
+    assert_eq!(chain.height, 3);
+    assert_eq!(chain.length, 3);
+    assert_eq!(
+        chain.block_hashes,
+        alt_blocks
+            .iter()
+            .rev()
+            .map(|block| block.block_hash)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(chain.main_chain_parent_block, BLOCK_V9_TX3.block_hash);
+    assert_eq!(
+        combine_low_high_bits_to_u128(chain.difficulty, chain.difficulty_top64),
+        top_block.cumulative_difficulty
+    );
+
     // Flush all alt blocks.
     let request = BlockchainWriteRequest::FlushAltBlocks;
     let response = writer.ready().await.unwrap().call(request).await.unwrap();
@@ -493,3 +813,42 @@ async fn alt_chain_requests() {
     let response = reader.clone().oneshot(request).await.unwrap();
     assert!(matches!(response, BlockchainResponse::ChainHeight(1, _)));
 }
+
+#[tokio::test]
+async fn prune_request() {
+    let (_reader, mut writer, env, _tempdir) = init_service();
+
+    let mut block = BLOCK_V1_TX2.clone();
+    block.height = 0;
+
+    let request = BlockchainWriteRequest::WriteBlock(block);
+    let response = writer.ready().await.unwrap().call(request).await.unwrap();
+    assert_eq!(response, BlockchainResponse::Ok);
+
+    // An unpruned database stays unpruned.
+    let request = BlockchainWriteRequest::Prune(PruningSeed::NotPruned);
+    let response = writer.ready().await.unwrap().call(request).await.unwrap();
+    assert_eq!(response, BlockchainResponse::Prune(PruningSeed::NotPruned));
+
+    let pruning_seed = PruningSeed::new_pruned(2, 3).unwrap();
+    let request = BlockchainWriteRequest::Prune(pruning_seed);
+    let response = writer.ready().await.unwrap().call(request).await.unwrap();
+    assert_eq!(response, BlockchainResponse::Prune(pruning_seed));
+
+    // The seed of a pruned database is kept.
+    for seed in [
+        PruningSeed::NotPruned,
+        PruningSeed::new_pruned(5, 3).unwrap(),
+    ] {
+        let request = BlockchainWriteRequest::Prune(seed);
+        let response = writer.ready().await.unwrap().call(request).await.unwrap();
+        assert_eq!(response, BlockchainResponse::Prune(pruning_seed));
+    }
+
+    // The block is in the blockchain tip, so it is not pruned.
+    let env_inner = env.env_inner();
+    let tx_ro = env_inner.tx_ro().unwrap();
+    let tables = env_inner.open_tables(&tx_ro).unwrap();
+    assert_eq!(tables.pruned_tx_blobs().len().unwrap(), 0);
+    assert_eq!(tables.tx_blobs().len().unwrap(), 3);
+}
@@ -4,14 +4,16 @@ use std::sync::Arc;
 
 use cuprate_database::{ConcreteEnv, DbResult, Env, EnvInner, TxRw};
 use cuprate_database_service::DatabaseWriteHandle;
+use cuprate_pruning::PruningSeed;
 use cuprate_types::{
     blockchain::{BlockchainResponse, BlockchainWriteRequest},
     AltBlockInformation, ChainId, VerifiedBlockInformation,
 };
 
 use crate::{
+    ops::property::{get_blockchain_pruning_seed, set_blockchain_pruning_seed},
     service::types::{BlockchainWriteHandle, ResponseResult},
-    tables::OpenTables,
+    tables::{OpenTables, Tables, TablesMut},
 };
 
 /// Write functions within this module abort if the write transaction
@@ -21,6 +23,10 @@ use crate::{
 const TX_RW_ABORT_FAIL: &str =
     "Could not maintain blockchain database atomicity by aborting write transaction";
 
+/// The maximum amount of blocks [`BlockchainWriteRequest::Prune`]
+/// prunes in a single database transaction.
+const PRUNE_BATCH_SIZE: usize = 1_000;
+
 //---------------------------------------------------------------------------------------------------- init_write_service
 /// Initialize the blockchain write service from a [`ConcreteEnv`].
 pub fn init_write_service(env: Arc<ConcreteEnv>) -> BlockchainWriteHandle {
@@ -39,6 +45,7 @@ fn handle_blockchain_request(
         BlockchainWriteRequest::WriteAltBlock(alt_block) => write_alt_block(env, alt_block),
         BlockchainWriteRequest::PopBlocks(numb_blocks) => pop_blocks(env, *numb_blocks),
         BlockchainWriteRequest::FlushAltBlocks => flush_alt_blocks(env),
+        BlockchainWriteRequest::Prune(pruning_seed) => prune(env, *pruning_seed),
     }
 }
 
@@ -177,3 +184,43 @@ fn flush_alt_blocks(env: &ConcreteEnv) -> ResponseResult {
         }
     }
 }
+
+/// [`BlockchainWriteRequest::Prune`].
+fn prune(env: &ConcreteEnv, pruning_seed: PruningSeed) -> ResponseResult {
+    let env_inner = env.env_inner();
+
+    // Prune in batches, so a single database transaction does not grow too large.
+    loop {
+        let tx_rw = env_inner.tx_rw()?;
+
+        // FIXME: turn this function into a try block once stable.
+        let result = || {
+            let mut tables_mut = env_inner.open_tables_mut(&tx_rw)?;
+
+            // Keep the seed of an already pruned database.
+            if pruning_seed != PruningSeed::NotPruned
+                && get_blockchain_pruning_seed(tables_mut.properties())? == PruningSeed::NotPruned
+            {
+                set_blockchain_pruning_seed(&pruning_seed, tables_mut.properties_mut())?;
+            }
+
+            let done = crate::ops::blockchain::prune_blocks(PRUNE_BATCH_SIZE, &mut tables_mut)?;
+
+            Ok((get_blockchain_pruning_seed(tables_mut.properties())?, done))
+        };
+
+        match result() {
+            Ok((pruning_seed, done)) => {
+                TxRw::commit(tx_rw)?;
+
+                if done {
+                    return Ok(BlockchainResponse::Prune(pruning_seed));
+                }
+            }
+            Err(e) => {
+                TxRw::abort(tx_rw).expect(TX_RW_ABORT_FAIL);
+                return Err(e);
+            }
+        }
+    }
+}
@@ -19,8 +19,8 @@
 use crate::types::{
     AltBlockHeight, AltChainInfo, AltTransactionInfo, Amount, AmountIndex, AmountIndices,
     BlockBlob, BlockHash, BlockHeaderBlob, BlockHeight, BlockInfo, BlockTxHashes,
-    CompactAltBlockInfo, KeyImage, Output, PreRctOutputId, PrunableBlob, PrunableHash, PrunedBlob,
-    RawChainId, RctOutput, TxBlob, TxHash, TxId, UnlockTime,
+    CompactAltBlockInfo, KeyImage, Output, PreRctOutputId, PropertyKey, PrunableBlob, PrunableHash,
+    PrunedBlob, RawChainId, RctOutput, TxBlob, TxHash, TxId, UnlockTime,
 };
 
 //---------------------------------------------------------------------------------------------------- Tables
@@ -76,7 +76,8 @@ cuprate_database::define_tables! {
 
     /// Pruned transaction blobs (bytes).
     ///
-    /// Contains the pruned portion of serialized transaction data.
+    /// Contains the pruned portion of serialized transaction data,
+    /// only for transactions that have been pruned.
     7 => PrunedTxBlobs,
     TxId => PrunedBlob,
 
@@ -89,25 +90,21 @@ cuprate_database::define_tables! {
 
     /// Prunable transaction hashes.
     ///
-    /// Contains the prunable portion of transaction hashes.
-    // SOMEDAY: impl when `monero-serai` supports pruning
+    /// Contains the prunable portion of transaction hashes,
+    /// only for transactions that have been pruned.
     9 => PrunableHashes,
     TxId => PrunableHash,
 
-    // SOMEDAY: impl a properties table:
-    // - db version
-    // - pruning seed
-    // Properties,
-    // StorableString => StorableVec,
-
     /// RCT output data.
     10 => RctOutputs,
     AmountIndex => RctOutput,
 
     /// Transaction blobs (bytes).
     ///
-    /// Contains the serialized version of all transactions.
-    // SOMEDAY: remove when `monero-serai` supports pruning
+    /// Contains the serialized version of all transactions that have not been pruned.
+    ///
+    /// When a transaction is pruned it is moved from this table
+    /// to [`PrunedTxBlobs`] and [`PrunableHashes`].
     11 => TxBlobs,
     TxId => TxBlob,
 
@@ -170,6 +167,12 @@ cuprate_database::define_tables! {
     /// Contains information on all alt transactions, even if they are in the main-chain.
     21 => AltTransactionInfos,
     TxHash => AltTransactionInfo,
+
+    /// Database properties.
+    ///
+    /// Contains values that apply to the whole database, e.g. the pruning seed.
+    22 => Properties,
+    PropertyKey => u64,
 }
 
 //---------------------------------------------------------------------------------------------------- Tests
@@ -9,12 +9,12 @@ use std::fmt::Debug;
 
 use pretty_assertions::assert_eq;
 
-use cuprate_database::{DatabaseRo, Env, EnvInner};
+use cuprate_database::{migration, DatabaseIter, DatabaseRo, Env, EnvInner};
 use cuprate_types::{AltBlockInformation, ChainId, VerifiedBlockInformation};
 
 use crate::{
     config::ConfigBuilder,
-    tables::{OpenTables, Tables},
+    tables::{OpenTables, Tables, TablesIter},
 };
 
 //---------------------------------------------------------------------------------------------------- Struct
@@ -83,11 +83,47 @@ pub(crate) fn tmp_concrete_env() -> (impl Env, tempfile::TempDir) {
 }
 
 /// Assert all the tables in the environment are empty.
+///
+/// The only exception is [`Properties`](crate::tables::Properties)
+/// which must only contain the database version.
 pub(crate) fn assert_all_tables_are_empty(env: &impl Env) {
     let env_inner = env.env_inner();
     let tx_ro = env_inner.tx_ro().unwrap();
     let tables = env_inner.open_tables(&tx_ro).unwrap();
-    assert!(tables.all_tables_empty().unwrap());
+
+    let properties = tables
+        .properties_iter()
+        .keys()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(properties, [migration::VERSION_KEY]);
+
+    let tables_empty = [
+        tables.block_header_blobs().is_empty(),
+        tables.block_txs_hashes().is_empty(),
+        tables.block_heights().is_empty(),
+        tables.block_infos().is_empty(),
+        tables.key_images().is_empty(),
+        tables.num_outputs().is_empty(),
+        tables.outputs().is_empty(),
+        tables.pruned_tx_blobs().is_empty(),
+        tables.prunable_tx_blobs().is_empty(),
+        tables.prunable_hashes().is_empty(),
+        tables.rct_outputs().is_empty(),
+        tables.tx_blobs().is_empty(),
+        tables.tx_ids().is_empty(),
+        tables.tx_heights().is_empty(),
+        tables.tx_outputs().is_empty(),
+        tables.tx_unlock_time().is_empty(),
+        tables.alt_chain_infos().is_empty(),
+        tables.alt_block_heights().is_empty(),
+        tables.alt_blocks_info().is_empty(),
+        tables.alt_block_blobs().is_empty(),
+        tables.alt_transaction_blobs().is_empty(),
+        tables.alt_transaction_infos().is_empty(),
+    ];
+    assert!(tables_empty.into_iter().all(|empty| empty.unwrap()));
     assert_eq!(crate::ops::tx::get_num_tx(tables.tx_ids()).unwrap(), 0);
 }
 
@@ -47,7 +47,7 @@ use bytemuck::{Pod, Zeroable};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use cuprate_database::{Key, StorableVec};
+use cuprate_database::{Key, StorableStr, StorableVec};
 use cuprate_types::{Chain, ChainId};
 
 //---------------------------------------------------------------------------------------------------- Aliases
@@ -81,6 +81,9 @@ pub type BlockHeight = usize;
 /// A key image.
 pub type KeyImage = [u8; 32];
 
+/// The key of a database property, e.g. `"pruning_seed"`.
+pub type PropertyKey = StorableStr;
+
 /// Pruned serialized bytes.
 pub type PrunedBlob = StorableVec<u8>;
 
@@ -35,6 +35,34 @@ pub enum InitError {
     #[error("database file is valid, but version is incorrect")]
     InvalidVersion,
 
+    /// The database schema version is newer than the
+    /// version this build supports, i.e. it was
+    /// created by a newer version of Cuprate.
+    ///
+    /// See [`migration`](crate::migration).
+    #[error("database schema version {found} is newer than the supported version {supported}")]
+    UnsupportedVersion {
+        /// The schema version stored in the database.
+        found: u64,
+        /// The latest schema version this build supports.
+        supported: u64,
+    },
+
+    /// A schema migration failed.
+    ///
+    /// The database is left at `version`, the
+    /// migration is retried the next time it is opened.
+    ///
+    /// See [`migration`](crate::migration).
+    #[error("database migration from schema version {version} failed: {error}")]
+    Migration {
+        /// The schema version the failed migration started from.
+        version: u64,
+        /// The error the migration returned.
+        #[source]
+        error: RuntimeError,
+    },
+
     /// I/O error.
     #[error("database I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -0,0 +1,119 @@
+//! Database schema versions and migrations.
+//!
+//! `cuprate_database` is agnostic to the tables stored in it, so the crates
+//! defining tables are responsible for their schema. Each such crate:
+//! - stores its schema version in a properties table under [`VERSION_KEY`]
+//! - increments its version when its tables change
+//! - provides a migration step from every old version to the next
+//!
+//! and calls [`migrate`] when opening its database.
+//!
+//! # Crash safety
+//! Each migration step runs in its own [`TxRw`], which also stores the new version.
+//! A database is therefore always fully at one version; if a migration is
+//! interrupted, the next [`migrate`] resumes from the last completed step.
+
+//---------------------------------------------------------------------------------------------------- Import
+use std::borrow::Cow;
+
+use crate::{
+    DatabaseRo, DatabaseRw, DbResult, EnvInner, InitError, RuntimeError, StorableStr, Table, TxRw,
+};
+
+//---------------------------------------------------------------------------------------------------- Constants
+/// The key of the schema version in a properties table.
+pub const VERSION_KEY: StorableStr = StorableStr(Cow::Borrowed("database_version"));
+
+//---------------------------------------------------------------------------------------------------- Free functions
+/// Bring the database up to schema `version`.
+///
+/// - `P` is the properties table the version is stored in
+/// - `D` is a table that is never empty once the database has been used
+/// - `migration(n, env_inner, tx_rw)` must migrate the database from version `n` to `n + 1`
+///
+/// If no version is stored and `D` is empty this is a new database and `version` is stored.
+/// If no version is stored but `D` has data, the database was created before versions
+/// were stored and is at version `0`.
+///
+/// # Errors
+/// This returns:
+/// - [`InitError::UnsupportedVersion`] if the stored version is newer than `version`
+/// - [`InitError::Migration`] if a migration step failed, on [`RuntimeError::ResizeNeeded`]
+///   the caller should resize the memory map and call this function again
+/// - [`InitError::Io`] or [`InitError::Unknown`] if reading or storing the version failed
+pub fn migrate<'env, Ei, P, D>(
+    env_inner: &Ei,
+    version: u64,
+    mut migration: impl FnMut(u64, &Ei, &Ei::Rw<'_>) -> DbResult<()>,
+) -> Result<(), InitError>
+where
+    Ei: EnvInner<'env>,
+    P: Table<Key = StorableStr, Value = u64>,
+    D: Table,
+{
+    let mut current = {
+        let tx_rw = env_inner.tx_rw().map_err(init_error)?;
+
+        let current = {
+            let mut table_properties = env_inner.open_db_rw::<P>(&tx_rw).map_err(init_error)?;
+
+            match table_properties.get(&VERSION_KEY) {
+                Ok(current) => current,
+                Err(RuntimeError::KeyNotFound) => {
+                    let is_new = env_inner
+                        .open_db_rw::<D>(&tx_rw)
+                        .and_then(|table| table.is_empty())
+                        .map_err(init_error)?;
+
+                    if is_new {
+                        table_properties
+                            .put(&VERSION_KEY, &version)
+                            .map_err(init_error)?;
+                        version
+                    } else {
+                        0
+                    }
+                }
+                Err(e) => return Err(init_error(e)),
+            }
+        };
+
+        TxRw::commit(tx_rw).map_err(init_error)?;
+        current
+    };
+
+    if current > version {
+        return Err(InitError::UnsupportedVersion {
+            found: current,
+            supported: version,
+        });
+    }
+
+    while current < version {
+        let tx_rw = env_inner.tx_rw().map_err(init_error)?;
+
+        migration(current, env_inner, &tx_rw)
+            .and_then(|()| {
+                env_inner
+                    .open_db_rw::<P>(&tx_rw)?
+                    .put(&VERSION_KEY, &(current + 1))
+            })
+            .map_err(|error| InitError::Migration {
+                version: current,
+                error,
+            })?;
+
+        TxRw::commit(tx_rw).map_err(init_error)?;
+        current += 1;
+    }
+
+    Ok(())
+}
+
+/// Convert errors that are not from a migration step to [`InitError`]s.
+fn init_error(runtime: RuntimeError) -> InitError {
+    match runtime {
+        RuntimeError::Io(io_error) => io_error.into(),
+        e => InitError::Unknown(Box::new(e)),
+    }
+}
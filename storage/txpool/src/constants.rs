@@ -0,0 +1,14 @@
+//! General constants used throughout `cuprate-txpool`.
+
+//---------------------------------------------------------------------------------------------------- Version
+/// Current major version of the database.
+///
+/// This is incremented by 1 when `cuprate_txpool`'s
+/// structure/schema/tables change, a migration from the
+/// previous version must be added to `src/migrations.rs`.
+///
+/// Version history:
+/// - `0`: databases created before the version was stored
+/// - `1`: adds [`TransactionInfo::received_at`](crate::types::TransactionInfo::received_at)
+///   and the [`Properties`](crate::tables::Properties) table
+pub const DATABASE_VERSION: u64 = 1;
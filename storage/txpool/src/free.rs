@@ -1,9 +1,15 @@
 //! General free functions (related to the tx-pool database).
 
 //---------------------------------------------------------------------------------------------------- Import
-use cuprate_database::{ConcreteEnv, Env, EnvInner, InitError, RuntimeError, TxRw};
+use cuprate_database::{migration, ConcreteEnv, Env, EnvInner, InitError, RuntimeError, TxRw};
 
-use crate::{config::Config, tables::OpenTables, types::TransactionBlobHash};
+use crate::{
+    config::Config,
+    constants::DATABASE_VERSION,
+    migrations,
+    tables::{OpenTables, Properties, TransactionInfos},
+    types::TransactionBlobHash,
+};
 
 //---------------------------------------------------------------------------------------------------- Free functions
 /// Open the txpool database using the passed [`Config`].
@@ -20,6 +26,8 @@ use crate::{config::Config, tables::OpenTables, types::TransactionBlobHash};
 /// - The database file could not be opened
 /// - A write transaction could not be opened
 /// - A table could not be created/opened
+/// - The database version is newer than [`DATABASE_VERSION`]
+/// - A migration from an older version failed
 #[cold]
 #[inline(never)] // only called once
 pub fn open(config: Config) -> Result<ConcreteEnv, InitError> {
@@ -58,6 +66,29 @@ pub fn open(config: Config) -> Result<ConcreteEnv, InitError> {
         TxRw::commit(tx_rw).map_err(runtime_to_init_error)?;
     }
 
+    // Store the version of new databases, or migrate old ones.
+    loop {
+        let result = migration::migrate::<_, Properties, TransactionInfos>(
+            &env.env_inner(),
+            DATABASE_VERSION,
+            migrations::migrate,
+        );
+
+        match result {
+            // Migrations can write a lot, resize the memory map and resume.
+            Err(InitError::Migration {
+                error: RuntimeError::ResizeNeeded,
+                ..
+            }) if ConcreteEnv::MANUAL_RESIZE => {
+                env.resize_map(None);
+            }
+            result => {
+                result?;
+                break;
+            }
+        }
+    }
+
     Ok(env)
 }
 
@@ -8,7 +8,9 @@
 use tower as _;
 
 pub mod config;
+mod constants;
 mod free;
+mod migrations;
 pub mod ops;
 pub mod service;
 pub mod tables;
@@ -16,6 +18,7 @@ mod tx;
 pub mod types;
 
 pub use config::Config;
+pub use constants::DATABASE_VERSION;
 pub use free::{open, transaction_blob_hash};
 pub use tx::TxEntry;
 
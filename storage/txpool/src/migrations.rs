@@ -0,0 +1,205 @@
+//! Database schema migrations.
+//!
+//! [`migrate`] upgrades a database from one version to the next,
+//! it is run by [`cuprate_database::migration::migrate`] in [`crate::open`].
+//!
+//! See [`crate::DATABASE_VERSION`] for the version history.
+use bytemuck::{Pod, Zeroable};
+
+use cuprate_database::{DatabaseRw, DbResult, EnvInner, RuntimeError, Table};
+use cuprate_helper::time::current_unix_timestamp;
+
+use crate::{
+    tables::TransactionInfos,
+    types::{TransactionHash, TransactionInfo, TxStateFlags},
+};
+
+/// Migrate the database from `version` to `version + 1`.
+///
+/// # Errors
+/// This returns the [`RuntimeError`] of a failed migration.
+///
+/// # Panics
+/// This panics if there is no migration from `version`,
+/// i.e. `version >= DATABASE_VERSION`.
+pub(crate) fn migrate<'env, Ei: EnvInner<'env>>(
+    version: u64,
+    env_inner: &Ei,
+    tx_rw: &Ei::Rw<'_>,
+) -> DbResult<()> {
+    match version {
+        0 => v0_to_v1(env_inner, tx_rw),
+        _ => unreachable!("no migration from database version {version}"),
+    }
+}
+
+/// [`TransactionInfo`] in version `0`, before [`TransactionInfo::received_at`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+struct TransactionInfoV0 {
+    fee: u64,
+    weight: usize,
+    flags: TxStateFlags,
+    _padding: [u8; 7],
+}
+
+/// [`TransactionInfos`] in version `0`.
+struct TransactionInfosV0;
+
+impl Table for TransactionInfosV0 {
+    const NAME: &'static str = TransactionInfos::NAME;
+    type Key = TransactionHash;
+    type Value = TransactionInfoV0;
+}
+
+/// Add [`TransactionInfo::received_at`], the transactions
+/// already in the pool are treated as received now.
+fn v0_to_v1<'env, Ei: EnvInner<'env>>(env_inner: &Ei, tx_rw: &Ei::Rw<'_>) -> DbResult<()> {
+    let mut tx_infos = Vec::new();
+
+    {
+        let mut table_tx_infos = env_inner.open_db_rw::<TransactionInfosV0>(tx_rw)?;
+
+        loop {
+            match table_tx_infos.pop_first() {
+                Ok(tx_info) => tx_infos.push(tx_info),
+                Err(RuntimeError::KeyNotFound) => break,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    let mut table_tx_infos = env_inner.open_db_rw::<TransactionInfos>(tx_rw)?;
+    let received_at = current_unix_timestamp();
+
+    for (tx_hash, tx_info) in tx_infos {
+        table_tx_infos.put(
+            &tx_hash,
+            &TransactionInfo {
+                fee: tx_info.fee,
+                weight: tx_info.weight,
+                received_at,
+                flags: tx_info.flags,
+                _padding: [0; 7],
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use cuprate_database::{migration, ConcreteEnv, DatabaseRo, Env, InitError, TxRw};
+
+    use crate::{
+        config::{Config, ConfigBuilder},
+        tables::{OpenTables, Tables, TablesMut},
+        DATABASE_VERSION,
+    };
+
+    use super::*;
+
+    /// Create a [`Config`] in a temporary directory.
+    fn tmp_config() -> (Config, tempfile::TempDir) {
+        let tempdir = tempfile::tempdir().unwrap();
+        let config = ConfigBuilder::new()
+            .data_directory(tempdir.path().into())
+            .low_power()
+            .build();
+
+        (config, tempdir)
+    }
+
+    /// Returns the version stored in the database.
+    fn db_version(env: &ConcreteEnv) -> u64 {
+        let env_inner = env.env_inner();
+        let tx_ro = env_inner.tx_ro().unwrap();
+        let tables = env_inner.open_tables(&tx_ro).unwrap();
+
+        tables.properties().get(&migration::VERSION_KEY).unwrap()
+    }
+
+    /// New databases are created at [`DATABASE_VERSION`].
+    #[test]
+    fn new_database() {
+        let (config, _tmp) = tmp_config();
+        let env = crate::open(config).unwrap();
+
+        assert_eq!(db_version(&env), DATABASE_VERSION);
+    }
+
+    /// A version 0 database, with a transaction but without a
+    /// stored version, is migrated to [`DATABASE_VERSION`].
+    #[test]
+    fn v0_database() {
+        let (config, _tmp) = tmp_config();
+
+        let tx_info_v0 = TransactionInfoV0 {
+            fee: 1_000,
+            weight: 2_000,
+            flags: TxStateFlags::STATE_STEM,
+            _padding: [0; 7],
+        };
+
+        // Create the version 0 database.
+        {
+            let env = ConcreteEnv::open(config.db_config.clone()).unwrap();
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+            OpenTables::create_tables(&env_inner, &tx_rw).unwrap();
+
+            env_inner
+                .open_db_rw::<TransactionInfosV0>(&tx_rw)
+                .unwrap()
+                .put(&[1; 32], &tx_info_v0)
+                .unwrap();
+
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        let before = current_unix_timestamp();
+        let env = crate::open(config).unwrap();
+
+        assert_eq!(db_version(&env), DATABASE_VERSION);
+
+        let env_inner = env.env_inner();
+        let tx_ro = env_inner.tx_ro().unwrap();
+        let tables = env_inner.open_tables(&tx_ro).unwrap();
+
+        assert_eq!(tables.transaction_infos().len().unwrap(), 1);
+
+        let tx_info = tables.transaction_infos().get(&[1; 32]).unwrap();
+        assert_eq!(tx_info.fee, tx_info_v0.fee);
+        assert_eq!(tx_info.weight, tx_info_v0.weight);
+        assert_eq!(tx_info.flags, tx_info_v0.flags);
+        assert!(tx_info.received_at >= before);
+    }
+
+    /// Databases from a newer version are refused.
+    #[test]
+    fn newer_database() {
+        let (config, _tmp) = tmp_config();
+
+        {
+            let env = crate::open(config.clone()).unwrap();
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+
+            let mut tables = env_inner.open_tables_mut(&tx_rw).unwrap();
+            tables
+                .properties_mut()
+                .put(&migration::VERSION_KEY, &(DATABASE_VERSION + 1))
+                .unwrap();
+
+            drop(tables);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        assert!(matches!(
+            crate::open(config),
+            Err(InitError::UnsupportedVersion { found, supported })
+                if found == DATABASE_VERSION + 1 && supported == DATABASE_VERSION
+        ));
+    }
+}
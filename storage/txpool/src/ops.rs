@@ -81,10 +81,12 @@
 //! # Ok(()) }
 //! ```
 
+mod eviction;
 mod key_images;
 mod tx_read;
 mod tx_write;
 
+pub use eviction::{expired_txs, txs_to_evict, FLUFF_TX_LIVETIME, STEM_TX_LIVETIME};
 pub use tx_read::{get_transaction_verification_data, in_stem_pool};
 pub use tx_write::{add_transaction, remove_transaction};
 
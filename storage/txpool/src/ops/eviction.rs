@@ -0,0 +1,264 @@
+//! Transaction eviction ops.
+//!
+//! This module handles finding the transactions that should be dropped from the pool,
+//! either because the pool is too large or because the transactions are too old.
+use std::cmp::Ordering;
+
+use cuprate_database::{DatabaseIter, DbResult};
+use cuprate_helper::cast::usize_to_u64;
+
+use crate::{
+    tables::TransactionInfos,
+    types::{TransactionHash, TransactionInfo, TxStateFlags},
+};
+
+/// The amount of seconds a transaction in the stem state can stay in the pool, 3 days.
+///
+/// Stem transactions should be fluffed by their embargo timer long before this.
+pub const STEM_TX_LIVETIME: u64 = 60 * 60 * 24 * 3;
+
+/// The amount of seconds a transaction that has been relayed (fluffed) can stay in the pool, 1 week.
+pub const FLUFF_TX_LIVETIME: u64 = 60 * 60 * 24 * 7;
+
+/// Returns the hashes of the transactions that need to be removed for the pool to be under `max_weight`.
+///
+/// Transactions with the lowest fee-per-byte are picked first, the oldest first when equal.
+///
+/// Only fluff transactions are picked, stem transactions are only in the pool until their
+/// embargo timer fluffs them and dropping them early would let a peer that fills our pool
+/// find out which transactions we are stemming. Stem transactions still count towards the
+/// weight of the pool.
+pub fn txs_to_evict(
+    max_weight: usize,
+    tx_infos: &impl DatabaseIter<TransactionInfos>,
+) -> DbResult<Vec<TransactionHash>> {
+    let mut pool_weight = 0_usize;
+    let mut fluff_txs = Vec::new();
+
+    for res in tx_infos.iter()? {
+        let (tx_hash, tx_info) = res?;
+
+        pool_weight += tx_info.weight;
+
+        if !tx_info.flags.contains(TxStateFlags::STATE_STEM) {
+            fluff_txs.push((tx_hash, tx_info));
+        }
+    }
+
+    if pool_weight <= max_weight {
+        return Ok(vec![]);
+    }
+
+    fluff_txs.sort_unstable_by(|(_, a), (_, b)| cmp_fee_per_byte(a, b));
+
+    Ok(fluff_txs
+        .into_iter()
+        .take_while(|(_, tx_info)| {
+            if pool_weight <= max_weight {
+                return false;
+            }
+
+            pool_weight -= tx_info.weight;
+            true
+        })
+        .map(|(tx_hash, _)| tx_hash)
+        .collect())
+}
+
+/// Returns the hashes of the transactions that have been in the pool for too long at the UNIX timestamp `now`.
+///
+/// See [`STEM_TX_LIVETIME`] and [`FLUFF_TX_LIVETIME`].
+pub fn expired_txs(
+    now: u64,
+    tx_infos: &impl DatabaseIter<TransactionInfos>,
+) -> DbResult<Vec<TransactionHash>> {
+    tx_infos
+        .iter()?
+        .filter_map(|res| {
+            let (tx_hash, tx_info) = match res {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let livetime = if tx_info.flags.contains(TxStateFlags::STATE_STEM) {
+                STEM_TX_LIVETIME
+            } else {
+                FLUFF_TX_LIVETIME
+            };
+
+            (now.saturating_sub(tx_info.received_at) > livetime).then_some(Ok(tx_hash))
+        })
+        .collect()
+}
+
+/// Compares the fee-per-byte of 2 transactions, older transactions are ordered first if equal.
+fn cmp_fee_per_byte(a: &TransactionInfo, b: &TransactionInfo) -> Ordering {
+    // a.fee / a.weight <=> b.fee / b.weight, without the division.
+    let a_fee = u128::from(a.fee) * u128::from(usize_to_u64(b.weight));
+    let b_fee = u128::from(b.fee) * u128::from(usize_to_u64(a.weight));
+
+    a_fee
+        .cmp(&b_fee)
+        .then_with(|| a.received_at.cmp(&b.received_at))
+}
+
+#[cfg(test)]
+mod test {
+    use cuprate_database::{ConcreteEnv, DatabaseRw, Env, EnvInner, TxRw};
+
+    use crate::config::ConfigBuilder;
+
+    use super::*;
+
+    fn tx_info(fee: u64, weight: usize, received_at: u64, stem: bool) -> TransactionInfo {
+        TransactionInfo {
+            fee,
+            weight,
+            received_at,
+            flags: if stem {
+                TxStateFlags::STATE_STEM
+            } else {
+                TxStateFlags::empty()
+            },
+            _padding: [0; 7],
+        }
+    }
+
+    /// Open a database in a temporary directory, with `tx_infos` in the [`TransactionInfos`] table.
+    fn env_with_tx_infos(
+        tx_infos: &[(TransactionHash, TransactionInfo)],
+    ) -> (ConcreteEnv, tempfile::TempDir) {
+        let tempdir = tempfile::tempdir().unwrap();
+        let config = ConfigBuilder::new()
+            .data_directory(tempdir.path().into())
+            .low_power()
+            .build();
+        let env = crate::open(config).unwrap();
+
+        {
+            let env_inner = env.env_inner();
+            let tx_rw = env_inner.tx_rw().unwrap();
+            let mut table = env_inner.open_db_rw::<TransactionInfos>(&tx_rw).unwrap();
+
+            for (tx_hash, tx_info) in tx_infos {
+                table.put(tx_hash, tx_info).unwrap();
+            }
+
+            drop(table);
+            TxRw::commit(tx_rw).unwrap();
+        }
+
+        (env, tempdir)
+    }
+
+    fn evict(
+        max_weight: usize,
+        tx_infos: &[(TransactionHash, TransactionInfo)],
+    ) -> Vec<TransactionHash> {
+        let (env, _tempdir) = env_with_tx_infos(tx_infos);
+        let env_inner = env.env_inner();
+        let tx_ro = env_inner.tx_ro().unwrap();
+        let table = env_inner.open_db_ro::<TransactionInfos>(&tx_ro).unwrap();
+
+        txs_to_evict(max_weight, &table).unwrap()
+    }
+
+    fn expired(now: u64, tx_infos: &[(TransactionHash, TransactionInfo)]) -> Vec<TransactionHash> {
+        let (env, _tempdir) = env_with_tx_infos(tx_infos);
+        let env_inner = env.env_inner();
+        let tx_ro = env_inner.tx_ro().unwrap();
+        let table = env_inner.open_db_ro::<TransactionInfos>(&tx_ro).unwrap();
+
+        let mut expired = expired_txs(now, &table).unwrap();
+        expired.sort_unstable();
+        expired
+    }
+
+    #[test]
+    fn fee_per_byte_ordering() {
+        // 1 atomic unit per byte vs 2.
+        assert_eq!(
+            cmp_fee_per_byte(&tx_info(100, 100, 0, false), &tx_info(100, 50, 0, false)),
+            Ordering::Less
+        );
+        assert_eq!(
+            cmp_fee_per_byte(&tx_info(300, 100, 0, false), &tx_info(100, 50, 0, false)),
+            Ordering::Greater
+        );
+
+        // Equal fee-per-byte, the older tx is first.
+        assert_eq!(
+            cmp_fee_per_byte(&tx_info(100, 50, 1, false), &tx_info(200, 100, 2, false)),
+            Ordering::Less
+        );
+        assert_eq!(
+            cmp_fee_per_byte(&tx_info(200, 100, 2, false), &tx_info(100, 50, 1, false)),
+            Ordering::Greater
+        );
+
+        // Large values must not overflow.
+        assert_eq!(
+            cmp_fee_per_byte(
+                &tx_info(u64::MAX, usize::MAX, 0, false),
+                &tx_info(u64::MAX, 1, 0, false)
+            ),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn evicts_lowest_fee_per_byte_first() {
+        let tx_infos = [
+            ([1; 32], tx_info(300, 100, 0, false)),
+            ([2; 32], tx_info(100, 100, 0, false)),
+            ([3; 32], tx_info(200, 100, 0, false)),
+            ([4; 32], tx_info(400, 100, 0, false)),
+        ];
+
+        // Under the limit, nothing is evicted.
+        assert!(evict(400, &tx_infos).is_empty());
+
+        // Trimming stops once the pool is under `max_weight`.
+        assert_eq!(evict(300, &tx_infos), vec![[2; 32]]);
+        assert_eq!(evict(250, &tx_infos), vec![[2; 32], [3; 32]]);
+        assert_eq!(
+            evict(0, &tx_infos),
+            vec![[2; 32], [3; 32], [1; 32], [4; 32]]
+        );
+    }
+
+    #[test]
+    fn stem_txs_never_evicted() {
+        let tx_infos = [
+            ([1; 32], tx_info(1, 100, 0, true)),
+            ([2; 32], tx_info(100, 100, 0, false)),
+            ([3; 32], tx_info(2, 100, 0, true)),
+        ];
+
+        // Stem txs count towards the weight, but only the fluff tx can be evicted.
+        assert_eq!(evict(250, &tx_infos), vec![[2; 32]]);
+        assert_eq!(evict(0, &tx_infos), vec![[2; 32]]);
+
+        // Only stem txs, the pool stays over the limit.
+        assert!(evict(0, &[tx_infos[0], tx_infos[2]]).is_empty());
+    }
+
+    #[test]
+    fn expiry() {
+        let now = FLUFF_TX_LIVETIME * 2;
+
+        let tx_infos = [
+            // Stem txs.
+            ([1; 32], tx_info(0, 1, now - STEM_TX_LIVETIME, true)),
+            ([2; 32], tx_info(0, 1, now - STEM_TX_LIVETIME - 1, true)),
+            // Fluff txs, past the stem live time but not the fluff one.
+            ([3; 32], tx_info(0, 1, now - STEM_TX_LIVETIME - 1, false)),
+            ([4; 32], tx_info(0, 1, now - FLUFF_TX_LIVETIME, false)),
+            ([5; 32], tx_info(0, 1, now - FLUFF_TX_LIVETIME - 1, false)),
+            // Received after `now`, e.g. the clock went backwards.
+            ([6; 32], tx_info(0, 1, now + 1, false)),
+        ];
+
+        assert_eq!(expired(now, &tx_infos), vec![[2; 32], [5; 32]]);
+    }
+}
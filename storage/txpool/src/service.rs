@@ -125,8 +125,12 @@
 mod free;
 pub mod interface;
 mod read;
+mod removed_txs;
 mod types;
 mod write;
 
+#[cfg(test)]
+mod tests;
+
 pub use free::{init, init_with_pool};
 pub use types::{TxpoolReadHandle, TxpoolWriteHandle};
@@ -1,12 +1,14 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use rayon::ThreadPool;
 
 use cuprate_database::{ConcreteEnv, InitError};
+use cuprate_helper::time::current_unix_timestamp;
 
 use crate::{
     service::{
         read::{init_read_service, init_read_service_with_pool},
+        removed_txs::RemovedTxs,
         types::{TxpoolReadHandle, TxpoolWriteHandle},
         write::init_write_service,
     },
@@ -27,13 +29,16 @@ pub fn init(
     config: Config,
 ) -> Result<(TxpoolReadHandle, TxpoolWriteHandle, Arc<ConcreteEnv>), InitError> {
     let reader_threads = config.reader_threads;
+    let max_txpool_weight = config.max_txpool_weight;
 
     // Initialize the database itself.
     let db = Arc::new(crate::open(config)?);
 
+    let removed_txs = Arc::new(Mutex::new(RemovedTxs::new(current_unix_timestamp())));
+
     // Spawn the Reader thread pool and Writer.
-    let readers = init_read_service(Arc::clone(&db), reader_threads);
-    let writer = init_write_service(Arc::clone(&db));
+    let readers = init_read_service(Arc::clone(&db), reader_threads, Arc::clone(&removed_txs));
+    let writer = init_write_service(Arc::clone(&db), max_txpool_weight, removed_txs);
 
     Ok((readers, writer, db))
 }
@@ -54,12 +59,16 @@ pub fn init_with_pool(
     config: Config,
     pool: Arc<ThreadPool>,
 ) -> Result<(TxpoolReadHandle, TxpoolWriteHandle, Arc<ConcreteEnv>), InitError> {
+    let max_txpool_weight = config.max_txpool_weight;
+
     // Initialize the database itself.
     let db = Arc::new(crate::open(config)?);
 
+    let removed_txs = Arc::new(Mutex::new(RemovedTxs::new(current_unix_timestamp())));
+
     // Spawn the Reader thread pool and Writer.
-    let readers = init_read_service_with_pool(Arc::clone(&db), pool);
-    let writer = init_write_service(Arc::clone(&db));
+    let readers = init_read_service_with_pool(Arc::clone(&db), pool, Arc::clone(&removed_txs));
+    let writer = init_write_service(Arc::clone(&db), max_txpool_weight, removed_txs);
 
     Ok((readers, writer, db))
 }
@@ -40,6 +40,8 @@ pub enum TxpoolReadRequest {
     TxsForBlock(Vec<TransactionHash>),
 
     /// Get information on all transactions in the pool.
+    ///
+    /// This does not include stem transactions.
     Backlog,
 
     /// Get the number of transactions in the pool.
@@ -182,6 +184,22 @@ pub enum TxpoolWriteRequest {
         /// The spent key images in the new block.
         spent_key_images: Vec<KeyImage>,
     },
+
+    /// Remove the lowest fee-per-byte transactions until the pool's weight
+    /// is under [`Config::max_txpool_weight`](crate::Config::max_txpool_weight).
+    ///
+    /// Stem transactions are never removed by this request.
+    ///
+    /// Returns [`TxpoolWriteResponse::RemovedTransactions`].
+    TrimPool,
+
+    /// Remove transactions that have been in the pool for too long.
+    ///
+    /// See [`STEM_TX_LIVETIME`](crate::ops::STEM_TX_LIVETIME) and
+    /// [`FLUFF_TX_LIVETIME`](crate::ops::FLUFF_TX_LIVETIME).
+    ///
+    /// Returns [`TxpoolWriteResponse::RemovedTransactions`].
+    RemoveExpired,
 }
 
 //---------------------------------------------------------------------------------------------------- TxpoolWriteResponse
@@ -198,4 +216,16 @@ pub enum TxpoolWriteResponse {
     ///
     /// If the inner value is [`Some`] the tx was not added to the pool as it double spends a tx with the given hash.
     AddTransaction(Option<TransactionHash>),
+
+    /// Response to:
+    /// - [`TxpoolWriteRequest::TrimPool`]
+    /// - [`TxpoolWriteRequest::RemoveExpired`]
+    RemovedTransactions {
+        /// The hashes of the removed transactions that were in the fluff state.
+        fluff_tx_hashes: Vec<TransactionHash>,
+        /// The hashes of the removed transactions that were in the stem state.
+        ///
+        /// These should not be exposed publicly, as they were never fluffed by us.
+        stem_tx_hashes: Vec<TransactionHash>,
+    },
 }
@@ -8,22 +8,29 @@
 use std::{
     collections::{HashMap, HashSet},
     num::NonZero,
-    sync::Arc,
+    sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use rayon::ThreadPool;
 
-use cuprate_database::{ConcreteEnv, DatabaseRo, DbResult, Env, EnvInner, RuntimeError};
+use cuprate_database::{
+    ConcreteEnv, DatabaseIter, DatabaseRo, DbResult, Env, EnvInner, RuntimeError,
+};
 use cuprate_database_service::{init_thread_pool, DatabaseReadService, ReaderThreads};
+use cuprate_helper::{cast::usize_to_u64, time::current_unix_timestamp};
+use cuprate_types::rpc::{PoolInfo, PoolInfoFull, PoolInfoIncremental, PoolTxInfo};
 
 use crate::{
     ops::{get_transaction_verification_data, in_stem_pool},
     service::{
         interface::{TxpoolReadRequest, TxpoolReadResponse},
+        removed_txs::RemovedTxs,
         types::{ReadResponseResult, TxpoolReadHandle},
     },
     tables::{KnownBlobHashes, OpenTables, TransactionBlobs, TransactionInfos},
-    types::{TransactionBlobHash, TransactionHash},
+    types::{TransactionBlobHash, TransactionHash, TxStateFlags},
+    TxEntry,
 };
 
 // TODO: update the docs here
@@ -33,11 +40,17 @@ use crate::{
 /// This spawns `threads` amount of reader threads
 /// attached to `env` and returns a handle to the pool.
 ///
+/// `removed_txs` is shared with the write service.
+///
 /// Should be called _once_ per actual database.
 #[cold]
 #[inline(never)] // Only called once.
-pub(super) fn init_read_service(env: Arc<ConcreteEnv>, threads: ReaderThreads) -> TxpoolReadHandle {
-    init_read_service_with_pool(env, init_thread_pool(threads))
+pub(super) fn init_read_service(
+    env: Arc<ConcreteEnv>,
+    threads: ReaderThreads,
+    removed_txs: Arc<Mutex<RemovedTxs>>,
+) -> TxpoolReadHandle {
+    init_read_service_with_pool(env, init_thread_pool(threads), removed_txs)
 }
 
 /// Initialize the [`TxpoolReadHandle`], with a specific rayon thread-pool instead of
@@ -49,8 +62,11 @@ pub(super) fn init_read_service(env: Arc<ConcreteEnv>, threads: ReaderThreads) -
 pub(super) fn init_read_service_with_pool(
     env: Arc<ConcreteEnv>,
     pool: Arc<ThreadPool>,
+    removed_txs: Arc<Mutex<RemovedTxs>>,
 ) -> TxpoolReadHandle {
-    DatabaseReadService::new(env, pool, map_request)
+    DatabaseReadService::new(env, pool, move |env, request| {
+        map_request(env, request, &removed_txs)
+    })
 }
 
 //---------------------------------------------------------------------------------------------------- Request Mapping
@@ -65,8 +81,9 @@ pub(super) fn init_read_service_with_pool(
 /// 2. Handler function is called
 /// 3. [`TxpoolReadResponse`] is returned
 fn map_request(
-    env: &ConcreteEnv,          // Access to the database
-    request: TxpoolReadRequest, // The request we must fulfill
+    env: &ConcreteEnv,               // Access to the database
+    request: TxpoolReadRequest,      // The request we must fulfill
+    removed_txs: &Mutex<RemovedTxs>, // The txs removed from the pool
 ) -> ReadResponseResult {
     match request {
         TxpoolReadRequest::TxBlob(tx_hash) => tx_blob(env, &tx_hash),
@@ -83,7 +100,13 @@ fn map_request(
             include_sensitive_txs,
             max_tx_count,
             start_time,
-        } => pool_info(env, include_sensitive_txs, max_tx_count, start_time),
+        } => pool_info(
+            env,
+            include_sensitive_txs,
+            max_tx_count,
+            start_time,
+            removed_txs,
+        ),
         TxpoolReadRequest::TxsByHash {
             tx_hashes,
             include_sensitive_txs,
@@ -227,9 +250,38 @@ fn txs_for_block(env: &ConcreteEnv, txs: Vec<TransactionHash>) -> ReadResponseRe
 }
 
 /// [`TxpoolReadRequest::Backlog`].
-#[inline]
+///
+/// Stem transactions are not included.
 fn backlog(env: &ConcreteEnv) -> ReadResponseResult {
-    Ok(TxpoolReadResponse::Backlog(todo!()))
+    let inner_env = env.env_inner();
+    let tx_ro = inner_env.tx_ro()?;
+
+    let tx_infos = inner_env.open_db_ro::<TransactionInfos>(&tx_ro)?;
+
+    let now = current_unix_timestamp();
+
+    let backlog = tx_infos
+        .iter()?
+        .filter_map(|res| {
+            let (tx_hash, tx_info) = match res {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if tx_info.flags.contains(TxStateFlags::STATE_STEM) {
+                return None;
+            }
+
+            Some(Ok(TxEntry {
+                id: tx_hash,
+                weight: usize_to_u64(tx_info.weight),
+                fee: tx_info.fee,
+                time_in_pool: Duration::from_secs(now.saturating_sub(tx_info.received_at)),
+            }))
+        })
+        .collect::<DbResult<_>>()?;
+
+    Ok(TxpoolReadResponse::Backlog(backlog))
 }
 
 /// [`TxpoolReadRequest::Size`].
@@ -239,13 +291,75 @@ fn size(env: &ConcreteEnv, include_sensitive_txs: bool) -> ReadResponseResult {
 }
 
 /// [`TxpoolReadRequest::PoolInfo`].
+///
+/// If the removals since `start_time` are known, an incremental update with the transactions received
+/// and removed since `start_time` is returned, otherwise the full pool is returned.
 fn pool_info(
     env: &ConcreteEnv,
     include_sensitive_txs: bool,
     max_tx_count: usize,
     start_time: Option<NonZero<usize>>,
+    removed_txs: &Mutex<RemovedTxs>,
 ) -> ReadResponseResult {
-    Ok(TxpoolReadResponse::PoolInfo(todo!()))
+    // The removals are read first, a tx removed after this is in the next update.
+    let (start_time, removed_pool_txids) = start_time
+        .and_then(|start_time| {
+            let start_time = usize_to_u64(start_time.get());
+            removed_txs
+                .lock()
+                .unwrap()
+                .removed_since(start_time, include_sensitive_txs)
+                .map(|removed_pool_txids| (start_time, Some(removed_pool_txids)))
+        })
+        .unwrap_or((0, None));
+
+    let inner_env = env.env_inner();
+    let tx_ro = inner_env.tx_ro()?;
+
+    let tx_blobs = inner_env.open_db_ro::<TransactionBlobs>(&tx_ro)?;
+    let tx_infos = inner_env.open_db_ro::<TransactionInfos>(&tx_ro)?;
+
+    let mut added_pool_txs = Vec::new();
+    let mut remaining_added_pool_txids = Vec::new();
+
+    for res in tx_infos.iter()? {
+        let (tx_hash, tx_info) = res?;
+
+        if !include_sensitive_txs && tx_info.flags.contains(TxStateFlags::STATE_STEM) {
+            continue;
+        }
+
+        if tx_info.received_at < start_time {
+            continue;
+        }
+
+        if added_pool_txs.len() >= max_tx_count {
+            remaining_added_pool_txids.push(tx_hash);
+            continue;
+        }
+
+        added_pool_txs.push(PoolTxInfo {
+            tx_hash,
+            tx_blob: tx_blobs.get(&tx_hash)?.0,
+            double_spend_seen: tx_info.flags.contains(TxStateFlags::DOUBLE_SPENT),
+        });
+    }
+
+    let remaining_added_pool_txids = remaining_added_pool_txids.into();
+
+    let pool_info = match removed_pool_txids {
+        Some(removed_pool_txids) => PoolInfo::Incremental(PoolInfoIncremental {
+            added_pool_txs,
+            remaining_added_pool_txids,
+            removed_pool_txids: removed_pool_txids.into(),
+        }),
+        None => PoolInfo::Full(PoolInfoFull {
+            added_pool_txs,
+            remaining_added_pool_txids,
+        }),
+    };
+
+    Ok(TxpoolReadResponse::PoolInfo(pool_info))
 }
 
 /// [`TxpoolReadRequest::TxsByHash`].
@@ -290,5 +404,20 @@ fn pool_stats(env: &ConcreteEnv, include_sensitive_txs: bool) -> ReadResponseRes
 
 /// [`TxpoolReadRequest::AllHashes`].
 fn all_hashes(env: &ConcreteEnv, include_sensitive_txs: bool) -> ReadResponseResult {
-    Ok(TxpoolReadResponse::AllHashes(todo!()))
+    let inner_env = env.env_inner();
+    let tx_ro = inner_env.tx_ro()?;
+
+    let tx_infos = inner_env.open_db_ro::<TransactionInfos>(&tx_ro)?;
+
+    let hashes = tx_infos
+        .iter()?
+        .filter_map(|res| match res {
+            Ok((tx_hash, tx_info)) => (include_sensitive_txs
+                || !tx_info.flags.contains(TxStateFlags::STATE_STEM))
+            .then_some(Ok(tx_hash)),
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<DbResult<_>>()?;
+
+    Ok(TxpoolReadResponse::AllHashes(hashes))
 }
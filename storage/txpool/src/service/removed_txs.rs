@@ -0,0 +1,120 @@
+//! Removed transactions.
+//!
+//! This module keeps a record of the transactions removed from the pool, so
+//! [`TxpoolReadRequest::PoolInfo`](super::interface::TxpoolReadRequest::PoolInfo)
+//! can return incremental updates.
+use std::collections::VecDeque;
+
+use crate::types::TransactionHash;
+
+/// The amount of seconds a removed transaction is remembered for, 1 hour.
+const REMOVED_TX_LIVETIME: u64 = 60 * 60;
+
+/// A transaction removed from the pool.
+struct RemovedTx {
+    tx_hash: TransactionHash,
+    /// The UNIX timestamp the transaction was removed at.
+    removed_at: u64,
+    /// If the transaction was in the stem state.
+    stem: bool,
+}
+
+/// The transactions removed from the pool in the last [`REMOVED_TX_LIVETIME`] seconds.
+///
+/// This is only kept in memory, so removals from before the pool was opened are not known.
+pub(super) struct RemovedTxs {
+    /// The UNIX timestamp all removals are known from.
+    known_since: u64,
+    /// The removed transactions, oldest first.
+    txs: VecDeque<RemovedTx>,
+}
+
+impl RemovedTxs {
+    /// Returns an empty [`RemovedTxs`], knowing all removals from `now`.
+    pub(super) const fn new(now: u64) -> Self {
+        Self {
+            known_since: now,
+            txs: VecDeque::new(),
+        }
+    }
+
+    /// Record the removal of the given transactions at `now`, forgetting removals older
+    /// than [`REMOVED_TX_LIVETIME`].
+    pub(super) fn record(
+        &mut self,
+        now: u64,
+        fluff_tx_hashes: &[TransactionHash],
+        stem_tx_hashes: &[TransactionHash],
+    ) {
+        for (tx_hashes, stem) in [(fluff_tx_hashes, false), (stem_tx_hashes, true)] {
+            self.txs.extend(tx_hashes.iter().map(|tx_hash| RemovedTx {
+                tx_hash: *tx_hash,
+                removed_at: now,
+                stem,
+            }));
+        }
+
+        while let Some(tx) = self.txs.front() {
+            if now.saturating_sub(tx.removed_at) <= REMOVED_TX_LIVETIME {
+                break;
+            }
+
+            self.known_since = tx.removed_at;
+            self.txs.pop_front();
+        }
+    }
+
+    /// Returns the hashes of the transactions removed at or after `start_time`.
+    ///
+    /// Returns [`None`] if removals from that far back are not known.
+    ///
+    /// Stem transactions are only included if `include_sensitive_txs` is true.
+    pub(super) fn removed_since(
+        &self,
+        start_time: u64,
+        include_sensitive_txs: bool,
+    ) -> Option<Vec<TransactionHash>> {
+        if start_time <= self.known_since {
+            return None;
+        }
+
+        Some(
+            self.txs
+                .iter()
+                .filter(|tx| tx.removed_at >= start_time && (include_sensitive_txs || !tx.stem))
+                .map(|tx| tx.tx_hash)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removed_since() {
+        let mut removed_txs = RemovedTxs::new(100);
+
+        removed_txs.record(110, &[[1; 32]], &[[2; 32]]);
+        removed_txs.record(120, &[[3; 32]], &[]);
+
+        // Removals from before the pool was opened are not known.
+        assert_eq!(removed_txs.removed_since(100, true), None);
+
+        assert_eq!(
+            removed_txs.removed_since(101, true),
+            Some(vec![[1; 32], [2; 32], [3; 32]])
+        );
+        assert_eq!(
+            removed_txs.removed_since(101, false),
+            Some(vec![[1; 32], [3; 32]])
+        );
+        assert_eq!(removed_txs.removed_since(111, true), Some(vec![[3; 32]]));
+
+        // Forgetting the removals at 110 means removals are only known after it.
+        removed_txs.record(111 + REMOVED_TX_LIVETIME, &[], &[]);
+        assert_eq!(removed_txs.removed_since(110, true), None);
+        assert_eq!(removed_txs.removed_since(111, true), Some(vec![[3; 32]]));
+    }
+}
@@ -0,0 +1,158 @@
+//! `crate::service` tests.
+//!
+//! This module contains tests for the pool maintenance write requests.
+
+//---------------------------------------------------------------------------------------------------- Use
+use tower::{Service, ServiceExt};
+
+use cuprate_database::{DatabaseRo, DatabaseRw, Env, EnvInner, TxRw};
+use cuprate_helper::time::current_unix_timestamp;
+use cuprate_test_utils::data::{TX_V1_SIG0, TX_V1_SIG2, TX_V2_RCT3};
+use cuprate_types::{TransactionVerificationData, VerifiedTransactionInformation};
+
+use crate::{
+    config::ConfigBuilder,
+    ops::{FLUFF_TX_LIVETIME, STEM_TX_LIVETIME},
+    service::{
+        init,
+        interface::{TxpoolWriteRequest, TxpoolWriteResponse},
+        TxpoolWriteHandle,
+    },
+    tables::TransactionInfos,
+    types::TransactionHash,
+};
+
+//---------------------------------------------------------------------------------------------------- Helper functions
+/// Add `tx` to the pool with a fee of `fee`, so the fee-per-byte order is known.
+async fn add_tx(
+    writer: &mut TxpoolWriteHandle,
+    tx: &VerifiedTransactionInformation,
+    fee: u64,
+    state_stem: bool,
+) {
+    let mut tx = TransactionVerificationData::try_from(tx.clone()).unwrap();
+    tx.fee = fee;
+
+    let response = writer
+        .ready()
+        .await
+        .unwrap()
+        .call(TxpoolWriteRequest::AddTransaction {
+            tx: Box::new(tx),
+            state_stem,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response, TxpoolWriteResponse::AddTransaction(None));
+}
+
+async fn removed_txs(
+    writer: &mut TxpoolWriteHandle,
+    request: TxpoolWriteRequest,
+) -> (Vec<TransactionHash>, Vec<TransactionHash>) {
+    let TxpoolWriteResponse::RemovedTransactions {
+        fluff_tx_hashes,
+        stem_tx_hashes,
+    } = writer.ready().await.unwrap().call(request).await.unwrap()
+    else {
+        panic!("Wrong response type was returned");
+    };
+
+    (fluff_tx_hashes, stem_tx_hashes)
+}
+
+//---------------------------------------------------------------------------------------------------- Tests
+/// [`TxpoolWriteRequest::TrimPool`] removes the fluff txs with the lowest
+/// fee-per-byte until the pool is under its weight limit, never stem txs.
+#[tokio::test]
+async fn trim_pool() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let config = ConfigBuilder::new()
+        .data_directory(tempdir.path().into())
+        .low_power()
+        // Only room for 2 of the 3 txs.
+        .max_txpool_weight(TX_V1_SIG0.tx_weight + TX_V2_RCT3.tx_weight)
+        .build();
+    let (_reader, mut writer, env) = init(config).unwrap();
+
+    // The stem tx has the lowest fee-per-byte.
+    add_tx(&mut writer, &TX_V1_SIG0, 0, true).await;
+    add_tx(&mut writer, &TX_V1_SIG2, 1, false).await;
+    add_tx(&mut writer, &TX_V2_RCT3, 1_000_000, false).await;
+
+    assert_eq!(
+        removed_txs(&mut writer, TxpoolWriteRequest::TrimPool).await,
+        (vec![TX_V1_SIG2.tx_hash], vec![])
+    );
+
+    // The pool is now under the limit.
+    assert_eq!(
+        removed_txs(&mut writer, TxpoolWriteRequest::TrimPool).await,
+        (vec![], vec![])
+    );
+
+    let env_inner = env.env_inner();
+    let tx_ro = env_inner.tx_ro().unwrap();
+    let tx_infos = env_inner.open_db_ro::<TransactionInfos>(&tx_ro).unwrap();
+    assert!(tx_infos.contains(&TX_V1_SIG0.tx_hash).unwrap());
+    assert!(!tx_infos.contains(&TX_V1_SIG2.tx_hash).unwrap());
+    assert!(tx_infos.contains(&TX_V2_RCT3.tx_hash).unwrap());
+}
+
+/// [`TxpoolWriteRequest::RemoveExpired`] uses [`STEM_TX_LIVETIME`] for stem txs and
+/// [`FLUFF_TX_LIVETIME`] for fluff txs, and splits the removed hashes by state.
+#[tokio::test]
+async fn remove_expired() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let config = ConfigBuilder::new()
+        .data_directory(tempdir.path().into())
+        .low_power()
+        .build();
+    let (_reader, mut writer, env) = init(config).unwrap();
+
+    add_tx(&mut writer, &TX_V1_SIG0, 0, true).await;
+    add_tx(&mut writer, &TX_V1_SIG2, 0, false).await;
+    add_tx(&mut writer, &TX_V2_RCT3, 0, false).await;
+
+    // Nothing has expired yet.
+    assert_eq!(
+        removed_txs(&mut writer, TxpoolWriteRequest::RemoveExpired).await,
+        (vec![], vec![])
+    );
+
+    // Age the txs, the writer is idle so the tables can be written to directly.
+    let now = current_unix_timestamp();
+    {
+        let env_inner = env.env_inner();
+        let tx_rw = env_inner.tx_rw().unwrap();
+        let mut tx_infos = env_inner.open_db_rw::<TransactionInfos>(&tx_rw).unwrap();
+
+        for (tx_hash, age) in [
+            // Expired.
+            (TX_V1_SIG0.tx_hash, STEM_TX_LIVETIME + 60),
+            // Past the stem live time, but a fluff tx.
+            (TX_V1_SIG2.tx_hash, STEM_TX_LIVETIME + 60),
+            // Expired.
+            (TX_V2_RCT3.tx_hash, FLUFF_TX_LIVETIME + 60),
+        ] {
+            let mut tx_info = tx_infos.get(&tx_hash).unwrap();
+            tx_info.received_at = now - age;
+            tx_infos.put(&tx_hash, &tx_info).unwrap();
+        }
+
+        drop(tx_infos);
+        TxRw::commit(tx_rw).unwrap();
+    }
+
+    assert_eq!(
+        removed_txs(&mut writer, TxpoolWriteRequest::RemoveExpired).await,
+        (vec![TX_V2_RCT3.tx_hash], vec![TX_V1_SIG0.tx_hash])
+    );
+
+    let env_inner = env.env_inner();
+    let tx_ro = env_inner.tx_ro().unwrap();
+    let tx_infos = env_inner.open_db_ro::<TransactionInfos>(&tx_ro).unwrap();
+    assert_eq!(tx_infos.len().unwrap(), 1);
+    assert!(tx_infos.contains(&TX_V1_SIG2.tx_hash).unwrap());
+}
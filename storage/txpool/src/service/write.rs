@@ -1,15 +1,17 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use cuprate_database::{
     ConcreteEnv, DatabaseRo, DatabaseRw, DbResult, Env, EnvInner, RuntimeError, TxRw,
 };
 use cuprate_database_service::DatabaseWriteHandle;
+use cuprate_helper::time::current_unix_timestamp;
 use cuprate_types::TransactionVerificationData;
 
 use crate::{
     ops::{self, TxPoolWriteError},
     service::{
         interface::{TxpoolWriteRequest, TxpoolWriteResponse},
+        removed_txs::RemovedTxs,
         types::TxpoolWriteHandle,
     },
     tables::{OpenTables, Tables, TransactionInfos},
@@ -18,8 +20,17 @@ use crate::{
 
 //---------------------------------------------------------------------------------------------------- init_write_service
 /// Initialize the txpool write service from a [`ConcreteEnv`].
-pub(super) fn init_write_service(env: Arc<ConcreteEnv>) -> TxpoolWriteHandle {
-    DatabaseWriteHandle::init(env, handle_txpool_request)
+///
+/// `max_txpool_weight` is the weight [`TxpoolWriteRequest::TrimPool`] trims the pool to,
+/// removed transactions are recorded in `removed_txs`.
+pub(super) fn init_write_service(
+    env: Arc<ConcreteEnv>,
+    max_txpool_weight: usize,
+    removed_txs: Arc<Mutex<RemovedTxs>>,
+) -> TxpoolWriteHandle {
+    DatabaseWriteHandle::init(env, move |env, req| {
+        handle_txpool_request(env, req, max_txpool_weight, &removed_txs)
+    })
 }
 
 //---------------------------------------------------------------------------------------------------- handle_txpool_request
@@ -27,14 +38,22 @@ pub(super) fn init_write_service(env: Arc<ConcreteEnv>) -> TxpoolWriteHandle {
 fn handle_txpool_request(
     env: &ConcreteEnv,
     req: &TxpoolWriteRequest,
+    max_txpool_weight: usize,
+    removed_txs: &Mutex<RemovedTxs>,
 ) -> DbResult<TxpoolWriteResponse> {
     match req {
         TxpoolWriteRequest::AddTransaction { tx, state_stem } => {
             add_transaction(env, tx, *state_stem)
         }
-        TxpoolWriteRequest::RemoveTransaction(tx_hash) => remove_transaction(env, tx_hash),
+        TxpoolWriteRequest::RemoveTransaction(tx_hash) => {
+            remove_transaction(env, tx_hash, removed_txs)
+        }
         TxpoolWriteRequest::Promote(tx_hash) => promote(env, tx_hash),
-        TxpoolWriteRequest::NewBlock { spent_key_images } => new_block(env, spent_key_images),
+        TxpoolWriteRequest::NewBlock { spent_key_images } => {
+            new_block(env, spent_key_images, removed_txs)
+        }
+        TxpoolWriteRequest::TrimPool => trim_pool(env, max_txpool_weight, removed_txs),
+        TxpoolWriteRequest::RemoveExpired => remove_expired(env, removed_txs),
     }
 }
 
@@ -85,24 +104,37 @@ fn add_transaction(
 fn remove_transaction(
     env: &ConcreteEnv,
     tx_hash: &TransactionHash,
+    removed_txs: &Mutex<RemovedTxs>,
 ) -> DbResult<TxpoolWriteResponse> {
     let env_inner = env.env_inner();
     let tx_rw = env_inner.tx_rw()?;
 
     let mut tables_mut = env_inner.open_tables_mut(&tx_rw)?;
 
-    if let Err(e) = ops::remove_transaction(tx_hash, &mut tables_mut) {
-        drop(tables_mut);
-        // error removing the tx, abort the DB transaction.
-        TxRw::abort(tx_rw)
-            .expect("could not maintain database atomicity by aborting write transaction");
+    let stem = match ops::in_stem_pool(tx_hash, tables_mut.transaction_infos())
+        .and_then(|stem| ops::remove_transaction(tx_hash, &mut tables_mut).map(|()| stem))
+    {
+        Ok(stem) => stem,
+        Err(e) => {
+            drop(tables_mut);
+            // error removing the tx, abort the DB transaction.
+            TxRw::abort(tx_rw)
+                .expect("could not maintain database atomicity by aborting write transaction");
 
-        return Err(e);
-    }
+            return Err(e);
+        }
+    };
 
     drop(tables_mut);
 
     TxRw::commit(tx_rw)?;
+
+    if stem {
+        record_removed_txs(removed_txs, &[], &[*tx_hash]);
+    } else {
+        record_removed_txs(removed_txs, &[*tx_hash], &[]);
+    }
+
     Ok(TxpoolWriteResponse::Ok)
 }
 
@@ -133,24 +165,36 @@ fn promote(env: &ConcreteEnv, tx_hash: &TransactionHash) -> DbResult<TxpoolWrite
 }
 
 /// [`TxpoolWriteRequest::NewBlock`]
-fn new_block(env: &ConcreteEnv, spent_key_images: &[KeyImage]) -> DbResult<TxpoolWriteResponse> {
+fn new_block(
+    env: &ConcreteEnv,
+    spent_key_images: &[KeyImage],
+    removed_txs: &Mutex<RemovedTxs>,
+) -> DbResult<TxpoolWriteResponse> {
     let env_inner = env.env_inner();
     let tx_rw = env_inner.tx_rw()?;
 
+    let mut fluff_tx_hashes = Vec::new();
+    let mut stem_tx_hashes = Vec::new();
+
     // FIXME: use try blocks once stable.
-    let result = || {
+    let mut result = || {
         let mut tables_mut = env_inner.open_tables_mut(&tx_rw)?;
 
         // Remove all txs which spend key images that were spent in the new block.
         for key_image in spent_key_images {
-            match tables_mut
-                .spent_key_images()
-                .get(key_image)
-                .and_then(|tx_hash| ops::remove_transaction(&tx_hash, &mut tables_mut))
-            {
-                Ok(()) | Err(RuntimeError::KeyNotFound) => (),
+            let tx_hash = match tables_mut.spent_key_images().get(key_image) {
+                Ok(tx_hash) => tx_hash,
+                Err(RuntimeError::KeyNotFound) => continue,
                 Err(e) => return Err(e),
+            };
+
+            if ops::in_stem_pool(&tx_hash, tables_mut.transaction_infos())? {
+                stem_tx_hashes.push(tx_hash);
+            } else {
+                fluff_tx_hashes.push(tx_hash);
             }
+
+            ops::remove_transaction(&tx_hash, &mut tables_mut)?;
         }
 
         Ok(())
@@ -162,5 +206,103 @@ fn new_block(env: &ConcreteEnv, spent_key_images: &[KeyImage]) -> DbResult<Txpoo
     }
 
     TxRw::commit(tx_rw)?;
+    record_removed_txs(removed_txs, &fluff_tx_hashes, &stem_tx_hashes);
+
     Ok(TxpoolWriteResponse::Ok)
 }
+
+/// [`TxpoolWriteRequest::TrimPool`]
+fn trim_pool(
+    env: &ConcreteEnv,
+    max_txpool_weight: usize,
+    removed_txs: &Mutex<RemovedTxs>,
+) -> DbResult<TxpoolWriteResponse> {
+    let env_inner = env.env_inner();
+
+    // Write tables can't be iterated, as this is the only writer the pool can't change in between.
+    let tx_hashes = {
+        let tx_ro = env_inner.tx_ro()?;
+        let tx_infos = env_inner.open_db_ro::<TransactionInfos>(&tx_ro)?;
+        ops::txs_to_evict(max_txpool_weight, &tx_infos)?
+    };
+
+    remove_transactions(env, tx_hashes, removed_txs)
+}
+
+/// [`TxpoolWriteRequest::RemoveExpired`]
+fn remove_expired(
+    env: &ConcreteEnv,
+    removed_txs: &Mutex<RemovedTxs>,
+) -> DbResult<TxpoolWriteResponse> {
+    let env_inner = env.env_inner();
+
+    let tx_hashes = {
+        let tx_ro = env_inner.tx_ro()?;
+        let tx_infos = env_inner.open_db_ro::<TransactionInfos>(&tx_ro)?;
+        ops::expired_txs(current_unix_timestamp(), &tx_infos)?
+    };
+
+    remove_transactions(env, tx_hashes, removed_txs)
+}
+
+/// Remove the given transactions from the pool, returning [`TxpoolWriteResponse::RemovedTransactions`].
+fn remove_transactions(
+    env: &ConcreteEnv,
+    tx_hashes: Vec<TransactionHash>,
+    removed_txs: &Mutex<RemovedTxs>,
+) -> DbResult<TxpoolWriteResponse> {
+    let mut fluff_tx_hashes = Vec::new();
+    let mut stem_tx_hashes = Vec::new();
+
+    if tx_hashes.is_empty() {
+        return Ok(TxpoolWriteResponse::RemovedTransactions {
+            fluff_tx_hashes,
+            stem_tx_hashes,
+        });
+    }
+
+    let env_inner = env.env_inner();
+    let tx_rw = env_inner.tx_rw()?;
+
+    // FIXME: use try blocks once stable.
+    let mut result = || {
+        let mut tables_mut = env_inner.open_tables_mut(&tx_rw)?;
+
+        for tx_hash in &tx_hashes {
+            if ops::in_stem_pool(tx_hash, tables_mut.transaction_infos())? {
+                stem_tx_hashes.push(*tx_hash);
+            } else {
+                fluff_tx_hashes.push(*tx_hash);
+            }
+
+            ops::remove_transaction(tx_hash, &mut tables_mut)?;
+        }
+
+        Ok(())
+    };
+
+    if let Err(e) = result() {
+        TxRw::abort(tx_rw)?;
+        return Err(e);
+    }
+
+    TxRw::commit(tx_rw)?;
+    record_removed_txs(removed_txs, &fluff_tx_hashes, &stem_tx_hashes);
+
+    Ok(TxpoolWriteResponse::RemovedTransactions {
+        fluff_tx_hashes,
+        stem_tx_hashes,
+    })
+}
+
+/// Record the removal of the given transactions in `removed_txs`.
+fn record_removed_txs(
+    removed_txs: &Mutex<RemovedTxs>,
+    fluff_tx_hashes: &[TransactionHash],
+    stem_tx_hashes: &[TransactionHash],
+) {
+    removed_txs
+        .lock()
+        .unwrap()
+        .record(current_unix_timestamp(), fluff_tx_hashes, stem_tx_hashes);
+}
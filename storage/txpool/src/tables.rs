@@ -17,7 +17,8 @@
 use cuprate_database::{define_tables, StorableVec};
 
 use crate::types::{
-    KeyImage, RawCachedVerificationState, TransactionBlobHash, TransactionHash, TransactionInfo,
+    KeyImage, PropertyKey, RawCachedVerificationState, TransactionBlobHash, TransactionHash,
+    TransactionInfo,
 };
 
 define_tables! {
@@ -48,4 +49,10 @@ define_tables! {
     /// Transaction blob hashes that are in the pool.
     4 => KnownBlobHashes,
     TransactionBlobHash => TransactionHash,
+
+    /// Database properties.
+    ///
+    /// Contains values that apply to the whole database, e.g. the database version.
+    5 => Properties,
+    PropertyKey => u64,
 }
@@ -8,6 +8,7 @@
 use bytemuck::{Pod, Zeroable};
 use monero_serai::transaction::Timelock;
 
+use cuprate_database::StorableStr;
 use cuprate_types::{CachedVerificationState, HardFork};
 
 /// An inputs key image.
@@ -19,6 +20,9 @@ pub type TransactionHash = [u8; 32];
 /// A transaction blob hash.
 pub type TransactionBlobHash = [u8; 32];
 
+/// The key of a database property, e.g. `"database_version"`.
+pub type PropertyKey = StorableStr;
+
 bitflags::bitflags! {
     /// Flags representing the state of the transaction in the pool.
     #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Pod, Zeroable)]
@@ -39,6 +43,8 @@ pub struct TransactionInfo {
     pub fee: u64,
     /// The transaction's weight.
     pub weight: usize,
+    /// The UNIX timestamp of when this transaction was added to the pool.
+    pub received_at: u64,
     /// [`TxStateFlags`] of this transaction.
     pub flags: TxStateFlags,
     #[expect(clippy::pub_underscore_fields)]
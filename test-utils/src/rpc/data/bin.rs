@@ -1,6 +1,9 @@
 //! Binary data from [`.bin` endpoints](https://www.getmonero.org/resources/developer-guides/daemon-rpc.html#get_blocksbin).
 //!
-//! TODO: Not implemented yet.
+//! The filled in data is for a node whose chain only has the mainnet genesis block,
+//! it is encoded the same way `monerod` encodes it (fields sorted by name).
+//!
+//! TODO: the other endpoints are not implemented yet.
 
 //---------------------------------------------------------------------------------------------------- Import
 use crate::rpc::data::macros::define_request_and_response;
@@ -8,9 +11,42 @@ use crate::rpc::data::macros::define_request_and_response;
 //---------------------------------------------------------------------------------------------------- TODO
 define_request_and_response! {
     get_blocksbin,
+    /// Requests the pruned chain after an unknown block and the genesis block.
     GET_BLOCKS: &[u8],
-    Request = &[];
-    Response = &[];
+    Request = &[
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x10, 0x09, 0x62, 0x6c, 0x6f, 0x63, 0x6b,
+        0x5f, 0x69, 0x64, 0x73, 0x0a, 0x01, 0x01, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42,
+        0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42,
+        0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x41, 0x80, 0x15, 0xbb, 0x9a, 0xe9, 0x82, 0xa1, 0x97,
+        0x5d, 0xa7, 0xd7, 0x92, 0x77, 0xc2, 0x70, 0x57, 0x27, 0xa5, 0x68, 0x94, 0xba, 0x0f, 0xb2, 0x46,
+        0xad, 0xaa, 0xbb, 0x1f, 0x46, 0x32, 0xe3, 0x0b, 0x6e, 0x6f, 0x5f, 0x6d, 0x69, 0x6e, 0x65, 0x72,
+        0x5f, 0x74, 0x78, 0x0b, 0x00, 0x05, 0x70, 0x72, 0x75, 0x6e, 0x65, 0x0b, 0x01, 0x0c, 0x73, 0x74,
+        0x61, 0x72, 0x74, 0x5f, 0x68, 0x65, 0x69, 0x67, 0x68, 0x74, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00,
+    ];
+    Response = &[
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x24, 0x06, 0x62, 0x6c, 0x6f, 0x63, 0x6b,
+        0x73, 0x8c, 0x04, 0x08, 0x05, 0x62, 0x6c, 0x6f, 0x63, 0x6b, 0x0a, 0xe1, 0x01, 0x01, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x10, 0x27, 0x00, 0x00, 0x01, 0x3c, 0x01, 0xff, 0x00, 0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x03, 0x02, 0x9b, 0x2e, 0x4c, 0x02, 0x81, 0xc0, 0xb0, 0x2e, 0x7c, 0x53, 0x29, 0x1a, 0x94, 0xd1,
+        0xd0, 0xcb, 0xff, 0x88, 0x83, 0xf8, 0x02, 0x4f, 0x51, 0x42, 0xee, 0x49, 0x4f, 0xfb, 0xbd, 0x08,
+        0x80, 0x71, 0x21, 0x01, 0x77, 0x67, 0xaa, 0xfc, 0xde, 0x9b, 0xe0, 0x0d, 0xcf, 0xd0, 0x98, 0x71,
+        0x5e, 0xbc, 0xf7, 0xf4, 0x10, 0xda, 0xeb, 0xc5, 0x82, 0xfd, 0xa6, 0x9d, 0x24, 0xa2, 0x8e, 0x9d,
+        0x0b, 0xc8, 0x90, 0xd1, 0x00, 0x06, 0x70, 0x72, 0x75, 0x6e, 0x65, 0x64, 0x0b, 0x01, 0x07, 0x63,
+        0x72, 0x65, 0x64, 0x69, 0x74, 0x73, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0e,
+        0x63, 0x75, 0x72, 0x72, 0x65, 0x6e, 0x74, 0x5f, 0x68, 0x65, 0x69, 0x67, 0x68, 0x74, 0x05, 0x01,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0b, 0x64, 0x61, 0x65, 0x6d, 0x6f, 0x6e, 0x5f, 0x74,
+        0x69, 0x6d, 0x65, 0x05, 0x00, 0xf1, 0x53, 0x65, 0x00, 0x00, 0x00, 0x00, 0x0e, 0x6f, 0x75, 0x74,
+        0x70, 0x75, 0x74, 0x5f, 0x69, 0x6e, 0x64, 0x69, 0x63, 0x65, 0x73, 0x8c, 0x04, 0x04, 0x07, 0x69,
+        0x6e, 0x64, 0x69, 0x63, 0x65, 0x73, 0x8c, 0x04, 0x04, 0x07, 0x69, 0x6e, 0x64, 0x69, 0x63, 0x65,
+        0x73, 0x85, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x73, 0x74, 0x61, 0x72,
+        0x74, 0x5f, 0x68, 0x65, 0x69, 0x67, 0x68, 0x74, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x06, 0x73, 0x74, 0x61, 0x74, 0x75, 0x73, 0x0a, 0x08, 0x4f, 0x4b, 0x08, 0x74, 0x6f, 0x70,
+        0x5f, 0x68, 0x61, 0x73, 0x68, 0x0a, 0x00, 0x09, 0x75, 0x6e, 0x74, 0x72, 0x75, 0x73, 0x74, 0x65,
+        0x64, 0x0b, 0x00,
+    ];
 }
 
 define_request_and_response! {
@@ -29,16 +65,50 @@ define_request_and_response! {
 
 define_request_and_response! {
     get_o_indexesbin,
+    /// Requests the output indexes of the genesis block's miner transaction.
     GET_O_INDEXES: &[u8],
-    Request = &[];
-    Response = &[];
+    Request = &[
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x04, 0x04, 0x74, 0x78, 0x69, 0x64, 0x0a,
+        0x80, 0xc8, 0x8c, 0xe9, 0x78, 0x3b, 0x4f, 0x11, 0x19, 0x0d, 0x7b, 0x9c, 0x17, 0xa6, 0x9c, 0x1c,
+        0x52, 0x20, 0x0f, 0x9f, 0xaa, 0xee, 0x8e, 0x98, 0xdd, 0x07, 0xe6, 0x81, 0x11, 0x75, 0x17, 0x71,
+        0x39,
+    ];
+    Response = &[
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x14, 0x07, 0x63, 0x72, 0x65, 0x64, 0x69,
+        0x74, 0x73, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x6f, 0x5f, 0x69, 0x6e,
+        0x64, 0x65, 0x78, 0x65, 0x73, 0x0a, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06,
+        0x73, 0x74, 0x61, 0x74, 0x75, 0x73, 0x0a, 0x08, 0x4f, 0x4b, 0x08, 0x74, 0x6f, 0x70, 0x5f, 0x68,
+        0x61, 0x73, 0x68, 0x0a, 0x00, 0x09, 0x75, 0x6e, 0x74, 0x72, 0x75, 0x73, 0x74, 0x65, 0x64, 0x0b,
+        0x00,
+    ];
 }
 
 define_request_and_response! {
     get_outsbin,
+    /// Requests the genesis block's output.
     GET_OUTS: &[u8],
-    Request = &[];
-    Response = &[];
+    Request = &[
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x08, 0x08, 0x67, 0x65, 0x74, 0x5f, 0x74,
+        0x78, 0x69, 0x64, 0x0b, 0x01, 0x07, 0x6f, 0x75, 0x74, 0x70, 0x75, 0x74, 0x73, 0x8c, 0x04, 0x08,
+        0x06, 0x61, 0x6d, 0x6f, 0x75, 0x6e, 0x74, 0x05, 0xff, 0xff, 0xff, 0xff, 0xff, 0x0f, 0x00, 0x00,
+        0x05, 0x69, 0x6e, 0x64, 0x65, 0x78, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    Response = &[
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x14, 0x07, 0x63, 0x72, 0x65, 0x64, 0x69,
+        0x74, 0x73, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x6f, 0x75, 0x74, 0x73,
+        0x8c, 0x04, 0x14, 0x06, 0x68, 0x65, 0x69, 0x67, 0x68, 0x74, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x03, 0x6b, 0x65, 0x79, 0x0a, 0x80, 0x9b, 0x2e, 0x4c, 0x02, 0x81, 0xc0, 0xb0,
+        0x2e, 0x7c, 0x53, 0x29, 0x1a, 0x94, 0xd1, 0xd0, 0xcb, 0xff, 0x88, 0x83, 0xf8, 0x02, 0x4f, 0x51,
+        0x42, 0xee, 0x49, 0x4f, 0xfb, 0xbd, 0x08, 0x80, 0x71, 0x04, 0x6d, 0x61, 0x73, 0x6b, 0x0a, 0x80,
+        0x7c, 0xbd, 0xd8, 0x62, 0xa9, 0x5d, 0x18, 0x07, 0x79, 0xce, 0x25, 0x0b, 0x46, 0x00, 0x43, 0xaf,
+        0xc4, 0xa4, 0x92, 0xb9, 0x02, 0x3b, 0x41, 0xa7, 0x8f, 0xbd, 0x45, 0xe7, 0xd7, 0x3b, 0xb0, 0x85,
+        0x04, 0x74, 0x78, 0x69, 0x64, 0x0a, 0x80, 0xc8, 0x8c, 0xe9, 0x78, 0x3b, 0x4f, 0x11, 0x19, 0x0d,
+        0x7b, 0x9c, 0x17, 0xa6, 0x9c, 0x1c, 0x52, 0x20, 0x0f, 0x9f, 0xaa, 0xee, 0x8e, 0x98, 0xdd, 0x07,
+        0xe6, 0x81, 0x11, 0x75, 0x17, 0x71, 0x39, 0x08, 0x75, 0x6e, 0x6c, 0x6f, 0x63, 0x6b, 0x65, 0x64,
+        0x0b, 0x00, 0x06, 0x73, 0x74, 0x61, 0x74, 0x75, 0x73, 0x0a, 0x08, 0x4f, 0x4b, 0x08, 0x74, 0x6f,
+        0x70, 0x5f, 0x68, 0x61, 0x73, 0x68, 0x0a, 0x00, 0x09, 0x75, 0x6e, 0x74, 0x72, 0x75, 0x73, 0x74,
+        0x65, 0x64, 0x0b, 0x00,
+    ];
 }
 
 define_request_and_response! {
@@ -48,6 +118,33 @@ define_request_and_response! {
     Response = &[];
 }
 
+define_request_and_response! {
+    get_output_distribution,
+    /// Requests the binary RingCT output distribution.
+    GET_OUTPUT_DISTRIBUTION: &[u8],
+    Request = &[
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x10, 0x07, 0x61, 0x6d, 0x6f, 0x75, 0x6e,
+        0x74, 0x73, 0x85, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a, 0x63, 0x75, 0x6d,
+        0x75, 0x6c, 0x61, 0x74, 0x69, 0x76, 0x65, 0x0b, 0x00, 0x0b, 0x66, 0x72, 0x6f, 0x6d, 0x5f, 0x68,
+        0x65, 0x69, 0x67, 0x68, 0x74, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x74,
+        0x6f, 0x5f, 0x68, 0x65, 0x69, 0x67, 0x68, 0x74, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00,
+    ];
+    Response = &[
+        0x01, 0x11, 0x01, 0x01, 0x01, 0x01, 0x02, 0x01, 0x01, 0x14, 0x07, 0x63, 0x72, 0x65, 0x64, 0x69,
+        0x74, 0x73, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0d, 0x64, 0x69, 0x73, 0x74,
+        0x72, 0x69, 0x62, 0x75, 0x74, 0x69, 0x6f, 0x6e, 0x73, 0x8c, 0x04, 0x18, 0x06, 0x61, 0x6d, 0x6f,
+        0x75, 0x6e, 0x74, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x62, 0x61, 0x73,
+        0x65, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x62, 0x69, 0x6e, 0x61, 0x72,
+        0x79, 0x0b, 0x01, 0x08, 0x63, 0x6f, 0x6d, 0x70, 0x72, 0x65, 0x73, 0x73, 0x0b, 0x00, 0x0c, 0x64,
+        0x69, 0x73, 0x74, 0x72, 0x69, 0x62, 0x75, 0x74, 0x69, 0x6f, 0x6e, 0x0a, 0x20, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x73, 0x74, 0x61, 0x72, 0x74, 0x5f, 0x68, 0x65, 0x69, 0x67,
+        0x68, 0x74, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x73, 0x74, 0x61, 0x74,
+        0x75, 0x73, 0x0a, 0x08, 0x4f, 0x4b, 0x08, 0x74, 0x6f, 0x70, 0x5f, 0x68, 0x61, 0x73, 0x68, 0x0a,
+        0x00, 0x09, 0x75, 0x6e, 0x74, 0x72, 0x75, 0x73, 0x74, 0x65, 0x64, 0x0b, 0x00,
+    ];
+}
+
 //---------------------------------------------------------------------------------------------------- Tests
 #[cfg(test)]
 mod test {
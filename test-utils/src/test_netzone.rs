@@ -19,6 +19,8 @@ pub struct TestNetZoneAddr(pub u32);
 
 impl NetZoneAddress for TestNetZoneAddr {
     type BanID = Self;
+    /// Test addresses are in a subnet `(start, end)` if `start <= id < end`.
+    type BanSubnet = (u32, u32);
 
     fn set_port(&mut self, _: u16) {}
 
@@ -28,6 +30,20 @@ impl NetZoneAddress for TestNetZoneAddr {
         *self
     }
 
+    fn in_subnet(&self, subnet: &Self::BanSubnet) -> bool {
+        (subnet.0..subnet.1).contains(&self.0)
+    }
+
+    /// Test addresses are grouped into subnets of 256 IDs.
+    fn diversity_subnet(&self) -> Option<Self::BanSubnet> {
+        let start = self.0 & !0xFF;
+        Some((start, start.saturating_add(0x100)))
+    }
+
+    fn is_local(&self) -> bool {
+        false
+    }
+
     fn should_add_to_peer_list(&self) -> bool {
         true
     }
@@ -11,6 +11,8 @@ use std::{
 use indexmap::{IndexMap, IndexSet};
 use monero_serai::block::Block;
 
+use cuprate_pruning::PruningSeed;
+
 use crate::{
     output_cache::OutputCache,
     rpc::{
@@ -208,6 +210,11 @@ pub enum BlockchainReadRequest {
 
     /// Get the output indexes of a transaction.
     TxOutputIndexes { tx_hash: [u8; 32] },
+
+    /// Get the output indexes of multiple transactions.
+    ///
+    /// This returns an error if any transaction is not found.
+    TxsOutputIndexes { tx_hashes: Vec<[u8; 32]> },
 }
 
 //---------------------------------------------------------------------------------------------------- WriteRequest
@@ -239,6 +246,17 @@ pub enum BlockchainWriteRequest {
 
     /// A request to flush all alternative blocks.
     FlushAltBlocks,
+
+    /// A request to prune the blockchain.
+    ///
+    /// Input is the [`PruningSeed`] to prune with, this is only used if the database is
+    /// not already pruned, otherwise the database's seed is kept. Pruning resumes from
+    /// where it last stopped, so [`PruningSeed::NotPruned`] can be used to continue
+    /// pruning an already pruned database.
+    ///
+    /// All blocks outside the blockchain tip and our pruning stripe are pruned
+    /// before responding, which may take a long time.
+    Prune(PruningSeed),
 }
 
 //---------------------------------------------------------------------------------------------------- Response
@@ -428,6 +446,11 @@ pub enum BlockchainResponse {
     /// Response to [`BlockchainReadRequest::TxOutputIndexes`].
     TxOutputIndexes(Vec<u64>),
 
+    /// Response to [`BlockchainReadRequest::TxsOutputIndexes`].
+    ///
+    /// The output indexes of each transaction, in the same order as the request.
+    TxsOutputIndexes(Vec<Vec<u64>>),
+
     //------------------------------------------------------ Writes
     /// A generic Ok response to indicate a request was successfully handled.
     ///
@@ -441,6 +464,11 @@ pub enum BlockchainResponse {
     ///
     /// The inner value is the alt-chain ID for the old main chain blocks.
     PopBlocks(ChainId),
+
+    /// Response to [`BlockchainWriteRequest::Prune`].
+    ///
+    /// The inner value is the [`PruningSeed`] the database is pruned with.
+    Prune(PruningSeed),
 }
 
 //---------------------------------------------------------------------------------------------------- Tests
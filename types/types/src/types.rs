@@ -33,6 +33,10 @@ pub struct ExtendedBlockHeader {
     pub block_weight: usize,
     /// The long term block weight, based on the median weight of the preceding `100_000` blocks.
     pub long_term_weight: usize,
+    /// The total amount of coins generated in all blocks so far, including this block's.
+    ///
+    /// This is [`None`] for alt-blocks, which do not store it.
+    pub cumulative_generated_coins: Option<u64>,
 }
 
 /// Verified information of a transaction.
@@ -38,6 +38,28 @@ pub struct TxPoolAdd {
     pub ringct: PoolRingCt,
 }
 
+impl TxPoolAdd {
+    /// Create a new [`TxPoolAdd`], the obsolete fields are left empty.
+    pub const fn new(
+        version: u8,
+        unlock_time: u64,
+        inputs: Vec<PoolInput>,
+        outputs: Vec<Output>,
+        extra: Vec<u8>,
+        ringct: PoolRingCt,
+    ) -> Self {
+        Self {
+            version,
+            unlock_time,
+            inputs,
+            outputs,
+            extra,
+            signatures: [],
+            ringct,
+        }
+    }
+}
+
 /// ZMQ `json-minimal-txpool_add` subscriber messages contain an array of
 /// `TxPoolAddMin` JSON objects. See `TxPoolAdd` for information on which
 /// transactions are published to subscribers.
@@ -129,6 +151,17 @@ pub struct ToKey {
     pub key_image: Hex<32>,
 }
 
+impl ToKey {
+    /// Create a new [`ToKey`], the obsolete amount is set to `0`.
+    pub const fn new(key_offsets: Vec<u64>, key_image: Hex<32>) -> Self {
+        Self {
+            amount: 0,
+            key_offsets,
+            key_image,
+        }
+    }
+}
+
 /// Holds the block height of the coinbase transaction.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct MinerInput {
@@ -196,6 +229,16 @@ pub struct Encrypted {
     pub amount: Hex<32>,
 }
 
+impl Encrypted {
+    /// Create a new [`Encrypted`], the obsolete mask is set to zeros.
+    pub const fn new(amount: Hex<32>) -> Self {
+        Self {
+            mask: Hex([0; 32]),
+            amount,
+        }
+    }
+}
+
 /// Data needed to validate a transaction that can optionally be pruned from
 /// older blocks.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -215,6 +258,24 @@ pub struct Prunable {
     pub pseudo_outs: Vec<Hex<32>>,
 }
 
+impl Prunable {
+    /// Create a new [`Prunable`], the obsolete fields are left empty.
+    pub const fn new(
+        bulletproof_plus: BulletproofPlus,
+        clsags: Vec<Clsag>,
+        pseudo_outs: Vec<Hex<32>>,
+    ) -> Self {
+        Self {
+            range_proofs: [],
+            bulletproofs: [],
+            bulletproofs_plus: [bulletproof_plus],
+            mlsags: [],
+            clsags,
+            pseudo_outs,
+        }
+    }
+}
+
 /// Bulletproofs+ data used to validate the legitimacy of a Ring CT transaction.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[expect(non_snake_case)]
@@ -267,6 +328,29 @@ pub struct MinerTx {
     ringct: MinerRingCt,
 }
 
+impl MinerTx {
+    /// Create a new [`MinerTx`] for the block at `height`, the obsolete fields are left empty.
+    pub const fn new(
+        version: u8,
+        unlock_time: u64,
+        height: u64,
+        outputs: Vec<Output>,
+        extra: Vec<u8>,
+    ) -> Self {
+        Self {
+            version,
+            unlock_time,
+            inputs: [MinerInput {
+                r#gen: Gen { height },
+            }],
+            outputs,
+            extra,
+            signatures: [],
+            ringct: MinerRingCt { r#type: 0 },
+        }
+    }
+}
+
 /// Holds a transaction entry in the `MinerData` `tx_backlog` field.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TxBacklog {